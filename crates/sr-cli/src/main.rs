@@ -1,18 +1,44 @@
 use clap::{Parser, Subcommand};
-use sr_common::{ErrorItem, SR_CMP_002, SR_EVD_002, SR_RUN_001};
+use sr_common::{
+    ErrorItem, SR_CMP_002, SR_EVD_002, SR_OPS_301, SR_POL_001, SR_POL_002, SR_RUN_001,
+};
 use sr_compiler::{compile_dry_run, CompileBundle};
 use sr_evidence::{
-    build_report, compute_artifact_hashes_from_json, compute_integrity_digest, event_time_range,
-    mount_audit_from_events, network_audit_from_events, resource_usage_from_events,
-    verify_report_file, ArtifactJsonInputs, EvidenceEvent, PolicySummary, RunReport,
+    archive_report_to_store, archive_report_with_artifacts, build_report,
+    compute_artifact_hashes_from_json, compute_integrity_digest, diff_reports, event_time_range,
+    export_archived_bundle, list_store_bundles, load_archived_report, load_store_report,
+    mount_audit_from_events, network_audit_from_events, parse_retention, parse_unix_seconds,
+    prune_archive, query_archive_index, redact_report, reindex_archive, render_diff_text,
+    render_report, resource_usage_from_events, verify_bundle_package,
+    verify_redacted_against_original, verify_report_file, ArchiveQuery, ArtifactJsonInputs,
+    BundleArtifact, BundleCompression, DiffFormat, EventSelector, EvidenceEvent, FieldRedaction,
+    PolicySummary, RedactionRequest, RenderFormat, RunReport, S3ArchiveStore, S3StoreConfig,
+};
+use sr_policy::{
+    bind_parameters, lint_policy, load_policy_from_path, migrate_policy, policy_json_schema,
+    render_diagnostics, validate_policy_with_allowlist, LintConfig, NetworkMode, PolicySourceMap,
+    PolicySpec, ValidationResult,
 };
-use sr_policy::{load_policy_from_path, validate_policy_with_allowlist, NetworkMode, PolicySpec};
 use sr_runner::{MonitorResult, RunState, Runner, RunnerControlRequest, RuntimeContext};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+const ARCHIVE_ROOT_ENV: &str = "SAFE_RUN_ARCHIVE_ROOT";
+const S3_ARCHIVE_PREFIX: &str = "s3://";
+const S3_ENDPOINT_ENV: &str = "SAFE_RUN_S3_ENDPOINT";
+const S3_REGION_ENV: &str = "AWS_REGION";
+const S3_ACCESS_KEY_ENV: &str = "AWS_ACCESS_KEY_ID";
+const S3_SECRET_KEY_ENV: &str = "AWS_SECRET_ACCESS_KEY";
+const DEFAULT_ARCHIVE_RETENTION: &str = "30d";
+const BUNDLE_EVENTS_FILE: &str = "events.jsonl";
+const BUNDLE_POLICY_FILE: &str = "policy.normalized.json";
+const BUNDLE_FIRECRACKER_CONFIG_FILE: &str = "firecracker-config.json";
+const BUNDLE_RUNTIME_CONTEXT_FILE: &str = "runtime-context.json";
+const BUNDLE_LOG_FILES: [&str; 2] = ["vm.stdout.log", "vm.stderr.log"];
+
 #[derive(Debug, Parser)]
 #[command(name = "safe-run")]
 #[command(about = "Safe-Run CLI (M0-M2)")]
@@ -27,6 +53,18 @@ enum Commands {
         policy: String,
         #[arg(long = "mount-allowlist")]
         mount_allowlist: Option<String>,
+        /// `json` (default) or `text` for compiler-style diagnostics.
+        #[arg(long, default_value = "json")]
+        format: String,
+    },
+    /// Report security best-practice findings; exits non-zero on error-severity findings.
+    Lint {
+        policy: String,
+        #[arg(long = "mount-allowlist")]
+        mount_allowlist: Option<String>,
+        /// `safe-run.lint/v1` file with per-rule severity overrides.
+        #[arg(long)]
+        config: Option<String>,
     },
     Compile {
         #[arg(long = "dry-run", default_value_t = false)]
@@ -41,16 +79,136 @@ enum Commands {
         policy: String,
         #[arg(long = "mount-allowlist")]
         mount_allowlist: Option<String>,
+        #[arg(long = "archive-root")]
+        archive_root: Option<String>,
+        #[arg(long, default_value = DEFAULT_ARCHIVE_RETENTION)]
+        retention: String,
+        #[arg(long, default_value = "none")]
+        compression: String,
+        #[arg(long = "mount-scan-max-entries")]
+        mount_scan_max_entries: Option<u64>,
+        /// Bind a policy parameter, e.g. `--set input_dir=/data/in`; repeatable.
+        #[arg(long = "set", value_name = "KEY=VALUE")]
+        set: Vec<String>,
     },
     Report {
         #[command(subcommand)]
         command: ReportCommands,
     },
+    Archive {
+        #[command(subcommand)]
+        command: ArchiveCommands,
+    },
+    Schema {
+        #[command(subcommand)]
+        command: SchemaCommands,
+    },
+    Policy {
+        #[command(subcommand)]
+        command: PolicyCommands,
+    },
 }
 
 #[derive(Debug, Subcommand)]
 enum ReportCommands {
-    Verify { report: String },
+    Verify {
+        report: String,
+        /// Full report a redacted report must be derived from.
+        #[arg(long)]
+        original: Option<String>,
+    },
+    Redact {
+        report: String,
+        #[arg(long = "remove-event")]
+        remove_events: Vec<String>,
+        #[arg(long = "redact-field")]
+        redact_fields: Vec<String>,
+        #[arg(long)]
+        output: Option<String>,
+    },
+    Render {
+        report: String,
+        #[arg(long, default_value = "html")]
+        format: String,
+        #[arg(long)]
+        output: Option<String>,
+    },
+    Diff {
+        left: String,
+        right: String,
+        #[arg(long, default_value = "json")]
+        format: String,
+    },
+    Archive {
+        report: String,
+        #[arg(long = "archive-root")]
+        archive_root: Option<String>,
+        #[arg(long, default_value = DEFAULT_ARCHIVE_RETENTION)]
+        retention: String,
+        #[arg(long, default_value = "none")]
+        compression: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum SchemaCommands {
+    /// Print the policy JSON Schema for editor integration.
+    Policy,
+}
+
+#[derive(Debug, Subcommand)]
+enum PolicyCommands {
+    /// Rewrite a policy file to the latest apiVersion, keeping comments and layout.
+    Migrate {
+        file: String,
+        /// Report pending changes and exit 2 when there are any; the file is not written.
+        #[arg(long, default_value_t = false)]
+        check: bool,
+        /// Write the migrated policy here instead of rewriting `file`.
+        #[arg(long)]
+        output: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum ArchiveCommands {
+    List {
+        #[arg(long = "archive-root")]
+        archive_root: Option<String>,
+        #[arg(long = "run-id")]
+        run_id: Option<String>,
+        #[arg(long)]
+        since: Option<String>,
+        #[arg(long)]
+        until: Option<String>,
+        #[arg(long)]
+        result: Option<String>,
+    },
+    Show {
+        bundle_id: String,
+        #[arg(long = "archive-root")]
+        archive_root: Option<String>,
+    },
+    Prune {
+        #[arg(long = "archive-root")]
+        archive_root: Option<String>,
+        #[arg(long = "dry-run", default_value_t = false)]
+        dry_run: bool,
+    },
+    Export {
+        bundle_id: String,
+        #[arg(long = "archive-root")]
+        archive_root: Option<String>,
+        #[arg(long)]
+        output: String,
+    },
+    Reindex {
+        #[arg(long = "archive-root")]
+        archive_root: Option<String>,
+    },
+    Verify {
+        package: String,
+    },
 }
 
 fn main() -> ExitCode {
@@ -60,7 +218,13 @@ fn main() -> ExitCode {
         Commands::Validate {
             policy,
             mount_allowlist,
-        } => validate_cmd(&policy, mount_allowlist.as_deref()),
+            format,
+        } => validate_cmd(&policy, mount_allowlist.as_deref(), &format),
+        Commands::Lint {
+            policy,
+            mount_allowlist,
+            config,
+        } => lint_cmd(&policy, mount_allowlist.as_deref(), config.as_deref()),
         Commands::Compile {
             dry_run,
             policy,
@@ -69,15 +233,144 @@ fn main() -> ExitCode {
         Commands::Run {
             policy,
             mount_allowlist,
-        } => run_cmd(&policy, mount_allowlist.as_deref()),
+            archive_root,
+            retention,
+            compression,
+            mount_scan_max_entries,
+            set,
+        } => run_cmd(
+            &policy,
+            mount_allowlist.as_deref(),
+            archive_root.as_deref(),
+            &retention,
+            &compression,
+            mount_scan_max_entries,
+            &set,
+        ),
         Commands::Report { command } => match command {
-            ReportCommands::Verify { report } => verify_report_cmd(&report),
+            ReportCommands::Verify { report, original } => {
+                verify_report_cmd(&report, original.as_deref())
+            }
+            ReportCommands::Redact {
+                report,
+                remove_events,
+                redact_fields,
+                output,
+            } => redact_report_cmd(&report, &remove_events, &redact_fields, output.as_deref()),
+            ReportCommands::Render {
+                report,
+                format,
+                output,
+            } => render_report_cmd(&report, &format, output.as_deref()),
+            ReportCommands::Diff {
+                left,
+                right,
+                format,
+            } => diff_report_cmd(&left, &right, &format),
+            ReportCommands::Archive {
+                report,
+                archive_root,
+                retention,
+                compression,
+            } => archive_report_cmd(&report, archive_root.as_deref(), &retention, &compression),
+        },
+        Commands::Archive { command } => match command {
+            ArchiveCommands::List {
+                archive_root,
+                run_id,
+                since,
+                until,
+                result,
+            } => archive_list_cmd(
+                archive_root.as_deref(),
+                run_id,
+                since.as_deref(),
+                until.as_deref(),
+                result,
+            ),
+            ArchiveCommands::Show {
+                bundle_id,
+                archive_root,
+            } => archive_show_cmd(archive_root.as_deref(), &bundle_id),
+            ArchiveCommands::Prune {
+                archive_root,
+                dry_run,
+            } => archive_prune_cmd(archive_root.as_deref(), dry_run),
+            ArchiveCommands::Export {
+                bundle_id,
+                archive_root,
+                output,
+            } => archive_export_cmd(archive_root.as_deref(), &bundle_id, &output),
+            ArchiveCommands::Reindex { archive_root } => {
+                archive_reindex_cmd(archive_root.as_deref())
+            }
+            ArchiveCommands::Verify { package } => archive_verify_cmd(&package),
+        },
+        Commands::Schema { command } => match command {
+            SchemaCommands::Policy => {
+                print_json_value(&policy_json_schema());
+                ExitCode::SUCCESS
+            }
         },
+        Commands::Policy { command } => match command {
+            PolicyCommands::Migrate {
+                file,
+                check,
+                output,
+            } => migrate_policy_cmd(&file, check, output.as_deref()),
+        },
+    }
+}
+
+fn migrate_policy_cmd(policy_path: &str, check: bool, output_path: Option<&str>) -> ExitCode {
+    let text = match fs::read_to_string(policy_path) {
+        Ok(text) => text,
+        Err(err) => {
+            return exit_with_error(&ErrorItem::new(
+                SR_POL_001,
+                "policy",
+                format!("failed to read policy file: {err}"),
+            ))
+        }
+    };
+    let migration = match migrate_policy(&text) {
+        Ok(migration) => migration,
+        Err(mut err) => {
+            locate_policy_errors(policy_path, std::slice::from_mut(&mut err));
+            return exit_with_error(&err);
+        }
+    };
+    let target = output_path.unwrap_or(policy_path);
+    let written = !check && (migration.changed() || output_path.is_some());
+    if written {
+        if let Err(err) = fs::write(target, &migration.output) {
+            return exit_with_error(&ErrorItem::new(
+                SR_POL_002,
+                "policy.migrate",
+                format!("failed to write migrated policy '{target}': {err}"),
+            ));
+        }
+    }
+    let mut summary = serde_json::to_value(&migration).expect("convert policy migration");
+    summary["file"] = serde_json::json!(policy_path);
+    summary["written"] = serde_json::json!(written.then_some(target));
+    print_json_value(&summary);
+    if check && migration.changed() {
+        ExitCode::from(2)
+    } else {
+        ExitCode::SUCCESS
     }
 }
 
-fn verify_report_cmd(report_path: &str) -> ExitCode {
-    match verify_report_file(Path::new(report_path)) {
+fn verify_report_cmd(report_path: &str, original_path: Option<&str>) -> ExitCode {
+    let verified = match original_path {
+        Some(original_path) => read_report(Path::new(report_path)).and_then(|report| {
+            let original = read_report(Path::new(original_path))?;
+            Ok(verify_redacted_against_original(&report, &original))
+        }),
+        None => verify_report_file(Path::new(report_path)),
+    };
+    match verified {
         Ok(result) => {
             print_json_value(&serde_json::to_value(&result).expect("convert verify result"));
             if result.valid {
@@ -97,11 +390,216 @@ fn verify_report_cmd(report_path: &str) -> ExitCode {
     }
 }
 
-fn validate_cmd(policy_path: &str, allowlist_path: Option<&str>) -> ExitCode {
-    match load_policy_from_path(policy_path) {
-        Ok(policy) => {
-            let result = validate_policy_with_allowlist(policy, allowlist_path);
-            print_json_value(&serde_json::to_value(&result).expect("convert validation result"));
+fn redact_report_cmd(
+    report_path: &str,
+    remove_events: &[String],
+    redact_fields: &[String],
+    output_path: Option<&str>,
+) -> ExitCode {
+    let request = match parse_redaction_request(remove_events, redact_fields) {
+        Ok(request) => request,
+        Err(err) => return exit_with_error(&err),
+    };
+    let report = match read_report(Path::new(report_path)) {
+        Ok(report) => report,
+        Err(err) => return exit_with_error(&err),
+    };
+    let redacted = match redact_report(&report, &request) {
+        Ok(redacted) => redacted,
+        Err(err) => return exit_with_error(&err),
+    };
+    match output_path {
+        Some(path) => {
+            if let Err(err) = write_report(Path::new(path), &redacted) {
+                return exit_with_error(&err);
+            }
+            print_json_value(&serde_json::json!({
+                "runId": redacted.run_id,
+                "disclosedEvents": redacted.events.len(),
+                "report": path
+            }));
+        }
+        None => {
+            print_json_value(&serde_json::to_value(&redacted).expect("convert redacted report"))
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+fn render_report_cmd(report_path: &str, format: &str, output_path: Option<&str>) -> ExitCode {
+    let format = match RenderFormat::parse(format) {
+        Ok(format) => format,
+        Err(err) => return exit_with_error(&err),
+    };
+    let report = match read_report(Path::new(report_path)) {
+        Ok(report) => report,
+        Err(err) => return exit_with_error(&err),
+    };
+    let rendered = render_report(&report, format);
+    match output_path {
+        Some(path) => {
+            if let Err(err) = fs::write(path, rendered) {
+                return exit_with_error(&ErrorItem::new(
+                    SR_EVD_002,
+                    "render.write",
+                    format!("failed to write rendered report '{path}': {err}"),
+                ));
+            }
+            print_json_value(&serde_json::json!({
+                "runId": report.run_id,
+                "output": path
+            }));
+        }
+        None => print!("{rendered}"),
+    }
+    ExitCode::SUCCESS
+}
+
+fn diff_report_cmd(left_path: &str, right_path: &str, format: &str) -> ExitCode {
+    let format = match DiffFormat::parse(format) {
+        Ok(format) => format,
+        Err(err) => return exit_with_error(&err),
+    };
+    let left = match read_report(Path::new(left_path)) {
+        Ok(report) => report,
+        Err(err) => return exit_with_error(&err),
+    };
+    let right = match read_report(Path::new(right_path)) {
+        Ok(report) => report,
+        Err(err) => return exit_with_error(&err),
+    };
+    let diff = match diff_reports(&left, &right) {
+        Ok(diff) => diff,
+        Err(err) => return exit_with_error(&err),
+    };
+    match format {
+        DiffFormat::Json => {
+            print_json_value(&serde_json::to_value(&diff).expect("convert report diff"))
+        }
+        DiffFormat::Text => print!("{}", render_diff_text(&diff)),
+    }
+    ExitCode::SUCCESS
+}
+
+fn archive_list_cmd(
+    archive_root: Option<&str>,
+    run_id: Option<String>,
+    since: Option<&str>,
+    until: Option<&str>,
+    result: Option<String>,
+) -> ExitCode {
+    let archive_root = match require_archive_root(archive_root) {
+        Ok(root) => root,
+        Err(err) => return exit_with_error(&err),
+    };
+    let now = unix_now_secs();
+    let query = match (
+        since.map(|raw| parse_time_filter(raw, now)).transpose(),
+        until.map(|raw| parse_time_filter(raw, now)).transpose(),
+    ) {
+        (Ok(since), Ok(until)) => ArchiveQuery {
+            run_id,
+            since,
+            until,
+            result,
+        },
+        (Err(err), _) | (_, Err(err)) => return exit_with_error(&err),
+    };
+    let entries = match s3_archive_url(&archive_root) {
+        Some(url) => open_s3_store(&url)
+            .and_then(|store| list_store_bundles(&store))
+            .map(|entries| {
+                entries
+                    .into_iter()
+                    .filter(|entry| query.matches(entry))
+                    .collect::<Vec<_>>()
+            }),
+        None => query_archive_index(&archive_root, &query),
+    };
+    match entries {
+        Ok(entries) => {
+            print_json_value(&serde_json::json!({
+                "count": entries.len(),
+                "entries": entries
+            }));
+            ExitCode::SUCCESS
+        }
+        Err(err) => exit_with_error(&err),
+    }
+}
+
+fn archive_show_cmd(archive_root: Option<&str>, bundle_id: &str) -> ExitCode {
+    let archive_root = match require_archive_root(archive_root) {
+        Ok(root) => root,
+        Err(err) => return exit_with_error(&err),
+    };
+    let report = match s3_archive_url(&archive_root) {
+        Some(url) => open_s3_store(&url).and_then(|store| load_store_report(&store, bundle_id)),
+        None => load_archived_report(&archive_root, bundle_id),
+    };
+    match report {
+        Ok(report) => {
+            print_json_value(&serde_json::to_value(&report).expect("convert archived report"));
+            ExitCode::SUCCESS
+        }
+        Err(err) => exit_with_error(&err),
+    }
+}
+
+fn archive_prune_cmd(archive_root: Option<&str>, dry_run: bool) -> ExitCode {
+    let archive_root = match require_local_archive_root(archive_root) {
+        Ok(root) => root,
+        Err(err) => return exit_with_error(&err),
+    };
+    match prune_archive(&archive_root, unix_now_secs(), dry_run) {
+        Ok(outcome) => {
+            print_json_value(&serde_json::to_value(&outcome).expect("convert prune outcome"));
+            ExitCode::SUCCESS
+        }
+        Err(err) => exit_with_error(&err),
+    }
+}
+
+fn archive_export_cmd(archive_root: Option<&str>, bundle_id: &str, output: &str) -> ExitCode {
+    let archive_root = match require_local_archive_root(archive_root) {
+        Ok(root) => root,
+        Err(err) => return exit_with_error(&err),
+    };
+    match export_archived_bundle(&archive_root, bundle_id, Path::new(output)) {
+        Ok(path) => {
+            print_json_value(&serde_json::json!({
+                "bundleId": bundle_id,
+                "output": path.to_string_lossy()
+            }));
+            ExitCode::SUCCESS
+        }
+        Err(err) => exit_with_error(&err),
+    }
+}
+
+fn archive_reindex_cmd(archive_root: Option<&str>) -> ExitCode {
+    let archive_root = match require_local_archive_root(archive_root) {
+        Ok(root) => root,
+        Err(err) => return exit_with_error(&err),
+    };
+    match reindex_archive(&archive_root) {
+        Ok(index) => {
+            print_json_value(&serde_json::json!({
+                "archiveRoot": archive_root,
+                "count": index.entries.len(),
+                "entries": index.entries
+            }));
+            ExitCode::SUCCESS
+        }
+        Err(err) => exit_with_error(&err),
+    }
+}
+
+/// Verify a bundle package offline; output mirrors `report verify`.
+fn archive_verify_cmd(package_path: &str) -> ExitCode {
+    match verify_bundle_package(Path::new(package_path)) {
+        Ok(result) => {
+            print_json_value(&serde_json::to_value(&result).expect("convert verify result"));
             if result.valid {
                 ExitCode::SUCCESS
             } else {
@@ -109,12 +607,303 @@ fn validate_cmd(policy_path: &str, allowlist_path: Option<&str>) -> ExitCode {
             }
         }
         Err(err) => {
-            print_error_result(&err);
+            print_json_value(&serde_json::json!({
+                "valid": false,
+                "checks": [],
+                "errors": [err]
+            }));
             ExitCode::from(2)
         }
     }
 }
 
+fn archive_report_cmd(
+    report_path: &str,
+    archive_root: Option<&str>,
+    retention: &str,
+    compression: &str,
+) -> ExitCode {
+    let archive_root = match require_archive_root(archive_root) {
+        Ok(root) => root,
+        Err(err) => return exit_with_error(&err),
+    };
+    if let Err(err) = parse_retention(retention) {
+        return exit_with_error(&err);
+    }
+    let compression = match BundleCompression::parse(compression) {
+        Ok(compression) => compression,
+        Err(err) => return exit_with_error(&err),
+    };
+    let report_path = Path::new(report_path);
+    let report = match read_report(report_path) {
+        Ok(report) => report,
+        Err(err) => return exit_with_error(&err),
+    };
+    let artifacts = match report_bundle_artifacts(report_path) {
+        Ok(artifacts) => artifacts,
+        Err(err) => return exit_with_error(&err),
+    };
+    match archive_into(&report, &archive_root, retention, &artifacts, compression) {
+        Ok(summary) => {
+            print_json_value(&summary);
+            ExitCode::SUCCESS
+        }
+        Err(err) => exit_with_error(&err),
+    }
+}
+
+/// Resolve the archive root from `--archive-root`, falling back to `SAFE_RUN_ARCHIVE_ROOT`.
+fn resolve_archive_root(flag: Option<&str>) -> Option<PathBuf> {
+    flag.map(|value| value.trim().to_string())
+        .or_else(|| std::env::var(ARCHIVE_ROOT_ENV).ok())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+fn require_archive_root(flag: Option<&str>) -> Result<PathBuf, ErrorItem> {
+    resolve_archive_root(flag).ok_or_else(|| {
+        ErrorItem::new(
+            SR_OPS_301,
+            "archive.root",
+            format!("archive root is not set; pass --archive-root or set {ARCHIVE_ROOT_ENV}"),
+        )
+    })
+}
+
+/// Prune, export and reindex operate on the local index and bundle directories; object
+/// stores expire bundles through their object-lock retention instead.
+fn require_local_archive_root(flag: Option<&str>) -> Result<PathBuf, ErrorItem> {
+    let root = require_archive_root(flag)?;
+    if s3_archive_url(&root).is_some() {
+        return Err(ErrorItem::new(
+            SR_OPS_301,
+            "archive.root",
+            "this archive command only supports local archive roots, not s3:// stores",
+        ));
+    }
+    Ok(root)
+}
+
+fn s3_archive_url(root: &Path) -> Option<String> {
+    let raw = root.to_string_lossy();
+    raw.starts_with(S3_ARCHIVE_PREFIX).then(|| raw.to_string())
+}
+
+/// Open an `s3://bucket/prefix` archive; endpoint and credentials come from the environment.
+fn open_s3_store(url: &str) -> Result<S3ArchiveStore, ErrorItem> {
+    let required = |name: &str| {
+        std::env::var(name)
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+            .ok_or_else(|| {
+                ErrorItem::new(
+                    SR_OPS_301,
+                    "archive.store",
+                    format!("{name} must be set to use an s3:// archive root"),
+                )
+            })
+    };
+    let config = S3StoreConfig::from_url(
+        url,
+        &required(S3_ENDPOINT_ENV)?,
+        std::env::var(S3_REGION_ENV).ok().as_deref(),
+        &required(S3_ACCESS_KEY_ENV)?,
+        &required(S3_SECRET_KEY_ENV)?,
+    )?;
+    S3ArchiveStore::new(config)
+}
+
+/// Archive into a local root (bundle directory plus index) or an `s3://` object store.
+fn archive_into(
+    report: &RunReport,
+    archive_root: &Path,
+    retention: &str,
+    artifacts: &[BundleArtifact],
+    compression: BundleCompression,
+) -> Result<serde_json::Value, ErrorItem> {
+    let archived = match s3_archive_url(archive_root) {
+        Some(url) => archive_report_to_store(
+            report,
+            &open_s3_store(&url)?,
+            retention,
+            artifacts,
+            compression,
+        )?,
+        None => {
+            archive_report_with_artifacts(report, archive_root, retention, artifacts, compression)?
+        }
+    };
+    Ok(archive_summary(&archived, archive_root))
+}
+
+fn archive_summary(archived: &RunReport, archive_root: &Path) -> serde_json::Value {
+    let metadata = archived.archive.as_ref();
+    serde_json::json!({
+        "runId": archived.run_id,
+        "archiveRoot": archive_root,
+        "bundleId": metadata.map(|item| item.bundle_id.clone()),
+        "retention": metadata.map(|item| item.retention.clone()),
+        "package": metadata.and_then(|item| item.package.clone()),
+        "digest": archived.integrity.digest
+    })
+}
+
+/// Parse `--since/--until`: `unix:<sec>`, bare seconds, or a retention-style age such as
+/// `7d` meaning "now minus seven days".
+fn parse_time_filter(raw: &str, now_secs: u64) -> Result<u64, ErrorItem> {
+    let trimmed = raw.trim();
+    if let Some(secs) = parse_unix_seconds(trimmed) {
+        return Ok(secs);
+    }
+    if let Ok(secs) = trimmed.parse::<u64>() {
+        return Ok(secs);
+    }
+    match parse_retention(trimmed) {
+        Ok(Some(age)) => Ok(now_secs.saturating_sub(age.as_secs())),
+        _ => Err(ErrorItem::new(
+            SR_OPS_301,
+            "archive.filter",
+            format!("invalid time filter '{raw}', expected unix:<sec>, <sec> or an age like 7d"),
+        )),
+    }
+}
+
+fn unix_now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn parse_redaction_request(
+    remove_events: &[String],
+    redact_fields: &[String],
+) -> Result<RedactionRequest, ErrorItem> {
+    Ok(RedactionRequest {
+        remove_events: remove_events
+            .iter()
+            .map(|raw| EventSelector::parse(raw))
+            .collect::<Result<Vec<_>, _>>()?,
+        redact_fields: redact_fields
+            .iter()
+            .map(|raw| FieldRedaction::parse(raw))
+            .collect::<Result<Vec<_>, _>>()?,
+    })
+}
+
+fn read_report(path: &Path) -> Result<RunReport, ErrorItem> {
+    let raw = fs::read_to_string(path).map_err(|err| {
+        ErrorItem::new(
+            SR_EVD_002,
+            "report.read",
+            format!("failed to read run report '{}': {err}", path.display()),
+        )
+    })?;
+    serde_json::from_str(&raw).map_err(|err| {
+        ErrorItem::new(
+            SR_EVD_002,
+            "report.read",
+            format!("failed to parse run report '{}': {err}", path.display()),
+        )
+    })
+}
+
+fn validate_cmd(policy_path: &str, allowlist_path: Option<&str>, format: &str) -> ExitCode {
+    let text = match format.to_ascii_lowercase().as_str() {
+        "json" => false,
+        "text" => true,
+        other => {
+            return exit_with_error(&ErrorItem::new(
+                SR_POL_002,
+                "validate.format",
+                format!("unsupported validate format '{other}', expected json or text"),
+            ));
+        }
+    };
+    let source = PolicySourceMap::load(policy_path);
+    let mut result = match load_policy_from_path(policy_path) {
+        Ok(policy) => validate_policy_with_allowlist(policy, allowlist_path),
+        Err(err) => ValidationResult {
+            valid: false,
+            errors: vec![err],
+            warnings: Vec::new(),
+            normalized_policy: None,
+            effective_denylist: None,
+            parameters: BTreeMap::new(),
+            admission: None,
+        },
+    };
+    if let Some(source) = source.as_ref() {
+        source.annotate(&mut result.errors);
+    }
+    if text {
+        print!("{}", render_validation_text(&result, source.as_ref()));
+    } else {
+        print_json_value(&serde_json::to_value(&result).expect("convert validation result"));
+    }
+    if result.valid {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(2)
+    }
+}
+
+fn render_validation_text(result: &ValidationResult, source: Option<&PolicySourceMap>) -> String {
+    let mut out = render_diagnostics(&result.errors, source);
+    for warning in &result.warnings {
+        out.push_str(&format!("warning: {warning}\n"));
+    }
+    if result.valid {
+        out.push_str("policy is valid\n");
+    } else {
+        out.push_str(&format!(
+            "policy is invalid: {} error(s)\n",
+            result.errors.len()
+        ));
+    }
+    out
+}
+
+fn lint_cmd(
+    policy_path: &str,
+    allowlist_path: Option<&str>,
+    config_path: Option<&str>,
+) -> ExitCode {
+    let config = match config_path.map(|path| LintConfig::from_file(Path::new(path))) {
+        Some(Ok(config)) => config,
+        Some(Err(err)) => return exit_with_error(&err),
+        None => LintConfig::default(),
+    };
+    let policy = match load_policy_from_path(policy_path) {
+        Ok(policy) => policy,
+        Err(err) => return exit_with_error(&err),
+    };
+    let mut report = match lint_policy(&policy, allowlist_path, &config) {
+        Ok(report) => report,
+        Err(mut errors) => {
+            locate_policy_errors(policy_path, &mut errors);
+            print_json_value(&serde_json::json!({
+                "passed": false,
+                "errors": errors,
+                "findings": []
+            }));
+            return ExitCode::from(2);
+        }
+    };
+    if let Some(source) = PolicySourceMap::load(policy_path) {
+        for finding in &mut report.findings {
+            source.annotate(std::slice::from_mut(&mut finding.item));
+        }
+    }
+    print_json_value(&serde_json::to_value(&report).expect("convert lint report"));
+    if report.passed {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(2)
+    }
+}
+
 fn compile_cmd(dry_run: bool, policy_path: &str, allowlist_path: Option<&str>) -> ExitCode {
     if !dry_run {
         let err = ErrorItem::new(
@@ -134,8 +923,9 @@ fn compile_cmd(dry_run: bool, policy_path: &str, allowlist_path: Option<&str>) -
         }
     };
 
-    let validation = validate_policy_with_allowlist(policy, allowlist_path);
+    let mut validation = validate_policy_with_allowlist(policy, allowlist_path);
     if !validation.valid {
+        locate_policy_errors(policy_path, &mut validation.errors);
         print_json_value(&serde_json::to_value(&validation).expect("convert validation result"));
         return ExitCode::from(2);
     }
@@ -156,9 +946,35 @@ fn compile_cmd(dry_run: bool, policy_path: &str, allowlist_path: Option<&str>) -
     }
 }
 
-fn run_cmd(policy_path: &str, allowlist_path: Option<&str>) -> ExitCode {
-    let normalized = match load_and_validate_policy(policy_path, allowlist_path) {
-        Ok(policy) => policy,
+fn run_cmd(
+    policy_path: &str,
+    allowlist_path: Option<&str>,
+    archive_root: Option<&str>,
+    retention: &str,
+    compression: &str,
+    mount_scan_max_entries: Option<u64>,
+    set: &[String],
+) -> ExitCode {
+    let overrides = match parse_parameter_overrides(set) {
+        Ok(overrides) => overrides,
+        Err(err) => return exit_with_error(&err),
+    };
+    let archive_root = resolve_archive_root(archive_root);
+    let compression = match BundleCompression::parse(compression) {
+        Ok(compression) => compression,
+        Err(err) => return exit_with_error(&err),
+    };
+    if archive_root.is_some() {
+        if let Err(err) = parse_retention(retention) {
+            return exit_with_error(&err);
+        }
+    }
+    let ValidatedPolicy {
+        policy: normalized,
+        parameters,
+        admission_hash,
+    } = match load_and_validate_policy(policy_path, allowlist_path, &overrides) {
+        Ok(validated) => validated,
         Err(code) => return code,
     };
     let compile_bundle = match compile_dry_run(&normalized) {
@@ -166,27 +982,143 @@ fn run_cmd(policy_path: &str, allowlist_path: Option<&str>) -> ExitCode {
         Err(err) => return exit_with_error(&err),
     };
     let run_id = derive_run_id();
-    let (prepared, monitor_result) = match execute_run(&compile_bundle, &run_id) {
-        Ok(result) => result,
-        Err(err) => return exit_with_error(&err),
-    };
+    let (prepared, monitor_result) =
+        match execute_run(&compile_bundle, &run_id, mount_scan_max_entries) {
+            Ok(result) => result,
+            Err(err) => return exit_with_error(&err),
+        };
+    if let Err(err) = persist_run_inputs(&prepared, &normalized) {
+        return exit_with_error(&err);
+    }
     let report_path = prepared.artifacts_dir().join(&prepared.artifacts.report);
-    match build_and_write_report(&prepared, &normalized, &monitor_result, &report_path) {
+    match build_and_write_report(
+        &prepared,
+        &normalized,
+        &parameters,
+        admission_hash.as_deref(),
+        &monitor_result,
+        &report_path,
+    ) {
         Ok(report) => {
+            // Failed runs are archived too; the index records them with result `fail`.
+            let archived = match archive_root.as_deref() {
+                Some(root) => {
+                    let archived = report_bundle_artifacts(&report_path).and_then(|artifacts| {
+                        archive_into(&report, root, retention, &artifacts, compression)
+                    });
+                    match archived {
+                        Ok(summary) => Some(summary),
+                        Err(err) => return exit_with_error(&err),
+                    }
+                }
+                None => None,
+            };
             if let Some(err) = run_outcome_error(prepared.state, &monitor_result, &report_path) {
                 return exit_with_error(&err);
             }
-            print_json_value(&serde_json::json!({
+            let mut output = serde_json::json!({
                 "runId": report.run_id,
                 "state": state_label(prepared.state),
                 "report": report_path
-            }));
+            });
+            if let Some(archived) = archived {
+                output["archive"] = archived;
+            }
+            print_json_value(&output);
             ExitCode::SUCCESS
         }
         Err(err) => exit_with_error(&err),
     }
 }
 
+/// Keep the normalized policy and runtime context next to the report. Cleanup removes the
+/// workdir copy of the runtime context, so archives read these copies instead.
+fn persist_run_inputs(
+    prepared: &sr_runner::PreparedRun,
+    policy: &PolicySpec,
+) -> Result<(), ErrorItem> {
+    let inputs = [
+        (
+            BUNDLE_POLICY_FILE,
+            bundle_json(serde_json::to_vec_pretty(policy))?,
+        ),
+        (
+            BUNDLE_RUNTIME_CONTEXT_FILE,
+            bundle_json(serde_json::to_vec_pretty(&prepared.runtime_context))?,
+        ),
+    ];
+    for (name, content) in inputs {
+        fs::write(prepared.artifacts_dir().join(name), content).map_err(|err| {
+            ErrorItem::new(
+                SR_OPS_301,
+                "archive.artifacts",
+                format!("failed to persist run input '{name}': {err}"),
+            )
+        })?;
+    }
+    Ok(())
+}
+
+/// Raw artifacts packaged with an archived run, picked up from the report's artifacts
+/// directory and the run workdir above it. The event log, normalized policy and runtime
+/// context are required; a report without them is not archived.
+fn report_bundle_artifacts(report_path: &Path) -> Result<Vec<BundleArtifact>, ErrorItem> {
+    let artifacts_dir = report_path.parent().unwrap_or_else(|| Path::new("."));
+    let mut artifacts = Vec::new();
+    for name in [
+        BUNDLE_EVENTS_FILE,
+        BUNDLE_POLICY_FILE,
+        BUNDLE_RUNTIME_CONTEXT_FILE,
+    ] {
+        let path = artifacts_dir.join(name);
+        if !path.is_file() {
+            return Err(ErrorItem::new(
+                SR_OPS_301,
+                "archive.artifacts",
+                format!(
+                    "required run artifact '{}' is missing next to the report",
+                    path.display()
+                ),
+            ));
+        }
+        artifacts.push(BundleArtifact::from_path(name, &path)?);
+    }
+    if let Some(workdir) = artifacts_dir.parent() {
+        let config_path = workdir.join(BUNDLE_FIRECRACKER_CONFIG_FILE);
+        if config_path.is_file() {
+            artifacts.push(BundleArtifact::from_path(
+                BUNDLE_FIRECRACKER_CONFIG_FILE,
+                &config_path,
+            )?);
+        }
+    }
+    push_log_artifacts(&mut artifacts, artifacts_dir)?;
+    Ok(artifacts)
+}
+
+fn push_log_artifacts(
+    artifacts: &mut Vec<BundleArtifact>,
+    artifacts_dir: &Path,
+) -> Result<(), ErrorItem> {
+    for name in BUNDLE_LOG_FILES {
+        let path = artifacts_dir.join(name);
+        if path.is_file() {
+            artifacts.push(BundleArtifact::from_path(format!("logs/{name}"), &path)?);
+        }
+    }
+    Ok(())
+}
+
+fn bundle_json(serialized: serde_json::Result<Vec<u8>>) -> Result<Vec<u8>, ErrorItem> {
+    serialized.map_err(|err| {
+        ErrorItem::new(
+            SR_OPS_301,
+            "archive.artifacts",
+            format!("failed to serialize bundle artifact: {err}"),
+        )
+    })
+}
+
 fn run_outcome_error(
     state: RunState,
     monitor_result: &MonitorResult,
@@ -206,32 +1138,99 @@ fn run_outcome_error(
     None
 }
 
+struct ValidatedPolicy {
+    policy: PolicySpec,
+    parameters: BTreeMap<String, String>,
+    /// Digest of the admission constraints the policy was checked against, if any.
+    admission_hash: Option<String>,
+}
+
+/// `--set` overrides are bound before validation so substituted paths and hosts still go
+/// through mount and network checks.
 fn load_and_validate_policy(
     policy_path: &str,
     allowlist_path: Option<&str>,
-) -> Result<PolicySpec, ExitCode> {
-    let policy = match load_policy_from_path(policy_path) {
+    overrides: &BTreeMap<String, String>,
+) -> Result<ValidatedPolicy, ExitCode> {
+    let mut policy = match load_policy_from_path(policy_path) {
         Ok(policy) => policy,
         Err(err) => {
             print_error_result(&err);
             return Err(ExitCode::from(2));
         }
     };
-    let validation = validate_policy_with_allowlist(policy, allowlist_path);
+    let mut parameters = BTreeMap::new();
+    if !overrides.is_empty() || !policy.parameters.is_empty() {
+        match bind_parameters(&mut policy, overrides) {
+            Ok(bound) => parameters = bound,
+            Err(mut errors) => {
+                locate_policy_errors(policy_path, &mut errors);
+                print_json_value(&serde_json::json!({
+                    "valid": false,
+                    "errors": errors,
+                    "warnings": [],
+                    "normalizedPolicy": null
+                }));
+                return Err(ExitCode::from(2));
+            }
+        }
+    }
+    let mut validation = validate_policy_with_allowlist(policy, allowlist_path);
     if !validation.valid {
+        locate_policy_errors(policy_path, &mut validation.errors);
         print_json_value(&serde_json::to_value(&validation).expect("convert validation result"));
         return Err(ExitCode::from(2));
     }
-    Ok(validation
+    let admission_hash = validation.admission.map(|admission| admission.hash);
+    let normalized = validation
         .normalized_policy
-        .expect("normalized policy exists on valid result"))
+        .expect("normalized policy exists on valid result");
+    Ok(ValidatedPolicy {
+        policy: normalized,
+        parameters,
+        admission_hash,
+    })
+}
+
+fn locate_policy_errors(policy_path: &str, errors: &mut [ErrorItem]) {
+    if let Some(source) = PolicySourceMap::load(policy_path) {
+        source.annotate(errors);
+    }
+}
+
+/// Parse repeated `--set key=value` flags; a key may only be set once.
+fn parse_parameter_overrides(set: &[String]) -> Result<BTreeMap<String, String>, ErrorItem> {
+    let mut overrides = BTreeMap::new();
+    for entry in set {
+        let Some((key, value)) = entry.split_once('=') else {
+            return Err(ErrorItem::new(
+                SR_POL_002,
+                "run.set",
+                format!("--set expects key=value, got '{entry}'"),
+            ));
+        };
+        let key = key.trim();
+        if overrides
+            .insert(key.to_string(), value.to_string())
+            .is_some()
+        {
+            return Err(ErrorItem::new(
+                SR_POL_002,
+                "run.set",
+                format!("parameter '{key}' is set more than once"),
+            ));
+        }
+    }
+    Ok(overrides)
 }
 
 fn execute_run(
     compile_bundle: &CompileBundle,
     run_id: &str,
+    mount_scan_max_entries: Option<u64>,
 ) -> Result<(sr_runner::PreparedRun, MonitorResult), ErrorItem> {
-    let runtime_context = default_runtime_context(run_id);
+    let mut runtime_context = default_runtime_context(run_id);
+    runtime_context.mount_scan_max_entries = mount_scan_max_entries;
     let request = RunnerControlRequest {
         compile_bundle: compile_bundle.clone(),
         runtime_context,
@@ -249,9 +1248,7 @@ fn execute_run(
             return Err(err);
         }
     };
-    if let Err(err) = runner.cleanup(&mut prepared) {
-        return Err(err);
-    }
+    runner.cleanup(&mut prepared)?;
     Ok((prepared, monitor_result))
 }
 
@@ -268,6 +1265,7 @@ fn default_runtime_context(run_id: &str) -> RuntimeContext {
         timeout_sec: 300,
         sample_interval_ms: None,
         cgroup_path: detect_default_cgroup_path(),
+        mount_scan_max_entries: None,
     }
 }
 
@@ -290,11 +1288,15 @@ fn derive_run_id() -> String {
 fn build_and_write_report(
     prepared: &sr_runner::PreparedRun,
     policy: &PolicySpec,
+    parameters: &BTreeMap<String, String>,
+    admission_hash: Option<&str>,
     monitor_result: &MonitorResult,
     report_path: &Path,
 ) -> Result<RunReport, ErrorItem> {
     let events = load_events(prepared.event_log_path().as_path())?;
     let mut report = build_report_from_events(prepared, policy, monitor_result, &events)?;
+    report.policy_summary.parameters = parameters.clone();
+    report.policy_summary.admission_constraints_hash = admission_hash.map(str::to_string);
     let digest = compute_integrity_digest(&report)?;
     report.integrity.digest = digest;
     write_report(report_path, &report)?;
@@ -313,12 +1315,14 @@ fn build_report_from_events(
     let policy_summary = PolicySummary {
         network: network_mode.clone(),
         mounts: policy.mounts.len(),
+        parameters: BTreeMap::new(),
+        admission_constraints_hash: None,
     };
     let mount_audit = mount_audit_from_events(events);
     let network_audit =
         network_audit_from_events(events, &network_mode, policy.network.egress.len());
     let artifacts = compute_report_artifacts(prepared, policy)?;
-    Ok(build_report(
+    build_report(
         prepared.run_id.clone(),
         started_at,
         finished_at,
@@ -330,7 +1334,7 @@ fn build_report_from_events(
         mount_audit,
         network_audit,
         String::new(),
-    ))
+    )
 }
 
 fn compute_report_artifacts(
@@ -344,10 +1348,13 @@ fn compute_report_artifacts(
             format!("failed to serialize policy for hashing: {err}"),
         )
     })?;
-    let command_json = serde_json::json!({
-        "command": policy.runtime.command,
-        "args": policy.runtime.args
-    });
+    let command_json = serde_json::to_value(&prepared.command_payload).map_err(|err| {
+        ErrorItem::new(
+            SR_EVD_002,
+            "report.command",
+            format!("failed to serialize command payload for hashing: {err}"),
+        )
+    })?;
     let firecracker_config = load_firecracker_config(prepared.firecracker_config_path())?;
     let (kernel_path, rootfs_path) =
         resolve_artifact_paths(prepared.workdir(), &firecracker_config)?;
@@ -432,7 +1439,8 @@ mod allowlist_tests {
     use std::path::PathBuf;
     use std::sync::Mutex;
 
-    static ENV_LOCK: Mutex<()> = Mutex::new(());
+    /// Serializes every test in this binary that sets process environment variables.
+    pub(super) static ENV_LOCK: Mutex<()> = Mutex::new(());
 
     fn repo_file(path: &str) -> String {
         let mut p = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -595,6 +1603,7 @@ mod tests {
         Audit, Cpu, Memory, Metadata, Network, NetworkEgressRule, NetworkMode, Resources, Runtime,
     };
     use sr_runner::RunnerRuntime;
+    use std::collections::BTreeMap;
     use std::fs::File;
     use std::io::Write;
 
@@ -606,7 +1615,15 @@ mod tests {
 
     #[test]
     fn run_rejects_missing_policy_file() {
-        let code = run_cmd("/tmp/safe-run-cli-missing.yaml", None);
+        let code = run_cmd(
+            "/tmp/safe-run-cli-missing.yaml",
+            None,
+            None,
+            DEFAULT_ARCHIVE_RETENTION,
+            "none",
+            None,
+            &[],
+        );
         assert_eq!(code, ExitCode::from(2));
     }
 
@@ -620,7 +1637,15 @@ mod tests {
         )
         .expect("write policy");
 
-        let code = run_cmd(path.to_string_lossy().as_ref(), None);
+        let code = run_cmd(
+            path.to_string_lossy().as_ref(),
+            None,
+            None,
+            DEFAULT_ARCHIVE_RETENTION,
+            "none",
+            None,
+            &[],
+        );
         assert_eq!(code, ExitCode::from(2));
 
         let _ = fs::remove_file(&path);
@@ -641,6 +1666,7 @@ mod tests {
                 timeout_sec: 1,
                 sample_interval_ms: None,
                 cgroup_path: None,
+                mount_scan_max_entries: None,
             },
         };
         let runner = Runner::with_runtime(RunnerRuntime {
@@ -659,7 +1685,15 @@ mod tests {
             timed_out: false,
             sample_count: 0,
         };
-        let result = build_and_write_report(&prepared, &policy, &monitor_result, &report_path);
+        let parameters = BTreeMap::from([("mode".to_string(), "fast".to_string())]);
+        let result = build_and_write_report(
+            &prepared,
+            &policy,
+            &parameters,
+            None,
+            &monitor_result,
+            &report_path,
+        );
         assert!(prepared.firecracker_config_path().exists());
         assert!(prepared.event_log_path().exists());
         let report = result.unwrap_or_else(|err| {
@@ -677,10 +1711,78 @@ mod tests {
                 .expect("parse report json");
         assert_eq!(report_json["networkAudit"]["mode"], "none");
         assert_eq!(report_json["networkAudit"]["rulesTotal"], 0);
+        assert_eq!(report_json["policySummary"]["parameters"]["mode"], "fast");
 
         let _ = fs::remove_dir_all(&workdir);
     }
 
+    #[test]
+    fn parameter_overrides_require_unique_key_value_pairs() {
+        let parsed = parse_parameter_overrides(&["mode=fast".to_string(), "n=a=b".to_string()])
+            .expect("parse overrides");
+        assert_eq!(parsed.get("n").map(String::as_str), Some("a=b"));
+
+        for invalid in [
+            vec!["mode".to_string()],
+            vec!["mode=fast".to_string(), "mode=slow".to_string()],
+        ] {
+            let err = parse_parameter_overrides(&invalid).expect_err("invalid --set");
+            assert_eq!(err.code, SR_POL_002);
+            assert_eq!(err.path, "run.set");
+        }
+    }
+
+    #[test]
+    fn run_rejects_undeclared_parameter_override() {
+        let path = temp_policy_path("undeclared-parameter");
+        fs::write(
+            &path,
+            "apiVersion: policy.safe-run.dev/v1alpha1\nmetadata:\n  name: demo\nruntime:\n  command: /bin/echo\n  args: [ok]\nresources:\n  cpu:\n    max: '100000 100000'\n  memory:\n    max: 256Mi\nnetwork:\n  mode: none\nmounts: []\naudit:\n  level: basic\n",
+        )
+        .expect("write policy");
+
+        let code = run_cmd(
+            path.to_string_lossy().as_ref(),
+            None,
+            None,
+            DEFAULT_ARCHIVE_RETENTION,
+            "none",
+            None,
+            &["mode=fast".to_string()],
+        );
+        assert_eq!(code, ExitCode::from(2));
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn policy_migrate_check_leaves_file_and_rewrite_upgrades_it() {
+        let path = temp_policy_path("migrate-policy");
+        let legacy = "# keep me\napiVersion: policy.safe-run.dev/v1alpha1\nmetadata:\n  name: demo\nruntime:\n  command: /bin/echo\n  args: [ok]\nresources:\n  cpu:\n    max: '100000 100000'\n  memory:\n    max: 256Mi\nnetwork:\n  mode: none\nmounts:\n  - guestPath: /data/out\n    mode: scratch\naudit:\n  level: basic\n";
+        fs::write(&path, legacy).expect("write policy");
+        let policy_path = path.to_string_lossy().to_string();
+
+        assert_eq!(
+            migrate_policy_cmd(&policy_path, true, None),
+            ExitCode::from(2)
+        );
+        assert_eq!(fs::read_to_string(&path).expect("read policy"), legacy);
+
+        assert_eq!(
+            migrate_policy_cmd(&policy_path, false, None),
+            ExitCode::SUCCESS
+        );
+        let migrated = fs::read_to_string(&path).expect("read migrated policy");
+        assert!(migrated.starts_with("# keep me\napiVersion: policy.safe-run.dev/v1beta1\n"));
+        assert!(migrated.contains("  - target: /data/out\n"));
+        assert_eq!(
+            migrate_policy_cmd(&policy_path, true, None),
+            ExitCode::SUCCESS
+        );
+
+        let _ = fs::remove_file(&path);
+    }
+
     #[test]
     fn report_build_allowlist_uses_policy_defaults_for_network_audit() {
         let run_id = "sr-test-report-allowlist";
@@ -703,6 +1805,7 @@ mod tests {
                 timeout_sec: 1,
                 sample_interval_ms: None,
                 cgroup_path: None,
+                mount_scan_max_entries: None,
             },
         };
         let runner = Runner::with_runtime(RunnerRuntime {
@@ -721,8 +1824,15 @@ mod tests {
             timed_out: false,
             sample_count: 0,
         };
-        let report = build_and_write_report(&prepared, &policy, &monitor_result, &report_path)
-            .expect("report build should succeed");
+        let report = build_and_write_report(
+            &prepared,
+            &policy,
+            &BTreeMap::new(),
+            None,
+            &monitor_result,
+            &report_path,
+        )
+        .expect("report build should succeed");
         let recomputed = compute_integrity_digest(&report).expect("recompute digest");
         assert_eq!(report.integrity.digest, recomputed);
         assert_eq!(report.network_audit.mode, "allowlist");
@@ -761,6 +1871,7 @@ mod tests {
                 timeout_sec: 1,
                 sample_interval_ms: None,
                 cgroup_path: None,
+                mount_scan_max_entries: None,
             },
         };
         let runner = Runner::with_runtime(RunnerRuntime {
@@ -808,8 +1919,15 @@ mod tests {
             timed_out: false,
             sample_count: 0,
         };
-        let report = build_and_write_report(&prepared, &policy, &monitor_result, &report_path)
-            .expect("report build should succeed");
+        let report = build_and_write_report(
+            &prepared,
+            &policy,
+            &BTreeMap::new(),
+            None,
+            &monitor_result,
+            &report_path,
+        )
+        .expect("report build should succeed");
         assert_eq!(report.network_audit.mode, "allowlist");
         assert_eq!(report.network_audit.rules_total, 1);
         assert_eq!(report.network_audit.allowed_hits, 5);
@@ -858,7 +1976,7 @@ mod tests {
         let content = serde_json::to_string_pretty(&report).expect("serialize report");
         fs::write(&report_path, content).expect("write report");
 
-        let code = verify_report_cmd(report_path.to_string_lossy().as_ref());
+        let code = verify_report_cmd(report_path.to_string_lossy().as_ref(), None);
         assert_eq!(code, ExitCode::SUCCESS);
 
         let _ = fs::remove_file(&report_path);
@@ -873,21 +1991,134 @@ mod tests {
         let content = serde_json::to_string_pretty(&report).expect("serialize report");
         fs::write(&report_path, content).expect("write report");
 
-        let code = verify_report_cmd(report_path.to_string_lossy().as_ref());
+        let code = verify_report_cmd(report_path.to_string_lossy().as_ref(), None);
         assert_eq!(code, ExitCode::from(2));
 
         let _ = fs::remove_file(&report_path);
     }
 
+    #[test]
+    fn report_archive_cmd_stores_verified_report_with_index_entry() {
+        let report = valid_report_for_verify();
+        let workdir = temp_run_dir("archive-ok");
+        let report_path = write_report_with_run_inputs(&workdir, &report);
+        let archive_root = temp_run_dir("archive-root-ok");
+
+        let code = archive_report_cmd(
+            report_path.to_string_lossy().as_ref(),
+            Some(archive_root.to_string_lossy().as_ref()),
+            "7d",
+            "none",
+        );
+        assert_eq!(code, ExitCode::SUCCESS);
+        let entries = sr_evidence::load_archive_index(&archive_root)
+            .expect("load index")
+            .entries;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].run_id, report.run_id);
+        assert_eq!(entries[0].retention, "7d");
+        let archived = sr_evidence::load_archived_report(&archive_root, &entries[0].bundle_id)
+            .expect("load archived report");
+        assert!(archived
+            .archive
+            .and_then(|archive| archive.package)
+            .is_some());
+
+        let _ = fs::remove_dir_all(&workdir);
+        let _ = fs::remove_dir_all(&archive_root);
+    }
+
+    #[test]
+    fn report_archive_cmd_requires_persisted_run_inputs() {
+        let report = valid_report_for_verify();
+        let workdir = temp_run_dir("archive-missing-inputs");
+        let report_path = write_report_with_run_inputs(&workdir, &report);
+        fs::remove_file(report_path.with_file_name("runtime-context.json"))
+            .expect("remove runtime context");
+        let archive_root = temp_run_dir("archive-root-missing-inputs");
+
+        let err = report_bundle_artifacts(&report_path).expect_err("runtime context is required");
+        assert_eq!(err.code, SR_OPS_301);
+        assert!(err.message.contains("runtime-context.json"));
+        let code = archive_report_cmd(
+            report_path.to_string_lossy().as_ref(),
+            Some(archive_root.to_string_lossy().as_ref()),
+            "7d",
+            "none",
+        );
+        assert_eq!(code, ExitCode::from(2));
+        assert!(sr_evidence::load_archive_index(&archive_root)
+            .expect("load index")
+            .entries
+            .is_empty());
+
+        let _ = fs::remove_dir_all(&workdir);
+        let _ = fs::remove_dir_all(&archive_root);
+    }
+
+    #[test]
+    fn report_archive_cmd_rejects_tampered_report_and_bad_retention() {
+        let mut report = valid_report_for_verify();
+        report.exit_code = 9;
+        let report_path = temp_report_path("archive-tampered");
+        let archive_root = temp_run_dir("archive-root-tampered");
+        let content = serde_json::to_string_pretty(&report).expect("serialize report");
+        fs::write(&report_path, content).expect("write report");
+
+        let code = archive_report_cmd(
+            report_path.to_string_lossy().as_ref(),
+            Some(archive_root.to_string_lossy().as_ref()),
+            "30d",
+            "none",
+        );
+        assert_eq!(code, ExitCode::from(2));
+        assert!(sr_evidence::load_archive_index(&archive_root)
+            .expect("load index")
+            .entries
+            .is_empty());
+
+        let code = archive_report_cmd(
+            report_path.to_string_lossy().as_ref(),
+            Some(archive_root.to_string_lossy().as_ref()),
+            "thirty days",
+            "none",
+        );
+        assert_eq!(code, ExitCode::from(2));
+
+        let _ = fs::remove_file(&report_path);
+        let _ = fs::remove_dir_all(&archive_root);
+    }
+
+    #[test]
+    fn archive_root_flag_takes_precedence_over_environment() {
+        let _guard = super::allowlist_tests::ENV_LOCK.lock().expect("lock env");
+        std::env::set_var(ARCHIVE_ROOT_ENV, "/var/lib/safe-run/env-archive");
+        let from_flag = resolve_archive_root(Some(" /srv/safe-run/archive "));
+        let from_env = resolve_archive_root(None);
+        std::env::remove_var(ARCHIVE_ROOT_ENV);
+
+        assert_eq!(from_flag, Some(PathBuf::from("/srv/safe-run/archive")));
+        assert_eq!(
+            from_env,
+            Some(PathBuf::from("/var/lib/safe-run/env-archive"))
+        );
+    }
+
     fn sample_policy() -> PolicySpec {
         PolicySpec {
             api_version: "policy.safe-run.dev/v1alpha1".to_string(),
             metadata: Metadata {
                 name: "cli-report-test".to_string(),
             },
+            parameters: BTreeMap::new(),
             runtime: Runtime {
                 command: "/bin/echo".to_string(),
                 args: vec!["ok".to_string()],
+                env: BTreeMap::new(),
+                env_passthrough: Vec::new(),
+                cwd: None,
+                user: None,
+                command_sha256: None,
             },
             resources: Resources {
                 cpu: Cpu {
@@ -902,6 +2133,7 @@ mod tests {
                 egress: vec![],
             },
             mounts: vec![],
+            secrets: Vec::new(),
             audit: Audit {
                 level: "basic".to_string(),
             },
@@ -938,6 +2170,32 @@ mod tests {
         path
     }
 
+    /// Lay out a report the way `run` leaves it: artifacts dir with events and run inputs.
+    fn write_report_with_run_inputs(workdir: &Path, report: &RunReport) -> PathBuf {
+        let artifacts_dir = workdir.join("artifacts");
+        fs::create_dir_all(&artifacts_dir).expect("create artifacts dir");
+        let events = report
+            .events
+            .iter()
+            .map(|event| serde_json::to_string(event).expect("serialize event") + "\n")
+            .collect::<String>();
+        fs::write(artifacts_dir.join("events.jsonl"), events).expect("write events");
+        fs::write(
+            artifacts_dir.join("policy.normalized.json"),
+            serde_json::to_vec_pretty(&sample_policy()).expect("serialize policy"),
+        )
+        .expect("write policy");
+        fs::write(artifacts_dir.join("runtime-context.json"), b"{}")
+            .expect("write runtime context");
+        let report_path = artifacts_dir.join("run_report.json");
+        fs::write(
+            &report_path,
+            serde_json::to_string_pretty(report).expect("serialize report"),
+        )
+        .expect("write report");
+        report_path
+    }
+
     fn temp_report_path(label: &str) -> PathBuf {
         let mut path = std::env::temp_dir();
         let id = SystemTime::now()
@@ -955,9 +2213,8 @@ mod tests {
             stage: STAGE_PREPARE.to_string(),
             event_type: "run.prepared".to_string(),
             payload: serde_json::json!({"workdir": "/tmp/safe-run/test"}),
-            hash_prev:
-                "sha256:0000000000000000000000000000000000000000000000000000000000000000"
-                    .to_string(),
+            hash_prev: "sha256:0000000000000000000000000000000000000000000000000000000000000000"
+                .to_string(),
             hash_self: String::new(),
         };
         event.hash_self = derive_event_hash(&event);
@@ -985,21 +2242,57 @@ mod tests {
             policy_summary: PolicySummary {
                 network: "none".to_string(),
                 mounts: 0,
+                parameters: BTreeMap::new(),
+                admission_constraints_hash: None,
             },
             resource_usage: ResourceUsage {
                 cpu: "cpuUsageUsec=0".to_string(),
                 memory: "memoryCurrentBytes=0".to_string(),
+                ..ResourceUsage::default()
             },
             events: vec![event],
             mount_audit: MountAudit::default(),
             network_audit: NetworkAudit::default(),
             archive: None,
             verification: None,
+            redaction: None,
             integrity: Integrity {
                 digest: String::new(),
+                merkle_root: None,
+                field_salt_seed: None,
             },
         };
         report.integrity.digest = compute_integrity_digest(&report).expect("compute digest");
         report
     }
+
+    #[test]
+    fn archive_time_filter_accepts_unix_seconds_and_relative_age() {
+        let now = 1_000_000;
+        assert_eq!(
+            parse_time_filter("unix:123.000000001", now).expect("unix"),
+            123
+        );
+        assert_eq!(parse_time_filter("456", now).expect("seconds"), 456);
+        assert_eq!(parse_time_filter("1d", now).expect("age"), now - 86_400);
+        let err = parse_time_filter("yesterday", now).expect_err("must fail");
+        assert_eq!(err.code, SR_OPS_301);
+    }
+
+    #[test]
+    fn s3_archive_roots_are_detected_and_rejected_for_local_only_commands() {
+        assert_eq!(
+            s3_archive_url(Path::new("s3://evidence/runs")).as_deref(),
+            Some("s3://evidence/runs")
+        );
+        assert_eq!(s3_archive_url(Path::new("/var/lib/safe-run/archive")), None);
+
+        let err = require_local_archive_root(Some("s3://evidence/runs")).expect_err("must fail");
+        assert_eq!(err.code, SR_OPS_301);
+        assert_eq!(err.path, "archive.root");
+        assert_eq!(
+            archive_prune_cmd(Some("s3://evidence/runs"), true),
+            ExitCode::from(2)
+        );
+    }
 }
@@ -6,6 +6,20 @@ pub struct ErrorItem {
     pub code: String,
     pub path: String,
     pub message: String,
+    /// Source document the error points into, when known. Boxed, like `anchor`, to keep
+    /// `Result<_, ErrorItem>` small.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<Box<str>>,
+    /// 1-based line in `file`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+    /// 1-based column in `file`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<u32>,
+    /// Policy field to locate the error at when `path` names a field of another document,
+    /// such as a mount allowlist rule. Used for annotation only and never serialized.
+    #[serde(skip)]
+    pub anchor: Option<Box<str>>,
 }
 
 impl ErrorItem {
@@ -18,8 +32,29 @@ impl ErrorItem {
             code: code.into(),
             path: path.into(),
             message: message.into(),
+            file: None,
+            line: None,
+            column: None,
+            anchor: None,
         }
     }
+
+    pub fn with_anchor(mut self, anchor: impl Into<String>) -> Self {
+        self.anchor = Some(anchor.into().into_boxed_str());
+        self
+    }
+
+    pub fn with_file(mut self, file: impl Into<String>) -> Self {
+        self.file = Some(file.into().into_boxed_str());
+        self
+    }
+
+    /// Attach a 1-based location; values beyond `u32::MAX` saturate.
+    pub fn with_location(mut self, line: usize, column: usize) -> Self {
+        self.line = Some(u32::try_from(line).unwrap_or(u32::MAX));
+        self.column = Some(u32::try_from(column).unwrap_or(u32::MAX));
+        self
+    }
 }
 
 #[derive(Debug, Error)]
@@ -35,6 +70,17 @@ pub const SR_POL_101: &str = "SR-POL-101";
 pub const SR_POL_102: &str = "SR-POL-102";
 pub const SR_POL_103: &str = "SR-POL-103";
 pub const SR_POL_201: &str = "SR-POL-201";
+pub const SR_ADM_001: &str = "SR-ADM-001";
+pub const SR_ADM_101: &str = "SR-ADM-101";
+pub const SR_ADM_102: &str = "SR-ADM-102";
+pub const SR_ADM_201: &str = "SR-ADM-201";
+pub const SR_ADM_301: &str = "SR-ADM-301";
+pub const SR_ADM_302: &str = "SR-ADM-302";
+pub const SR_ADM_401: &str = "SR-ADM-401";
+pub const SR_LNT_001: &str = "SR-LNT-001";
+pub const SR_LNT_002: &str = "SR-LNT-002";
+pub const SR_LNT_003: &str = "SR-LNT-003";
+pub const SR_LNT_004: &str = "SR-LNT-004";
 pub const SR_CMP_001: &str = "SR-CMP-001";
 pub const SR_CMP_002: &str = "SR-CMP-002";
 pub const SR_CMP_201: &str = "SR-CMP-201";
@@ -44,9 +90,13 @@ pub const SR_RUN_003: &str = "SR-RUN-003";
 pub const SR_RUN_101: &str = "SR-RUN-101";
 pub const SR_RUN_201: &str = "SR-RUN-201";
 pub const SR_RUN_202: &str = "SR-RUN-202";
+pub const SR_RUN_301: &str = "SR-RUN-301";
+pub const SR_RUN_401: &str = "SR-RUN-401";
 pub const SR_EVD_001: &str = "SR-EVD-001";
 pub const SR_EVD_002: &str = "SR-EVD-002";
 pub const SR_EVD_301: &str = "SR-EVD-301";
 pub const SR_EVD_302: &str = "SR-EVD-302";
 pub const SR_EVD_303: &str = "SR-EVD-303";
+pub const SR_EVD_304: &str = "SR-EVD-304";
+pub const SR_EVD_305: &str = "SR-EVD-305";
 pub const SR_OPS_301: &str = "SR-OPS-301";
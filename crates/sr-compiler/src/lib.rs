@@ -2,17 +2,23 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use sr_common::{ErrorItem, SR_CMP_001, SR_CMP_002, SR_CMP_201};
 use sr_evidence::{
+    EVENT_COMMAND_VERIFIED, EVENT_MOUNT_OUTPUT_COLLECTED, EVENT_MOUNT_SCAN,
     EVENT_NETWORK_PLAN_GENERATED, EVENT_NETWORK_RULE_APPLIED, EVENT_NETWORK_RULE_CLEANUP_FAILED,
-    EVENT_NETWORK_RULE_HIT, EVENT_NETWORK_RULE_RELEASED, REQUIRED_EVIDENCE_EVENTS,
+    EVENT_NETWORK_RULE_HIT, EVENT_NETWORK_RULE_RELEASED, EVENT_SECRETS_INJECTED,
+    REQUIRED_EVIDENCE_EVENTS,
 };
-use sr_policy::{NetworkMode, PolicySpec};
+use sr_policy::{MountMode, NetworkMode, PolicySpec, Runtime};
+use std::collections::BTreeMap;
 
 mod mount_plan;
 mod network_plan;
+mod secret_plan;
 use mount_plan::MountPlanBuilder;
-pub use mount_plan::{MountPlan, MountPlanEntry};
+pub use mount_plan::{MountDrive, MountPlan, MountPlanEntry};
 use network_plan::NetworkPlanBuilder;
 pub use network_plan::{NetworkPlan, NftPlan, NftRule, TapPlan};
+pub use secret_plan::SecretPlan;
+use secret_plan::SecretPlanBuilder;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompileBundle {
@@ -28,6 +34,64 @@ pub struct CompileBundle {
     pub network_plan: Option<NetworkPlan>,
     #[serde(rename = "evidencePlan")]
     pub evidence_plan: EvidencePlan,
+    #[serde(rename = "commandPlan")]
+    pub command_plan: CommandPlan,
+    #[serde(
+        rename = "secretPlan",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub secret_plan: Option<SecretPlan>,
+}
+
+/// Guest command and its execution context. Host passthrough names are resolved by the
+/// runner at prepare time, so the plan itself stays host-independent.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CommandPlan {
+    pub command: String,
+    pub args: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    #[serde(
+        rename = "envPassthrough",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub env_passthrough: Vec<String>,
+    pub cwd: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<CommandUser>,
+    /// Pinned digest of `command` in the rootfs; the runner verifies it before launch.
+    #[serde(
+        rename = "commandSha256",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub command_sha256: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CommandUser {
+    pub uid: u32,
+    pub gid: u32,
+}
+
+impl CommandPlan {
+    /// Defaults: cwd `/`, gid equal to uid.
+    pub fn from_runtime(runtime: &Runtime) -> Self {
+        Self {
+            command: runtime.command.clone(),
+            args: runtime.args.clone(),
+            env: runtime.env.clone(),
+            env_passthrough: runtime.env_passthrough.clone(),
+            cwd: runtime.cwd.clone().unwrap_or_else(|| "/".to_string()),
+            user: runtime.user.map(|user| CommandUser {
+                uid: user.uid,
+                gid: user.gid.unwrap_or(user.uid),
+            }),
+            command_sha256: runtime.command_sha256.clone(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -68,6 +132,54 @@ pub fn compile_dry_run(policy: &PolicySpec) -> Result<CompileBundle, ErrorItem>
         )
     })?;
 
+    let mount_plan = MountPlanBuilder::build(&policy.mounts).ok_or_else(|| {
+        cmp_template_error(
+            "mounts.max_size",
+            "compile template cannot map mount max_size to MiB",
+        )
+    })?;
+    let mut boot_args = "console=ttyS0 reboot=k panic=1 pci=off".to_string();
+    for drive in &mount_plan.drives {
+        if !sr_policy::is_plain_mount_target(&drive.target) {
+            return Err(cmp_error(
+                "mounts.target",
+                format!(
+                    "mount target for drive '{}' cannot be passed on the kernel command line",
+                    drive.drive_id
+                ),
+            ));
+        }
+        boot_args.push_str(&format!(
+            " safe_run.drive={}:{}",
+            drive.drive_id, drive.target
+        ));
+    }
+    let mut drives = mount_plan
+        .drives
+        .iter()
+        .map(|drive| {
+            json!({
+                "drive_id": drive.drive_id,
+                "path_on_host": drive.image,
+                "is_root_device": false,
+                "is_read_only": false
+            })
+        })
+        .collect::<Vec<_>>();
+    let secret_plan = SecretPlanBuilder::build(&policy.secrets);
+    if let Some(plan) = secret_plan.as_ref() {
+        boot_args.push_str(&format!(
+            " safe_run.secrets={}:{}",
+            plan.drive_id, plan.target
+        ));
+        drives.push(json!({
+            "drive_id": plan.drive_id,
+            "path_on_host": plan.image,
+            "is_root_device": false,
+            "is_read_only": true
+        }));
+    }
+
     let firecracker_config = json!({
         "machine-config": {
             "vcpu_count": 1,
@@ -76,17 +188,24 @@ pub fn compile_dry_run(policy: &PolicySpec) -> Result<CompileBundle, ErrorItem>
         },
         "boot-source": {
             "kernel_image_path": "artifacts/vmlinux",
-            "boot_args": "console=ttyS0 reboot=k panic=1 pci=off"
+            "boot_args": boot_args
         },
-        "drives": [],
+        "drives": drives,
         "rootfs": {
             "path": "artifacts/rootfs.ext4",
             "readOnly": true
         }
     });
 
-    let mount_plan = MountPlanBuilder::build(&policy.mounts);
     let network_plan = NetworkPlanBuilder::build(&policy.network)?;
+    let mut evidence_events = required_evidence_events(&policy.network.mode);
+    evidence_events.extend(mount_evidence_events(&mount_plan));
+    if secret_plan.is_some() {
+        evidence_events.push(EVENT_SECRETS_INJECTED.to_string());
+    }
+    if policy.runtime.command_sha256.is_some() {
+        evidence_events.push(EVENT_COMMAND_VERIFIED.to_string());
+    }
 
     let bundle = CompileBundle {
         firecracker_config,
@@ -105,24 +224,18 @@ pub fn compile_dry_run(policy: &PolicySpec) -> Result<CompileBundle, ErrorItem>
         network_plan,
         evidence_plan: EvidencePlan {
             enabled: true,
-            events: required_evidence_events(&policy.network.mode),
+            events: evidence_events,
         },
+        command_plan: CommandPlan::from_runtime(&policy.runtime),
+        secret_plan,
     };
     ensure_bundle_complete(&bundle, &policy.network.mode)?;
     Ok(bundle)
 }
 
+/// Whole MiB for a policy size; sizes below 1 MiB have no mapping rather than becoming 0.
 fn memory_to_mib(memory: &str) -> Option<u64> {
-    if let Some(raw) = memory.strip_suffix("Mi") {
-        return raw.parse::<u64>().ok();
-    }
-    if let Some(raw) = memory.strip_suffix("Gi") {
-        return raw.parse::<u64>().ok().map(|n| n * 1024);
-    }
-    if let Some(raw) = memory.strip_suffix("Ki") {
-        return raw.parse::<u64>().ok().map(|n| n / 1024);
-    }
-    None
+    sr_policy::memory_mib(memory).filter(|mib| *mib > 0)
 }
 
 fn ensure_bundle_complete(
@@ -210,7 +323,8 @@ fn ensure_bundle_complete(
         ));
     }
 
-    let required = required_evidence_events(network_mode);
+    let mut required = required_evidence_events(network_mode);
+    required.extend(mount_evidence_events(&bundle.mount_plan));
     for event in required {
         if !bundle
             .evidence_plan
@@ -243,6 +357,23 @@ fn required_evidence_events(network_mode: &NetworkMode) -> Vec<String> {
     events
 }
 
+/// Bind mounts are scanned before attach; `output` drives are collected after the VM exits.
+/// Scratch drives leave no evidence.
+fn mount_evidence_events(mount_plan: &MountPlan) -> Vec<String> {
+    let mut events = Vec::new();
+    if !mount_plan.mounts.is_empty() {
+        events.push(EVENT_MOUNT_SCAN.to_string());
+    }
+    if mount_plan
+        .drives
+        .iter()
+        .any(|drive| drive.mode == MountMode::Output)
+    {
+        events.push(EVENT_MOUNT_OUTPUT_COLLECTED.to_string());
+    }
+    events
+}
+
 fn cmp_template_error(path: impl Into<String>, message: impl Into<String>) -> ErrorItem {
     ErrorItem::new(SR_CMP_001, path, message)
 }
@@ -259,6 +390,19 @@ fn cmp_network_error(path: impl Into<String>, message: impl Into<String>) -> Err
 mod tests {
     use super::*;
     use sr_policy::{Audit, Cpu, Memory, Metadata, Network, NetworkMode, Resources, Runtime};
+    use std::collections::BTreeMap;
+
+    fn sample_command_plan() -> CommandPlan {
+        CommandPlan {
+            command: "/bin/echo".to_string(),
+            args: vec!["ok".to_string()],
+            env: BTreeMap::new(),
+            env_passthrough: Vec::new(),
+            cwd: "/".to_string(),
+            user: None,
+            command_sha256: None,
+        }
+    }
 
     #[test]
     fn compile_bundle_network_is_none() {
@@ -267,9 +411,15 @@ mod tests {
             metadata: Metadata {
                 name: "demo".to_string(),
             },
+            parameters: BTreeMap::new(),
             runtime: Runtime {
                 command: "/bin/echo".to_string(),
                 args: vec!["hello".to_string()],
+                env: BTreeMap::new(),
+                env_passthrough: Vec::new(),
+                cwd: None,
+                user: None,
+                command_sha256: None,
             },
             resources: Resources {
                 cpu: Cpu {
@@ -284,6 +434,7 @@ mod tests {
                 egress: vec![],
             },
             mounts: vec![],
+            secrets: Vec::new(),
             audit: Audit {
                 level: "basic".to_string(),
             },
@@ -300,9 +451,15 @@ mod tests {
             metadata: Metadata {
                 name: "demo".to_string(),
             },
+            parameters: BTreeMap::new(),
             runtime: Runtime {
                 command: "/bin/echo".to_string(),
                 args: vec!["hello".to_string()],
+                env: BTreeMap::new(),
+                env_passthrough: Vec::new(),
+                cwd: None,
+                user: None,
+                command_sha256: None,
             },
             resources: Resources {
                 cpu: Cpu {
@@ -322,6 +479,7 @@ mod tests {
                 }],
             },
             mounts: vec![],
+            secrets: Vec::new(),
             audit: Audit {
                 level: "basic".to_string(),
             },
@@ -343,22 +501,28 @@ mod tests {
     }
 
     #[test]
-    fn compile_invalid_memory_format_returns_cmp_001() {
+    fn compile_memory_below_one_mib_returns_cmp_001() {
         let policy = PolicySpec {
             api_version: "policy.safe-run.dev/v1alpha1".to_string(),
             metadata: Metadata {
                 name: "demo".to_string(),
             },
+            parameters: BTreeMap::new(),
             runtime: Runtime {
                 command: "/bin/echo".to_string(),
                 args: vec!["hello".to_string()],
+                env: BTreeMap::new(),
+                env_passthrough: Vec::new(),
+                cwd: None,
+                user: None,
+                command_sha256: None,
             },
             resources: Resources {
                 cpu: Cpu {
                     max: "100000 100000".to_string(),
                 },
                 memory: Memory {
-                    max: "256MB".to_string(),
+                    max: "512Ki".to_string(),
                 },
             },
             network: Network {
@@ -366,6 +530,7 @@ mod tests {
                 egress: vec![],
             },
             mounts: vec![],
+            secrets: Vec::new(),
             audit: Audit {
                 level: "basic".to_string(),
             },
@@ -382,9 +547,15 @@ mod tests {
             metadata: Metadata {
                 name: "demo".to_string(),
             },
+            parameters: BTreeMap::new(),
             runtime: Runtime {
                 command: "".to_string(),
                 args: vec![],
+                env: BTreeMap::new(),
+                env_passthrough: Vec::new(),
+                cwd: None,
+                user: None,
+                command_sha256: None,
             },
             resources: Resources {
                 cpu: Cpu {
@@ -399,6 +570,7 @@ mod tests {
                 egress: vec![],
             },
             mounts: vec![],
+            secrets: Vec::new(),
             audit: Audit {
                 level: "basic".to_string(),
             },
@@ -427,6 +599,7 @@ mod tests {
             },
             mount_plan: MountPlan {
                 enabled: true,
+                drives: Vec::new(),
                 mounts: vec![],
             },
             network_plan: None,
@@ -434,6 +607,8 @@ mod tests {
                 enabled: true,
                 events: required_evidence_events(&NetworkMode::None),
             },
+            command_plan: sample_command_plan(),
+            secret_plan: None,
         };
 
         let err = ensure_bundle_complete(&bundle, &NetworkMode::None)
@@ -468,6 +643,7 @@ mod tests {
             },
             mount_plan: MountPlan {
                 enabled: true,
+                drives: Vec::new(),
                 mounts: vec![],
             },
             network_plan: None,
@@ -475,6 +651,8 @@ mod tests {
                 enabled: true,
                 events: vec![sr_evidence::EVENT_RUN_PREPARED.to_string()],
             },
+            command_plan: sample_command_plan(),
+            secret_plan: None,
         };
 
         let err = ensure_bundle_complete(&bundle, &NetworkMode::None)
@@ -511,6 +689,7 @@ mod tests {
             },
             mount_plan: MountPlan {
                 enabled: true,
+                drives: Vec::new(),
                 mounts: vec![],
             },
             network_plan: None,
@@ -518,6 +697,8 @@ mod tests {
                 enabled: true,
                 events: required_evidence_events(&NetworkMode::Allowlist),
             },
+            command_plan: sample_command_plan(),
+            secret_plan: None,
         };
 
         let err = ensure_bundle_complete(&bundle, &NetworkMode::Allowlist)
@@ -1,10 +1,13 @@
 use serde::{Deserialize, Serialize};
-use sr_policy::Mount;
+use sr_policy::{Mount, MountMode, MountSourceIdentity};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct MountPlan {
     pub enabled: bool,
     pub mounts: Vec<MountPlanEntry>,
+    /// Writable `output`/`scratch` mounts, attached to the VM as extra drives.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub drives: Vec<MountDrive>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -12,23 +15,54 @@ pub struct MountPlanEntry {
     pub source: String,
     pub target: String,
     pub read_only: bool,
+    /// Validated source identity; the runner refuses to mount a source that no longer matches.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_identity: Option<MountSourceIdentity>,
+}
+
+/// Per-run sparse image attached as a writable drive and mounted by the guest at `target`.
+/// `image` is relative to the run workdir.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MountDrive {
+    pub drive_id: String,
+    pub target: String,
+    pub mode: MountMode,
+    pub size_mib: u64,
+    pub image: String,
 }
 
 pub struct MountPlanBuilder;
 
 impl MountPlanBuilder {
-    pub fn build(mounts: &[Mount]) -> MountPlan {
-        let plan_mounts = mounts
-            .iter()
-            .map(|mount| MountPlanEntry {
-                source: mount.source.clone(),
-                target: mount.target.clone(),
-                read_only: mount.read_only,
-            })
-            .collect::<Vec<_>>();
-        MountPlan {
+    /// Read-only mounts become bind mounts; `output`/`scratch` mounts become drives.
+    /// Returns `None` if a writable mount's size cap cannot be mapped to MiB.
+    pub fn build(mounts: &[Mount]) -> Option<MountPlan> {
+        let mut plan_mounts = Vec::new();
+        let mut drives = Vec::new();
+        for (idx, mount) in mounts.iter().enumerate() {
+            match mount.mode {
+                Some(mode) => {
+                    let drive_id = format!("{}_{idx}", mode.as_str());
+                    drives.push(MountDrive {
+                        image: format!("mounts/{drive_id}.img"),
+                        drive_id,
+                        target: mount.target.clone(),
+                        mode,
+                        size_mib: crate::memory_to_mib(mount.max_size.as_deref()?)?,
+                    });
+                }
+                None => plan_mounts.push(MountPlanEntry {
+                    source: mount.source.clone(),
+                    target: mount.target.clone(),
+                    read_only: mount.read_only,
+                    source_identity: mount.source_identity.clone(),
+                }),
+            }
+        }
+        Some(MountPlan {
             enabled: true,
             mounts: plan_mounts,
-        }
+            drives,
+        })
     }
 }
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use sr_policy::Secret;
+
+const SECRETS_DRIVE_ID: &str = "secrets";
+const SECRETS_IMAGE: &str = "secrets/secrets.img";
+const SECRETS_GUEST_DIR: &str = "/run/secrets";
+
+/// Ephemeral read-only drive carrying resolved secret values; the plan only holds host
+/// references, so the bundle and everything derived from it stays free of secret material.
+/// `image` is relative to the run workdir.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SecretPlan {
+    pub drive_id: String,
+    pub image: String,
+    pub target: String,
+    pub secrets: Vec<Secret>,
+}
+
+pub struct SecretPlanBuilder;
+
+impl SecretPlanBuilder {
+    /// Returns `None` when the policy declares no secrets, so no drive is attached.
+    pub fn build(secrets: &[Secret]) -> Option<SecretPlan> {
+        if secrets.is_empty() {
+            return None;
+        }
+        Some(SecretPlan {
+            drive_id: SECRETS_DRIVE_ID.to_string(),
+            image: SECRETS_IMAGE.to_string(),
+            target: SECRETS_GUEST_DIR.to_string(),
+            secrets: secrets.to_vec(),
+        })
+    }
+}
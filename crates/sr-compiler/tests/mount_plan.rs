@@ -1,18 +1,26 @@
+use sr_common::SR_CMP_002;
 use sr_compiler::compile_dry_run;
 use sr_policy::{
-    Audit, Cpu, Memory, Metadata, Mount, Network, NetworkMode, PolicySpec, Resources, Runtime,
+    Audit, Cpu, Memory, Metadata, Mount, MountMode, Network, NetworkMode, PolicySpec, Resources,
+    Runtime,
 };
+use std::collections::BTreeMap;
 
-#[test]
-fn compile_includes_mount_plan_in_order() {
-    let policy = PolicySpec {
+fn policy_with_mounts(mounts: Vec<Mount>) -> PolicySpec {
+    PolicySpec {
         api_version: "policy.safe-run.dev/v1alpha1".to_string(),
         metadata: Metadata {
             name: "demo".to_string(),
         },
+        parameters: BTreeMap::new(),
         runtime: Runtime {
             command: "/bin/echo".to_string(),
             args: vec!["ok".to_string()],
+            env: BTreeMap::new(),
+            env_passthrough: Vec::new(),
+            cwd: None,
+            user: None,
+            command_sha256: None,
         },
         resources: Resources {
             cpu: Cpu {
@@ -26,22 +34,34 @@ fn compile_includes_mount_plan_in_order() {
             mode: NetworkMode::None,
             egress: vec![],
         },
-        mounts: vec![
-            Mount {
-                source: "/var/lib/safe-run/input".to_string(),
-                target: "/data/input".to_string(),
-                read_only: true,
-            },
-            Mount {
-                source: "/var/lib/safe-run/output".to_string(),
-                target: "/data/output".to_string(),
-                read_only: true,
-            },
-        ],
+        mounts,
+        secrets: Vec::new(),
         audit: Audit {
             level: "basic".to_string(),
         },
-    };
+    }
+}
+
+#[test]
+fn compile_includes_mount_plan_in_order() {
+    let policy = policy_with_mounts(vec![
+        Mount {
+            source: "/var/lib/safe-run/input".to_string(),
+            target: "/data/input".to_string(),
+            read_only: true,
+            source_identity: None,
+            mode: None,
+            max_size: None,
+        },
+        Mount {
+            source: "/var/lib/safe-run/output".to_string(),
+            target: "/data/output".to_string(),
+            read_only: true,
+            source_identity: None,
+            mode: None,
+            max_size: None,
+        },
+    ]);
 
     let bundle = compile_dry_run(&policy).expect("compile bundle");
     assert!(bundle.mount_plan.enabled);
@@ -59,3 +79,20 @@ fn compile_includes_mount_plan_in_order() {
     assert_eq!(bundle.mount_plan.mounts[1].target, "/data/output");
     assert!(bundle.mount_plan.mounts[1].read_only);
 }
+
+#[test]
+fn compile_refuses_drive_targets_that_would_inject_boot_args() {
+    let policy = policy_with_mounts(vec![Mount {
+        source: String::new(),
+        target: "/data/out init=/bin/sh".to_string(),
+        read_only: false,
+        source_identity: None,
+        mode: Some(MountMode::Output),
+        max_size: Some("4Mi".to_string()),
+    }]);
+
+    let err = compile_dry_run(&policy).expect_err("injected target must not compile");
+    assert_eq!(err.code, SR_CMP_002);
+    assert_eq!(err.path, "mounts.target");
+    assert!(!err.message.contains("init=/bin/sh"));
+}
@@ -1,18 +1,46 @@
+use crate::bundle::{build_bundle_package, validate_bundle_artifacts};
+use crate::index::{lock_index, replace_archive_index};
+use crate::retention::parse_unix_seconds;
+use crate::store::validate_object_key;
 use crate::{
-    append_archive_index, compute_integrity_digest, ArchiveIndexEntry, ArchiveMetadata, RunReport,
-    VerificationMetadata,
+    append_archive_index, compute_integrity_digest, verify_report, ArchiveIndex, ArchiveIndexEntry,
+    ArchiveMetadata, ArchiveStore, BundleArtifact, BundleCompression, BundleManifest,
+    FsArchiveStore, ObjectLock, RunReport, VerificationMetadata,
 };
 use sr_common::{ErrorItem, SR_OPS_301};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 const ARCHIVED_REPORT_FILE: &str = "run_report.json";
 
+/// Store a verified report as a new bundle under `archive_root` and index it.
+/// The report must pass `verify_report` first so archiving never re-seals a tampered report.
+/// The index `result` records the run outcome: `pass` for exit code 0, otherwise `fail`.
 pub fn archive_report(
     report: &RunReport,
     archive_root: &Path,
     retention: &str,
+) -> Result<RunReport, ErrorItem> {
+    archive_report_with_artifacts(
+        report,
+        archive_root,
+        retention,
+        &[],
+        BundleCompression::None,
+    )
+}
+
+/// Like `archive_report`, but also packages the raw run artifacts (events, normalized
+/// policy, VM config, logs, ...) with a hash manifest into a deterministic tar next to
+/// the report, so the bundle can be moved and re-verified offline.
+/// With no artifacts, no package is written.
+pub fn archive_report_with_artifacts(
+    report: &RunReport,
+    archive_root: &Path,
+    retention: &str,
+    artifacts: &[BundleArtifact],
+    compression: BundleCompression,
 ) -> Result<RunReport, ErrorItem> {
     fs::create_dir_all(archive_root).map_err(|err| {
         ErrorItem::new(
@@ -25,18 +53,58 @@ pub fn archive_report(
         )
     })?;
 
+    let store = FsArchiveStore::new(archive_root);
+    let archived = archive_report_to_store(report, &store, retention, artifacts, compression)?;
+    append_archive_index(archive_root, archive_index_entry(&archived)?)?;
+    Ok(archived)
+}
+
+/// Store a verified report (and optional artifact package) as a new bundle in any
+/// `ArchiveStore`. Objects are written once with an object lock derived from `retention`;
+/// the report is written last, so a bundle without `run_report.json` is incomplete.
+/// No index is maintained here; `list_store_bundles` rebuilds entries from the store.
+pub fn archive_report_to_store(
+    report: &RunReport,
+    store: &dyn ArchiveStore,
+    retention: &str,
+    artifacts: &[BundleArtifact],
+    compression: BundleCompression,
+) -> Result<RunReport, ErrorItem> {
+    validate_bundle_artifacts(artifacts)?;
+    let verification = verify_report(report);
+    if !verification.valid {
+        let detail = verification
+            .errors
+            .first()
+            .map(|err| format!("{}: {}", err.code, err.message))
+            .unwrap_or_default();
+        return Err(ErrorItem::new(
+            SR_OPS_301,
+            "archive.verify",
+            format!("report failed verification before archiving: {detail}"),
+        ));
+    }
+
     let stored_at = unix_timestamp();
     let bundle_id = build_bundle_id(&report.run_id);
+    let lock = ObjectLock::from_retention(&stored_at, retention)?;
+
+    let manifest =
+        (!artifacts.is_empty()).then(|| BundleManifest::from_artifacts(&report.run_id, artifacts));
 
     let mut archived = report.clone();
     archived.archive = Some(ArchiveMetadata {
         bundle_id: bundle_id.clone(),
         stored_at: stored_at.clone(),
         retention: retention.to_string(),
+        package: manifest
+            .as_ref()
+            .map(|_| compression.package_file_name().to_string()),
+        manifest_digest: manifest.as_ref().map(BundleManifest::digest),
     });
     archived.verification = Some(VerificationMetadata {
         algorithm: "sha256".to_string(),
-        verified_at: stored_at.clone(),
+        verified_at: stored_at,
         result: "pass".to_string(),
     });
     archived.integrity.digest = compute_integrity_digest(&archived).map_err(|err| {
@@ -47,22 +115,70 @@ pub fn archive_report(
         )
     })?;
 
-    write_archived_report(archive_root, &bundle_id, &archived)?;
-    append_archive_index(
-        archive_root,
-        ArchiveIndexEntry {
-            bundle_id,
-            run_id: archived.run_id.clone(),
-            stored_at,
-            retention: retention.to_string(),
-            result: "pass".to_string(),
-        },
+    if let Some(manifest) = manifest.as_ref() {
+        let package = build_bundle_package(&archived, manifest, artifacts, compression)?;
+        store.put_object(
+            &format!("{bundle_id}/{}", compression.package_file_name()),
+            &package,
+            &lock,
+        )?;
+    }
+    let report_json = serde_json::to_vec_pretty(&archived).map_err(|err| {
+        ErrorItem::new(
+            SR_OPS_301,
+            "archive.write",
+            format!("failed to serialize archived report: {err}"),
+        )
+    })?;
+    store.put_object(
+        &format!("{bundle_id}/{ARCHIVED_REPORT_FILE}"),
+        &report_json,
+        &lock,
     )?;
-
     Ok(archived)
 }
 
+/// Load a bundle's report from an `ArchiveStore`.
+pub fn load_store_report(
+    store: &dyn ArchiveStore,
+    bundle_id: &str,
+) -> Result<RunReport, ErrorItem> {
+    validate_bundle_id(bundle_id)?;
+    let raw = store.get_object(&format!("{bundle_id}/{ARCHIVED_REPORT_FILE}"))?;
+    serde_json::from_slice::<RunReport>(&raw).map_err(|err| {
+        ErrorItem::new(
+            SR_OPS_301,
+            "archive.read",
+            format!(
+                "failed to parse archived report '{bundle_id}' in '{}': {err}",
+                store.location()
+            ),
+        )
+    })
+}
+
+/// Index entries for every complete bundle in a store, ordered by `storedAt`.
+/// Bundles whose report is unreadable or lacks archive metadata are skipped.
+pub fn list_store_bundles(store: &dyn ArchiveStore) -> Result<Vec<ArchiveIndexEntry>, ErrorItem> {
+    let suffix = format!("/{ARCHIVED_REPORT_FILE}");
+    let mut entries = store
+        .list_objects("")?
+        .iter()
+        .filter_map(|key| key.strip_suffix(&suffix))
+        .filter(|bundle_id| !bundle_id.contains('/'))
+        .filter_map(|bundle_id| load_store_report(store, bundle_id).ok())
+        .filter_map(|report| archive_index_entry(&report).ok())
+        .collect::<Vec<ArchiveIndexEntry>>();
+    entries.sort_by(|left, right| {
+        stored_at_key(&left.stored_at)
+            .cmp(&stored_at_key(&right.stored_at))
+            .then_with(|| left.bundle_id.cmp(&right.bundle_id))
+    });
+    Ok(entries)
+}
+
 pub fn load_archived_report(archive_root: &Path, bundle_id: &str) -> Result<RunReport, ErrorItem> {
+    validate_bundle_id(bundle_id)?;
     let path = archive_root.join(bundle_id).join(ARCHIVED_REPORT_FILE);
     let raw = fs::read_to_string(&path).map_err(|err| {
         ErrorItem::new(
@@ -83,43 +199,126 @@ pub fn load_archived_report(archive_root: &Path, bundle_id: &str) -> Result<RunR
     })
 }
 
-fn write_archived_report(
+/// Copy a bundle directory to `<output_dir>/<bundle_id>` after verifying its report.
+/// Bundles whose report fails verification are not exported.
+pub fn export_archived_bundle(
     archive_root: &Path,
     bundle_id: &str,
-    report: &RunReport,
-) -> Result<(), ErrorItem> {
-    let bundle_dir = archive_root.join(bundle_id);
-    fs::create_dir_all(&bundle_dir).map_err(|err| {
+    output_dir: &Path,
+) -> Result<PathBuf, ErrorItem> {
+    validate_bundle_id(bundle_id)?;
+    let report = load_archived_report(archive_root, bundle_id)?;
+    let verification = verify_report(&report);
+    if !verification.valid {
+        let detail = verification
+            .errors
+            .first()
+            .map(|err| format!("{}: {}", err.code, err.message))
+            .unwrap_or_default();
+        return Err(ErrorItem::new(
+            SR_OPS_301,
+            "archive.export",
+            format!("archived bundle '{bundle_id}' failed verification: {detail}"),
+        ));
+    }
+
+    let source_dir = archive_root.join(bundle_id);
+    let target_dir = output_dir.join(bundle_id);
+    copy_dir(&source_dir, &target_dir).map_err(|err| {
         ErrorItem::new(
             SR_OPS_301,
-            "archive.bundle",
+            "archive.export",
             format!(
-                "failed to prepare archive bundle '{}': {err}",
-                bundle_dir.display()
+                "failed to export bundle '{}' to '{}': {err}",
+                source_dir.display(),
+                target_dir.display()
             ),
         )
     })?;
+    Ok(target_dir)
+}
 
-    let report_json = serde_json::to_string_pretty(report).map_err(|err| {
-        ErrorItem::new(
-            SR_OPS_301,
-            "archive.write",
-            format!("failed to serialize archived report: {err}"),
-        )
-    })?;
-    let report_path = bundle_dir.join(ARCHIVED_REPORT_FILE);
-    fs::write(&report_path, report_json).map_err(|err| {
-        ErrorItem::new(
+/// Rebuild the index from bundle directories, e.g. after a crash between writing a bundle
+/// and indexing it, or after the index was lost. Directories without a readable report
+/// carrying archive metadata are skipped. Entries are ordered by `storedAt`.
+pub fn reindex_archive(archive_root: &Path) -> Result<ArchiveIndex, ErrorItem> {
+    if !archive_root.is_dir() {
+        return Err(ErrorItem::new(
             SR_OPS_301,
-            "archive.write",
+            "archive.reindex",
             format!(
-                "failed to write archived report '{}': {err}",
-                report_path.display()
+                "failed to scan archive root '{}': not a directory",
+                archive_root.display()
             ),
+        ));
+    }
+    // Hold the lock across the scan so a bundle archived meanwhile is not dropped.
+    let lock = lock_index(archive_root)?;
+    let entries = list_store_bundles(&FsArchiveStore::new(archive_root))?;
+    replace_archive_index(archive_root, &entries, &lock)?;
+    Ok(ArchiveIndex { entries })
+}
+
+/// A bundle id names exactly one directory under the archive root: a single normal path
+/// component, so it cannot escape the root or address a nested object.
+pub(crate) fn validate_bundle_id(bundle_id: &str) -> Result<(), ErrorItem> {
+    if bundle_id.contains('/') || validate_object_key(bundle_id).is_err() {
+        return Err(ErrorItem::new(
+            SR_OPS_301,
+            "archive.bundleId",
+            format!("invalid archive bundle id '{bundle_id}'"),
+        ));
+    }
+    Ok(())
+}
+
+fn archive_index_entry(report: &RunReport) -> Result<ArchiveIndexEntry, ErrorItem> {
+    let metadata = report.archive.as_ref().ok_or_else(|| {
+        ErrorItem::new(
+            SR_OPS_301,
+            "archive.index",
+            format!("report '{}' has no archive metadata", report.run_id),
         )
+    })?;
+    Ok(ArchiveIndexEntry {
+        bundle_id: metadata.bundle_id.clone(),
+        run_id: report.run_id.clone(),
+        stored_at: metadata.stored_at.clone(),
+        retention: metadata.retention.clone(),
+        result: run_result_label(report.exit_code).to_string(),
     })
 }
 
+fn stored_at_key(stored_at: &str) -> (u64, String) {
+    (
+        parse_unix_seconds(stored_at).unwrap_or(0),
+        stored_at.to_string(),
+    )
+}
+
+fn copy_dir(source: &Path, target: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(target)?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let path = entry.path();
+        let destination = target.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&path, &destination)?;
+        } else {
+            fs::copy(&path, &destination)?;
+        }
+    }
+    Ok(())
+}
+
+fn run_result_label(exit_code: i32) -> &'static str {
+    if exit_code == 0 {
+        "pass"
+    } else {
+        "fail"
+    }
+}
+
 fn build_bundle_id(run_id: &str) -> String {
     let nanos = SystemTime::now()
         .duration_since(UNIX_EPOCH)
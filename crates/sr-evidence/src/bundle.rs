@@ -0,0 +1,415 @@
+use crate::hashing::{sha256_bytes, sha256_json_value};
+use crate::{verify_report, RunReport, VerifyCheck, VerifyResult};
+use serde::{Deserialize, Serialize};
+use sr_common::{ErrorItem, SR_EVD_002, SR_EVD_305, SR_OPS_301};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+pub const BUNDLE_SCHEMA_VERSION: &str = "safe-run.bundle/v1";
+pub const BUNDLE_MANIFEST_FILE: &str = "manifest.json";
+pub const BUNDLE_REPORT_FILE: &str = "run_report.json";
+
+const TAR_BLOCK: usize = 512;
+const TAR_NAME_LEN: usize = 100;
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+/// Upper bound on the unpacked size of a zstd bundle (256 MiB).
+pub const BUNDLE_MAX_UNPACKED_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Packaging of the bundle tarball.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BundleCompression {
+    #[default]
+    None,
+    Zstd,
+}
+
+impl BundleCompression {
+    /// Parse `none` or `zstd`.
+    pub fn parse(raw: &str) -> Result<Self, ErrorItem> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "none" | "" => Ok(Self::None),
+            "zstd" => Ok(Self::Zstd),
+            other => Err(ErrorItem::new(
+                SR_OPS_301,
+                "archive.compression",
+                format!("unsupported bundle compression '{other}', expected none or zstd"),
+            )),
+        }
+    }
+
+    pub fn package_file_name(self) -> &'static str {
+        match self {
+            Self::None => "bundle.tar",
+            Self::Zstd => "bundle.tar.zst",
+        }
+    }
+}
+
+/// One raw run artifact stored in the bundle under a relative `name`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleArtifact {
+    pub name: String,
+    pub content: Vec<u8>,
+}
+
+impl BundleArtifact {
+    pub fn from_bytes(name: impl Into<String>, content: impl Into<Vec<u8>>) -> Self {
+        Self {
+            name: name.into(),
+            content: content.into(),
+        }
+    }
+
+    pub fn from_path(name: impl Into<String>, path: &Path) -> Result<Self, ErrorItem> {
+        let name = name.into();
+        let content = fs::read(path).map_err(|err| {
+            ErrorItem::new(
+                SR_OPS_301,
+                format!("archive.artifacts.{name}"),
+                format!("failed to read bundle artifact '{}': {err}", path.display()),
+            )
+        })?;
+        Ok(Self { name, content })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BundleManifestEntry {
+    pub path: String,
+    pub sha256: String,
+    pub size: u64,
+}
+
+/// Hash list of every raw artifact in a bundle; the report itself is not listed because
+/// it carries the manifest digest in `archive.manifestDigest`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BundleManifest {
+    #[serde(rename = "schemaVersion")]
+    pub schema_version: String,
+    #[serde(rename = "runId")]
+    pub run_id: String,
+    pub files: Vec<BundleManifestEntry>,
+}
+
+impl BundleManifest {
+    /// Build a manifest with entries sorted by path.
+    pub fn from_artifacts(run_id: &str, artifacts: &[BundleArtifact]) -> Self {
+        let mut files = artifacts
+            .iter()
+            .map(|artifact| BundleManifestEntry {
+                path: artifact.name.clone(),
+                sha256: sha256_bytes(&artifact.content),
+                size: artifact.content.len() as u64,
+            })
+            .collect::<Vec<BundleManifestEntry>>();
+        files.sort_by(|left, right| left.path.cmp(&right.path));
+        Self {
+            schema_version: BUNDLE_SCHEMA_VERSION.to_string(),
+            run_id: run_id.to_string(),
+            files,
+        }
+    }
+
+    /// Normalized-JSON sha256 of the manifest.
+    pub fn digest(&self) -> String {
+        sha256_json_value(&serde_json::to_value(self).unwrap_or_default())
+    }
+}
+
+/// Reject duplicate, empty, absolute or parent-relative artifact names, and names that
+/// collide with the bundle's own files.
+pub fn validate_bundle_artifacts(artifacts: &[BundleArtifact]) -> Result<(), ErrorItem> {
+    let mut seen = BTreeMap::new();
+    for (idx, artifact) in artifacts.iter().enumerate() {
+        let name = artifact.name.as_str();
+        let invalid = name.is_empty()
+            || name.starts_with('/')
+            || name.split('/').any(|part| part.is_empty() || part == "..")
+            || name.len() >= TAR_NAME_LEN
+            || name == BUNDLE_MANIFEST_FILE
+            || name == BUNDLE_REPORT_FILE;
+        if invalid || seen.insert(name, idx).is_some() {
+            return Err(ErrorItem::new(
+                SR_OPS_301,
+                format!("archive.artifacts[{idx}]"),
+                format!("invalid or duplicate bundle artifact name '{name}'"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Build the bundle package: a ustar archive with `manifest.json`, `run_report.json`
+/// and the artifacts in path order. Headers use fixed mode/owner/mtime so identical
+/// inputs always produce identical bytes.
+pub fn build_bundle_package(
+    report: &RunReport,
+    manifest: &BundleManifest,
+    artifacts: &[BundleArtifact],
+    compression: BundleCompression,
+) -> Result<Vec<u8>, ErrorItem> {
+    validate_bundle_artifacts(artifacts)?;
+    let manifest_json = serde_json::to_vec_pretty(manifest).map_err(|err| {
+        ErrorItem::new(
+            SR_OPS_301,
+            "archive.manifest",
+            format!("failed to serialize bundle manifest: {err}"),
+        )
+    })?;
+    let report_json = serde_json::to_vec_pretty(report).map_err(|err| {
+        ErrorItem::new(
+            SR_OPS_301,
+            "archive.write",
+            format!("failed to serialize archived report: {err}"),
+        )
+    })?;
+
+    let mut sorted = artifacts.iter().collect::<Vec<&BundleArtifact>>();
+    sorted.sort_by(|left, right| left.name.cmp(&right.name));
+
+    let mut tar = Vec::new();
+    append_tar_entry(&mut tar, BUNDLE_MANIFEST_FILE, &manifest_json);
+    append_tar_entry(&mut tar, BUNDLE_REPORT_FILE, &report_json);
+    for artifact in sorted {
+        append_tar_entry(&mut tar, &artifact.name, &artifact.content);
+    }
+    tar.extend_from_slice(&[0u8; TAR_BLOCK * 2]);
+    compress(tar, compression)
+}
+
+/// Unpack a bundle package (plain or zstd, detected by magic bytes) into path -> bytes.
+/// Repeated entry names map to `SR-EVD-305`, so no copy can hide behind another.
+pub fn read_bundle_package(bytes: &[u8]) -> Result<BTreeMap<String, Vec<u8>>, ErrorItem> {
+    let tar = if bytes.starts_with(&ZSTD_MAGIC) {
+        decompress_zstd(bytes)?
+    } else {
+        bytes.to_vec()
+    };
+
+    let mut files = BTreeMap::new();
+    let mut offset = 0usize;
+    while offset + TAR_BLOCK <= tar.len() {
+        let header = &tar[offset..offset + TAR_BLOCK];
+        if header.iter().all(|byte| *byte == 0) {
+            return Ok(files);
+        }
+        if Some(tar_checksum(header)) != parse_octal(&header[148..156]) {
+            return Err(package_error(format!(
+                "tar header checksum mismatch at offset {offset}"
+            )));
+        }
+        let name = String::from_utf8_lossy(trim_nul(&header[0..TAR_NAME_LEN])).to_string();
+        let size = parse_octal(&header[124..136])
+            .and_then(|value| usize::try_from(value).ok())
+            .ok_or_else(|| package_error(format!("invalid size for tar entry '{name}'")))?;
+        let start = offset + TAR_BLOCK;
+        let end = start
+            .checked_add(size)
+            .filter(|end| *end <= tar.len())
+            .ok_or_else(|| package_error(format!("tar entry '{name}' is truncated")))?;
+        if files.contains_key(&name) {
+            return Err(ErrorItem::new(
+                SR_EVD_305,
+                format!("bundle.files.{name}"),
+                "bundle contains more than one entry with this name",
+            ));
+        }
+        files.insert(name, tar[start..end].to_vec());
+        offset = start + size.div_ceil(TAR_BLOCK) * TAR_BLOCK;
+    }
+    Err(package_error("tar archive is missing its end marker"))
+}
+
+/// Re-verify a bundle package offline: the embedded report must verify, the manifest
+/// digest must match `archive.manifestDigest`, and every listed artifact must be present
+/// with a matching hash. Hash mismatches and missing or unlisted files map to `SR-EVD-305`.
+pub fn verify_bundle_package(path: &Path) -> Result<VerifyResult, ErrorItem> {
+    let bytes = fs::read(path).map_err(|err| {
+        ErrorItem::new(
+            SR_EVD_002,
+            "bundle.read",
+            format!("failed to read bundle package '{}': {err}", path.display()),
+        )
+    })?;
+    let files = read_bundle_package(&bytes)?;
+    let report = parse_member::<RunReport>(&files, BUNDLE_REPORT_FILE)?;
+    let manifest = parse_member::<BundleManifest>(&files, BUNDLE_MANIFEST_FILE)?;
+
+    let mut result = verify_report(&report);
+    let mut errors = Vec::new();
+    let expected_digest = report
+        .archive
+        .as_ref()
+        .and_then(|archive| archive.manifest_digest.clone());
+    if expected_digest.as_deref() != Some(manifest.digest().as_str()) {
+        errors.push(ErrorItem::new(
+            SR_EVD_305,
+            "archive.manifestDigest",
+            "bundle manifest does not match the report's manifest digest",
+        ));
+    }
+    for entry in &manifest.files {
+        match files.get(&entry.path) {
+            Some(content) if sha256_bytes(content) == entry.sha256 => {}
+            Some(_) => errors.push(ErrorItem::new(
+                SR_EVD_305,
+                format!("bundle.files.{}", entry.path),
+                "artifact hash does not match the manifest",
+            )),
+            None => errors.push(ErrorItem::new(
+                SR_EVD_305,
+                format!("bundle.files.{}", entry.path),
+                "artifact listed in the manifest is missing from the bundle",
+            )),
+        }
+    }
+    for name in files.keys() {
+        let listed = manifest.files.iter().any(|entry| entry.path == *name);
+        if !listed && name != BUNDLE_MANIFEST_FILE && name != BUNDLE_REPORT_FILE {
+            errors.push(ErrorItem::new(
+                SR_EVD_305,
+                format!("bundle.files.{name}"),
+                "bundle contains a file that is not listed in the manifest",
+            ));
+        }
+    }
+
+    result.checks.push(VerifyCheck {
+        name: "bundle_manifest".to_string(),
+        ok: errors.is_empty(),
+    });
+    result.valid = result.valid && errors.is_empty();
+    result.errors.extend(errors);
+    Ok(result)
+}
+
+fn parse_member<T: serde::de::DeserializeOwned>(
+    files: &BTreeMap<String, Vec<u8>>,
+    name: &str,
+) -> Result<T, ErrorItem> {
+    let content = files
+        .get(name)
+        .ok_or_else(|| package_error(format!("bundle is missing '{name}'")))?;
+    serde_json::from_slice::<T>(content)
+        .map_err(|err| package_error(format!("failed to parse bundle member '{name}': {err}")))
+}
+
+fn append_tar_entry(tar: &mut Vec<u8>, name: &str, content: &[u8]) {
+    let mut header = [0u8; TAR_BLOCK];
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    write_octal(&mut header[100..108], 0o644);
+    write_octal(&mut header[108..116], 0);
+    write_octal(&mut header[116..124], 0);
+    write_octal(&mut header[124..136], content.len() as u64);
+    write_octal(&mut header[136..148], 0);
+    header[156] = b'0';
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    let checksum = tar_checksum(&header);
+    write_octal(&mut header[148..155], checksum);
+    header[155] = b' ';
+
+    tar.extend_from_slice(&header);
+    tar.extend_from_slice(content);
+    let padding = content.len().div_ceil(TAR_BLOCK) * TAR_BLOCK - content.len();
+    tar.extend(std::iter::repeat_n(0u8, padding));
+}
+
+/// Zero-padded octal followed by a NUL terminator, filling `field`.
+fn write_octal(field: &mut [u8], value: u64) {
+    let digits = field.len() - 1;
+    let text = format!("{value:0digits$o}");
+    field[..digits].copy_from_slice(&text.as_bytes()[text.len() - digits..]);
+    field[digits] = 0;
+}
+
+fn parse_octal(field: &[u8]) -> Option<u64> {
+    let text = String::from_utf8_lossy(trim_nul(field)).trim().to_string();
+    u64::from_str_radix(&text, 8).ok()
+}
+
+/// Header checksum with the checksum field itself counted as spaces.
+fn tar_checksum(header: &[u8]) -> u64 {
+    header
+        .iter()
+        .enumerate()
+        .map(|(idx, byte)| {
+            if (148..156).contains(&idx) {
+                u64::from(b' ')
+            } else {
+                u64::from(*byte)
+            }
+        })
+        .sum()
+}
+
+fn trim_nul(field: &[u8]) -> &[u8] {
+    let end = field
+        .iter()
+        .position(|byte| *byte == 0)
+        .unwrap_or(field.len());
+    &field[..end]
+}
+
+#[cfg(feature = "zstd")]
+fn compress(tar: Vec<u8>, compression: BundleCompression) -> Result<Vec<u8>, ErrorItem> {
+    match compression {
+        BundleCompression::None => Ok(tar),
+        BundleCompression::Zstd => zstd::encode_all(tar.as_slice(), 19).map_err(|err| {
+            ErrorItem::new(
+                SR_OPS_301,
+                "archive.compression",
+                format!("failed to compress bundle: {err}"),
+            )
+        }),
+    }
+}
+
+#[cfg(not(feature = "zstd"))]
+fn compress(tar: Vec<u8>, compression: BundleCompression) -> Result<Vec<u8>, ErrorItem> {
+    match compression {
+        BundleCompression::None => Ok(tar),
+        BundleCompression::Zstd => Err(zstd_unavailable()),
+    }
+}
+
+/// Decompression stops past `BUNDLE_MAX_UNPACKED_BYTES`, so a small package cannot
+/// expand into unbounded memory.
+#[cfg(feature = "zstd")]
+fn decompress_zstd(bytes: &[u8]) -> Result<Vec<u8>, ErrorItem> {
+    use std::io::Read;
+
+    let mut tar = Vec::new();
+    zstd::stream::read::Decoder::new(bytes)
+        .and_then(|decoder| {
+            decoder
+                .take(BUNDLE_MAX_UNPACKED_BYTES + 1)
+                .read_to_end(&mut tar)
+        })
+        .map_err(|err| package_error(format!("failed to decompress zstd bundle: {err}")))?;
+    if tar.len() as u64 > BUNDLE_MAX_UNPACKED_BYTES {
+        return Err(package_error(format!(
+            "zstd bundle unpacks to more than {BUNDLE_MAX_UNPACKED_BYTES} bytes"
+        )));
+    }
+    Ok(tar)
+}
+
+#[cfg(not(feature = "zstd"))]
+fn decompress_zstd(_bytes: &[u8]) -> Result<Vec<u8>, ErrorItem> {
+    Err(zstd_unavailable())
+}
+
+#[cfg(not(feature = "zstd"))]
+fn zstd_unavailable() -> ErrorItem {
+    ErrorItem::new(
+        SR_OPS_301,
+        "archive.compression",
+        "zstd bundles require building with the `zstd` feature",
+    )
+}
+
+fn package_error(message: impl Into<String>) -> ErrorItem {
+    ErrorItem::new(SR_EVD_002, "bundle.package", message)
+}
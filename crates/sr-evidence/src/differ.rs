@@ -0,0 +1,452 @@
+use crate::{EvidenceEvent, RunReport, EVENT_RESOURCE_SAMPLED};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use sr_common::{ErrorItem, SR_EVD_002};
+use std::ops::{Index, IndexMut, Range};
+
+/// Output format of `safe-run report diff`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffFormat {
+    Json,
+    Text,
+}
+
+impl DiffFormat {
+    /// Parse a `--format` value: `json` or `text`.
+    pub fn parse(raw: &str) -> Result<Self, ErrorItem> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "text" => Ok(Self::Text),
+            other => Err(ErrorItem::new(
+                SR_EVD_002,
+                "diff.format",
+                format!("unsupported diff format '{other}', expected json or text"),
+            )),
+        }
+    }
+}
+
+/// One differing value, addressed by a dotted report path such as `artifacts.rootfsHash`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FieldDiff {
+    pub path: String,
+    pub left: Value,
+    pub right: Value,
+}
+
+/// One step of the aligned event sequence that is not identical on both sides.
+/// `op` is `added` (only in b), `removed` (only in a) or `changed` (aligned, payload differs).
+/// The `resource.sampled` aggregate carries no indices; its changes sit under `samples.*`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EventDiff {
+    pub op: String,
+    pub stage: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    #[serde(rename = "leftIndex", skip_serializing_if = "Option::is_none")]
+    pub left_index: Option<usize>,
+    #[serde(rename = "rightIndex", skip_serializing_if = "Option::is_none")]
+    pub right_index: Option<usize>,
+    #[serde(
+        rename = "payloadChanges",
+        skip_serializing_if = "Vec::is_empty",
+        default
+    )]
+    pub payload_changes: Vec<FieldDiff>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReportDiff {
+    #[serde(rename = "leftRunId")]
+    pub left_run_id: String,
+    #[serde(rename = "rightRunId")]
+    pub right_run_id: String,
+    pub identical: bool,
+    pub fields: Vec<FieldDiff>,
+    pub events: Vec<EventDiff>,
+}
+
+/// Report sections compared field by field; event-level differences are handled separately.
+const COMPARED_SECTIONS: [&str; 6] = [
+    "artifacts",
+    "policySummary",
+    "exitCode",
+    "mountAudit",
+    "networkAudit",
+    "resourceUsage",
+];
+
+/// Payload keys that are expected to differ between otherwise equivalent runs.
+const VOLATILE_PAYLOAD_KEYS: [&str; 4] = ["pid", "workdir", "cgroupPath", "runId"];
+
+/// Compare two reports.
+/// Sections are flattened to dotted paths; arrays are compared as whole values.
+/// `resourceUsage.series` is skipped because sample timing differs on every run.
+/// Events are aligned by `(stage, type)` with a shortest edit script so that inserted or
+/// missing events do not shift every later comparison. `resource.sampled` events are left
+/// out of the alignment and reported as one aggregate entry without indices.
+pub fn diff_reports(left: &RunReport, right: &RunReport) -> Result<ReportDiff, ErrorItem> {
+    let left_value = report_value(left, "left")?;
+    let right_value = report_value(right, "right")?;
+
+    let mut fields = Vec::new();
+    for section in COMPARED_SECTIONS {
+        diff_values(
+            section,
+            left_value.get(section).unwrap_or(&Value::Null),
+            right_value.get(section).unwrap_or(&Value::Null),
+            &mut fields,
+        );
+    }
+    fields.retain(|item| !item.path.starts_with("resourceUsage.series"));
+
+    let events = diff_events(&left.events, &right.events);
+    Ok(ReportDiff {
+        left_run_id: left.run_id.clone(),
+        right_run_id: right.run_id.clone(),
+        identical: fields.is_empty() && events.is_empty(),
+        fields,
+        events,
+    })
+}
+
+/// Render a diff as plain text: one line per differing field, then the event alignment.
+pub fn render_diff_text(diff: &ReportDiff) -> String {
+    let mut out = format!("--- {}\n+++ {}\n", diff.left_run_id, diff.right_run_id);
+    if diff.identical {
+        out.push_str("reports are equivalent\n");
+        return out;
+    }
+    for field in &diff.fields {
+        out.push_str(&format!(
+            "~ {}: {} -> {}\n",
+            field.path,
+            compact(&field.left),
+            compact(&field.right)
+        ));
+    }
+    for event in &diff.events {
+        let marker = match event.op.as_str() {
+            "added" => '+',
+            "removed" => '-',
+            _ => '~',
+        };
+        let position = match (event.left_index, event.right_index) {
+            (Some(left), Some(right)) => format!("#{left} -> #{right}"),
+            (Some(left), None) => format!("#{left}"),
+            (None, Some(right)) => format!("#{right}"),
+            (None, None) => "(aggregate)".to_string(),
+        };
+        out.push_str(&format!(
+            "{marker} event {}/{} {position}\n",
+            event.stage, event.event_type
+        ));
+        for change in &event.payload_changes {
+            out.push_str(&format!(
+                "    {}: {} -> {}\n",
+                change.path,
+                compact(&change.left),
+                compact(&change.right)
+            ));
+        }
+    }
+    out
+}
+
+fn report_value(report: &RunReport, side: &str) -> Result<Value, ErrorItem> {
+    serde_json::to_value(report).map_err(|err| {
+        ErrorItem::new(
+            SR_EVD_002,
+            format!("diff.{side}"),
+            format!("failed to serialize report: {err}"),
+        )
+    })
+}
+
+fn diff_values(path: &str, left: &Value, right: &Value, out: &mut Vec<FieldDiff>) {
+    match (left, right) {
+        (Value::Object(left_map), Value::Object(right_map)) => {
+            let mut keys = left_map.keys().chain(right_map.keys()).collect::<Vec<_>>();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                diff_values(
+                    &format!("{path}.{key}"),
+                    left_map.get(key).unwrap_or(&Value::Null),
+                    right_map.get(key).unwrap_or(&Value::Null),
+                    out,
+                );
+            }
+        }
+        _ if left != right => out.push(FieldDiff {
+            path: path.to_string(),
+            left: left.clone(),
+            right: right.clone(),
+        }),
+        _ => {}
+    }
+}
+
+fn diff_events<'a>(left: &'a [EvidenceEvent], right: &'a [EvidenceEvent]) -> Vec<EventDiff> {
+    let (left_samples, left) = split_samples(left);
+    let (right_samples, right) = split_samples(right);
+    let key = |&(_, event): &(usize, &'a EvidenceEvent)| {
+        (event.stage.as_str(), event.event_type.as_str())
+    };
+    let left_keys = left.iter().map(key).collect::<Vec<_>>();
+    let right_keys = right.iter().map(key).collect::<Vec<_>>();
+
+    let mut steps = Vec::new();
+    align(
+        &left_keys,
+        0..left_keys.len(),
+        &right_keys,
+        0..right_keys.len(),
+        &mut steps,
+    );
+
+    let mut diffs = Vec::new();
+    for step in steps {
+        match step {
+            Step::Same(i, j) => {
+                let ((left_index, left_event), (right_index, right_event)) = (left[i], right[j]);
+                let mut changes = Vec::new();
+                diff_values(
+                    "payload",
+                    &stable_payload(&left_event.payload),
+                    &stable_payload(&right_event.payload),
+                    &mut changes,
+                );
+                if !changes.is_empty() {
+                    diffs.push(event_diff(
+                        "changed",
+                        left_event,
+                        Some(left_index),
+                        Some(right_index),
+                        changes,
+                    ));
+                }
+            }
+            Step::Removed(i) => {
+                let (index, event) = left[i];
+                diffs.push(event_diff("removed", event, Some(index), None, Vec::new()));
+            }
+            Step::Added(j) => {
+                let (index, event) = right[j];
+                diffs.push(event_diff("added", event, None, Some(index), Vec::new()));
+            }
+        }
+    }
+    diffs.extend(diff_samples(&left_samples, &right_samples));
+    diffs
+}
+
+/// Separate `resource.sampled` events from the rest, keeping original indices.
+/// Sample counts follow run duration, so aligning them one by one only produces noise.
+#[allow(clippy::type_complexity)]
+fn split_samples(events: &[EvidenceEvent]) -> (Vec<&EvidenceEvent>, Vec<(usize, &EvidenceEvent)>) {
+    let (samples, rest): (Vec<_>, Vec<_>) = events
+        .iter()
+        .enumerate()
+        .partition(|(_, event)| event.event_type == EVENT_RESOURCE_SAMPLED);
+    (samples.into_iter().map(|(_, event)| event).collect(), rest)
+}
+
+/// One aggregate entry for the `resource.sampled` events: sample count and peak counters.
+fn diff_samples(left: &[&EvidenceEvent], right: &[&EvidenceEvent]) -> Option<EventDiff> {
+    let template = left.first().or(right.first())?;
+    let aggregate = |samples: &[&EvidenceEvent]| {
+        let peak = |key: &str| {
+            samples
+                .iter()
+                .filter_map(|event| event.payload.get(key).and_then(Value::as_u64))
+                .max()
+        };
+        json!({
+            "count": samples.len(),
+            "cpuUsageUsecMax": peak("cpuUsageUsec"),
+            "memoryCurrentBytesMax": peak("memoryCurrentBytes"),
+        })
+    };
+    let mut changes = Vec::new();
+    diff_values("samples", &aggregate(left), &aggregate(right), &mut changes);
+    if changes.is_empty() {
+        return None;
+    }
+    let op = match (left.is_empty(), right.is_empty()) {
+        (true, _) => "added",
+        (_, true) => "removed",
+        _ => "changed",
+    };
+    Some(event_diff(op, template, None, None, changes))
+}
+
+/// One step of an event alignment, by position in the compared key lists.
+enum Step {
+    Same(usize, usize),
+    Removed(usize),
+    Added(usize),
+}
+
+/// Shortest edit script between two key lists with Myers' linear-space divide and conquer:
+/// O((n + m) * d) time for `d` edits and O(n + m) memory.
+fn align<K: PartialEq>(
+    left: &[K],
+    mut left_range: Range<usize>,
+    right: &[K],
+    mut right_range: Range<usize>,
+    steps: &mut Vec<Step>,
+) {
+    while !left_range.is_empty()
+        && !right_range.is_empty()
+        && left[left_range.start] == right[right_range.start]
+    {
+        steps.push(Step::Same(left_range.start, right_range.start));
+        left_range.start += 1;
+        right_range.start += 1;
+    }
+    let mut suffix = 0;
+    while left_range.len() > suffix
+        && right_range.len() > suffix
+        && left[left_range.end - suffix - 1] == right[right_range.end - suffix - 1]
+    {
+        suffix += 1;
+    }
+    left_range.end -= suffix;
+    right_range.end -= suffix;
+
+    if left_range.is_empty() || right_range.is_empty() {
+        steps.extend(left_range.clone().map(Step::Removed));
+        steps.extend(right_range.clone().map(Step::Added));
+    } else {
+        let (x, y) = middle_snake(left, left_range.clone(), right, right_range.clone());
+        align(
+            left,
+            left_range.start..x,
+            right,
+            right_range.start..y,
+            steps,
+        );
+        align(left, x..left_range.end, right, y..right_range.end, steps);
+    }
+    steps.extend(
+        (0..suffix).map(|offset| Step::Same(left_range.end + offset, right_range.end + offset)),
+    );
+}
+
+/// Split point of the middle snake of a shortest edit script. Both ranges are non-empty and
+/// share neither a first nor a last key, so the split is strictly inside the problem.
+fn middle_snake<K: PartialEq>(
+    left: &[K],
+    left_range: Range<usize>,
+    right: &[K],
+    right_range: Range<usize>,
+) -> (usize, usize) {
+    let (n, m) = (left_range.len(), right_range.len());
+    let delta = n as isize - m as isize;
+    let odd = delta % 2 != 0;
+    let max_d = (n + m).div_ceil(2) + 1;
+    // Furthest x reached on each diagonal k, forwards from the start and backwards from the end.
+    let mut forward = Diagonals::new(max_d);
+    let mut backward = Diagonals::new(max_d);
+    let same = |x: usize, y: usize| left[left_range.start + x] == right[right_range.start + y];
+
+    for d in 0..max_d as isize {
+        for k in (-d..=d).rev().step_by(2) {
+            let mut x = if k == -d || (k != d && forward[k - 1] < forward[k + 1]) {
+                forward[k + 1]
+            } else {
+                forward[k - 1] + 1
+            };
+            let (start_x, start_y) = (x, (x as isize - k) as usize);
+            let mut y = start_y;
+            while x < n && y < m && same(x, y) {
+                x += 1;
+                y += 1;
+            }
+            forward[k] = x;
+            if odd && (k - delta).abs() < d && forward[k] + backward[delta - k] >= n {
+                return (left_range.start + start_x, right_range.start + start_y);
+            }
+        }
+        for k in (-d..=d).rev().step_by(2) {
+            let mut x = if k == -d || (k != d && backward[k - 1] < backward[k + 1]) {
+                backward[k + 1]
+            } else {
+                backward[k - 1] + 1
+            };
+            let mut y = (x as isize - k) as usize;
+            while x < n && y < m && same(n - x - 1, m - y - 1) {
+                x += 1;
+                y += 1;
+            }
+            backward[k] = x;
+            if !odd && (k - delta).abs() <= d && backward[k] + forward[delta - k] >= n {
+                return (left_range.start + n - x, right_range.start + m - y);
+            }
+        }
+    }
+    unreachable!("an edit script of at most n + m steps always exists")
+}
+
+/// Furthest-reaching x per diagonal `k` in `-max_d..=max_d`.
+struct Diagonals {
+    offset: isize,
+    x: Vec<usize>,
+}
+
+impl Diagonals {
+    fn new(max_d: usize) -> Self {
+        Self {
+            offset: max_d as isize,
+            x: vec![0; 2 * max_d + 1],
+        }
+    }
+}
+
+impl Index<isize> for Diagonals {
+    type Output = usize;
+
+    fn index(&self, k: isize) -> &usize {
+        &self.x[(k + self.offset) as usize]
+    }
+}
+
+impl IndexMut<isize> for Diagonals {
+    fn index_mut(&mut self, k: isize) -> &mut usize {
+        &mut self.x[(k + self.offset) as usize]
+    }
+}
+
+fn stable_payload(payload: &Value) -> Value {
+    match payload {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .filter(|(key, _)| !VOLATILE_PAYLOAD_KEYS.contains(&key.as_str()))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect::<Map<String, Value>>(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn event_diff(
+    op: &str,
+    event: &EvidenceEvent,
+    left_index: Option<usize>,
+    right_index: Option<usize>,
+    payload_changes: Vec<FieldDiff>,
+) -> EventDiff {
+    EventDiff {
+        op: op.to_string(),
+        stage: event.stage.clone(),
+        event_type: event.event_type.clone(),
+        left_index,
+        right_index,
+        payload_changes,
+    }
+}
+
+fn compact(value: &Value) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "null".to_string())
+}
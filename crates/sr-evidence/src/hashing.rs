@@ -17,6 +17,16 @@ pub fn sha256_string(data: &str) -> String {
     sha256_bytes(data.as_bytes())
 }
 
+/// Hash a secret value under a per-run hex salt so evidence can confirm which value was
+/// delivered without recording it. Returns `None` when the salt is not valid hex.
+pub fn salted_secret_hash(salt_hex: &str, value: &[u8]) -> Option<String> {
+    let salt = hex::decode(salt_hex).ok()?;
+    let mut hasher = Sha256::new();
+    hasher.update(&salt);
+    hasher.update(value);
+    Some(format!("sha256:{}", hex::encode(hasher.finalize())))
+}
+
 /// Normalize a JSON value with sorted object keys and no whitespace.
 pub fn normalize_json_string(value: &Value) -> String {
     let canonical = canonicalize_value(value);
@@ -30,9 +40,11 @@ pub fn sha256_json_value(value: &Value) -> String {
 }
 
 /// Compute sha256 digest of a file on disk and return with prefix.
+/// Streams the file, so large artifacts such as output images are not read into memory.
 pub fn sha256_file(path: &Path) -> Result<String, std::io::Error> {
-    let bytes = fs::read(path)?;
-    Ok(sha256_bytes(&bytes))
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut fs::File::open(path)?, &mut hasher)?;
+    Ok(format!("sha256:{}", hex::encode(hasher.finalize())))
 }
 
 /// Derive a stable hash for an evidence event from its semantic fields.
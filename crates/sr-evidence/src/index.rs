@@ -1,9 +1,16 @@
+use crate::retention::parse_unix_seconds;
 use serde::{Deserialize, Serialize};
 use sr_common::{ErrorItem, SR_OPS_301};
-use std::fs;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::path::Path;
 
-const ARCHIVE_INDEX_FILE: &str = "index.json";
+/// Append-only JSON Lines log of index operations.
+const ARCHIVE_INDEX_LOG_FILE: &str = "index.jsonl";
+/// Single-document index written by earlier versions; still read, never written.
+const LEGACY_ARCHIVE_INDEX_FILE: &str = "index.json";
+const ARCHIVE_INDEX_LOCK_FILE: &str = "index.lock";
+const ARCHIVE_INDEX_TMP_FILE: &str = "index.jsonl.tmp";
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub struct ArchiveIndex {
@@ -22,51 +29,269 @@ pub struct ArchiveIndexEntry {
     pub result: String,
 }
 
+/// One line of `index.jsonl`. Replaying the log in order yields the current index;
+/// an `add` for an existing bundle replaces it so replays stay idempotent.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "op", rename_all = "lowercase")]
+enum IndexRecord {
+    Add {
+        #[serde(flatten)]
+        entry: ArchiveIndexEntry,
+    },
+    Remove {
+        #[serde(rename = "bundleId")]
+        bundle_id: String,
+    },
+}
+
+/// Load the index by replaying the legacy `index.json` (if any) and then `index.jsonl`.
+/// A torn final line left by a crashed writer is ignored; corruption elsewhere is an error.
 pub fn load_archive_index(archive_root: &Path) -> Result<ArchiveIndex, ErrorItem> {
-    let index_path = archive_root.join(ARCHIVE_INDEX_FILE);
-    if !index_path.exists() {
-        return Ok(ArchiveIndex::default());
+    let mut entries = load_legacy_index(archive_root)?;
+    let log_path = archive_root.join(ARCHIVE_INDEX_LOG_FILE);
+    if !log_path.exists() {
+        return Ok(ArchiveIndex { entries });
     }
 
-    let raw = fs::read_to_string(&index_path).map_err(|err| {
-        ErrorItem::new(
-            SR_OPS_301,
-            "archive.index",
-            format!("failed to read archive index '{}': {err}", index_path.display()),
-        )
-    })?;
-    serde_json::from_str::<ArchiveIndex>(&raw).map_err(|err| {
-        ErrorItem::new(
-            SR_OPS_301,
-            "archive.index",
-            format!(
-                "failed to parse archive index '{}': {err}",
-                index_path.display()
-            ),
-        )
-    })
+    let raw = fs::read_to_string(&log_path)
+        .map_err(|err| index_error(format!("failed to read archive index: {err}"), &log_path))?;
+    let complete = complete_prefix(&raw);
+    for (line_no, line) in complete.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = serde_json::from_str::<IndexRecord>(line).map_err(|err| {
+            index_error(
+                format!("failed to parse archive index line {}: {err}", line_no + 1),
+                &log_path,
+            )
+        })?;
+        apply_record(&mut entries, record);
+    }
+    Ok(ArchiveIndex { entries })
 }
 
+/// Append an `add` record under the index lock.
 pub fn append_archive_index(
     archive_root: &Path,
     entry: ArchiveIndexEntry,
 ) -> Result<(), ErrorItem> {
-    let mut index = load_archive_index(archive_root)?;
-    index.entries.push(entry);
-
-    let index_path = archive_root.join(ARCHIVE_INDEX_FILE);
-    let content = serde_json::to_string_pretty(&index).map_err(|err| {
-        ErrorItem::new(
-            SR_OPS_301,
-            "archive.index",
-            format!("failed to serialize archive index: {err}"),
-        )
-    })?;
-    fs::write(&index_path, content).map_err(|err| {
-        ErrorItem::new(
-            SR_OPS_301,
-            "archive.index",
-            format!("failed to write archive index '{}': {err}", index_path.display()),
-        )
-    })
+    let lock = lock_index(archive_root)?;
+    append_records(archive_root, &[IndexRecord::Add { entry }], &lock)
+}
+
+/// Append `remove` records for pruned bundles; the caller holds the index lock.
+pub(crate) fn remove_archive_index_entries(
+    archive_root: &Path,
+    bundle_ids: &[String],
+    lock: &IndexLock,
+) -> Result<(), ErrorItem> {
+    let records = bundle_ids
+        .iter()
+        .map(|bundle_id| IndexRecord::Remove {
+            bundle_id: bundle_id.clone(),
+        })
+        .collect::<Vec<IndexRecord>>();
+    append_records(archive_root, &records, lock)
+}
+
+/// Replace the whole index with `entries`: written to a temp file, synced, then renamed
+/// over `index.jsonl`. The caller holds `lock` from before it computed `entries`, so no
+/// append lands in between. The legacy `index.json` is retired afterwards.
+pub(crate) fn replace_archive_index(
+    archive_root: &Path,
+    entries: &[ArchiveIndexEntry],
+    _lock: &IndexLock,
+) -> Result<(), ErrorItem> {
+    let tmp_path = archive_root.join(ARCHIVE_INDEX_TMP_FILE);
+    let log_path = archive_root.join(ARCHIVE_INDEX_LOG_FILE);
+    let mut content = String::new();
+    for entry in entries {
+        content.push_str(&record_line(&IndexRecord::Add {
+            entry: entry.clone(),
+        })?);
+    }
+    File::create(&tmp_path)
+        .and_then(|mut file| {
+            file.write_all(content.as_bytes())?;
+            file.sync_all()
+        })
+        .map_err(|err| index_error(format!("failed to write archive index: {err}"), &tmp_path))?;
+    fs::rename(&tmp_path, &log_path)
+        .map_err(|err| index_error(format!("failed to replace archive index: {err}"), &log_path))?;
+
+    let legacy_path = archive_root.join(LEGACY_ARCHIVE_INDEX_FILE);
+    if legacy_path.is_file() {
+        fs::remove_file(&legacy_path).map_err(|err| {
+            index_error(
+                format!("failed to retire legacy archive index: {err}"),
+                &legacy_path,
+            )
+        })?;
+    }
+    Ok(())
+}
+
+/// Filters for `safe-run archive list`; unset fields match everything.
+/// `since`/`until` are inclusive unix seconds compared against `storedAt`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArchiveQuery {
+    pub run_id: Option<String>,
+    pub since: Option<u64>,
+    pub until: Option<u64>,
+    pub result: Option<String>,
+}
+
+impl ArchiveQuery {
+    pub fn matches(&self, entry: &ArchiveIndexEntry) -> bool {
+        if self
+            .run_id
+            .as_ref()
+            .is_some_and(|run_id| *run_id != entry.run_id)
+        {
+            return false;
+        }
+        if self
+            .result
+            .as_ref()
+            .is_some_and(|result| *result != entry.result)
+        {
+            return false;
+        }
+        if self.since.is_none() && self.until.is_none() {
+            return true;
+        }
+        let Some(stored_at) = parse_unix_seconds(&entry.stored_at) else {
+            return false;
+        };
+        self.since.is_none_or(|since| stored_at >= since)
+            && self.until.is_none_or(|until| stored_at <= until)
+    }
+}
+
+pub fn query_archive_index(
+    archive_root: &Path,
+    query: &ArchiveQuery,
+) -> Result<Vec<ArchiveIndexEntry>, ErrorItem> {
+    Ok(load_archive_index(archive_root)?
+        .entries
+        .into_iter()
+        .filter(|entry| query.matches(entry))
+        .collect())
+}
+
+fn load_legacy_index(archive_root: &Path) -> Result<Vec<ArchiveIndexEntry>, ErrorItem> {
+    let legacy_path = archive_root.join(LEGACY_ARCHIVE_INDEX_FILE);
+    if !legacy_path.exists() {
+        return Ok(Vec::new());
+    }
+    let raw = fs::read_to_string(&legacy_path)
+        .map_err(|err| index_error(format!("failed to read archive index: {err}"), &legacy_path))?;
+    serde_json::from_str::<ArchiveIndex>(&raw)
+        .map(|index| index.entries)
+        .map_err(|err| {
+            index_error(
+                format!("failed to parse archive index: {err}"),
+                &legacy_path,
+            )
+        })
+}
+
+fn apply_record(entries: &mut Vec<ArchiveIndexEntry>, record: IndexRecord) {
+    match record {
+        IndexRecord::Add { entry } => {
+            entries.retain(|item| item.bundle_id != entry.bundle_id);
+            entries.push(entry);
+        }
+        IndexRecord::Remove { bundle_id } => {
+            entries.retain(|item| item.bundle_id != bundle_id);
+        }
+    }
+}
+
+fn append_records(
+    archive_root: &Path,
+    records: &[IndexRecord],
+    _lock: &IndexLock,
+) -> Result<(), ErrorItem> {
+    if records.is_empty() {
+        return Ok(());
+    }
+    let mut content = String::new();
+    for record in records {
+        content.push_str(&record_line(record)?);
+    }
+
+    let log_path = archive_root.join(ARCHIVE_INDEX_LOG_FILE);
+    let mut file = OpenOptions::new()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(&log_path)
+        .map_err(|err| index_error(format!("failed to open archive index: {err}"), &log_path))?;
+    truncate_torn_tail(&mut file)
+        .map_err(|err| index_error(format!("failed to recover archive index: {err}"), &log_path))?;
+    file.write_all(content.as_bytes())
+        .and_then(|_| file.sync_data())
+        .map_err(|err| index_error(format!("failed to write archive index: {err}"), &log_path))
+}
+
+/// Drop a partial last line (no trailing newline) left behind by a crashed writer.
+fn truncate_torn_tail(file: &mut File) -> std::io::Result<()> {
+    let mut raw = String::new();
+    file.seek(SeekFrom::Start(0))?;
+    file.read_to_string(&mut raw)?;
+    let keep = complete_prefix(&raw).len();
+    if keep < raw.len() {
+        file.set_len(keep as u64)?;
+    }
+    Ok(())
+}
+
+fn complete_prefix(raw: &str) -> &str {
+    match raw.rfind('\n') {
+        Some(pos) => &raw[..=pos],
+        None => "",
+    }
+}
+
+/// Held exclusive lock on `index.lock`. Index writers take `&IndexLock` so read-modify-write
+/// sequences keep one lock instead of re-locking (which would deadlock on `flock`).
+pub(crate) struct IndexLock {
+    _file: File,
+}
+
+/// Exclusive advisory lock on `index.lock`; released when the returned guard is dropped,
+/// including when the holding process dies.
+pub(crate) fn lock_index(archive_root: &Path) -> Result<IndexLock, ErrorItem> {
+    let lock_path = archive_root.join(ARCHIVE_INDEX_LOCK_FILE);
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .map_err(|err| index_error(format!("failed to open index lock: {err}"), &lock_path))?;
+    file.lock()
+        .map_err(|err| index_error(format!("failed to lock archive index: {err}"), &lock_path))?;
+    Ok(IndexLock { _file: file })
+}
+
+fn record_line(record: &IndexRecord) -> Result<String, ErrorItem> {
+    serde_json::to_string(record)
+        .map(|line| format!("{line}\n"))
+        .map_err(|err| {
+            ErrorItem::new(
+                SR_OPS_301,
+                "archive.index",
+                format!("failed to serialize archive index record: {err}"),
+            )
+        })
+}
+
+fn index_error(message: String, path: &Path) -> ErrorItem {
+    ErrorItem::new(
+        SR_OPS_301,
+        "archive.index",
+        format!("{message} ('{}')", path.display()),
+    )
 }
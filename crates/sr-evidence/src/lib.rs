@@ -1,27 +1,60 @@
 mod archiver;
+mod bundle;
+mod differ;
 mod event_writer;
 mod hashing;
 mod index;
+mod merkle;
+mod redactor;
+mod renderer;
 mod report_builder;
+mod retention;
+mod s3_store;
+mod store;
 mod verifier;
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-pub use archiver::{archive_report, load_archived_report};
+pub use archiver::{
+    archive_report, archive_report_to_store, archive_report_with_artifacts, export_archived_bundle,
+    list_store_bundles, load_archived_report, load_store_report, reindex_archive,
+};
+pub use bundle::{
+    build_bundle_package, read_bundle_package, validate_bundle_artifacts, verify_bundle_package,
+    BundleArtifact, BundleCompression, BundleManifest, BundleManifestEntry, BUNDLE_MANIFEST_FILE,
+    BUNDLE_MAX_UNPACKED_BYTES, BUNDLE_SCHEMA_VERSION,
+};
+pub use differ::{diff_reports, render_diff_text, DiffFormat, EventDiff, FieldDiff, ReportDiff};
 pub use event_writer::append_event;
 pub use hashing::{
-    derive_event_hash, normalize_json_string, sha256_bytes, sha256_file, sha256_json_value,
-    sha256_string,
+    derive_event_hash, normalize_json_string, salted_secret_hash, sha256_bytes, sha256_file,
+    sha256_json_value, sha256_string,
 };
 pub use index::{
-    append_archive_index, load_archive_index, ArchiveIndex, ArchiveIndexEntry,
+    append_archive_index, load_archive_index, query_archive_index, ArchiveIndex, ArchiveIndexEntry,
+    ArchiveQuery,
+};
+pub use merkle::{
+    compute_event_merkle_root, event_leaf_hash, merkle_inclusion_proof, payload_field_commitment,
+    payload_field_salt, verify_merkle_proof, MerkleProofStep,
 };
+pub use redactor::{redact_report, EventSelector, FieldRedaction, RedactionRequest};
+pub use renderer::{render_report, RenderFormat};
 pub use report_builder::{
     build_report, compute_artifact_hashes, compute_artifact_hashes_from_json,
     compute_integrity_digest, event_time_range, mount_audit_from_events, network_audit_from_events,
-    resource_usage_from_events, ArtifactInputs, ArtifactJsonInputs,
+    resource_usage_from_events, ArtifactInputs, ArtifactJsonInputs, RESOURCE_SERIES_MAX_POINTS,
+};
+pub use retention::{
+    load_prune_log, parse_retention, parse_unix_seconds, prune_archive, verify_prune_log,
+    PruneLogRecord, PruneOutcome,
+};
+pub use s3_store::{S3ArchiveStore, S3StoreConfig};
+pub use store::{ArchiveStore, FsArchiveStore, ObjectLock, ARCHIVE_OBJECT_LOCK_MODE};
+pub use verifier::{
+    verify_redacted_against_original, verify_report, verify_report_file, VerifyCheck, VerifyResult,
 };
-pub use verifier::{verify_report, verify_report_file, VerifyCheck, VerifyResult};
 
 pub const RUN_REPORT_SCHEMA_VERSION: &str = "safe-run.report/v1";
 pub const STAGE_COMPILE: &str = "compile";
@@ -35,6 +68,10 @@ pub const EVENT_RUN_PREPARED: &str = "run.prepared";
 pub const EVENT_MOUNT_VALIDATED: &str = "mount.validated";
 pub const EVENT_MOUNT_REJECTED: &str = "mount.rejected";
 pub const EVENT_MOUNT_APPLIED: &str = "mount.applied";
+pub const EVENT_MOUNT_OUTPUT_COLLECTED: &str = "mount.output.collected";
+pub const EVENT_MOUNT_SCAN: &str = "mount.scan";
+pub const EVENT_SECRETS_INJECTED: &str = "secrets.injected";
+pub const EVENT_COMMAND_VERIFIED: &str = "command.verified";
 pub const EVENT_NETWORK_PLAN_GENERATED: &str = "network.plan.generated";
 pub const EVENT_NETWORK_RULE_APPLIED: &str = "network.rule.applied";
 pub const EVENT_NETWORK_RULE_HIT: &str = "network.rule.hit";
@@ -99,6 +136,8 @@ pub struct RunReport {
     pub archive: Option<ArchiveMetadata>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub verification: Option<VerificationMetadata>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redaction: Option<RedactionMetadata>,
     pub integrity: Integrity,
 }
 
@@ -109,6 +148,16 @@ pub struct ArchiveMetadata {
     #[serde(rename = "storedAt")]
     pub stored_at: String,
     pub retention: String,
+    /// File name of the raw-artifact bundle package inside the bundle directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    /// Digest of the bundle manifest, binding the packaged artifacts to this report.
+    #[serde(
+        rename = "manifestDigest",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub manifest_digest: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -119,23 +168,85 @@ pub struct VerificationMetadata {
     pub result: String,
 }
 
+/// Selective-disclosure metadata attached by `redact_report`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RedactionMetadata {
+    #[serde(rename = "leafCount")]
+    pub leaf_count: usize,
+    #[serde(rename = "originalDigest")]
+    pub original_digest: String,
+    pub disclosures: Vec<EventDisclosure>,
+    /// Report summaries derived from event contents that the redacted copy leaves out,
+    /// e.g. `mountAudit.reasons`, since they could restate removed events or fields.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub withheld: Vec<String>,
+}
+
+/// Inclusion proof for one disclosed event, aligned with `RunReport.events` by position.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EventDisclosure {
+    pub index: usize,
+    #[serde(
+        rename = "redactedFields",
+        default,
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub redacted_fields: BTreeMap<String, String>,
+    /// Salts of the disclosed payload fields; a redacted field's salt is never disclosed.
+    #[serde(
+        rename = "fieldSalts",
+        default,
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub field_salts: BTreeMap<String, String>,
+    pub proof: Vec<MerkleProofStep>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub struct MountAudit {
     pub requested: usize,
     pub accepted: usize,
     pub rejected: usize,
     pub reasons: Vec<String>,
+    /// Writable `output` mounts collected into the run artifacts after the VM exited.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outputs: Vec<MountOutput>,
+    /// Pre-attach content scans of bind mount sources, one per `mount.scan` event.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scans: Vec<MountScanSummary>,
 }
 
-impl Default for MountAudit {
-    fn default() -> Self {
-        Self {
-            requested: 0,
-            accepted: 0,
-            rejected: 0,
-            reasons: Vec::new(),
-        }
-    }
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MountScanSummary {
+    pub source: String,
+    pub target: String,
+    pub files: u64,
+    #[serde(rename = "totalBytes")]
+    pub total_bytes: u64,
+    #[serde(rename = "findingsTotal")]
+    pub findings_total: u64,
+    #[serde(default)]
+    pub findings: Vec<MountScanFindingRecord>,
+    #[serde(rename = "budgetExhausted")]
+    pub budget_exhausted: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MountScanFindingRecord {
+    pub kind: String,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MountOutput {
+    #[serde(rename = "driveId")]
+    pub drive_id: String,
+    pub target: String,
+    /// Image path relative to the run artifacts directory.
+    pub artifact: String,
+    pub sha256: String,
+    #[serde(rename = "sizeBytes")]
+    pub size_bytes: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -176,17 +287,118 @@ pub struct ReportArtifacts {
 pub struct PolicySummary {
     pub network: String,
     pub mounts: usize,
+    /// Template parameter values bound for this run.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub parameters: BTreeMap<String, String>,
+    /// `sha256:` digest of the admission-constraints file that admitted the policy.
+    #[serde(
+        rename = "admissionConstraintsHash",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub admission_constraints_hash: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+/// Resource summary derived from `resource.sampled` events.
+/// `cpu`/`memory` keep the v1 string form; the numeric fields are additive and optional.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub struct ResourceUsage {
     pub cpu: String,
     pub memory: String,
+    #[serde(rename = "sampleCount", default, skip_serializing_if = "is_zero")]
+    pub sample_count: u64,
+    #[serde(
+        rename = "cpuTotalUsec",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub cpu_total_usec: Option<u64>,
+    #[serde(
+        rename = "cpuAvgMillicores",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub cpu_avg_millicores: Option<u64>,
+    #[serde(
+        rename = "memoryPeakBytes",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub memory_peak_bytes: Option<u64>,
+    #[serde(
+        rename = "memoryEvents",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub memory_events: Option<MemoryEventCounters>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub io: Option<IoCounters>,
+    #[serde(
+        rename = "pidsCurrent",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub pids_current: Option<u64>,
+    #[serde(rename = "pidsPeak", default, skip_serializing_if = "Option::is_none")]
+    pub pids_peak: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub series: Option<Vec<ResourceSeriesPoint>>,
+}
+
+/// Counters from cgroup v2 `memory.events`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct MemoryEventCounters {
+    pub low: u64,
+    pub high: u64,
+    pub max: u64,
+    pub oom: u64,
+    #[serde(rename = "oomKill")]
+    pub oom_kill: u64,
+}
+
+/// Byte/operation totals summed over all devices in cgroup v2 `io.stat`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct IoCounters {
+    #[serde(rename = "readBytes")]
+    pub read_bytes: u64,
+    #[serde(rename = "writeBytes")]
+    pub write_bytes: u64,
+    #[serde(rename = "readOps")]
+    pub read_ops: u64,
+    #[serde(rename = "writeOps")]
+    pub write_ops: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ResourceSeriesPoint {
+    pub timestamp: String,
+    #[serde(rename = "cpuUsageUsec")]
+    pub cpu_usage_usec: u64,
+    #[serde(rename = "memoryCurrentBytes")]
+    pub memory_current_bytes: u64,
+}
+
+fn is_zero(value: &u64) -> bool {
+    *value == 0
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Integrity {
     pub digest: String,
+    #[serde(
+        rename = "merkleRoot",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub merkle_root: Option<String>,
+    /// Secret seed of the per-field salts committed under `merkleRoot`. Only the full
+    /// report carries it; redacted copies disclose individual salts instead.
+    #[serde(
+        rename = "fieldSaltSeed",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub field_salt_seed: Option<String>,
 }
 
 #[cfg(test)]
@@ -272,7 +484,7 @@ mod tests {
     #[test]
     fn report_includes_mount_audit_from_event_stream() {
         let log_path = temp_event_log_path("mount-audit");
-        let events = vec![
+        let events = [
             mount_event(
                 EVENT_MOUNT_VALIDATED,
                 json!({"source": "/var/lib/safe-run/input"}),
@@ -314,16 +526,20 @@ mod tests {
             PolicySummary {
                 network: "none".to_string(),
                 mounts: 1,
+                parameters: BTreeMap::new(),
+                admission_constraints_hash: None,
             },
             ResourceUsage {
                 cpu: "10000 100000".to_string(),
                 memory: "256Mi".to_string(),
+                ..ResourceUsage::default()
             },
             parsed,
             mount_audit,
             network_audit,
             "".to_string(),
-        );
+        )
+        .expect("build report");
 
         assert_eq!(report.mount_audit.requested, 2);
         assert_eq!(report.mount_audit.accepted, 1);
@@ -365,6 +581,92 @@ mod tests {
         assert_eq!(audit.blocked_hits, 2);
     }
 
+    fn resource_event(timestamp: &str, payload: serde_json::Value) -> EvidenceEvent {
+        EvidenceEvent {
+            timestamp: timestamp.to_string(),
+            run_id: "sr-20260206-001".to_string(),
+            stage: STAGE_MONITOR.to_string(),
+            event_type: EVENT_RESOURCE_SAMPLED.to_string(),
+            payload,
+            hash_prev: "sha256:0000000000000000".to_string(),
+            hash_self: "sha256:1111111111111111".to_string(),
+        }
+    }
+
+    #[test]
+    fn resource_usage_aggregates_samples_and_cgroup_counters() {
+        let events = vec![
+            resource_event(
+                "unix:100.000000000",
+                json!({"cpuUsageUsec": 1000, "memoryCurrentBytes": 4096, "pidsCurrent": 3}),
+            ),
+            resource_event(
+                "unix:101.000000000",
+                json!({
+                    "cpuUsageUsec": 501000,
+                    "memoryCurrentBytes": 16384,
+                    "pidsCurrent": 9,
+                    "memoryEvents": {"low": 0, "high": 1, "max": 2, "oom": 1, "oomKill": 1}
+                }),
+            ),
+            resource_event(
+                "unix:102.000000000",
+                json!({
+                    "cpuUsageUsec": 1001000,
+                    "memoryCurrentBytes": 8192,
+                    "pidsCurrent": 4,
+                    "io": {"readBytes": 10, "writeBytes": 20, "readOps": 1, "writeOps": 2}
+                }),
+            ),
+        ];
+
+        let usage = resource_usage_from_events(&events);
+
+        assert_eq!(usage.cpu, "cpuUsageUsec=1001000");
+        assert_eq!(usage.memory, "memoryCurrentBytes=8192");
+        assert_eq!(usage.sample_count, 3);
+        assert_eq!(usage.cpu_total_usec, Some(1_001_000));
+        assert_eq!(usage.cpu_avg_millicores, Some(500));
+        assert_eq!(usage.memory_peak_bytes, Some(16384));
+        assert_eq!(usage.pids_current, Some(4));
+        assert_eq!(usage.pids_peak, Some(9));
+        assert_eq!(usage.memory_events.expect("memory events").oom_kill, 1);
+        assert_eq!(usage.io.expect("io counters").write_bytes, 20);
+        assert_eq!(usage.series.expect("series").len(), 3);
+    }
+
+    #[test]
+    fn resource_usage_downsamples_series_and_keeps_v1_defaults() {
+        let events = (0..200u64)
+            .map(|idx| {
+                resource_event(
+                    &format!("unix:{}.000000000", 100 + idx),
+                    json!({"cpuUsageUsec": idx * 10, "memoryCurrentBytes": idx}),
+                )
+            })
+            .collect::<Vec<EvidenceEvent>>();
+
+        let usage = resource_usage_from_events(&events);
+        let series = usage.series.expect("series");
+        assert_eq!(usage.sample_count, 200);
+        assert_eq!(series.len(), RESOURCE_SERIES_MAX_POINTS);
+        assert_eq!(
+            series.first().map(|point| point.memory_current_bytes),
+            Some(0)
+        );
+        assert_eq!(
+            series.last().map(|point| point.memory_current_bytes),
+            Some(199)
+        );
+
+        let empty = resource_usage_from_events(&[]);
+        let value = serde_json::to_value(&empty).expect("serialize usage");
+        assert_eq!(
+            value,
+            json!({"cpu": "cpuUsageUsec=0", "memory": "memoryCurrentBytes=0"})
+        );
+    }
+
     #[test]
     fn run_report_serializes_m1_subset_fields() {
         let report = RunReport {
@@ -382,10 +684,13 @@ mod tests {
             policy_summary: PolicySummary {
                 network: "none".to_string(),
                 mounts: 0,
+                parameters: BTreeMap::new(),
+                admission_constraints_hash: None,
             },
             resource_usage: ResourceUsage {
                 cpu: "10000 100000".to_string(),
                 memory: "256Mi".to_string(),
+                ..ResourceUsage::default()
             },
             events: vec![EvidenceEvent {
                 timestamp: "2026-02-06T10:00:00Z".to_string(),
@@ -400,8 +705,11 @@ mod tests {
             network_audit: NetworkAudit::default(),
             archive: None,
             verification: None,
+            redaction: None,
             integrity: Integrity {
                 digest: "sha256:report".to_string(),
+                merkle_root: None,
+                field_salt_seed: None,
             },
         };
 
@@ -498,16 +806,20 @@ mod tests {
             PolicySummary {
                 network: "none".to_string(),
                 mounts: 0,
+                parameters: BTreeMap::new(),
+                admission_constraints_hash: None,
             },
             ResourceUsage {
                 cpu: "10000 100000".to_string(),
                 memory: "256Mi".to_string(),
+                ..ResourceUsage::default()
             },
             vec![],
             MountAudit::default(),
             NetworkAudit::default(),
             "sha256:digest".to_string(),
-        );
+        )
+        .expect("build report");
 
         assert_eq!(report.schema_version, RUN_REPORT_SCHEMA_VERSION);
         assert_eq!(report.run_id, "sr-20260206-001");
@@ -550,16 +862,20 @@ mod tests {
             PolicySummary {
                 network: "none".to_string(),
                 mounts: 0,
+                parameters: BTreeMap::new(),
+                admission_constraints_hash: None,
             },
             ResourceUsage {
                 cpu: "10000 100000".to_string(),
                 memory: "256Mi".to_string(),
+                ..ResourceUsage::default()
             },
             vec![],
             MountAudit::default(),
             NetworkAudit::default(),
             "sha256:placeholder-a".to_string(),
-        );
+        )
+        .expect("build report");
 
         let mut report_b = base_report.clone();
         report_b.integrity.digest = "sha256:placeholder-b".to_string();
@@ -0,0 +1,221 @@
+use crate::hashing::{normalize_json_string, sha256_json_value, sha256_string};
+use crate::EvidenceEvent;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sr_common::{ErrorItem, SR_EVD_002};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+
+const FIELD_SALT_SEED_BYTES: usize = 32;
+
+/// One step of a Merkle inclusion proof, from leaf level towards the root.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MerkleProofStep {
+    pub hash: String,
+    /// `left` when the sibling sits to the left of the running hash.
+    pub side: String,
+}
+
+/// Fresh random seed for `integrity.fieldSaltSeed`.
+pub(crate) fn random_field_salt_seed() -> Result<String, ErrorItem> {
+    let mut seed = [0u8; FIELD_SALT_SEED_BYTES];
+    File::open("/dev/urandom")
+        .and_then(|mut urandom| urandom.read_exact(&mut seed))
+        .map_err(|err| {
+            ErrorItem::new(
+                SR_EVD_002,
+                "integrity.fieldSaltSeed",
+                format!("failed to read field salt seed: {err}"),
+            )
+        })?;
+    Ok(hex::encode(seed))
+}
+
+/// Salt of payload field `key` of the event at `index`. Salts are derived from the full
+/// report's secret seed; a redacted report carries only the salts of disclosed fields.
+pub fn payload_field_salt(seed: &str, index: usize, key: &str) -> String {
+    sha256_string(&format!("salt|{seed}|{index}|{key}"))
+}
+
+/// Salts for every payload field of the event at `index`.
+pub(crate) fn payload_field_salts(
+    event: &EvidenceEvent,
+    index: usize,
+    seed: Option<&str>,
+) -> BTreeMap<String, String> {
+    let (Some(seed), Some(payload)) = (seed, event.payload.as_object()) else {
+        return BTreeMap::new();
+    };
+    payload
+        .keys()
+        .map(|key| (key.clone(), payload_field_salt(seed, index, key)))
+        .collect()
+}
+
+/// Hash commitment of a single top-level payload field:
+/// `sha256(normalized {key, salt, value})`. The salt keeps low-entropy values from being
+/// guessed back from a redacted commitment.
+pub fn payload_field_commitment(key: &str, value: &Value, salt: &str) -> String {
+    sha256_json_value(&json!({"key": key, "salt": salt, "value": value}))
+}
+
+/// Derive the Merkle leaf hash of an event.
+/// Payload fields are committed one by one under `field_salts` so single fields can later be
+/// redacted without changing the leaf; `redacted_fields` supplies commitments for removed
+/// keys. The chain hashes are left out so they can be withheld next to redacted fields.
+pub fn event_leaf_hash(
+    event: &EvidenceEvent,
+    field_salts: &BTreeMap<String, String>,
+    redacted_fields: &BTreeMap<String, String>,
+) -> String {
+    let mut commitments = BTreeMap::new();
+    if let Some(payload) = event.payload.as_object() {
+        for (key, value) in payload {
+            let salt = field_salts.get(key).map(String::as_str).unwrap_or_default();
+            commitments.insert(key.clone(), payload_field_commitment(key, value, salt));
+        }
+    }
+    for (key, commitment) in redacted_fields {
+        commitments.insert(key.clone(), commitment.clone());
+    }
+    let scalar_payload = if event.payload.is_object() {
+        Value::Null
+    } else {
+        event.payload.clone()
+    };
+    let material = json!({
+        "timestamp": event.timestamp,
+        "runId": event.run_id,
+        "stage": event.stage,
+        "type": event.event_type,
+        "payloadFields": commitments,
+        "payloadScalar": scalar_payload
+    });
+    sha256_string(&format!("leaf|{}", normalize_json_string(&material)))
+}
+
+/// Leaf hashes of a full event list under the report's field salt seed.
+pub(crate) fn event_leaves(events: &[EvidenceEvent], seed: Option<&str>) -> Vec<String> {
+    events
+        .iter()
+        .enumerate()
+        .map(|(index, event)| {
+            event_leaf_hash(
+                event,
+                &payload_field_salts(event, index, seed),
+                &BTreeMap::new(),
+            )
+        })
+        .collect()
+}
+
+/// Compute the Merkle root over the full event list; the root commits to the leaf count.
+/// Returns `None` for an empty event stream.
+pub fn compute_event_merkle_root(events: &[EvidenceEvent], seed: Option<&str>) -> Option<String> {
+    merkle_root_from_leaves(&event_leaves(events, seed))
+}
+
+/// Build the inclusion proof for `index` over the given leaf hashes.
+pub fn merkle_inclusion_proof(leaves: &[String], index: usize) -> Option<Vec<MerkleProofStep>> {
+    if index >= leaves.len() {
+        return None;
+    }
+    let mut proof = Vec::new();
+    let mut level = leaves.to_vec();
+    let mut position = index;
+    while level.len() > 1 {
+        let sibling = if position.is_multiple_of(2) {
+            level.get(position + 1).map(|hash| (hash.clone(), "right"))
+        } else {
+            level.get(position - 1).map(|hash| (hash.clone(), "left"))
+        };
+        if let Some((hash, side)) = sibling {
+            proof.push(MerkleProofStep {
+                hash,
+                side: side.to_string(),
+            });
+        }
+        level = next_level(&level);
+        position /= 2;
+    }
+    Some(proof)
+}
+
+/// Fold a leaf hash through its inclusion proof and compare against `root`.
+/// The proof must have exactly the shape of leaf `index` in a `leaf_count`-leaf tree, so a
+/// valid proof also pins the event's original position.
+pub fn verify_merkle_proof(
+    leaf: &str,
+    index: usize,
+    leaf_count: usize,
+    proof: &[MerkleProofStep],
+    root: &str,
+) -> bool {
+    if index >= leaf_count {
+        return false;
+    }
+    let sides = proof_sides(index, leaf_count);
+    if sides.len() != proof.len() {
+        return false;
+    }
+    let mut running = leaf.to_string();
+    for (step, side) in proof.iter().zip(sides) {
+        if step.side != side {
+            return false;
+        }
+        running = match side {
+            "left" => node_hash(&step.hash, &running),
+            _ => node_hash(&running, &step.hash),
+        };
+    }
+    root_hash(leaf_count, &running) == root
+}
+
+pub(crate) fn merkle_root_from_leaves(leaves: &[String]) -> Option<String> {
+    if leaves.is_empty() {
+        return None;
+    }
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level.pop().map(|tree| root_hash(leaves.len(), &tree))
+}
+
+/// Sibling sides on the path from leaf `index` to the root; levels where the node is
+/// promoted without a sibling contribute no step.
+fn proof_sides(index: usize, leaf_count: usize) -> Vec<&'static str> {
+    let mut sides = Vec::new();
+    let (mut position, mut width) = (index, leaf_count);
+    while width > 1 {
+        if !position.is_multiple_of(2) {
+            sides.push("left");
+        } else if position + 1 < width {
+            sides.push("right");
+        }
+        position /= 2;
+        width = width.div_ceil(2);
+    }
+    sides
+}
+
+/// Pair up hashes; an odd trailing node is promoted unchanged.
+fn next_level(level: &[String]) -> Vec<String> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => node_hash(left, right),
+            [single] => single.clone(),
+            _ => unreachable!("chunks(2) yields one or two items"),
+        })
+        .collect()
+}
+
+fn node_hash(left: &str, right: &str) -> String {
+    sha256_string(&format!("node|{left}|{right}"))
+}
+
+fn root_hash(leaf_count: usize, tree: &str) -> String {
+    sha256_string(&format!("root|{leaf_count}|{tree}"))
+}
@@ -0,0 +1,248 @@
+use crate::merkle::{
+    event_leaves, merkle_inclusion_proof, payload_field_commitment, payload_field_salts,
+};
+use crate::{
+    compute_integrity_digest, EventDisclosure, EvidenceEvent, RedactionMetadata, RunReport,
+};
+use sr_common::{ErrorItem, SR_EVD_002};
+use std::collections::BTreeMap;
+
+/// Selects events by original position, by `type`, or all events.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventSelector {
+    Index(usize),
+    Type(String),
+    All,
+}
+
+impl EventSelector {
+    /// Parse `*`, a decimal event index, or an event type such as `mount.applied`.
+    pub fn parse(raw: &str) -> Result<Self, ErrorItem> {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            return Err(redact_error(
+                "redact.selector",
+                "event selector cannot be empty",
+            ));
+        }
+        if trimmed == "*" {
+            return Ok(Self::All);
+        }
+        if let Ok(index) = trimmed.parse::<usize>() {
+            return Ok(Self::Index(index));
+        }
+        Ok(Self::Type(trimmed.to_string()))
+    }
+
+    fn matches(&self, index: usize, event: &EvidenceEvent) -> bool {
+        match self {
+            Self::Index(value) => *value == index,
+            Self::Type(value) => event.event_type == *value,
+            Self::All => true,
+        }
+    }
+}
+
+/// Removes payload field `field` from every event matched by `selector`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldRedaction {
+    pub selector: EventSelector,
+    pub field: String,
+}
+
+impl FieldRedaction {
+    /// Parse `<selector>:<field>`, for example `mount.applied:source` or `*:message`.
+    pub fn parse(raw: &str) -> Result<Self, ErrorItem> {
+        let Some((selector, field)) = raw.rsplit_once(':') else {
+            return Err(redact_error(
+                "redact.field",
+                format!("field redaction '{raw}' must be '<selector>:<field>'"),
+            ));
+        };
+        if field.trim().is_empty() {
+            return Err(redact_error(
+                "redact.field",
+                format!("field redaction '{raw}' has an empty field name"),
+            ));
+        }
+        Ok(Self {
+            selector: EventSelector::parse(selector)?,
+            field: field.trim().to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RedactionRequest {
+    pub remove_events: Vec<EventSelector>,
+    pub redact_fields: Vec<FieldRedaction>,
+}
+
+/// Produce a selectively disclosed copy of `report`.
+/// Kept events carry an inclusion proof against `integrity.merkleRoot` and the salts of their
+/// disclosed payload fields; redacted fields are replaced by their salted commitments so the
+/// leaf hash stays reproducible. Events with redacted fields withhold `hashSelf` (and the
+/// next event its `hashPrev`), which would otherwise confirm guesses of the removed value.
+/// The salt seed and summaries restating event contents (see `WITHHELD_SUMMARIES`) are
+/// dropped. Already redacted reports can be redacted further.
+/// The copy's `integrity.digest` is recomputed over the copy, so on its own it only proves
+/// self-consistency; `redaction.originalDigest` ties it to the full report, which
+/// `verify_redacted_against_original` checks.
+/// Error mapping: reports without a Merkle root or unusable disclosures -> `SR-EVD-002`.
+pub fn redact_report(
+    report: &RunReport,
+    request: &RedactionRequest,
+) -> Result<RunReport, ErrorItem> {
+    if report.integrity.merkle_root.is_none() {
+        return Err(redact_error(
+            "integrity.merkleRoot",
+            "report has no event Merkle root; regenerate it before redacting",
+        ));
+    }
+
+    let (leaf_count, original_digest, disclosures) = current_disclosures(report)?;
+
+    let mut events = Vec::new();
+    let mut kept = Vec::new();
+    for (event, disclosure) in report.events.iter().zip(disclosures) {
+        if request
+            .remove_events
+            .iter()
+            .any(|selector| selector.matches(disclosure.index, event))
+        {
+            continue;
+        }
+        let mut event = event.clone();
+        let mut disclosure = disclosure;
+        for redaction in &request.redact_fields {
+            if !redaction.selector.matches(disclosure.index, &event) {
+                continue;
+            }
+            let Some(payload) = event.payload.as_object_mut() else {
+                continue;
+            };
+            if let Some(value) = payload.remove(&redaction.field) {
+                let salt = disclosure
+                    .field_salts
+                    .remove(&redaction.field)
+                    .unwrap_or_default();
+                disclosure.redacted_fields.insert(
+                    redaction.field.clone(),
+                    payload_field_commitment(&redaction.field, &value, &salt),
+                );
+            }
+        }
+        events.push(event);
+        kept.push(disclosure);
+    }
+    withhold_chain_hashes(&mut events, &kept);
+
+    let mut redacted = report.clone();
+    redacted.events = events;
+    redacted.integrity.field_salt_seed = None;
+    let mut withheld = report
+        .redaction
+        .as_ref()
+        .map(|existing| existing.withheld.clone())
+        .unwrap_or_default();
+    for summary in withhold_summaries(&mut redacted) {
+        if !withheld.contains(&summary) {
+            withheld.push(summary);
+        }
+    }
+    redacted.redaction = Some(RedactionMetadata {
+        leaf_count,
+        original_digest,
+        disclosures: kept,
+        withheld,
+    });
+    redacted.integrity.digest = compute_integrity_digest(&redacted)?;
+    Ok(redacted)
+}
+
+fn current_disclosures(
+    report: &RunReport,
+) -> Result<(usize, String, Vec<EventDisclosure>), ErrorItem> {
+    if let Some(existing) = report.redaction.as_ref() {
+        if existing.disclosures.len() != report.events.len() {
+            return Err(redact_error(
+                "redaction.disclosures",
+                "redaction disclosures do not match the disclosed events",
+            ));
+        }
+        return Ok((
+            existing.leaf_count,
+            existing.original_digest.clone(),
+            existing.disclosures.clone(),
+        ));
+    }
+
+    let seed = report.integrity.field_salt_seed.as_deref();
+    let leaves = event_leaves(&report.events, seed);
+    let mut disclosures = Vec::with_capacity(leaves.len());
+    for index in 0..leaves.len() {
+        let proof = merkle_inclusion_proof(&leaves, index).ok_or_else(|| {
+            redact_error(
+                "redaction.proof",
+                format!("failed to build inclusion proof for event {index}"),
+            )
+        })?;
+        disclosures.push(EventDisclosure {
+            index,
+            redacted_fields: BTreeMap::new(),
+            field_salts: payload_field_salts(&report.events[index], index, seed),
+            proof,
+        });
+    }
+    Ok((leaves.len(), report.integrity.digest.clone(), disclosures))
+}
+
+/// Blank `hashSelf` of events with redacted fields and `hashPrev` of the event right after
+/// them; both hash the full payload.
+fn withhold_chain_hashes(events: &mut [EvidenceEvent], disclosures: &[EventDisclosure]) {
+    let mut previous: Option<&EventDisclosure> = None;
+    for (event, disclosure) in events.iter_mut().zip(disclosures) {
+        if previous.is_some_and(|prev| {
+            prev.index + 1 == disclosure.index && !prev.redacted_fields.is_empty()
+        }) {
+            event.hash_prev.clear();
+        }
+        if !disclosure.redacted_fields.is_empty() {
+            event.hash_self.clear();
+        }
+        previous = Some(disclosure);
+    }
+}
+
+/// Summary fields built from event contents; a redacted copy cannot keep them without
+/// restating what was removed.
+const WITHHELD_SUMMARIES: [&str; 4] = [
+    "mountAudit.reasons",
+    "mountAudit.outputs",
+    "mountAudit.scans",
+    "policySummary.parameters",
+];
+
+/// Clear the `WITHHELD_SUMMARIES` fields and return the ones that had content.
+fn withhold_summaries(report: &mut RunReport) -> Vec<String> {
+    let present = [
+        !report.mount_audit.reasons.is_empty(),
+        !report.mount_audit.outputs.is_empty(),
+        !report.mount_audit.scans.is_empty(),
+        !report.policy_summary.parameters.is_empty(),
+    ];
+    report.mount_audit.reasons.clear();
+    report.mount_audit.outputs.clear();
+    report.mount_audit.scans.clear();
+    report.policy_summary.parameters.clear();
+    WITHHELD_SUMMARIES
+        .iter()
+        .zip(present)
+        .filter(|(_, present)| *present)
+        .map(|(summary, _)| summary.to_string())
+        .collect()
+}
+
+fn redact_error(path: impl Into<String>, message: impl Into<String>) -> ErrorItem {
+    ErrorItem::new(SR_EVD_002, path, message)
+}
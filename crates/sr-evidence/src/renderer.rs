@@ -0,0 +1,401 @@
+use crate::{
+    verify_report, EvidenceEvent, RunReport, VerifyResult, EVENT_RESOURCE_SAMPLED, STAGE_CLEANUP,
+    STAGE_COMPILE, STAGE_LAUNCH, STAGE_MONITOR, STAGE_MOUNT, STAGE_PREPARE,
+};
+use sr_common::{ErrorItem, SR_EVD_002};
+use std::fmt::Write;
+
+const STAGE_ORDER: [&str; 6] = [
+    STAGE_COMPILE,
+    STAGE_PREPARE,
+    STAGE_MOUNT,
+    STAGE_LAUNCH,
+    STAGE_MONITOR,
+    STAGE_CLEANUP,
+];
+const CHART_WIDTH: f64 = 640.0;
+const CHART_HEIGHT: f64 = 160.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderFormat {
+    Html,
+    Markdown,
+}
+
+impl RenderFormat {
+    /// Parse `html` or `md`/`markdown`.
+    /// Error mapping: unknown formats -> `SR-EVD-002`.
+    pub fn parse(raw: &str) -> Result<Self, ErrorItem> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "html" => Ok(Self::Html),
+            "md" | "markdown" => Ok(Self::Markdown),
+            other => Err(ErrorItem::new(
+                SR_EVD_002,
+                "render.format",
+                format!("unsupported render format '{other}', expected html or md"),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ResourcePoint {
+    cpu_usage_usec: u64,
+    memory_current_bytes: u64,
+}
+
+/// Render a self-contained audit view of `report`.
+/// The verification result is recomputed so the rendered file never trusts stale metadata.
+pub fn render_report(report: &RunReport, format: RenderFormat) -> String {
+    let verification = verify_report(report);
+    match format {
+        RenderFormat::Html => render_html(report, &verification),
+        RenderFormat::Markdown => render_markdown(report, &verification),
+    }
+}
+
+fn render_markdown(report: &RunReport, verification: &VerifyResult) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# Safe-Run report `{}`\n", report.run_id);
+    let _ = writeln!(out, "| Field | Value |\n| --- | --- |");
+    for (label, value) in summary_rows(report) {
+        let _ = writeln!(out, "| {label} | {} |", md_cell(&value));
+    }
+
+    let _ = writeln!(out, "\n## Verification\n");
+    let _ = writeln!(
+        out,
+        "Result: **{}**\n",
+        if verification.valid { "pass" } else { "fail" }
+    );
+    let _ = writeln!(out, "| Check | Status |\n| --- | --- |");
+    for check in &verification.checks {
+        let _ = writeln!(out, "| {} | {} |", check.name, ok_label(check.ok));
+    }
+    for err in &verification.errors {
+        let _ = writeln!(
+            out,
+            "\n- `{}` at `{}`: {}",
+            err.code,
+            err.path,
+            md_cell(&err.message)
+        );
+    }
+
+    let _ = writeln!(out, "\n## Mount audit\n");
+    let _ = writeln!(
+        out,
+        "| Requested | Accepted | Rejected | Reasons |\n| --- | --- | --- | --- |"
+    );
+    let _ = writeln!(
+        out,
+        "| {} | {} | {} | {} |",
+        report.mount_audit.requested,
+        report.mount_audit.accepted,
+        report.mount_audit.rejected,
+        md_cell(&report.mount_audit.reasons.join(", "))
+    );
+
+    let _ = writeln!(out, "\n## Network audit\n");
+    let _ = writeln!(
+        out,
+        "| Mode | Rules | Allowed hits | Blocked hits |\n| --- | --- | --- | --- |"
+    );
+    let _ = writeln!(
+        out,
+        "| {} | {} | {} | {} |",
+        report.network_audit.mode,
+        report.network_audit.rules_total,
+        report.network_audit.allowed_hits,
+        report.network_audit.blocked_hits
+    );
+
+    let points = resource_points(&report.events);
+    let _ = writeln!(out, "\n## Resource usage\n");
+    if points.is_empty() {
+        let _ = writeln!(out, "No `resource.sampled` events were recorded.");
+    } else {
+        let memory = points
+            .iter()
+            .map(|point| point.memory_current_bytes)
+            .collect::<Vec<u64>>();
+        let _ = writeln!(out, "Memory: `{}`\n", sparkline(&memory));
+        let _ = writeln!(
+            out,
+            "| Sample | cpuUsageUsec | memoryCurrentBytes |\n| --- | --- | --- |"
+        );
+        for (idx, point) in points.iter().enumerate() {
+            let _ = writeln!(
+                out,
+                "| {idx} | {} | {} |",
+                point.cpu_usage_usec, point.memory_current_bytes
+            );
+        }
+    }
+
+    let _ = writeln!(out, "\n## Timeline\n");
+    for (stage, events) in events_by_stage(&report.events) {
+        let _ = writeln!(out, "### {stage}\n");
+        let _ = writeln!(out, "| Time | Type | Payload |\n| --- | --- | --- |");
+        for event in events {
+            let _ = writeln!(
+                out,
+                "| {} | {} | `{}` |",
+                event.timestamp,
+                event.event_type,
+                md_cell(&event.payload.to_string())
+            );
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn render_html(report: &RunReport, verification: &VerifyResult) -> String {
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>Safe-Run report {}</title>\n<style>{}</style>\n</head>\n<body>\n",
+        html_escape(&report.run_id),
+        HTML_STYLE
+    );
+    let _ = writeln!(
+        out,
+        "<h1>Safe-Run report <code>{}</code></h1>",
+        html_escape(&report.run_id)
+    );
+    out.push_str("<table>\n");
+    for (label, value) in summary_rows(report) {
+        let _ = writeln!(
+            out,
+            "<tr><th>{label}</th><td>{}</td></tr>",
+            html_escape(&value)
+        );
+    }
+    out.push_str("</table>\n");
+
+    let status = if verification.valid { "pass" } else { "fail" };
+    let _ = writeln!(
+        out,
+        "<h2>Verification</h2>\n<p class=\"{status}\">Result: <strong>{status}</strong></p>"
+    );
+    out.push_str("<table>\n<tr><th>Check</th><th>Status</th></tr>\n");
+    for check in &verification.checks {
+        let _ = writeln!(
+            out,
+            "<tr><td>{}</td><td class=\"{}\">{}</td></tr>",
+            html_escape(&check.name),
+            if check.ok { "pass" } else { "fail" },
+            ok_label(check.ok)
+        );
+    }
+    out.push_str("</table>\n");
+    if !verification.errors.is_empty() {
+        out.push_str("<ul>\n");
+        for err in &verification.errors {
+            let _ = writeln!(
+                out,
+                "<li><code>{}</code> at <code>{}</code>: {}</li>",
+                html_escape(&err.code),
+                html_escape(&err.path),
+                html_escape(&err.message)
+            );
+        }
+        out.push_str("</ul>\n");
+    }
+
+    let _ = writeln!(
+        out,
+        "<h2>Mount audit</h2>\n<table>\n<tr><th>Requested</th><th>Accepted</th><th>Rejected</th><th>Reasons</th></tr>\n<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n</table>",
+        report.mount_audit.requested,
+        report.mount_audit.accepted,
+        report.mount_audit.rejected,
+        html_escape(&report.mount_audit.reasons.join(", "))
+    );
+    let _ = writeln!(
+        out,
+        "<h2>Network audit</h2>\n<table>\n<tr><th>Mode</th><th>Rules</th><th>Allowed hits</th><th>Blocked hits</th></tr>\n<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n</table>",
+        html_escape(&report.network_audit.mode),
+        report.network_audit.rules_total,
+        report.network_audit.allowed_hits,
+        report.network_audit.blocked_hits
+    );
+
+    out.push_str("<h2>Resource usage</h2>\n");
+    let points = resource_points(&report.events);
+    if points.is_empty() {
+        out.push_str("<p>No <code>resource.sampled</code> events were recorded.</p>\n");
+    } else {
+        out.push_str(&resource_chart_svg(&points));
+    }
+
+    out.push_str("<h2>Timeline</h2>\n");
+    for (stage, events) in events_by_stage(&report.events) {
+        let _ = writeln!(
+            out,
+            "<h3>{}</h3>\n<table>\n<tr><th>Time</th><th>Type</th><th>Payload</th></tr>",
+            html_escape(&stage)
+        );
+        for event in events {
+            let _ = writeln!(
+                out,
+                "<tr><td>{}</td><td>{}</td><td><code>{}</code></td></tr>",
+                html_escape(&event.timestamp),
+                html_escape(&event.event_type),
+                html_escape(&event.payload.to_string())
+            );
+        }
+        out.push_str("</table>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+const HTML_STYLE: &str = "body{font-family:sans-serif;margin:2em;color:#222}table{border-collapse:collapse;margin:0.5em 0}th,td{border:1px solid #ccc;padding:4px 8px;text-align:left;vertical-align:top}code{font-size:0.9em}.pass{color:#1a7f37}.fail{color:#cf222e}svg{border:1px solid #ccc}";
+
+fn summary_rows(report: &RunReport) -> Vec<(&'static str, String)> {
+    let mut rows = vec![
+        ("Schema", report.schema_version.clone()),
+        ("Started", report.started_at.clone()),
+        ("Finished", report.finished_at.clone()),
+        ("Exit code", report.exit_code.to_string()),
+        ("Network", report.policy_summary.network.clone()),
+        ("Mounts", report.policy_summary.mounts.to_string()),
+        ("Kernel hash", report.artifacts.kernel_hash.clone()),
+        ("Rootfs hash", report.artifacts.rootfs_hash.clone()),
+        ("Policy hash", report.artifacts.policy_hash.clone()),
+        ("Command hash", report.artifacts.command_hash.clone()),
+        ("CPU", report.resource_usage.cpu.clone()),
+        ("Memory", report.resource_usage.memory.clone()),
+        ("Integrity digest", report.integrity.digest.clone()),
+    ];
+    if !report.policy_summary.parameters.is_empty() {
+        let bound = report
+            .policy_summary
+            .parameters
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        rows.insert(6, ("Parameters", bound));
+    }
+    if let Some(hash) = report.policy_summary.admission_constraints_hash.as_ref() {
+        let at = rows
+            .iter()
+            .position(|(label, _)| *label == "Command hash")
+            .map_or(rows.len(), |idx| idx + 1);
+        rows.insert(at, ("Admission constraints hash", hash.clone()));
+    }
+    rows
+}
+
+/// Group events by stage in pipeline order; unknown stages follow in first-seen order.
+fn events_by_stage(events: &[EvidenceEvent]) -> Vec<(String, Vec<&EvidenceEvent>)> {
+    let mut groups: Vec<(String, Vec<&EvidenceEvent>)> = STAGE_ORDER
+        .iter()
+        .map(|stage| (stage.to_string(), Vec::new()))
+        .collect();
+    for event in events {
+        match groups.iter_mut().find(|(stage, _)| *stage == event.stage) {
+            Some((_, items)) => items.push(event),
+            None => groups.push((event.stage.clone(), vec![event])),
+        }
+    }
+    groups.retain(|(_, items)| !items.is_empty());
+    groups
+}
+
+fn resource_points(events: &[EvidenceEvent]) -> Vec<ResourcePoint> {
+    events
+        .iter()
+        .filter(|event| event.event_type == EVENT_RESOURCE_SAMPLED)
+        .map(|event| ResourcePoint {
+            cpu_usage_usec: event
+                .payload
+                .get("cpuUsageUsec")
+                .and_then(|value| value.as_u64())
+                .unwrap_or(0),
+            memory_current_bytes: event
+                .payload
+                .get("memoryCurrentBytes")
+                .and_then(|value| value.as_u64())
+                .unwrap_or(0),
+        })
+        .collect()
+}
+
+fn resource_chart_svg(points: &[ResourcePoint]) -> String {
+    let memory = points
+        .iter()
+        .map(|point| point.memory_current_bytes)
+        .collect::<Vec<u64>>();
+    let cpu = points
+        .iter()
+        .map(|point| point.cpu_usage_usec)
+        .collect::<Vec<u64>>();
+    let peak_memory = memory.iter().copied().max().unwrap_or(0);
+    let peak_cpu = cpu.iter().copied().max().unwrap_or(0);
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n<polyline fill=\"none\" stroke=\"#0969da\" stroke-width=\"2\" points=\"{mem}\"/>\n<polyline fill=\"none\" stroke=\"#bf8700\" stroke-width=\"2\" points=\"{cpu}\"/>\n</svg>\n<p><span style=\"color:#0969da\">memoryCurrentBytes</span> (peak {peak_memory}) / <span style=\"color:#bf8700\">cpuUsageUsec</span> (peak {peak_cpu}), {count} samples</p>\n",
+        w = CHART_WIDTH,
+        h = CHART_HEIGHT,
+        mem = polyline_points(&memory),
+        cpu = polyline_points(&cpu),
+        count = points.len()
+    )
+}
+
+fn polyline_points(values: &[u64]) -> String {
+    let max = values.iter().copied().max().unwrap_or(0).max(1) as f64;
+    let step = if values.len() > 1 {
+        CHART_WIDTH / (values.len() - 1) as f64
+    } else {
+        0.0
+    };
+    values
+        .iter()
+        .enumerate()
+        .map(|(idx, value)| {
+            let x = idx as f64 * step;
+            let y = CHART_HEIGHT - (*value as f64 / max) * CHART_HEIGHT;
+            format!("{x:.1},{y:.1}")
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn sparkline(values: &[u64]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().copied().max().unwrap_or(0).max(1);
+    values
+        .iter()
+        .map(|value| BARS[((*value as u128 * 7) / max as u128) as usize])
+        .collect()
+}
+
+fn ok_label(ok: bool) -> &'static str {
+    if ok {
+        "ok"
+    } else {
+        "failed"
+    }
+}
+
+fn md_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', " ")
+}
+
+fn html_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
@@ -1,9 +1,12 @@
 use crate::hashing::{sha256_file, sha256_json_value};
+use crate::merkle::{compute_event_merkle_root, random_field_salt_seed};
 use crate::{
-    EvidenceEvent, Integrity, MountAudit, NetworkAudit, PolicySummary, ReportArtifacts,
-    ResourceUsage, RunReport, EVENT_MOUNT_APPLIED, EVENT_MOUNT_REJECTED, EVENT_MOUNT_VALIDATED,
-    EVENT_NETWORK_PLAN_GENERATED, EVENT_NETWORK_RULE_APPLIED, EVENT_NETWORK_RULE_HIT,
-    EVENT_RESOURCE_SAMPLED, RUN_REPORT_SCHEMA_VERSION,
+    EvidenceEvent, Integrity, IoCounters, MemoryEventCounters, MountAudit, MountOutput,
+    MountScanSummary, NetworkAudit, PolicySummary, ReportArtifacts, ResourceSeriesPoint,
+    ResourceUsage, RunReport, EVENT_MOUNT_APPLIED, EVENT_MOUNT_OUTPUT_COLLECTED,
+    EVENT_MOUNT_REJECTED, EVENT_MOUNT_SCAN, EVENT_MOUNT_VALIDATED, EVENT_NETWORK_PLAN_GENERATED,
+    EVENT_NETWORK_RULE_APPLIED, EVENT_NETWORK_RULE_HIT, EVENT_RESOURCE_SAMPLED,
+    RUN_REPORT_SCHEMA_VERSION,
 };
 use serde_json::Value;
 use sr_common::{ErrorItem, SR_EVD_002};
@@ -98,6 +101,10 @@ pub fn compute_artifact_hashes_from_json(
 }
 
 /// Assemble a `safe-run.report/v1` report with a precomputed integrity digest.
+/// The event Merkle root is derived here, under a fresh field salt seed, so the digest
+/// always covers it.
+/// Error mapping: no randomness for the salt seed -> `SR-EVD-002`.
+#[allow(clippy::too_many_arguments)]
 pub fn build_report(
     run_id: String,
     started_at: String,
@@ -110,8 +117,10 @@ pub fn build_report(
     mount_audit: MountAudit,
     network_audit: NetworkAudit,
     integrity_digest: String,
-) -> RunReport {
-    RunReport {
+) -> Result<RunReport, ErrorItem> {
+    let field_salt_seed = random_field_salt_seed()?;
+    let merkle_root = compute_event_merkle_root(&events, Some(&field_salt_seed));
+    Ok(RunReport {
         schema_version: RUN_REPORT_SCHEMA_VERSION.to_string(),
         run_id,
         started_at,
@@ -125,10 +134,13 @@ pub fn build_report(
         network_audit,
         archive: None,
         verification: None,
+        redaction: None,
         integrity: Integrity {
             digest: integrity_digest,
+            merkle_root,
+            field_salt_seed: Some(field_salt_seed),
         },
-    }
+    })
 }
 
 /// Derive `(started_at, finished_at)` from event stream boundaries.
@@ -149,39 +161,124 @@ pub fn event_time_range(events: &[EvidenceEvent]) -> (String, String) {
     (started_at, finished_at)
 }
 
-/// Extract resource summary from latest `resource.sampled` event.
+/// Upper bound on points kept in `resourceUsage.series`.
+pub const RESOURCE_SERIES_MAX_POINTS: usize = 60;
+
+/// Aggregate resource summary from `resource.sampled` events.
+/// `cpu`/`memory` keep the latest-sample string form; numeric aggregates are filled from
+/// all samples. Optional cgroup counters (`memory.events`, `io.stat`, `pids.current`) come
+/// from the latest sample that carries them.
 /// Returns zero-valued defaults when event stream has no resource samples.
 pub fn resource_usage_from_events(events: &[EvidenceEvent]) -> ResourceUsage {
-    for event in events.iter().rev() {
-        if event.event_type != EVENT_RESOURCE_SAMPLED {
-            continue;
-        }
-        let cpu = event
+    let samples = events
+        .iter()
+        .filter(|event| event.event_type == EVENT_RESOURCE_SAMPLED)
+        .collect::<Vec<&EvidenceEvent>>();
+    let Some(last) = samples.last() else {
+        return ResourceUsage {
+            cpu: "cpuUsageUsec=0".to_string(),
+            memory: "memoryCurrentBytes=0".to_string(),
+            ..ResourceUsage::default()
+        };
+    };
+
+    let cpu_total = payload_u64(&last.payload, "cpuUsageUsec");
+    let memory_current = payload_u64(&last.payload, "memoryCurrentBytes");
+    let points = samples
+        .iter()
+        .map(|event| ResourceSeriesPoint {
+            timestamp: event.timestamp.clone(),
+            cpu_usage_usec: payload_u64(&event.payload, "cpuUsageUsec").unwrap_or(0),
+            memory_current_bytes: payload_u64(&event.payload, "memoryCurrentBytes").unwrap_or(0),
+        })
+        .collect::<Vec<ResourceSeriesPoint>>();
+
+    let memory_peak = samples
+        .iter()
+        .filter_map(|event| {
+            payload_u64(&event.payload, "memoryPeakBytes")
+                .or_else(|| payload_u64(&event.payload, "memoryCurrentBytes"))
+        })
+        .max();
+    let pids_peak = samples
+        .iter()
+        .filter_map(|event| payload_u64(&event.payload, "pidsCurrent"))
+        .max();
+    let memory_events = samples.iter().rev().find_map(|event| {
+        event
             .payload
-            .get("cpuUsageUsec")
-            .and_then(|value| value.as_u64())
-            .map(|value| format!("cpuUsageUsec={value}"))
-            .unwrap_or_else(|| "cpuUsageUsec=0".to_string());
-        let memory = event
+            .get("memoryEvents")
+            .and_then(|value| serde_json::from_value::<MemoryEventCounters>(value.clone()).ok())
+    });
+    let io = samples.iter().rev().find_map(|event| {
+        event
             .payload
-            .get("memoryCurrentBytes")
-            .and_then(|value| value.as_u64())
-            .map(|value| format!("memoryCurrentBytes={value}"))
-            .unwrap_or_else(|| "memoryCurrentBytes=0".to_string());
-        return ResourceUsage { cpu, memory };
-    }
+            .get("io")
+            .and_then(|value| serde_json::from_value::<IoCounters>(value.clone()).ok())
+    });
+
     ResourceUsage {
-        cpu: "cpuUsageUsec=0".to_string(),
-        memory: "memoryCurrentBytes=0".to_string(),
+        cpu: format!("cpuUsageUsec={}", cpu_total.unwrap_or(0)),
+        memory: format!("memoryCurrentBytes={}", memory_current.unwrap_or(0)),
+        sample_count: samples.len() as u64,
+        cpu_total_usec: cpu_total,
+        cpu_avg_millicores: average_cpu_millicores(&points),
+        memory_peak_bytes: memory_peak,
+        memory_events,
+        io,
+        pids_current: payload_u64(&last.payload, "pidsCurrent"),
+        pids_peak,
+        series: Some(downsample_series(points, RESOURCE_SERIES_MAX_POINTS)),
     }
 }
 
+fn payload_u64(payload: &Value, key: &str) -> Option<u64> {
+    payload.get(key).and_then(|value| value.as_u64())
+}
+
+/// Average CPU between first and last sample, in millicores (1000 = one full core).
+/// Requires two samples with `unix:<sec>.<nsec>` timestamps and a positive wall-clock delta.
+fn average_cpu_millicores(points: &[ResourceSeriesPoint]) -> Option<u64> {
+    let (first, last) = (points.first()?, points.last()?);
+    let elapsed_nanos = parse_unix_nanos(&last.timestamp)?
+        .checked_sub(parse_unix_nanos(&first.timestamp)?)
+        .filter(|value| *value > 0)?;
+    let cpu_usec = last.cpu_usage_usec.checked_sub(first.cpu_usage_usec)?;
+    let millicores = u128::from(cpu_usec) * 1_000_000 / elapsed_nanos;
+    u64::try_from(millicores).ok()
+}
+
+fn parse_unix_nanos(timestamp: &str) -> Option<u128> {
+    let raw = timestamp.strip_prefix("unix:")?;
+    let (secs, nanos) = raw.split_once('.').unwrap_or((raw, "0"));
+    let secs = secs.parse::<u128>().ok()?;
+    let nanos = format!("{nanos:0<9}").get(..9)?.parse::<u128>().ok()?;
+    Some(secs * 1_000_000_000 + nanos)
+}
+
+/// Keep at most `max_points` samples, evenly strided; first and last are always kept.
+fn downsample_series(
+    points: Vec<ResourceSeriesPoint>,
+    max_points: usize,
+) -> Vec<ResourceSeriesPoint> {
+    if points.len() <= max_points || max_points < 2 {
+        return points;
+    }
+    let last_index = points.len() - 1;
+    let slots = max_points - 1;
+    (0..max_points)
+        .map(|slot| points[slot * last_index / slots].clone())
+        .collect()
+}
+
 pub fn mount_audit_from_events(events: &[EvidenceEvent]) -> MountAudit {
     let mut requested = 0usize;
     let mut accepted = 0usize;
     let mut rejected = 0usize;
     let mut applied = 0usize;
     let mut reasons: Vec<String> = Vec::new();
+    let mut outputs = Vec::new();
+    let mut scans = Vec::new();
 
     for event in events {
         match event.event_type.as_str() {
@@ -197,6 +294,17 @@ pub fn mount_audit_from_events(events: &[EvidenceEvent]) -> MountAudit {
             EVENT_MOUNT_APPLIED => {
                 applied += 1;
             }
+            EVENT_MOUNT_OUTPUT_COLLECTED => {
+                if let Ok(output) = serde_json::from_value::<MountOutput>(event.payload.clone()) {
+                    outputs.push(output);
+                }
+            }
+            EVENT_MOUNT_SCAN => {
+                if let Ok(scan) = serde_json::from_value::<MountScanSummary>(event.payload.clone())
+                {
+                    scans.push(scan);
+                }
+            }
             _ => {}
         }
     }
@@ -213,6 +321,8 @@ pub fn mount_audit_from_events(events: &[EvidenceEvent]) -> MountAudit {
         accepted,
         rejected,
         reasons,
+        outputs,
+        scans,
     }
 }
 
@@ -0,0 +1,255 @@
+use crate::archiver::validate_bundle_id;
+use crate::hashing::sha256_json_value;
+use crate::index::{
+    load_archive_index, lock_index, remove_archive_index_entries, ArchiveIndexEntry,
+};
+use crate::load_archived_report;
+use serde::{Deserialize, Serialize};
+use sr_common::{ErrorItem, SR_OPS_301};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
+
+const PRUNE_LOG_FILE: &str = "prune-log.jsonl";
+const GENESIS_HASH: &str =
+    "sha256:0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Parse a retention string such as `30d`, `12h` or `2w`.
+/// `forever` (or `none`) disables expiry and yields `None`.
+/// Supported units: `s`, `m`, `h`, `d`, `w`.
+pub fn parse_retention(raw: &str) -> Result<Option<Duration>, ErrorItem> {
+    let trimmed = raw.trim();
+    if trimmed.eq_ignore_ascii_case("forever") || trimmed.eq_ignore_ascii_case("none") {
+        return Ok(None);
+    }
+    let split = trimmed
+        .find(|ch: char| !ch.is_ascii_digit())
+        .unwrap_or(trimmed.len());
+    let (amount, unit) = trimmed.split_at(split);
+    let amount = amount.parse::<u64>().map_err(|_| retention_error(raw))?;
+    let unit_secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3_600,
+        "d" => 86_400,
+        "w" => 604_800,
+        _ => return Err(retention_error(raw)),
+    };
+    amount
+        .checked_mul(unit_secs)
+        .map(|secs| Some(Duration::from_secs(secs)))
+        .ok_or_else(|| retention_error(raw))
+}
+
+/// Seconds part of a `unix:<sec>.<nsec>` timestamp.
+pub fn parse_unix_seconds(timestamp: &str) -> Option<u64> {
+    let raw = timestamp.strip_prefix("unix:")?;
+    raw.split('.').next()?.parse::<u64>().ok()
+}
+
+/// One tamper-evident deletion record in `prune-log.jsonl`.
+/// `hashSelf` is the normalized-JSON sha256 of the record with `hashSelf` set to "".
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PruneLogRecord {
+    #[serde(rename = "prunedAt")]
+    pub pruned_at: String,
+    #[serde(rename = "bundleId")]
+    pub bundle_id: String,
+    #[serde(rename = "runId")]
+    pub run_id: String,
+    #[serde(rename = "storedAt")]
+    pub stored_at: String,
+    pub retention: String,
+    #[serde(
+        rename = "reportDigest",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub report_digest: Option<String>,
+    #[serde(rename = "hashPrev")]
+    pub hash_prev: String,
+    #[serde(rename = "hashSelf")]
+    pub hash_self: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct PruneOutcome {
+    #[serde(rename = "dryRun")]
+    pub dry_run: bool,
+    pub pruned: Vec<ArchiveIndexEntry>,
+    pub kept: usize,
+}
+
+/// Remove bundles whose `storedAt + retention` is at or before `now_secs`.
+/// Each deletion is appended to the hash-chained prune log before the bundle directory
+/// is removed, so an interrupted prune never loses the record of what was deleted.
+/// Entries with an unparseable retention or timestamp are kept.
+pub fn prune_archive(
+    archive_root: &Path,
+    now_secs: u64,
+    dry_run: bool,
+) -> Result<PruneOutcome, ErrorItem> {
+    // The index lock is held from reading the index until its `remove` records are written,
+    // so the expired set cannot go stale; it also serializes prune log appends.
+    let lock = lock_index(archive_root)?;
+    let index = load_archive_index(archive_root)?;
+    let (expired, kept): (Vec<_>, Vec<_>) = index
+        .entries
+        .into_iter()
+        .partition(|entry| is_expired(entry, now_secs));
+
+    let outcome = PruneOutcome {
+        dry_run,
+        pruned: expired.clone(),
+        kept: kept.len(),
+    };
+    if dry_run || expired.is_empty() {
+        return Ok(outcome);
+    }
+    // Bundle ids come from the index file; never let one steer `remove_dir_all`.
+    for entry in &expired {
+        validate_bundle_id(&entry.bundle_id)?;
+    }
+
+    let pruned_at = format!("unix:{now_secs}.000000000");
+    for entry in &expired {
+        let report_digest = load_archived_report(archive_root, &entry.bundle_id)
+            .ok()
+            .map(|report| report.integrity.digest);
+        append_prune_log(archive_root, entry, &pruned_at, report_digest)?;
+        let bundle_dir = archive_root.join(&entry.bundle_id);
+        if bundle_dir.exists() {
+            fs::remove_dir_all(&bundle_dir).map_err(|err| {
+                ErrorItem::new(
+                    SR_OPS_301,
+                    "archive.prune",
+                    format!(
+                        "failed to remove archive bundle '{}': {err}",
+                        bundle_dir.display()
+                    ),
+                )
+            })?;
+        }
+    }
+
+    let pruned_ids = expired
+        .iter()
+        .map(|entry| entry.bundle_id.clone())
+        .collect::<Vec<String>>();
+    remove_archive_index_entries(archive_root, &pruned_ids, &lock)?;
+    Ok(outcome)
+}
+
+/// Load the prune log; a missing log is an empty log.
+pub fn load_prune_log(archive_root: &Path) -> Result<Vec<PruneLogRecord>, ErrorItem> {
+    let path = archive_root.join(PRUNE_LOG_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let raw = fs::read_to_string(&path).map_err(|err| {
+        ErrorItem::new(
+            SR_OPS_301,
+            "archive.pruneLog",
+            format!("failed to read prune log '{}': {err}", path.display()),
+        )
+    })?;
+    raw.lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(idx, line)| {
+            serde_json::from_str::<PruneLogRecord>(line).map_err(|err| {
+                ErrorItem::new(
+                    SR_OPS_301,
+                    format!("archive.pruneLog[{idx}]"),
+                    format!("failed to parse prune log record: {err}"),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Check the prune log hash chain; returns the first broken record as an error.
+pub fn verify_prune_log(archive_root: &Path) -> Result<usize, ErrorItem> {
+    let records = load_prune_log(archive_root)?;
+    let mut expected_prev = GENESIS_HASH.to_string();
+    for (idx, record) in records.iter().enumerate() {
+        if record.hash_prev != expected_prev || record.hash_self != prune_record_hash(record) {
+            return Err(ErrorItem::new(
+                SR_OPS_301,
+                format!("archive.pruneLog[{idx}]"),
+                "prune log hash chain is broken",
+            ));
+        }
+        expected_prev = record.hash_self.clone();
+    }
+    Ok(records.len())
+}
+
+fn is_expired(entry: &ArchiveIndexEntry, now_secs: u64) -> bool {
+    let Ok(Some(retention)) = parse_retention(&entry.retention) else {
+        return false;
+    };
+    let Some(stored_at) = parse_unix_seconds(&entry.stored_at) else {
+        return false;
+    };
+    stored_at.saturating_add(retention.as_secs()) <= now_secs
+}
+
+fn append_prune_log(
+    archive_root: &Path,
+    entry: &ArchiveIndexEntry,
+    pruned_at: &str,
+    report_digest: Option<String>,
+) -> Result<(), ErrorItem> {
+    let hash_prev = load_prune_log(archive_root)?
+        .last()
+        .map(|record| record.hash_self.clone())
+        .unwrap_or_else(|| GENESIS_HASH.to_string());
+    let mut record = PruneLogRecord {
+        pruned_at: pruned_at.to_string(),
+        bundle_id: entry.bundle_id.clone(),
+        run_id: entry.run_id.clone(),
+        stored_at: entry.stored_at.clone(),
+        retention: entry.retention.clone(),
+        report_digest,
+        hash_prev,
+        hash_self: String::new(),
+    };
+    record.hash_self = prune_record_hash(&record);
+
+    let path = archive_root.join(PRUNE_LOG_FILE);
+    let line = serde_json::to_string(&record).map_err(|err| {
+        ErrorItem::new(
+            SR_OPS_301,
+            "archive.pruneLog",
+            format!("failed to serialize prune log record: {err}"),
+        )
+    })?;
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| writeln!(file, "{line}"))
+        .map_err(|err| {
+            ErrorItem::new(
+                SR_OPS_301,
+                "archive.pruneLog",
+                format!("failed to append prune log '{}': {err}", path.display()),
+            )
+        })
+}
+
+fn prune_record_hash(record: &PruneLogRecord) -> String {
+    let mut material = record.clone();
+    material.hash_self = String::new();
+    sha256_json_value(&serde_json::to_value(&material).unwrap_or_default())
+}
+
+fn retention_error(raw: &str) -> ErrorItem {
+    ErrorItem::new(
+        SR_OPS_301,
+        "archive.retention",
+        format!("invalid retention '{raw}', expected <number><s|m|h|d|w> or 'forever'"),
+    )
+}
@@ -0,0 +1,500 @@
+use crate::store::{
+    store_error, validate_object_key, write_once_error, ArchiveStore, ObjectLock,
+    ARCHIVE_OBJECT_LOCK_MODE,
+};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use sr_common::ErrorItem;
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::net::IpAddr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const S3_SERVICE: &str = "s3";
+const SIGNING_ALGORITHM: &str = "AWS4-HMAC-SHA256";
+const DEFAULT_S3_REGION: &str = "us-east-1";
+const S3_IO_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Connection settings for an S3-compatible bucket (AWS S3 with object lock, MinIO, ...).
+/// Endpoints use `https://`; plain `http://` is accepted only for loopback hosts such as a
+/// local MinIO or TLS-terminating proxy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct S3StoreConfig {
+    pub endpoint: String,
+    pub bucket: String,
+    /// Key prefix inside the bucket; empty or ending with `/`.
+    pub prefix: String,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+}
+
+impl S3StoreConfig {
+    /// Build a config from an `s3://bucket[/prefix]` archive URL.
+    pub fn from_url(
+        url: &str,
+        endpoint: &str,
+        region: Option<&str>,
+        access_key_id: &str,
+        secret_access_key: &str,
+    ) -> Result<Self, ErrorItem> {
+        let rest = url
+            .strip_prefix("s3://")
+            .ok_or_else(|| store_error(format!("archive URL '{url}' must start with s3://")))?;
+        let (bucket, prefix) = rest.split_once('/').unwrap_or((rest, ""));
+        if bucket.is_empty() {
+            return Err(store_error(format!("archive URL '{url}' has no bucket")));
+        }
+        let prefix = prefix.trim_matches('/');
+        Ok(Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            bucket: bucket.to_string(),
+            prefix: if prefix.is_empty() {
+                String::new()
+            } else {
+                format!("{prefix}/")
+            },
+            region: region
+                .filter(|value| !value.trim().is_empty())
+                .unwrap_or(DEFAULT_S3_REGION)
+                .to_string(),
+            access_key_id: access_key_id.to_string(),
+            secret_access_key: secret_access_key.to_string(),
+        })
+    }
+}
+
+/// `ArchiveStore` over the S3 REST API with SigV4 signing and path-style addressing.
+/// Puts are conditional (`If-None-Match: *`) and carry object-lock headers derived from
+/// the bundle retention, so the bucket must have object lock enabled to enforce them.
+#[derive(Debug, Clone)]
+pub struct S3ArchiveStore {
+    config: S3StoreConfig,
+    host: String,
+    agent: ureq::Agent,
+}
+
+struct HttpResponse {
+    status: u16,
+    body: Vec<u8>,
+}
+
+impl S3ArchiveStore {
+    /// Error mapping: endpoints other than `https://host[:port]` or loopback
+    /// `http://host[:port]` -> `SR-OPS-301`.
+    pub fn new(config: S3StoreConfig) -> Result<Self, ErrorItem> {
+        let (host, tls) = if let Some(host) = config.endpoint.strip_prefix("https://") {
+            (host, true)
+        } else if let Some(host) = config.endpoint.strip_prefix("http://") {
+            (host, false)
+        } else {
+            return Err(store_error(format!(
+                "S3 endpoint '{}' must start with https://",
+                config.endpoint
+            )));
+        };
+        let host = host.trim_end_matches('/').to_string();
+        if host.is_empty() || host.contains(['/', '?', '#', '@']) {
+            return Err(store_error(format!(
+                "S3 endpoint '{}' must be https://host[:port]",
+                config.endpoint
+            )));
+        }
+        if !tls && !is_loopback_host(&host) {
+            return Err(store_error(format!(
+                "S3 endpoint '{}' uses plain http to a non-loopback host; use https",
+                config.endpoint
+            )));
+        }
+        let agent = ureq::AgentBuilder::new()
+            .timeout_connect(S3_IO_TIMEOUT)
+            .timeout_read(S3_IO_TIMEOUT)
+            .timeout_write(S3_IO_TIMEOUT)
+            .redirects(0)
+            .build();
+        Ok(Self {
+            config,
+            host,
+            agent,
+        })
+    }
+
+    pub fn config(&self) -> &S3StoreConfig {
+        &self.config
+    }
+
+    fn object_path(&self, key: &str) -> Result<String, ErrorItem> {
+        validate_object_key(key)?;
+        Ok(format!(
+            "/{}/{}",
+            self.config.bucket,
+            uri_encode(&format!("{}{key}", self.config.prefix), false)
+        ))
+    }
+
+    fn send(
+        &self,
+        method: &str,
+        path: &str,
+        query: &BTreeMap<String, String>,
+        extra_headers: BTreeMap<String, String>,
+        body: &[u8],
+    ) -> Result<HttpResponse, ErrorItem> {
+        let (amz_date, date) = amz_timestamps(unix_now_secs());
+        let payload_hash = hex::encode(Sha256::digest(body));
+        let mut headers = extra_headers;
+        headers.insert("host".to_string(), self.host.clone());
+        headers.insert("x-amz-content-sha256".to_string(), payload_hash.clone());
+        headers.insert("x-amz-date".to_string(), amz_date.clone());
+        if method == "PUT" {
+            headers.insert("content-length".to_string(), body.len().to_string());
+        }
+
+        let canonical_query = query
+            .iter()
+            .map(|(key, value)| format!("{}={}", uri_encode(key, true), uri_encode(value, true)))
+            .collect::<Vec<String>>()
+            .join("&");
+        let signed_headers = headers.keys().cloned().collect::<Vec<String>>().join(";");
+        let canonical_headers = headers
+            .iter()
+            .map(|(key, value)| format!("{key}:{}\n", value.trim()))
+            .collect::<String>();
+        let canonical_request = format!(
+            "{method}\n{path}\n{canonical_query}\n{canonical_headers}\n{signed_headers}\n{payload_hash}"
+        );
+        let scope = format!("{date}/{}/{S3_SERVICE}/aws4_request", self.config.region);
+        let string_to_sign = format!(
+            "{SIGNING_ALGORITHM}\n{amz_date}\n{scope}\n{}",
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+        let signing_key = sigv4_signing_key(
+            &self.config.secret_access_key,
+            &date,
+            &self.config.region,
+            S3_SERVICE,
+        );
+        let signature = hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+        headers.insert(
+            "authorization".to_string(),
+            format!(
+                "{SIGNING_ALGORITHM} Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+                self.config.access_key_id
+            ),
+        );
+
+        let target = if canonical_query.is_empty() {
+            path.to_string()
+        } else {
+            format!("{path}?{canonical_query}")
+        };
+        let mut request = self
+            .agent
+            .request(method, &format!("{}{target}", self.config.endpoint));
+        for (key, value) in &headers {
+            // The body length is set by the client from the payload itself.
+            if key != "content-length" {
+                request = request.set(key, value);
+            }
+        }
+        let result = if method == "PUT" {
+            request.send_bytes(body)
+        } else {
+            request.call()
+        };
+        let response = match result {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+            Err(err) => {
+                return Err(store_error(format!(
+                    "S3 {method} {target} to '{}' failed: {err}",
+                    self.config.endpoint
+                )))
+            }
+        };
+        let status = response.status();
+        let mut body = Vec::new();
+        if method != "HEAD" {
+            response
+                .into_reader()
+                .read_to_end(&mut body)
+                .map_err(|err| {
+                    store_error(format!(
+                        "S3 {method} {target} to '{}' failed reading the response: {err}",
+                        self.config.endpoint
+                    ))
+                })?;
+        }
+        Ok(HttpResponse { status, body })
+    }
+}
+
+impl ArchiveStore for S3ArchiveStore {
+    fn location(&self) -> String {
+        format!(
+            "s3://{}/{}",
+            self.config.bucket,
+            self.config.prefix.trim_end_matches('/')
+        )
+        .trim_end_matches('/')
+        .to_string()
+    }
+
+    fn put_object(&self, key: &str, content: &[u8], lock: &ObjectLock) -> Result<(), ErrorItem> {
+        if self.object_exists(key)? {
+            return Err(write_once_error(key));
+        }
+        let path = self.object_path(key)?;
+        let mut headers = BTreeMap::new();
+        headers.insert("if-none-match".to_string(), "*".to_string());
+        headers.insert(
+            "x-amz-checksum-sha256".to_string(),
+            BASE64.encode(Sha256::digest(content)),
+        );
+        if let Some(retain_until) = lock.retain_until {
+            headers.insert(
+                "x-amz-object-lock-mode".to_string(),
+                ARCHIVE_OBJECT_LOCK_MODE.to_string(),
+            );
+            headers.insert(
+                "x-amz-object-lock-retain-until-date".to_string(),
+                rfc3339_utc(retain_until),
+            );
+        }
+        if lock.legal_hold {
+            headers.insert("x-amz-object-lock-legal-hold".to_string(), "ON".to_string());
+        }
+        let response = self.send("PUT", &path, &BTreeMap::new(), headers, content)?;
+        match response.status {
+            200..=299 => Ok(()),
+            // 409 is returned by some stores for a conditional write race.
+            409 | 412 => Err(write_once_error(key)),
+            status => Err(status_error("PUT", key, status, &response.body)),
+        }
+    }
+
+    fn get_object(&self, key: &str) -> Result<Vec<u8>, ErrorItem> {
+        let path = self.object_path(key)?;
+        let response = self.send("GET", &path, &BTreeMap::new(), BTreeMap::new(), &[])?;
+        match response.status {
+            200 => Ok(response.body),
+            status => Err(status_error("GET", key, status, &response.body)),
+        }
+    }
+
+    fn object_exists(&self, key: &str) -> Result<bool, ErrorItem> {
+        let path = self.object_path(key)?;
+        let response = self.send("HEAD", &path, &BTreeMap::new(), BTreeMap::new(), &[])?;
+        match response.status {
+            200 => Ok(true),
+            404 => Ok(false),
+            status => Err(status_error("HEAD", key, status, &response.body)),
+        }
+    }
+
+    fn list_objects(&self, prefix: &str) -> Result<Vec<String>, ErrorItem> {
+        let path = format!("/{}", self.config.bucket);
+        let full_prefix = format!("{}{prefix}", self.config.prefix);
+        let mut keys = Vec::new();
+        let mut continuation = None;
+        loop {
+            let mut query = BTreeMap::new();
+            query.insert("list-type".to_string(), "2".to_string());
+            query.insert("prefix".to_string(), full_prefix.clone());
+            if let Some(token) = continuation.take() {
+                query.insert("continuation-token".to_string(), token);
+            }
+            let response = self.send("GET", &path, &query, BTreeMap::new(), &[])?;
+            if response.status != 200 {
+                return Err(status_error(
+                    "LIST",
+                    prefix,
+                    response.status,
+                    &response.body,
+                ));
+            }
+            let body = String::from_utf8_lossy(&response.body).to_string();
+            for key in xml_values(&body, "Key") {
+                if let Some(relative) = key.strip_prefix(&self.config.prefix) {
+                    keys.push(relative.to_string());
+                }
+            }
+            let truncated = xml_values(&body, "IsTruncated")
+                .first()
+                .is_some_and(|value| value == "true");
+            continuation = xml_values(&body, "NextContinuationToken")
+                .into_iter()
+                .next();
+            if !truncated || continuation.is_none() {
+                break;
+            }
+        }
+        keys.sort();
+        Ok(keys)
+    }
+}
+
+fn status_error(operation: &str, key: &str, status: u16, body: &[u8]) -> ErrorItem {
+    let detail = xml_values(&String::from_utf8_lossy(body), "Code")
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+    store_error(format!(
+        "S3 {operation} '{key}' failed with HTTP {status} {detail}"
+    ))
+}
+
+/// Text content of every `<tag>...</tag>` element; enough for S3 list/error documents.
+fn xml_values(body: &str, tag: &str) -> Vec<String> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    let mut values = Vec::new();
+    let mut rest = body;
+    while let Some(start) = rest.find(&open) {
+        rest = &rest[start + open.len()..];
+        let Some(end) = rest.find(&close) else {
+            break;
+        };
+        values.push(xml_unescape(&rest[..end]));
+        rest = &rest[end + close.len()..];
+    }
+    values
+}
+
+fn xml_unescape(raw: &str) -> String {
+    raw.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// SigV4 URI encoding: unreserved characters pass through, `/` only outside query values.
+fn uri_encode(raw: &str, encode_slash: bool) -> String {
+    let mut encoded = String::new();
+    for byte in raw.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            b'/' if !encode_slash => encoded.push('/'),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+fn sigv4_signing_key(secret: &str, date: &str, region: &str, service: &str) -> [u8; 32] {
+    let date_key = hmac_sha256(format!("AWS4{secret}").as_bytes(), date.as_bytes());
+    let region_key = hmac_sha256(&date_key, region.as_bytes());
+    let service_key = hmac_sha256(&region_key, service.as_bytes());
+    hmac_sha256(&service_key, b"aws4_request")
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().into()
+}
+
+/// `localhost`, `127.0.0.0/8` or `::1`, with or without a port.
+fn is_loopback_host(host: &str) -> bool {
+    let name = if let Some(rest) = host.strip_prefix('[') {
+        rest.split(']').next().unwrap_or_default()
+    } else {
+        host.rsplit_once(':').map_or(host, |(name, _)| name)
+    };
+    name.eq_ignore_ascii_case("localhost")
+        || name
+            .parse::<IpAddr>()
+            .is_ok_and(|address| address.is_loopback())
+}
+
+fn unix_now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// `(YYYYMMDDTHHMMSSZ, YYYYMMDD)` for SigV4.
+fn amz_timestamps(secs: u64) -> (String, String) {
+    let (year, month, day, hour, minute, second) = utc_parts(secs);
+    (
+        format!("{year:04}{month:02}{day:02}T{hour:02}{minute:02}{second:02}Z"),
+        format!("{year:04}{month:02}{day:02}"),
+    )
+}
+
+fn rfc3339_utc(secs: u64) -> String {
+    let (year, month, day, hour, minute, second) = utc_parts(secs);
+    format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}Z")
+}
+
+/// Civil UTC date/time from unix seconds (proleptic Gregorian calendar).
+fn utc_parts(secs: u64) -> (u64, u64, u64, u64, u64, u64) {
+    let days = secs / 86_400;
+    let rem = secs % 86_400;
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day, rem / 3_600, rem % 3_600 / 60, rem % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sigv4_signing_key_matches_aws_reference_vector() {
+        let key = sigv4_signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20120215",
+            "us-east-1",
+            "iam",
+        );
+        assert_eq!(
+            hex::encode(key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+
+    #[test]
+    fn utc_formatting_and_uri_encoding_match_known_values() {
+        assert_eq!(rfc3339_utc(0), "1970-01-01T00:00:00Z");
+        assert_eq!(rfc3339_utc(1_709_251_199), "2024-02-29T23:59:59Z");
+        assert_eq!(
+            amz_timestamps(1_700_000_000),
+            ("20231114T221320Z".to_string(), "20231114".to_string())
+        );
+        assert_eq!(uri_encode("a b/c~", false), "a%20b/c~");
+        assert_eq!(uri_encode("a/b", true), "a%2Fb");
+    }
+
+    #[test]
+    fn plain_http_is_limited_to_loopback_hosts() {
+        for host in [
+            "localhost:9000",
+            "127.0.0.1:9000",
+            "127.1.2.3",
+            "[::1]:9000",
+        ] {
+            assert!(is_loopback_host(host), "{host}");
+        }
+        for host in [
+            "s3.example.com",
+            "10.0.0.5:9000",
+            "localhost.example.com",
+            "[::2]",
+        ] {
+            assert!(!is_loopback_host(host), "{host}");
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use crate::retention::{parse_retention, parse_unix_seconds};
+use sr_common::{ErrorItem, SR_OPS_301};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+
+/// Object-lock mode requested for archived objects; compliance locks cannot be shortened.
+pub const ARCHIVE_OBJECT_LOCK_MODE: &str = "COMPLIANCE";
+
+/// Retention lock derived from `ArchiveMetadata.retention`: a fixed retain-until time for
+/// finite retentions, or a legal hold for `forever`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ObjectLock {
+    /// Unix seconds until which the object must not be deleted or overwritten.
+    pub retain_until: Option<u64>,
+    pub legal_hold: bool,
+}
+
+impl ObjectLock {
+    pub fn from_retention(stored_at: &str, retention: &str) -> Result<Self, ErrorItem> {
+        match parse_retention(retention)? {
+            Some(duration) => {
+                let stored_at_secs = parse_unix_seconds(stored_at).ok_or_else(|| {
+                    store_error(format!("invalid archive timestamp '{stored_at}'"))
+                })?;
+                Ok(Self {
+                    retain_until: Some(stored_at_secs.saturating_add(duration.as_secs())),
+                    legal_hold: false,
+                })
+            }
+            None => Ok(Self {
+                retain_until: None,
+                legal_hold: true,
+            }),
+        }
+    }
+}
+
+/// Write-once object storage for archive bundles. Keys are relative `/`-separated paths
+/// such as `<bundleId>/run_report.json`; `put_object` must fail if the key already exists.
+pub trait ArchiveStore {
+    /// Human-readable location used in command output, e.g. a directory or `s3://` URL.
+    fn location(&self) -> String;
+    fn put_object(&self, key: &str, content: &[u8], lock: &ObjectLock) -> Result<(), ErrorItem>;
+    fn get_object(&self, key: &str) -> Result<Vec<u8>, ErrorItem>;
+    fn object_exists(&self, key: &str) -> Result<bool, ErrorItem>;
+    /// Keys starting with `prefix`, sorted.
+    fn list_objects(&self, prefix: &str) -> Result<Vec<String>, ErrorItem>;
+}
+
+/// Local directory store. Object locks are not enforced by the filesystem; expiry is
+/// handled by `archive prune`, which records every deletion in the prune log.
+#[derive(Debug, Clone)]
+pub struct FsArchiveStore {
+    root: PathBuf,
+}
+
+impl FsArchiveStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn object_path(&self, key: &str) -> Result<PathBuf, ErrorItem> {
+        validate_object_key(key)?;
+        Ok(self.root.join(key))
+    }
+}
+
+impl ArchiveStore for FsArchiveStore {
+    fn location(&self) -> String {
+        self.root.display().to_string()
+    }
+
+    /// Written to a temp file, synced, then hard-linked into place so an existing object
+    /// is never replaced and a crash never leaves a truncated object.
+    fn put_object(&self, key: &str, content: &[u8], _lock: &ObjectLock) -> Result<(), ErrorItem> {
+        let path = self.object_path(key)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|err| {
+                store_error(format!(
+                    "failed to prepare archive directory '{}': {err}",
+                    parent.display()
+                ))
+            })?;
+        }
+        let tmp_path = path.with_file_name(format!(
+            "{}.tmp",
+            path.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default()
+        ));
+        let written = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&tmp_path)
+            .and_then(|mut file| {
+                file.write_all(content)?;
+                file.sync_all()
+            })
+            .and_then(|_| fs::hard_link(&tmp_path, &path));
+        let _ = fs::remove_file(&tmp_path);
+        written.map_err(|err| {
+            if err.kind() == std::io::ErrorKind::AlreadyExists {
+                write_once_error(key)
+            } else {
+                store_error(format!(
+                    "failed to write archive object '{}': {err}",
+                    path.display()
+                ))
+            }
+        })
+    }
+
+    fn get_object(&self, key: &str) -> Result<Vec<u8>, ErrorItem> {
+        let path = self.object_path(key)?;
+        fs::read(&path).map_err(|err| {
+            store_error(format!(
+                "failed to read archive object '{}': {err}",
+                path.display()
+            ))
+        })
+    }
+
+    fn object_exists(&self, key: &str) -> Result<bool, ErrorItem> {
+        Ok(self.object_path(key)?.is_file())
+    }
+
+    fn list_objects(&self, prefix: &str) -> Result<Vec<String>, ErrorItem> {
+        let mut keys = Vec::new();
+        if self.root.is_dir() {
+            collect_keys(&self.root, "", &mut keys).map_err(|err| {
+                store_error(format!(
+                    "failed to scan archive root '{}': {err}",
+                    self.root.display()
+                ))
+            })?;
+        }
+        keys.retain(|key| key.starts_with(prefix));
+        keys.sort();
+        Ok(keys)
+    }
+}
+
+/// Keys must be relative, non-empty and free of `.`/`..` segments.
+pub(crate) fn validate_object_key(key: &str) -> Result<(), ErrorItem> {
+    let valid = !key.is_empty()
+        && !key.starts_with('/')
+        && !key
+            .split('/')
+            .any(|part| part.is_empty() || part == "." || part == "..")
+        && Path::new(key)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+    if valid {
+        Ok(())
+    } else {
+        Err(store_error(format!("invalid archive object key '{key}'")))
+    }
+}
+
+pub(crate) fn write_once_error(key: &str) -> ErrorItem {
+    store_error(format!(
+        "archive object '{key}' already exists; archive objects are write-once"
+    ))
+}
+
+pub(crate) fn store_error(message: impl Into<String>) -> ErrorItem {
+    ErrorItem::new(SR_OPS_301, "archive.store", message)
+}
+
+fn collect_keys(dir: &Path, prefix: &str, keys: &mut Vec<String>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let key = format!("{prefix}{name}");
+        if entry.file_type()?.is_dir() {
+            collect_keys(&entry.path(), &format!("{key}/"), keys)?;
+        } else {
+            keys.push(key);
+        }
+    }
+    Ok(())
+}
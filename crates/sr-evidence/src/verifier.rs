@@ -1,10 +1,15 @@
-use crate::{compute_integrity_digest, derive_event_hash, RunReport, RUN_REPORT_SCHEMA_VERSION};
+use crate::merkle::{event_leaf_hash, event_leaves, merkle_root_from_leaves, verify_merkle_proof};
+use crate::{
+    compute_integrity_digest, derive_event_hash, EventDisclosure, RunReport,
+    RUN_REPORT_SCHEMA_VERSION,
+};
 use serde::{Deserialize, Serialize};
-use sr_common::{ErrorItem, SR_EVD_002, SR_EVD_301, SR_EVD_302, SR_EVD_303};
+use sr_common::{ErrorItem, SR_EVD_002, SR_EVD_301, SR_EVD_302, SR_EVD_303, SR_EVD_304};
 use std::fs;
 use std::path::Path;
 
-const GENESIS_HASH: &str = "sha256:0000000000000000000000000000000000000000000000000000000000000000";
+const GENESIS_HASH: &str =
+    "sha256:0000000000000000000000000000000000000000000000000000000000000000";
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct VerifyCheck {
@@ -37,6 +42,9 @@ pub fn verify_report_file(path: &Path) -> Result<VerifyResult, ErrorItem> {
     Ok(verify_report(&report))
 }
 
+/// Check one report on its own. For a redacted report this only proves the copy is
+/// self-consistent: `integrity.digest` is recomputed over the copy and nothing signs
+/// `merkleRoot`, so use `verify_redacted_against_original` to tie it to a trusted original.
 pub fn verify_report(report: &RunReport) -> VerifyResult {
     let mut checks = vec![
         VerifyCheck {
@@ -59,10 +67,7 @@ pub fn verify_report(report: &RunReport) -> VerifyResult {
         errors.push(ErrorItem::new(
             SR_EVD_301,
             "schemaVersion",
-            format!(
-                "schemaVersion must be '{}'",
-                RUN_REPORT_SCHEMA_VERSION
-            ),
+            format!("schemaVersion must be '{}'", RUN_REPORT_SCHEMA_VERSION),
         ));
     }
 
@@ -84,6 +89,21 @@ pub fn verify_report(report: &RunReport) -> VerifyResult {
         ));
     }
 
+    if let Some(root) = report.integrity.merkle_root.as_deref() {
+        let ok = merkle_root_ok(report, root);
+        checks.push(VerifyCheck {
+            name: "merkle_root".to_string(),
+            ok,
+        });
+        if !ok {
+            errors.push(ErrorItem::new(
+                SR_EVD_304,
+                "integrity.merkleRoot",
+                "event Merkle inclusion verification failed",
+            ));
+        }
+    }
+
     VerifyResult {
         valid: errors.is_empty(),
         checks,
@@ -91,6 +111,39 @@ pub fn verify_report(report: &RunReport) -> VerifyResult {
     }
 }
 
+/// Verify a redacted report against the full report it was derived from: both must verify,
+/// the original's digest must equal `redaction.originalDigest`, and run id, Merkle root and
+/// leaf count must match, so every disclosed event is proven to be part of the original.
+pub fn verify_redacted_against_original(
+    redacted: &RunReport,
+    original: &RunReport,
+) -> VerifyResult {
+    let mut result = verify_report(redacted);
+    let original_result = verify_report(original);
+    let bound = original_result.valid
+        && original.redaction.is_none()
+        && original.integrity.merkle_root.is_some()
+        && redacted.redaction.as_ref().is_some_and(|redaction| {
+            redaction.original_digest == original.integrity.digest
+                && redaction.leaf_count == original.events.len()
+        })
+        && redacted.run_id == original.run_id
+        && redacted.integrity.merkle_root == original.integrity.merkle_root;
+    result.checks.push(VerifyCheck {
+        name: "original_binding".to_string(),
+        ok: bound,
+    });
+    if !bound {
+        result.errors.push(ErrorItem::new(
+            SR_EVD_304,
+            "redaction.originalDigest",
+            "redacted report does not match the original report",
+        ));
+    }
+    result.valid = result.errors.is_empty();
+    result
+}
+
 fn set_check_status(checks: &mut [VerifyCheck], name: &str, ok: bool) {
     if let Some(check) = checks.iter_mut().find(|check| check.name == name) {
         check.ok = ok;
@@ -98,6 +151,9 @@ fn set_check_status(checks: &mut [VerifyCheck], name: &str, ok: bool) {
 }
 
 fn event_chain_ok(report: &RunReport) -> bool {
+    if let Some(redaction) = report.redaction.as_ref() {
+        return disclosed_event_chain_ok(report, &redaction.disclosures);
+    }
     let mut expected_prev = GENESIS_HASH.to_string();
     for event in &report.events {
         if event.hash_prev != expected_prev {
@@ -111,6 +167,70 @@ fn event_chain_ok(report: &RunReport) -> bool {
     true
 }
 
+/// Chain check for redacted reports: links are only checked between adjacent original
+/// positions, and self hashes only for events whose payload is fully disclosed. Hashes next
+/// to redacted fields are withheld by `redact_report` and must stay blank.
+fn disclosed_event_chain_ok(report: &RunReport, disclosures: &[EventDisclosure]) -> bool {
+    if disclosures.len() != report.events.len() {
+        return false;
+    }
+    let mut previous: Option<(&EventDisclosure, &str)> = None;
+    for (event, disclosure) in report.events.iter().zip(disclosures) {
+        let redacted = !disclosure.redacted_fields.is_empty();
+        if disclosure.index == 0 && event.hash_prev != GENESIS_HASH {
+            return false;
+        }
+        if redacted && !event.hash_self.is_empty() {
+            return false;
+        }
+        if let Some((prev, prev_hash)) = previous {
+            if disclosure.index <= prev.index {
+                return false;
+            }
+            if disclosure.index == prev.index + 1 {
+                let expected = if prev.redacted_fields.is_empty() {
+                    prev_hash
+                } else {
+                    ""
+                };
+                if event.hash_prev != expected {
+                    return false;
+                }
+            }
+        }
+        // A blank `hashPrev` was withheld after a redacted event that may since be removed.
+        let prev_withheld = event.hash_prev.is_empty();
+        if !redacted && !prev_withheld && derive_event_hash(event) != event.hash_self {
+            return false;
+        }
+        previous = Some((disclosure, event.hash_self.as_str()));
+    }
+    true
+}
+
+fn merkle_root_ok(report: &RunReport, root: &str) -> bool {
+    let Some(redaction) = report.redaction.as_ref() else {
+        let leaves = event_leaves(&report.events, report.integrity.field_salt_seed.as_deref());
+        return merkle_root_from_leaves(&leaves).as_deref() == Some(root);
+    };
+    if redaction.disclosures.len() != report.events.len() {
+        return false;
+    }
+    report
+        .events
+        .iter()
+        .zip(&redaction.disclosures)
+        .all(|(event, disclosure)| {
+            verify_merkle_proof(
+                &event_leaf_hash(event, &disclosure.field_salts, &disclosure.redacted_fields),
+                disclosure.index,
+                redaction.leaf_count,
+                &disclosure.proof,
+                root,
+            )
+        })
+}
+
 fn artifact_hash_ok(report: &RunReport) -> bool {
     if !is_sha256_hash(&report.artifacts.kernel_hash)
         || !is_sha256_hash(&report.artifacts.rootfs_hash)
@@ -0,0 +1,325 @@
+use serde_json::json;
+use sr_common::{SR_EVD_305, SR_OPS_301};
+use sr_evidence::{
+    archive_report_with_artifacts, build_bundle_package, compute_integrity_digest,
+    derive_event_hash, read_bundle_package, sha256_bytes, verify_bundle_package, BundleArtifact,
+    BundleCompression, BundleManifest, EvidenceEvent, Integrity, MountAudit, NetworkAudit,
+    PolicySummary, ReportArtifacts, ResourceUsage, RunReport, RUN_REPORT_SCHEMA_VERSION,
+    STAGE_PREPARE,
+};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const GENESIS_HASH: &str =
+    "sha256:0000000000000000000000000000000000000000000000000000000000000000";
+
+#[test]
+fn bundle_package_is_byte_for_byte_deterministic() {
+    let report = valid_report("sr-bundle-deterministic");
+    let artifacts = sample_artifacts();
+    let mut reversed = artifacts.clone();
+    reversed.reverse();
+    let manifest = BundleManifest::from_artifacts(&report.run_id, &artifacts);
+
+    let first = build_bundle_package(&report, &manifest, &artifacts, BundleCompression::None)
+        .expect("build first");
+    let second = build_bundle_package(&report, &manifest, &reversed, BundleCompression::None)
+        .expect("build second");
+    assert_eq!(first, second);
+    assert_eq!(first.len() % 512, 0);
+
+    let files = read_bundle_package(&first).expect("read package");
+    assert_eq!(
+        files.keys().map(String::as_str).collect::<Vec<_>>(),
+        vec![
+            "events.jsonl",
+            "firecracker-config.json",
+            "logs/vm.stdout.log",
+            "manifest.json",
+            "run_report.json",
+        ]
+    );
+}
+
+#[test]
+fn archive_with_artifacts_writes_package_and_verifies_offline() {
+    let report = valid_report("sr-bundle-archive");
+    let archive_root = temp_dir("archive-bundle-ok");
+
+    let archived = archive_report_with_artifacts(
+        &report,
+        &archive_root,
+        "30d",
+        &sample_artifacts(),
+        BundleCompression::None,
+    )
+    .expect("archive with artifacts");
+    let metadata = archived.archive.as_ref().expect("archive metadata");
+    assert_eq!(metadata.package.as_deref(), Some("bundle.tar"));
+    let package_path = archive_root.join(&metadata.bundle_id).join("bundle.tar");
+
+    let files = read_bundle_package(&fs::read(&package_path).expect("read package"))
+        .expect("unpack package");
+    let manifest: BundleManifest =
+        serde_json::from_slice(&files["manifest.json"]).expect("parse manifest");
+    assert_eq!(
+        metadata.manifest_digest.as_deref(),
+        Some(manifest.digest().as_str())
+    );
+    assert_eq!(manifest.files.len(), 3);
+    assert_eq!(manifest.files[0].path, "events.jsonl");
+    assert_eq!(
+        manifest.files[0].sha256,
+        sha256_bytes(b"{\"type\":\"run.prepared\"}\n")
+    );
+    let packaged: RunReport =
+        serde_json::from_slice(&files["run_report.json"]).expect("parse packaged report");
+    assert_eq!(packaged, archived);
+
+    let result = verify_bundle_package(&package_path).expect("verify package");
+    assert!(result.valid, "{:?}", result.errors);
+    assert!(result
+        .checks
+        .iter()
+        .any(|check| check.name == "bundle_manifest" && check.ok));
+
+    let _ = fs::remove_dir_all(&archive_root);
+}
+
+#[test]
+fn bundle_verify_detects_tampered_artifact_with_evd_305() {
+    let report = valid_report("sr-bundle-tamper");
+    let archive_root = temp_dir("archive-bundle-tamper");
+    let archived = archive_report_with_artifacts(
+        &report,
+        &archive_root,
+        "30d",
+        &sample_artifacts(),
+        BundleCompression::None,
+    )
+    .expect("archive with artifacts");
+    let bundle_id = &archived
+        .archive
+        .as_ref()
+        .expect("archive metadata")
+        .bundle_id;
+    let package_path = archive_root.join(bundle_id).join("bundle.tar");
+
+    let mut bytes = fs::read(&package_path).expect("read package");
+    let needle = b"vm booted";
+    let offset = bytes
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .expect("locate log content");
+    bytes[offset] = b'V';
+    fs::write(&package_path, bytes).expect("write tampered package");
+
+    let result = verify_bundle_package(&package_path).expect("verify package");
+    assert!(!result.valid);
+    assert!(result
+        .errors
+        .iter()
+        .any(|err| err.code == SR_EVD_305 && err.path == "bundle.files.logs/vm.stdout.log"));
+
+    let _ = fs::remove_dir_all(&archive_root);
+}
+
+#[test]
+fn bundle_rejects_invalid_or_duplicate_artifact_names() {
+    let report = valid_report("sr-bundle-names");
+    let archive_root = temp_dir("archive-bundle-names");
+
+    for artifacts in [
+        vec![BundleArtifact::from_bytes("../escape.log", b"x".to_vec())],
+        vec![BundleArtifact::from_bytes("run_report.json", b"x".to_vec())],
+        vec![
+            BundleArtifact::from_bytes("events.jsonl", b"a".to_vec()),
+            BundleArtifact::from_bytes("events.jsonl", b"b".to_vec()),
+        ],
+    ] {
+        let err = archive_report_with_artifacts(
+            &report,
+            &archive_root,
+            "30d",
+            &artifacts,
+            BundleCompression::None,
+        )
+        .expect_err("invalid artifact names must fail");
+        assert_eq!(err.code, SR_OPS_301);
+    }
+    assert!(!archive_root.join("index.jsonl").exists());
+
+    let _ = fs::remove_dir_all(&archive_root);
+}
+
+#[test]
+fn bundle_read_rejects_repeated_entry_names_with_evd_305() {
+    let report = valid_report("sr-bundle-repeated");
+    let artifacts = vec![
+        BundleArtifact::from_bytes("a.log", b"first".to_vec()),
+        BundleArtifact::from_bytes("b.log", b"other".to_vec()),
+    ];
+    let manifest = BundleManifest::from_artifacts(&report.run_id, &artifacts);
+    let mut package = build_bundle_package(&report, &manifest, &artifacts, BundleCompression::None)
+        .expect("build package");
+
+    // Rename the `b.log` entry to `a.log` and fix up its header checksum.
+    let offset = (0..package.len())
+        .step_by(512)
+        .find(|offset| package[*offset..].starts_with(b"b.log\0"))
+        .expect("b.log header");
+    let header = &mut package[offset..offset + 512];
+    header[0] = b'a';
+    header[148..156].fill(b' ');
+    let checksum = header.iter().map(|byte| u64::from(*byte)).sum::<u64>();
+    header[148..156].copy_from_slice(format!("{checksum:06o}\0 ").as_bytes());
+
+    let err = read_bundle_package(&package).expect_err("repeated names must fail");
+    assert_eq!(err.code, SR_EVD_305);
+    assert_eq!(err.path, "bundle.files.a.log");
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn bundle_zstd_decompression_is_bounded() {
+    use sr_evidence::BUNDLE_MAX_UNPACKED_BYTES;
+    use std::io::Write;
+
+    let mut encoder = zstd::stream::write::Encoder::new(Vec::new(), 1).expect("zstd encoder");
+    let chunk = vec![0u8; 1024 * 1024];
+    for _ in 0..=BUNDLE_MAX_UNPACKED_BYTES / chunk.len() as u64 {
+        encoder.write_all(&chunk).expect("compress zeros");
+    }
+    let package = encoder.finish().expect("finish zstd stream");
+
+    let err = read_bundle_package(&package).expect_err("oversized stream must fail");
+    assert!(err.message.contains("unpacks to more than"), "{err:?}");
+}
+
+#[cfg(not(feature = "zstd"))]
+#[test]
+fn bundle_zstd_compression_requires_feature() {
+    let report = valid_report("sr-bundle-zstd");
+    let artifacts = sample_artifacts();
+    let manifest = BundleManifest::from_artifacts(&report.run_id, &artifacts);
+    assert_eq!(
+        BundleCompression::parse("zstd").expect("parse zstd"),
+        BundleCompression::Zstd
+    );
+    assert_eq!(
+        BundleCompression::parse("gzip")
+            .expect_err("gzip unsupported")
+            .code,
+        SR_OPS_301
+    );
+
+    let err = build_bundle_package(&report, &manifest, &artifacts, BundleCompression::Zstd)
+        .expect_err("zstd without feature must fail");
+    assert_eq!(err.code, SR_OPS_301);
+    assert_eq!(err.path, "archive.compression");
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn bundle_zstd_compression_round_trips_with_feature() {
+    let report = valid_report("sr-bundle-zstd");
+    let archive_root = temp_dir("archive-bundle-zstd");
+    let archived = archive_report_with_artifacts(
+        &report,
+        &archive_root,
+        "30d",
+        &sample_artifacts(),
+        BundleCompression::Zstd,
+    )
+    .expect("archive with zstd");
+    let metadata = archived.archive.as_ref().expect("archive metadata");
+    assert_eq!(metadata.package.as_deref(), Some("bundle.tar.zst"));
+
+    let package_path = archive_root
+        .join(&metadata.bundle_id)
+        .join("bundle.tar.zst");
+    let result = verify_bundle_package(&package_path).expect("verify zstd package");
+    assert!(result.valid, "{:?}", result.errors);
+
+    let _ = fs::remove_dir_all(&archive_root);
+}
+
+fn sample_artifacts() -> Vec<BundleArtifact> {
+    vec![
+        BundleArtifact::from_bytes("logs/vm.stdout.log", b"vm booted\n".to_vec()),
+        BundleArtifact::from_bytes("events.jsonl", b"{\"type\":\"run.prepared\"}\n".to_vec()),
+        BundleArtifact::from_bytes(
+            "firecracker-config.json",
+            b"{\"machine-config\":{\"vcpu_count\":1}}".to_vec(),
+        ),
+    ]
+}
+
+fn valid_report(run_id: &str) -> RunReport {
+    let mut event = EvidenceEvent {
+        timestamp: "2026-02-21T10:00:00Z".to_string(),
+        run_id: run_id.to_string(),
+        stage: STAGE_PREPARE.to_string(),
+        event_type: "run.prepared".to_string(),
+        payload: json!({"workdir": "/tmp/safe-run/test"}),
+        hash_prev: GENESIS_HASH.to_string(),
+        hash_self: String::new(),
+    };
+    event.hash_self = derive_event_hash(&event);
+
+    let mut report = RunReport {
+        schema_version: RUN_REPORT_SCHEMA_VERSION.to_string(),
+        run_id: run_id.to_string(),
+        started_at: "2026-02-21T10:00:00Z".to_string(),
+        finished_at: "2026-02-21T10:00:01Z".to_string(),
+        exit_code: 0,
+        artifacts: ReportArtifacts {
+            kernel_hash: "sha256:1111111111111111111111111111111111111111111111111111111111111111"
+                .to_string(),
+            rootfs_hash: "sha256:2222222222222222222222222222222222222222222222222222222222222222"
+                .to_string(),
+            policy_hash: "sha256:3333333333333333333333333333333333333333333333333333333333333333"
+                .to_string(),
+            command_hash: "sha256:4444444444444444444444444444444444444444444444444444444444444444"
+                .to_string(),
+        },
+        policy_summary: PolicySummary {
+            network: "none".to_string(),
+            mounts: 0,
+            parameters: BTreeMap::new(),
+            admission_constraints_hash: None,
+        },
+        resource_usage: ResourceUsage {
+            cpu: "cpuUsageUsec=0".to_string(),
+            memory: "memoryCurrentBytes=0".to_string(),
+            ..ResourceUsage::default()
+        },
+        events: vec![event],
+        mount_audit: MountAudit::default(),
+        network_audit: NetworkAudit::default(),
+        archive: None,
+        verification: None,
+        redaction: None,
+        integrity: Integrity {
+            digest: String::new(),
+            merkle_root: None,
+            field_salt_seed: None,
+        },
+    };
+    report.integrity.digest = compute_integrity_digest(&report).expect("compute digest");
+    report
+}
+
+fn temp_dir(label: &str) -> PathBuf {
+    let mut path = std::env::temp_dir();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    path.push(format!("safe-run-vibe-{label}-{nanos}"));
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).expect("create temp dir");
+    path
+}
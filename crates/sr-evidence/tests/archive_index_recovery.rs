@@ -0,0 +1,242 @@
+use serde_json::json;
+use sr_common::SR_OPS_301;
+use sr_evidence::{
+    append_archive_index, archive_report, compute_integrity_digest, derive_event_hash,
+    load_archive_index, reindex_archive, ArchiveIndexEntry, EvidenceEvent, Integrity, MountAudit,
+    NetworkAudit, PolicySummary, ReportArtifacts, ResourceUsage, RunReport,
+    RUN_REPORT_SCHEMA_VERSION, STAGE_PREPARE,
+};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const GENESIS_HASH: &str =
+    "sha256:0000000000000000000000000000000000000000000000000000000000000000";
+
+#[test]
+fn archive_index_concurrent_appends_keep_every_entry() {
+    let archive_root = Arc::new(temp_dir("archive-index-concurrent"));
+    let handles = (0..8)
+        .map(|worker| {
+            let archive_root = Arc::clone(&archive_root);
+            thread::spawn(move || {
+                for item in 0..10 {
+                    append_archive_index(&archive_root, entry(&format!("bundle-{worker}-{item}")))
+                        .expect("append entry");
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for handle in handles {
+        handle.join().expect("join worker");
+    }
+
+    let index = load_archive_index(&archive_root).expect("load index");
+    assert_eq!(index.entries.len(), 80);
+
+    let _ = fs::remove_dir_all(archive_root.as_path());
+}
+
+#[test]
+fn archive_index_torn_tail_is_ignored_and_repaired_on_append() {
+    let archive_root = temp_dir("archive-index-torn");
+    append_archive_index(&archive_root, entry("bundle-a")).expect("append a");
+    let log_path = archive_root.join("index.jsonl");
+    let mut raw = fs::read_to_string(&log_path).expect("read log");
+    raw.push_str("{\"op\":\"add\",\"bundleId\":\"bundle-torn\"");
+    fs::write(&log_path, raw).expect("write torn log");
+
+    let index = load_archive_index(&archive_root).expect("load index with torn tail");
+    assert_eq!(index.entries.len(), 1);
+
+    append_archive_index(&archive_root, entry("bundle-b")).expect("append b");
+    let repaired = fs::read_to_string(&log_path).expect("read repaired log");
+    assert!(!repaired.contains("bundle-torn"));
+    let index = load_archive_index(&archive_root).expect("load repaired index");
+    assert_eq!(
+        index
+            .entries
+            .iter()
+            .map(|item| item.bundle_id.as_str())
+            .collect::<Vec<_>>(),
+        vec!["bundle-a", "bundle-b"]
+    );
+
+    let _ = fs::remove_dir_all(&archive_root);
+}
+
+#[test]
+fn archive_index_corrupt_complete_line_returns_ops_301() {
+    let archive_root = temp_dir("archive-index-corrupt");
+    fs::write(
+        archive_root.join("index.jsonl"),
+        "not-json\n{\"op\":\"remove\",\"bundleId\":\"x\"}\n",
+    )
+    .expect("write corrupt log");
+
+    let err = load_archive_index(&archive_root).expect_err("must fail");
+    assert_eq!(err.code, SR_OPS_301);
+    assert_eq!(err.path, "archive.index");
+
+    let _ = fs::remove_dir_all(&archive_root);
+}
+
+#[test]
+fn archive_index_legacy_json_index_is_still_read() {
+    let archive_root = temp_dir("archive-index-legacy");
+    fs::write(
+        archive_root.join("index.json"),
+        serde_json::to_string_pretty(&json!({"entries": [entry("bundle-legacy")]}))
+            .expect("serialize legacy index"),
+    )
+    .expect("write legacy index");
+    append_archive_index(&archive_root, entry("bundle-new")).expect("append new");
+
+    let index = load_archive_index(&archive_root).expect("load index");
+    assert_eq!(index.entries.len(), 2);
+    assert_eq!(index.entries[0].bundle_id, "bundle-legacy");
+
+    let _ = fs::remove_dir_all(&archive_root);
+}
+
+#[test]
+fn archive_reindex_rebuilds_index_from_bundle_directories() {
+    let archive_root = temp_dir("archive-reindex");
+    let first =
+        archive_report(&valid_report("sr-reindex-a"), &archive_root, "30d").expect("archive first");
+    let second =
+        archive_report(&valid_report("sr-reindex-b"), &archive_root, "7d").expect("archive second");
+    fs::remove_file(archive_root.join("index.jsonl")).expect("lose index");
+    fs::create_dir_all(archive_root.join("not-a-bundle")).expect("create junk dir");
+
+    let rebuilt = reindex_archive(&archive_root).expect("reindex");
+    assert_eq!(rebuilt.entries.len(), 2);
+    assert_eq!(
+        rebuilt.entries[0].bundle_id,
+        first.archive.as_ref().expect("archive").bundle_id
+    );
+    assert_eq!(
+        rebuilt.entries[1].bundle_id,
+        second.archive.as_ref().expect("archive").bundle_id
+    );
+    assert_eq!(rebuilt.entries[1].retention, "7d");
+    assert_eq!(
+        load_archive_index(&archive_root).expect("load index"),
+        rebuilt
+    );
+
+    let _ = fs::remove_dir_all(&archive_root);
+}
+
+#[test]
+fn archive_reindex_concurrent_with_archiving_keeps_every_bundle() {
+    let archive_root = Arc::new(temp_dir("archive-reindex-concurrent"));
+    let archivers = (0..4)
+        .map(|worker| {
+            let archive_root = Arc::clone(&archive_root);
+            thread::spawn(move || {
+                for item in 0..5 {
+                    let report = valid_report(&format!("sr-reindex-race-{worker}-{item}"));
+                    archive_report(&report, &archive_root, "30d").expect("archive report");
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    let reindexer = {
+        let archive_root = Arc::clone(&archive_root);
+        thread::spawn(move || {
+            for _ in 0..10 {
+                reindex_archive(&archive_root).expect("reindex");
+            }
+        })
+    };
+    for handle in archivers {
+        handle.join().expect("join archiver");
+    }
+    reindexer.join().expect("join reindexer");
+
+    let index = load_archive_index(&archive_root).expect("load index");
+    assert_eq!(index.entries.len(), 20);
+
+    let _ = fs::remove_dir_all(archive_root.as_path());
+}
+
+fn entry(bundle_id: &str) -> ArchiveIndexEntry {
+    ArchiveIndexEntry {
+        bundle_id: bundle_id.to_string(),
+        run_id: format!("sr-{bundle_id}"),
+        stored_at: "unix:1700000000.000000000".to_string(),
+        retention: "30d".to_string(),
+        result: "pass".to_string(),
+    }
+}
+
+fn valid_report(run_id: &str) -> RunReport {
+    let mut event = EvidenceEvent {
+        timestamp: "2026-02-21T10:00:00Z".to_string(),
+        run_id: run_id.to_string(),
+        stage: STAGE_PREPARE.to_string(),
+        event_type: "run.prepared".to_string(),
+        payload: json!({"workdir": "/tmp/safe-run/test"}),
+        hash_prev: GENESIS_HASH.to_string(),
+        hash_self: String::new(),
+    };
+    event.hash_self = derive_event_hash(&event);
+
+    let mut report = RunReport {
+        schema_version: RUN_REPORT_SCHEMA_VERSION.to_string(),
+        run_id: run_id.to_string(),
+        started_at: "2026-02-21T10:00:00Z".to_string(),
+        finished_at: "2026-02-21T10:00:01Z".to_string(),
+        exit_code: 0,
+        artifacts: ReportArtifacts {
+            kernel_hash: "sha256:1111111111111111111111111111111111111111111111111111111111111111"
+                .to_string(),
+            rootfs_hash: "sha256:2222222222222222222222222222222222222222222222222222222222222222"
+                .to_string(),
+            policy_hash: "sha256:3333333333333333333333333333333333333333333333333333333333333333"
+                .to_string(),
+            command_hash: "sha256:4444444444444444444444444444444444444444444444444444444444444444"
+                .to_string(),
+        },
+        policy_summary: PolicySummary {
+            network: "none".to_string(),
+            mounts: 0,
+            parameters: BTreeMap::new(),
+            admission_constraints_hash: None,
+        },
+        resource_usage: ResourceUsage {
+            cpu: "cpuUsageUsec=0".to_string(),
+            memory: "memoryCurrentBytes=0".to_string(),
+            ..ResourceUsage::default()
+        },
+        events: vec![event],
+        mount_audit: MountAudit::default(),
+        network_audit: NetworkAudit::default(),
+        archive: None,
+        verification: None,
+        redaction: None,
+        integrity: Integrity {
+            digest: String::new(),
+            merkle_root: None,
+            field_salt_seed: None,
+        },
+    };
+    report.integrity.digest = compute_integrity_digest(&report).expect("compute digest");
+    report
+}
+
+fn temp_dir(label: &str) -> PathBuf {
+    let mut path = std::env::temp_dir();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    path.push(format!("safe-run-vibe-{label}-{nanos}"));
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).expect("create temp dir");
+    path
+}
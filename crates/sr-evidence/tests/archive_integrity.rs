@@ -5,6 +5,7 @@ use sr_evidence::{
     load_archived_report, EvidenceEvent, Integrity, MountAudit, NetworkAudit, PolicySummary,
     ReportArtifacts, ResourceUsage, RunReport, RUN_REPORT_SCHEMA_VERSION, STAGE_PREPARE,
 };
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -100,7 +101,7 @@ fn archive_write_failure_returns_ops_301_when_archive_root_is_file() {
 fn archive_write_failure_returns_ops_301_when_index_path_is_directory() {
     let report = valid_report("sr-archive-fail-2");
     let archive_root = temp_dir("archive-fail-index-dir");
-    fs::create_dir_all(archive_root.join("index.json")).expect("create index dir blocker");
+    fs::create_dir_all(archive_root.join("index.jsonl")).expect("create index dir blocker");
 
     let err = archive_report(&report, &archive_root, "7d").expect_err("must fail");
     assert_eq!(err.code, SR_OPS_301);
@@ -108,6 +109,37 @@ fn archive_write_failure_returns_ops_301_when_index_path_is_directory() {
     let _ = fs::remove_dir_all(&archive_root);
 }
 
+#[test]
+fn archive_write_failure_returns_ops_301_when_report_fails_verification() {
+    let mut report = valid_report("sr-archive-fail-3");
+    report.exit_code = 1;
+    let archive_root = temp_dir("archive-fail-tampered");
+
+    let err = archive_report(&report, &archive_root, "7d").expect_err("must fail");
+    assert_eq!(err.code, SR_OPS_301);
+    assert_eq!(err.path, "archive.verify");
+    assert!(load_archive_index(&archive_root)
+        .expect("load index")
+        .entries
+        .is_empty());
+
+    let _ = fs::remove_dir_all(&archive_root);
+}
+
+#[test]
+fn archive_index_records_failed_run_result() {
+    let mut report = valid_report("sr-archive-index-3");
+    report.exit_code = 137;
+    report.integrity.digest = compute_integrity_digest(&report).expect("recompute digest");
+    let archive_root = temp_dir("archive-index-failed-run");
+
+    archive_report(&report, &archive_root, "7d").expect("archive failed run");
+    let index = load_archive_index(&archive_root).expect("load index");
+    assert_eq!(index.entries[0].result, "fail");
+
+    let _ = fs::remove_dir_all(&archive_root);
+}
+
 fn valid_report(run_id: &str) -> RunReport {
     let mut event = EvidenceEvent {
         timestamp: "2026-02-21T10:00:00Z".to_string(),
@@ -139,18 +171,24 @@ fn valid_report(run_id: &str) -> RunReport {
         policy_summary: PolicySummary {
             network: "none".to_string(),
             mounts: 0,
+            parameters: BTreeMap::new(),
+            admission_constraints_hash: None,
         },
         resource_usage: ResourceUsage {
             cpu: "cpuUsageUsec=0".to_string(),
             memory: "memoryCurrentBytes=0".to_string(),
+            ..ResourceUsage::default()
         },
         events: vec![event],
         mount_audit: MountAudit::default(),
         network_audit: NetworkAudit::default(),
         archive: None,
         verification: None,
+        redaction: None,
         integrity: Integrity {
             digest: String::new(),
+            merkle_root: None,
+            field_salt_seed: None,
         },
     };
     report.integrity.digest = compute_integrity_digest(&report).expect("compute digest");
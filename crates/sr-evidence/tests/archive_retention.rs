@@ -0,0 +1,310 @@
+use serde_json::json;
+use sr_common::SR_OPS_301;
+use sr_evidence::{
+    archive_report, compute_integrity_digest, derive_event_hash, export_archived_bundle,
+    load_archive_index, load_prune_log, parse_retention, parse_unix_seconds, prune_archive,
+    query_archive_index, verify_prune_log, ArchiveQuery, EvidenceEvent, Integrity, MountAudit,
+    NetworkAudit, PolicySummary, ReportArtifacts, ResourceUsage, RunReport,
+    RUN_REPORT_SCHEMA_VERSION, STAGE_PREPARE,
+};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const GENESIS_HASH: &str =
+    "sha256:0000000000000000000000000000000000000000000000000000000000000000";
+
+#[test]
+fn archive_retention_parses_duration_strings() {
+    assert_eq!(
+        parse_retention("30d").expect("30d"),
+        Some(Duration::from_secs(30 * 86_400))
+    );
+    assert_eq!(
+        parse_retention("12h").expect("12h"),
+        Some(Duration::from_secs(12 * 3_600))
+    );
+    assert_eq!(
+        parse_retention("2w").expect("2w"),
+        Some(Duration::from_secs(14 * 86_400))
+    );
+    assert_eq!(parse_retention("forever").expect("forever"), None);
+
+    for invalid in ["", "d", "30", "30y", "-1d"] {
+        let err = parse_retention(invalid).expect_err("must fail");
+        assert_eq!(err.code, SR_OPS_301);
+        assert_eq!(err.path, "archive.retention");
+    }
+}
+
+#[test]
+fn archive_retention_query_filters_by_run_id_result_and_time() {
+    let archive_root = temp_dir("archive-retention-query");
+    archive_report(&valid_report("sr-query-a"), &archive_root, "30d").expect("archive a");
+    archive_report(&valid_report("sr-query-b"), &archive_root, "30d").expect("archive b");
+    let stored_at = stored_at_secs(&archive_root, 0);
+
+    let by_run = query_archive_index(
+        &archive_root,
+        &ArchiveQuery {
+            run_id: Some("sr-query-b".to_string()),
+            ..ArchiveQuery::default()
+        },
+    )
+    .expect("query by run id");
+    assert_eq!(by_run.len(), 1);
+    assert_eq!(by_run[0].run_id, "sr-query-b");
+
+    let by_result = query_archive_index(
+        &archive_root,
+        &ArchiveQuery {
+            result: Some("fail".to_string()),
+            ..ArchiveQuery::default()
+        },
+    )
+    .expect("query by result");
+    assert!(by_result.is_empty());
+
+    let future = query_archive_index(
+        &archive_root,
+        &ArchiveQuery {
+            since: Some(stored_at + 3_600),
+            ..ArchiveQuery::default()
+        },
+    )
+    .expect("query by time");
+    assert!(future.is_empty());
+    let window = query_archive_index(
+        &archive_root,
+        &ArchiveQuery {
+            since: Some(stored_at - 60),
+            until: Some(stored_at + 60),
+            ..ArchiveQuery::default()
+        },
+    )
+    .expect("query by window");
+    assert_eq!(window.len(), 2);
+
+    let _ = fs::remove_dir_all(&archive_root);
+}
+
+#[test]
+fn archive_retention_prune_removes_expired_bundles_and_logs_them() {
+    let archive_root = temp_dir("archive-retention-prune");
+    let short = archive_report(&valid_report("sr-prune-short"), &archive_root, "1d")
+        .expect("archive short");
+    archive_report(&valid_report("sr-prune-long"), &archive_root, "30d").expect("archive long");
+    archive_report(&valid_report("sr-prune-forever"), &archive_root, "forever")
+        .expect("archive forever");
+    let short_bundle = short.archive.as_ref().expect("archive").bundle_id.clone();
+    let now = stored_at_secs(&archive_root, 0) + 2 * 86_400;
+
+    let outcome = prune_archive(&archive_root, now, false).expect("prune");
+    assert_eq!(outcome.pruned.len(), 1);
+    assert_eq!(outcome.pruned[0].bundle_id, short_bundle);
+    assert_eq!(outcome.kept, 2);
+    assert!(!archive_root.join(&short_bundle).exists());
+
+    let index = load_archive_index(&archive_root).expect("load index");
+    assert_eq!(index.entries.len(), 2);
+    let log = load_prune_log(&archive_root).expect("load prune log");
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0].bundle_id, short_bundle);
+    assert_eq!(
+        log[0].report_digest.as_deref(),
+        Some(short.integrity.digest.as_str())
+    );
+    assert_eq!(verify_prune_log(&archive_root).expect("verify log"), 1);
+
+    let _ = fs::remove_dir_all(&archive_root);
+}
+
+#[test]
+fn archive_retention_prune_dry_run_keeps_bundles() {
+    let archive_root = temp_dir("archive-retention-dry-run");
+    let archived =
+        archive_report(&valid_report("sr-prune-dry"), &archive_root, "1h").expect("archive");
+    let bundle_id = archived
+        .archive
+        .as_ref()
+        .expect("archive")
+        .bundle_id
+        .clone();
+    let now = stored_at_secs(&archive_root, 0) + 7_200;
+
+    let outcome = prune_archive(&archive_root, now, true).expect("prune dry run");
+    assert!(outcome.dry_run);
+    assert_eq!(outcome.pruned.len(), 1);
+    assert!(archive_root.join(&bundle_id).exists());
+    assert_eq!(
+        load_archive_index(&archive_root)
+            .expect("load index")
+            .entries
+            .len(),
+        1
+    );
+    assert!(load_prune_log(&archive_root).expect("load log").is_empty());
+
+    let _ = fs::remove_dir_all(&archive_root);
+}
+
+#[test]
+fn archive_retention_tampered_prune_log_is_detected() {
+    let archive_root = temp_dir("archive-retention-tamper");
+    archive_report(&valid_report("sr-prune-tamper-a"), &archive_root, "1d").expect("archive a");
+    archive_report(&valid_report("sr-prune-tamper-b"), &archive_root, "1d").expect("archive b");
+    let now = stored_at_secs(&archive_root, 1) + 2 * 86_400;
+    prune_archive(&archive_root, now, false).expect("prune");
+
+    let log_path = archive_root.join("prune-log.jsonl");
+    let raw = fs::read_to_string(&log_path).expect("read log");
+    fs::write(
+        &log_path,
+        raw.replacen("sr-prune-tamper-a", "sr-prune-other", 1),
+    )
+    .expect("tamper log");
+
+    let err = verify_prune_log(&archive_root).expect_err("must detect tampering");
+    assert_eq!(err.code, SR_OPS_301);
+    assert_eq!(err.path, "archive.pruneLog[0]");
+
+    let _ = fs::remove_dir_all(&archive_root);
+}
+
+#[test]
+fn archive_retention_export_copies_verified_bundle() {
+    let archive_root = temp_dir("archive-retention-export");
+    let output_dir = temp_dir("archive-retention-export-out");
+    let archived =
+        archive_report(&valid_report("sr-export"), &archive_root, "30d").expect("archive");
+    let bundle_id = archived
+        .archive
+        .as_ref()
+        .expect("archive")
+        .bundle_id
+        .clone();
+
+    let exported =
+        export_archived_bundle(&archive_root, &bundle_id, &output_dir).expect("export bundle");
+    assert!(exported.join("run_report.json").exists());
+
+    let report_path = archive_root.join(&bundle_id).join("run_report.json");
+    let raw = fs::read_to_string(&report_path).expect("read report");
+    fs::write(&report_path, raw.replace("sr-export", "sr-exp0rt")).expect("tamper report");
+    let err = export_archived_bundle(&archive_root, &bundle_id, &output_dir.join("again"))
+        .expect_err("tampered bundle must not export");
+    assert_eq!(err.code, SR_OPS_301);
+
+    let _ = fs::remove_dir_all(&archive_root);
+    let _ = fs::remove_dir_all(&output_dir);
+}
+
+#[test]
+fn archive_retention_rejects_bundle_ids_that_escape_the_root() {
+    let archive_root = temp_dir("archive-retention-escape");
+    let victim = temp_dir("archive-retention-victim");
+    let victim_name = victim
+        .file_name()
+        .expect("victim name")
+        .to_string_lossy()
+        .to_string();
+    let entry = json!({
+        "op": "add",
+        "bundleId": format!("../{victim_name}"),
+        "runId": "sr-escape",
+        "storedAt": "unix:0.000000000",
+        "retention": "1s",
+        "result": "pass"
+    });
+    fs::write(archive_root.join("index.jsonl"), format!("{entry}\n")).expect("write index");
+
+    let err = prune_archive(&archive_root, 10, false).expect_err("escaping id must not prune");
+    assert_eq!(err.code, SR_OPS_301);
+    assert_eq!(err.path, "archive.bundleId");
+    assert!(victim.exists());
+    assert!(load_prune_log(&archive_root).expect("load log").is_empty());
+
+    for bundle_id in ["../x", "a/b", "", ".", "/abs"] {
+        let err = export_archived_bundle(&archive_root, bundle_id, &victim)
+            .expect_err("invalid bundle id must not export");
+        assert_eq!(err.path, "archive.bundleId", "{bundle_id}");
+    }
+
+    let _ = fs::remove_dir_all(&archive_root);
+    let _ = fs::remove_dir_all(&victim);
+}
+
+fn stored_at_secs(archive_root: &std::path::Path, index: usize) -> u64 {
+    let entries = load_archive_index(archive_root)
+        .expect("load index")
+        .entries;
+    parse_unix_seconds(&entries[index].stored_at).expect("unix storedAt")
+}
+
+fn valid_report(run_id: &str) -> RunReport {
+    let mut event = EvidenceEvent {
+        timestamp: "2026-02-21T10:00:00Z".to_string(),
+        run_id: run_id.to_string(),
+        stage: STAGE_PREPARE.to_string(),
+        event_type: "run.prepared".to_string(),
+        payload: json!({"workdir": "/tmp/safe-run/test"}),
+        hash_prev: GENESIS_HASH.to_string(),
+        hash_self: String::new(),
+    };
+    event.hash_self = derive_event_hash(&event);
+
+    let mut report = RunReport {
+        schema_version: RUN_REPORT_SCHEMA_VERSION.to_string(),
+        run_id: run_id.to_string(),
+        started_at: "2026-02-21T10:00:00Z".to_string(),
+        finished_at: "2026-02-21T10:00:01Z".to_string(),
+        exit_code: 0,
+        artifacts: ReportArtifacts {
+            kernel_hash: "sha256:1111111111111111111111111111111111111111111111111111111111111111"
+                .to_string(),
+            rootfs_hash: "sha256:2222222222222222222222222222222222222222222222222222222222222222"
+                .to_string(),
+            policy_hash: "sha256:3333333333333333333333333333333333333333333333333333333333333333"
+                .to_string(),
+            command_hash: "sha256:4444444444444444444444444444444444444444444444444444444444444444"
+                .to_string(),
+        },
+        policy_summary: PolicySummary {
+            network: "none".to_string(),
+            mounts: 0,
+            parameters: BTreeMap::new(),
+            admission_constraints_hash: None,
+        },
+        resource_usage: ResourceUsage {
+            cpu: "cpuUsageUsec=0".to_string(),
+            memory: "memoryCurrentBytes=0".to_string(),
+            ..ResourceUsage::default()
+        },
+        events: vec![event],
+        mount_audit: MountAudit::default(),
+        network_audit: NetworkAudit::default(),
+        archive: None,
+        verification: None,
+        redaction: None,
+        integrity: Integrity {
+            digest: String::new(),
+            merkle_root: None,
+            field_salt_seed: None,
+        },
+    };
+    report.integrity.digest = compute_integrity_digest(&report).expect("compute digest");
+    report
+}
+
+fn temp_dir(label: &str) -> PathBuf {
+    let mut path = std::env::temp_dir();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    path.push(format!("safe-run-vibe-{label}-{nanos}"));
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).expect("create temp dir");
+    path
+}
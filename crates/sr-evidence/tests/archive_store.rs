@@ -0,0 +1,499 @@
+use serde_json::json;
+use sr_common::SR_OPS_301;
+use sr_evidence::{
+    archive_report_to_store, archive_report_with_artifacts, compute_integrity_digest,
+    derive_event_hash, list_store_bundles, load_store_report, parse_unix_seconds,
+    verify_bundle_package, ArchiveStore, BundleArtifact, BundleCompression, EvidenceEvent,
+    FsArchiveStore, Integrity, MountAudit, NetworkAudit, ObjectLock, PolicySummary,
+    ReportArtifacts, ResourceUsage, RunReport, S3ArchiveStore, S3StoreConfig,
+    ARCHIVE_OBJECT_LOCK_MODE, RUN_REPORT_SCHEMA_VERSION, STAGE_PREPARE,
+};
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const GENESIS_HASH: &str =
+    "sha256:0000000000000000000000000000000000000000000000000000000000000000";
+const BUCKET: &str = "evidence";
+const LIST_PAGE_SIZE: usize = 2;
+
+#[test]
+fn object_lock_maps_retention_to_retain_until_or_legal_hold() {
+    let lock = ObjectLock::from_retention("unix:1700000000.000000001", "30d").expect("lock");
+    assert_eq!(lock.retain_until, Some(1_700_000_000 + 30 * 86_400));
+    assert!(!lock.legal_hold);
+
+    let forever = ObjectLock::from_retention("unix:1700000000.0", "forever").expect("lock");
+    assert_eq!(forever.retain_until, None);
+    assert!(forever.legal_hold);
+
+    let err = ObjectLock::from_retention("unix:1700000000.0", "soon").expect_err("bad retention");
+    assert_eq!(err.code, SR_OPS_301);
+}
+
+#[test]
+fn fs_store_is_write_once_and_backs_local_archives() {
+    let archive_root = temp_dir("archive-store-fs");
+    let store = FsArchiveStore::new(&archive_root);
+    let lock = ObjectLock::default();
+    store
+        .put_object("bundle-a/note.txt", b"first", &lock)
+        .expect("first put");
+    let err = store
+        .put_object("bundle-a/note.txt", b"second", &lock)
+        .expect_err("overwrite must fail");
+    assert_eq!(err.code, SR_OPS_301);
+    assert_eq!(err.path, "archive.store");
+    assert_eq!(
+        store.get_object("bundle-a/note.txt").expect("get"),
+        b"first".to_vec()
+    );
+    assert!(store.put_object("../escape", b"x", &lock).is_err());
+
+    let archived = archive_report_with_artifacts(
+        &valid_report("sr-store-fs"),
+        &archive_root,
+        "7d",
+        &[],
+        BundleCompression::None,
+    )
+    .expect("archive locally");
+    let entries = list_store_bundles(&store).expect("list bundles");
+    assert_eq!(entries.len(), 1);
+    assert_eq!(
+        entries[0].bundle_id,
+        archived.archive.as_ref().expect("archive").bundle_id
+    );
+
+    let _ = fs::remove_dir_all(&archive_root);
+}
+
+#[test]
+fn s3_store_archives_bundle_with_object_lock_headers() {
+    let stand_in = ObjectStandIn::start();
+    let store = stand_in.store("safe-run/runs");
+    let artifacts = vec![BundleArtifact::from_bytes(
+        "events.jsonl",
+        b"{\"type\":\"run.prepared\"}\n".to_vec(),
+    )];
+
+    let archived = archive_report_to_store(
+        &valid_report("sr-store-s3"),
+        &store,
+        "30d",
+        &artifacts,
+        BundleCompression::None,
+    )
+    .expect("archive to s3");
+    let metadata = archived.archive.as_ref().expect("archive metadata");
+    let report_key = format!("safe-run/runs/{}/run_report.json", metadata.bundle_id);
+    let package_key = format!("safe-run/runs/{}/bundle.tar", metadata.bundle_id);
+
+    let objects = stand_in.objects.lock().expect("lock objects");
+    let stored = objects.get(&report_key).expect("report object stored");
+    let expected_until = parse_unix_seconds(&metadata.stored_at).expect("stored at") + 30 * 86_400;
+    assert_eq!(
+        stored
+            .headers
+            .get("x-amz-object-lock-mode")
+            .map(String::as_str),
+        Some(ARCHIVE_OBJECT_LOCK_MODE)
+    );
+    assert_eq!(
+        stored
+            .headers
+            .get("x-amz-object-lock-retain-until-date")
+            .map(String::as_str),
+        Some(rfc3339(expected_until).as_str())
+    );
+    assert_eq!(
+        stored.headers.get("if-none-match").map(String::as_str),
+        Some("*")
+    );
+    assert!(stored.headers["authorization"].starts_with("AWS4-HMAC-SHA256 Credential=minio/"));
+    let package = objects
+        .get(&package_key)
+        .expect("package stored")
+        .body
+        .clone();
+    drop(objects);
+
+    assert_eq!(
+        load_store_report(&store, &metadata.bundle_id).expect("load report"),
+        archived
+    );
+    let entries = list_store_bundles(&store).expect("list bundles");
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].retention, "30d");
+
+    let package_dir = temp_dir("archive-store-s3-package");
+    let package_path = package_dir.join("bundle.tar");
+    fs::write(&package_path, package).expect("write package");
+    let verified = verify_bundle_package(&package_path).expect("verify package");
+    assert!(verified.valid, "{:?}", verified.errors);
+
+    let _ = fs::remove_dir_all(&package_dir);
+}
+
+#[test]
+fn s3_store_rejects_overwrite_and_paginates_listing() {
+    let stand_in = ObjectStandIn::start();
+    let store = stand_in.store("");
+    let lock = ObjectLock {
+        retain_until: None,
+        legal_hold: true,
+    };
+    for idx in 0..5 {
+        store
+            .put_object(&format!("bundle-{idx}/run_report.json"), b"{}", &lock)
+            .expect("put object");
+    }
+    let err = store
+        .put_object("bundle-0/run_report.json", b"{\"x\":1}", &lock)
+        .expect_err("overwrite must fail");
+    assert_eq!(err.code, SR_OPS_301);
+    assert!(err.message.contains("write-once"));
+    assert_eq!(
+        stand_in.objects.lock().expect("lock objects")["bundle-0/run_report.json"].headers
+            ["x-amz-object-lock-legal-hold"],
+        "ON"
+    );
+
+    let keys = store.list_objects("bundle-").expect("list keys");
+    assert_eq!(keys.len(), 5);
+    assert_eq!(keys[4], "bundle-4/run_report.json");
+    assert!(!store
+        .object_exists("bundle-9/run_report.json")
+        .expect("head"));
+    let err = store
+        .get_object("bundle-9/run_report.json")
+        .expect_err("missing object");
+    assert!(err.message.contains("404"));
+}
+
+#[test]
+fn s3_config_parses_archive_url_and_requires_tls_off_loopback() {
+    let config = S3StoreConfig::from_url(
+        "s3://evidence/safe-run/",
+        "http://127.0.0.1:9000/",
+        None,
+        "key",
+        "secret",
+    )
+    .expect("parse url");
+    assert_eq!(config.bucket, "evidence");
+    assert_eq!(config.prefix, "safe-run/");
+    assert_eq!(config.region, "us-east-1");
+    assert_eq!(config.endpoint, "http://127.0.0.1:9000");
+    assert_eq!(
+        S3ArchiveStore::new(config.clone())
+            .expect("store")
+            .location(),
+        "s3://evidence/safe-run"
+    );
+
+    assert!(S3StoreConfig::from_url("/tmp/archive", "http://x", None, "k", "s").is_err());
+    let https = S3StoreConfig {
+        endpoint: "https://s3.example.com".to_string(),
+        ..config.clone()
+    };
+    S3ArchiveStore::new(https).expect("https endpoint");
+    for endpoint in [
+        "http://s3.example.com",
+        "http://10.0.0.5:9000",
+        "ftp://s3.example.com",
+        "https://s3.example.com/bucket",
+    ] {
+        let err = S3ArchiveStore::new(S3StoreConfig {
+            endpoint: endpoint.to_string(),
+            ..config.clone()
+        })
+        .expect_err("endpoint must be rejected");
+        assert_eq!(err.code, SR_OPS_301, "{endpoint}");
+    }
+}
+
+#[test]
+fn s3_https_endpoint_negotiates_tls() {
+    // The stand-in only speaks plain HTTP, so an https endpoint must fail the handshake
+    // instead of sending the request in the clear.
+    let server = ObjectStandIn::start();
+    let endpoint = server.endpoint.replacen("http://", "https://", 1);
+    let config = S3StoreConfig::from_url(
+        &format!("s3://{BUCKET}/tls"),
+        &endpoint,
+        None,
+        "minio",
+        "minio-secret",
+    )
+    .expect("config");
+    let err = S3ArchiveStore::new(config)
+        .expect("store")
+        .get_object("bundle/run_report.json")
+        .expect_err("plain HTTP server cannot complete TLS");
+    assert_eq!(err.code, SR_OPS_301);
+    assert!(server.objects.lock().expect("lock objects").is_empty());
+}
+
+#[derive(Clone)]
+struct StoredObject {
+    body: Vec<u8>,
+    headers: BTreeMap<String, String>,
+}
+
+/// Minimal MinIO-style S3 stand-in: path-style PUT/GET/HEAD, conditional writes and
+/// paginated ListObjectsV2.
+struct ObjectStandIn {
+    endpoint: String,
+    objects: Arc<Mutex<BTreeMap<String, StoredObject>>>,
+}
+
+impl ObjectStandIn {
+    fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind stand-in");
+        let authority = listener.local_addr().expect("addr").to_string();
+        let endpoint = format!("http://{authority}");
+        let objects = Arc::new(Mutex::new(BTreeMap::new()));
+        let shared = Arc::clone(&objects);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                handle_request(stream, &authority, &shared);
+            }
+        });
+        Self { endpoint, objects }
+    }
+
+    fn store(&self, prefix: &str) -> S3ArchiveStore {
+        let url = format!("s3://{BUCKET}/{prefix}");
+        let config = S3StoreConfig::from_url(&url, &self.endpoint, None, "minio", "minio-secret")
+            .expect("config");
+        S3ArchiveStore::new(config).expect("store")
+    }
+}
+
+fn handle_request(
+    stream: TcpStream,
+    authority: &str,
+    objects: &Mutex<BTreeMap<String, StoredObject>>,
+) {
+    let mut reader = BufReader::new(stream.try_clone().expect("clone stream"));
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).is_err() {
+        // e.g. a TLS ClientHello that is not valid UTF-8
+        return;
+    }
+    let mut headers = BTreeMap::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).is_err() {
+            return;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            headers.insert(key.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+    let length = headers
+        .get("content-length")
+        .and_then(|value| value.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut body = vec![0u8; length];
+    reader.read_exact(&mut body).expect("read body");
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or_default().to_string();
+    let (path, query) = target.split_once('?').unwrap_or((target.as_str(), ""));
+    let query = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (percent_decode(key), percent_decode(value)))
+        .collect::<BTreeMap<String, String>>();
+    let key = percent_decode(
+        path.strip_prefix(&format!("/{BUCKET}"))
+            .unwrap_or_default()
+            .trim_start_matches('/'),
+    );
+
+    let mut objects = objects.lock().expect("lock objects");
+    // The signed `host` header must be the one on the wire.
+    let (status, response_body) = if !headers
+        .get("authorization")
+        .is_some_and(|value| value.starts_with("AWS4-HMAC-SHA256 ") && value.contains("host;"))
+        || headers.get("host").map(String::as_str) != Some(authority)
+    {
+        (403, b"<Error><Code>AccessDenied</Code></Error>".to_vec())
+    } else if method == "PUT" {
+        match objects.entry(key) {
+            Entry::Occupied(_) => (
+                412,
+                b"<Error><Code>PreconditionFailed</Code></Error>".to_vec(),
+            ),
+            Entry::Vacant(slot) => {
+                slot.insert(StoredObject { body, headers });
+                (200, Vec::new())
+            }
+        }
+    } else if query.get("list-type").map(String::as_str) == Some("2") {
+        let prefix = query.get("prefix").cloned().unwrap_or_default();
+        let start = query
+            .get("continuation-token")
+            .and_then(|token| token.parse::<usize>().ok())
+            .unwrap_or(0);
+        let matching = objects
+            .keys()
+            .filter(|item| item.starts_with(&prefix))
+            .collect::<Vec<&String>>();
+        let page = matching
+            .iter()
+            .skip(start)
+            .take(LIST_PAGE_SIZE)
+            .map(|item| format!("<Contents><Key>{item}</Key></Contents>"))
+            .collect::<String>();
+        let next = start + LIST_PAGE_SIZE;
+        let truncated = next < matching.len();
+        let token = if truncated {
+            format!("<NextContinuationToken>{next}</NextContinuationToken>")
+        } else {
+            String::new()
+        };
+        (
+            200,
+            format!(
+                "<ListBucketResult><IsTruncated>{truncated}</IsTruncated>{page}{token}</ListBucketResult>"
+            )
+            .into_bytes(),
+        )
+    } else {
+        match objects.get(&key) {
+            Some(object) => (200, object.body.clone()),
+            None => (404, b"<Error><Code>NoSuchKey</Code></Error>".to_vec()),
+        }
+    };
+    drop(objects);
+
+    let mut stream = stream;
+    let head = format!(
+        "HTTP/1.1 {status} X\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+        response_body.len()
+    );
+    let _ = stream.write_all(head.as_bytes());
+    if method != "HEAD" {
+        let _ = stream.write_all(&response_body);
+    }
+}
+
+fn percent_decode(raw: &str) -> String {
+    let bytes = raw.as_bytes();
+    let mut decoded = Vec::new();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if bytes[idx] == b'%' && idx + 2 < bytes.len() {
+            if let Ok(value) = u8::from_str_radix(&raw[idx + 1..idx + 3], 16) {
+                decoded.push(value);
+                idx += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[idx]);
+        idx += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+fn rfc3339(secs: u64) -> String {
+    // Days-from-civil inverse, mirrored here so the test does not rely on the crate helper.
+    let days = secs / 86_400;
+    let rem = secs % 86_400;
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
+
+fn valid_report(run_id: &str) -> RunReport {
+    let mut event = EvidenceEvent {
+        timestamp: "2026-02-21T10:00:00Z".to_string(),
+        run_id: run_id.to_string(),
+        stage: STAGE_PREPARE.to_string(),
+        event_type: "run.prepared".to_string(),
+        payload: json!({"workdir": "/tmp/safe-run/test"}),
+        hash_prev: GENESIS_HASH.to_string(),
+        hash_self: String::new(),
+    };
+    event.hash_self = derive_event_hash(&event);
+
+    let mut report = RunReport {
+        schema_version: RUN_REPORT_SCHEMA_VERSION.to_string(),
+        run_id: run_id.to_string(),
+        started_at: "2026-02-21T10:00:00Z".to_string(),
+        finished_at: "2026-02-21T10:00:01Z".to_string(),
+        exit_code: 0,
+        artifacts: ReportArtifacts {
+            kernel_hash: "sha256:1111111111111111111111111111111111111111111111111111111111111111"
+                .to_string(),
+            rootfs_hash: "sha256:2222222222222222222222222222222222222222222222222222222222222222"
+                .to_string(),
+            policy_hash: "sha256:3333333333333333333333333333333333333333333333333333333333333333"
+                .to_string(),
+            command_hash: "sha256:4444444444444444444444444444444444444444444444444444444444444444"
+                .to_string(),
+        },
+        policy_summary: PolicySummary {
+            network: "none".to_string(),
+            mounts: 0,
+            parameters: BTreeMap::new(),
+            admission_constraints_hash: None,
+        },
+        resource_usage: ResourceUsage {
+            cpu: "cpuUsageUsec=0".to_string(),
+            memory: "memoryCurrentBytes=0".to_string(),
+            ..ResourceUsage::default()
+        },
+        events: vec![event],
+        mount_audit: MountAudit::default(),
+        network_audit: NetworkAudit::default(),
+        archive: None,
+        verification: None,
+        redaction: None,
+        integrity: Integrity {
+            digest: String::new(),
+            merkle_root: None,
+            field_salt_seed: None,
+        },
+    };
+    report.integrity.digest = compute_integrity_digest(&report).expect("compute digest");
+    report
+}
+
+fn temp_dir(label: &str) -> PathBuf {
+    let mut path = std::env::temp_dir();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    path.push(format!("safe-run-vibe-{label}-{nanos}"));
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).expect("create temp dir");
+    path
+}
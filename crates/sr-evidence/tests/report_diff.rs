@@ -0,0 +1,267 @@
+use serde_json::json;
+use sr_common::SR_EVD_002;
+use sr_evidence::{
+    build_report, compute_integrity_digest, derive_event_hash, diff_reports, render_diff_text,
+    DiffFormat, EvidenceEvent, MountAudit, NetworkAudit, PolicySummary, ReportArtifacts,
+    ResourceUsage, RunReport, EVENT_MOUNT_APPLIED, EVENT_MOUNT_REJECTED, EVENT_RESOURCE_SAMPLED,
+    EVENT_RUN_PREPARED, EVENT_VM_EXITED, EVENT_VM_STARTED, STAGE_LAUNCH, STAGE_MONITOR,
+    STAGE_MOUNT, STAGE_PREPARE,
+};
+use std::collections::BTreeMap;
+
+const GENESIS_HASH: &str =
+    "sha256:0000000000000000000000000000000000000000000000000000000000000000";
+
+#[test]
+fn report_diff_identical_runs_are_equivalent() {
+    let left = sample_report("sr-report-diff-a", 0, base_events(0));
+    let right = sample_report("sr-report-diff-b", 0, base_events(0));
+
+    let diff = diff_reports(&left, &right).expect("diff reports");
+    assert!(diff.identical, "{diff:?}");
+    assert!(diff.fields.is_empty());
+    assert!(diff.events.is_empty());
+    assert!(render_diff_text(&diff).contains("reports are equivalent"));
+}
+
+#[test]
+fn report_diff_reports_changed_fields_by_path() {
+    let left = sample_report("sr-report-diff-a", 0, base_events(0));
+    let mut right = sample_report("sr-report-diff-b", 137, base_events(137));
+    right.artifacts.rootfs_hash =
+        "sha256:9999999999999999999999999999999999999999999999999999999999999999".to_string();
+    right.network_audit.blocked_hits = 3;
+
+    let diff = diff_reports(&left, &right).expect("diff reports");
+    let paths = diff
+        .fields
+        .iter()
+        .map(|item| item.path.as_str())
+        .collect::<Vec<_>>();
+    assert!(paths.contains(&"artifacts.rootfsHash"));
+    assert!(paths.contains(&"exitCode"));
+    assert!(paths.contains(&"networkAudit.blockedHits"));
+    let exit = diff
+        .fields
+        .iter()
+        .find(|item| item.path == "exitCode")
+        .expect("exit code diff");
+    assert_eq!(exit.left, json!(0));
+    assert_eq!(exit.right, json!(137));
+}
+
+#[test]
+fn report_diff_aligns_events_by_stage_and_type() {
+    let left = sample_report("sr-report-diff-a", 0, base_events(0));
+    let mut right_events = base_events(0);
+    right_events.insert(
+        2,
+        (
+            STAGE_MOUNT,
+            EVENT_MOUNT_REJECTED,
+            json!({"reason": "path_outside_allowlist"}),
+        ),
+    );
+    let right = sample_report("sr-report-diff-b", 0, right_events);
+
+    let diff = diff_reports(&left, &right).expect("diff reports");
+    assert_eq!(diff.events.len(), 1, "{:?}", diff.events);
+    let added = &diff.events[0];
+    assert_eq!(added.op, "added");
+    assert_eq!(added.event_type, EVENT_MOUNT_REJECTED);
+    assert_eq!(added.right_index, Some(2));
+    assert_eq!(added.left_index, None);
+}
+
+#[test]
+fn report_diff_reports_payload_changes_and_ignores_volatile_keys() {
+    let left = sample_report("sr-report-diff-a", 0, base_events(0));
+    let mut right_events = base_events(0);
+    right_events[1].2 = json!({"source": "/var/lib/safe-run/other", "target": "/data/input"});
+    right_events[3].2 = json!({"pid": 9999});
+    let right = sample_report("sr-report-diff-b", 0, right_events);
+
+    let diff = diff_reports(&left, &right).expect("diff reports");
+    assert_eq!(diff.events.len(), 1, "{:?}", diff.events);
+    let changed = &diff.events[0];
+    assert_eq!(changed.op, "changed");
+    assert_eq!(changed.event_type, EVENT_MOUNT_APPLIED);
+    assert_eq!(changed.payload_changes[0].path, "payload.source");
+
+    let text = render_diff_text(&diff);
+    assert!(text.contains("~ event mount/mount.applied #1 -> #1"));
+    assert!(text.contains("payload.source"));
+}
+
+#[test]
+fn report_diff_collapses_resource_samples_into_one_aggregate() {
+    let with_samples = |count: u64| {
+        let mut events = base_events(0);
+        for idx in 0..count {
+            events.insert(
+                4,
+                (
+                    STAGE_MONITOR,
+                    EVENT_RESOURCE_SAMPLED,
+                    json!({"cpuUsageUsec": idx * 100, "memoryCurrentBytes": 4096}),
+                ),
+            );
+        }
+        events
+    };
+    let left = sample_report("sr-report-diff-a", 0, with_samples(3));
+    let right = sample_report("sr-report-diff-b", 0, with_samples(5));
+
+    let diff = diff_reports(&left, &right).expect("diff reports");
+    assert_eq!(diff.events.len(), 1, "{:?}", diff.events);
+    let aggregate = &diff.events[0];
+    assert_eq!(aggregate.op, "changed");
+    assert_eq!(aggregate.event_type, EVENT_RESOURCE_SAMPLED);
+    assert_eq!((aggregate.left_index, aggregate.right_index), (None, None));
+    let changes = aggregate
+        .payload_changes
+        .iter()
+        .map(|change| {
+            (
+                change.path.as_str(),
+                change.left.clone(),
+                change.right.clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        changes,
+        vec![
+            ("samples.count", json!(3), json!(5)),
+            ("samples.cpuUsageUsecMax", json!(200), json!(400)),
+        ]
+    );
+    assert!(render_diff_text(&diff).contains("~ event monitor/resource.sampled (aggregate)"));
+
+    let same = sample_report("sr-report-diff-c", 0, with_samples(3));
+    assert!(diff_reports(&left, &same).expect("diff reports").identical);
+}
+
+#[test]
+fn report_diff_aligns_long_event_streams() {
+    let long_events = |skip: usize| {
+        let mut events = base_events(0);
+        for idx in 0..5_000usize {
+            if idx != skip {
+                events.insert(
+                    events.len() - 1,
+                    (
+                        if idx % 2 == 0 {
+                            STAGE_MOUNT
+                        } else {
+                            STAGE_LAUNCH
+                        },
+                        if idx % 2 == 0 {
+                            EVENT_MOUNT_APPLIED
+                        } else {
+                            EVENT_VM_STARTED
+                        },
+                        json!({}),
+                    ),
+                );
+            }
+        }
+        events
+    };
+    let left = sample_report("sr-report-diff-a", 0, long_events(usize::MAX));
+    let right = sample_report("sr-report-diff-b", 0, long_events(2_501));
+
+    let diff = diff_reports(&left, &right).expect("diff reports");
+    assert_eq!(diff.events.len(), 1, "{:?}", diff.events);
+    assert_eq!(diff.events[0].op, "removed");
+    assert_eq!(diff.events[0].event_type, EVENT_VM_STARTED);
+}
+
+#[test]
+fn report_diff_format_parse_rejects_unknown_format() {
+    assert_eq!(DiffFormat::parse("TEXT").expect("text"), DiffFormat::Text);
+    let err = DiffFormat::parse("yaml").expect_err("yaml is unsupported");
+    assert_eq!(err.code, SR_EVD_002);
+}
+
+type EventSpec = (&'static str, &'static str, serde_json::Value);
+
+fn base_events(exit_code: i32) -> Vec<EventSpec> {
+    vec![
+        (
+            STAGE_PREPARE,
+            EVENT_RUN_PREPARED,
+            json!({"workdir": "/tmp/safe-run/runs/x"}),
+        ),
+        (
+            STAGE_MOUNT,
+            EVENT_MOUNT_APPLIED,
+            json!({"source": "/var/lib/safe-run/input", "target": "/data/input"}),
+        ),
+        (
+            STAGE_MOUNT,
+            EVENT_MOUNT_APPLIED,
+            json!({"source": "/var/lib/safe-run/cache", "target": "/data/cache"}),
+        ),
+        (STAGE_LAUNCH, EVENT_VM_STARTED, json!({"pid": 42})),
+        (
+            STAGE_MONITOR,
+            EVENT_VM_EXITED,
+            json!({"exitCode": exit_code, "timedOut": false}),
+        ),
+    ]
+}
+
+fn sample_report(run_id: &str, exit_code: i32, specs: Vec<EventSpec>) -> RunReport {
+    let mut events = Vec::new();
+    let mut prev = GENESIS_HASH.to_string();
+    for (idx, (stage, event_type, payload)) in specs.into_iter().enumerate() {
+        let mut event = EvidenceEvent {
+            timestamp: format!("2026-02-21T10:00:0{idx}Z"),
+            run_id: run_id.to_string(),
+            stage: stage.to_string(),
+            event_type: event_type.to_string(),
+            payload,
+            hash_prev: prev.clone(),
+            hash_self: String::new(),
+        };
+        event.hash_self = derive_event_hash(&event);
+        prev = event.hash_self.clone();
+        events.push(event);
+    }
+
+    let mut report = build_report(
+        run_id.to_string(),
+        "2026-02-21T10:00:00Z".to_string(),
+        "2026-02-21T10:00:05Z".to_string(),
+        exit_code,
+        ReportArtifacts {
+            kernel_hash: "sha256:1111111111111111111111111111111111111111111111111111111111111111"
+                .to_string(),
+            rootfs_hash: "sha256:2222222222222222222222222222222222222222222222222222222222222222"
+                .to_string(),
+            policy_hash: "sha256:3333333333333333333333333333333333333333333333333333333333333333"
+                .to_string(),
+            command_hash: "sha256:4444444444444444444444444444444444444444444444444444444444444444"
+                .to_string(),
+        },
+        PolicySummary {
+            network: "none".to_string(),
+            mounts: 2,
+            parameters: BTreeMap::new(),
+            admission_constraints_hash: None,
+        },
+        ResourceUsage {
+            cpu: "cpuUsageUsec=0".to_string(),
+            memory: "memoryCurrentBytes=0".to_string(),
+            ..ResourceUsage::default()
+        },
+        events,
+        MountAudit::default(),
+        NetworkAudit::default(),
+        String::new(),
+    )
+    .expect("build report");
+    report.integrity.digest = compute_integrity_digest(&report).expect("compute digest");
+    report
+}
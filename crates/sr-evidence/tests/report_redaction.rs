@@ -0,0 +1,340 @@
+use serde_json::json;
+use sr_common::{SR_EVD_002, SR_EVD_304};
+use sr_evidence::{
+    build_report, compute_event_merkle_root, compute_integrity_digest, derive_event_hash,
+    payload_field_commitment, redact_report, verify_redacted_against_original, verify_report,
+    EventSelector, EvidenceEvent, FieldRedaction, MountAudit, NetworkAudit, PolicySummary,
+    RedactionRequest, ReportArtifacts, ResourceUsage, RunReport, STAGE_MOUNT,
+};
+use std::collections::BTreeMap;
+
+const GENESIS_HASH: &str =
+    "sha256:0000000000000000000000000000000000000000000000000000000000000000";
+
+#[test]
+fn report_redaction_unredacted_report_carries_verifiable_merkle_root() {
+    let report = report_with_mount_events(5);
+    assert_eq!(
+        report.integrity.merkle_root,
+        compute_event_merkle_root(&report.events, report.integrity.field_salt_seed.as_deref())
+    );
+
+    let result = verify_report(&report);
+    assert!(result.valid, "{:?}", result.errors);
+    assert!(result
+        .checks
+        .iter()
+        .any(|check| check.name == "merkle_root" && check.ok));
+}
+
+#[test]
+fn report_redaction_removed_events_keep_inclusion_proofs_valid() {
+    let report = report_with_mount_events(5);
+    let request = RedactionRequest {
+        remove_events: vec![EventSelector::Index(1), EventSelector::Index(3)],
+        redact_fields: vec![],
+    };
+
+    let redacted = redact_report(&report, &request).expect("redact report");
+    assert_eq!(redacted.events.len(), 3);
+    let redaction = redacted.redaction.as_ref().expect("redaction metadata");
+    assert_eq!(redaction.leaf_count, 5);
+    assert_eq!(redaction.original_digest, report.integrity.digest);
+    assert_eq!(
+        redaction
+            .disclosures
+            .iter()
+            .map(|item| item.index)
+            .collect::<Vec<_>>(),
+        vec![0, 2, 4]
+    );
+
+    let result = verify_report(&redacted);
+    assert!(result.valid, "{:?}", result.errors);
+}
+
+#[test]
+fn report_redaction_payload_field_is_replaced_by_commitment() {
+    let report = report_with_mount_events(3);
+    let request = RedactionRequest {
+        remove_events: vec![],
+        redact_fields: vec![FieldRedaction::parse("mount.applied:source").expect("parse")],
+    };
+
+    let redacted = redact_report(&report, &request).expect("redact report");
+    assert!(redacted
+        .events
+        .iter()
+        .all(|event| event.payload.get("source").is_none()));
+    assert!(redacted
+        .events
+        .iter()
+        .all(|event| event.payload.get("target").is_some()));
+    let redaction = redacted.redaction.as_ref().expect("redaction metadata");
+    assert!(redaction
+        .disclosures
+        .iter()
+        .all(|item| item.redacted_fields.contains_key("source")));
+
+    let result = verify_report(&redacted);
+    assert!(result.valid, "{:?}", result.errors);
+}
+
+#[test]
+fn report_redaction_can_be_applied_twice() {
+    let report = report_with_mount_events(4);
+    let first = redact_report(
+        &report,
+        &RedactionRequest {
+            remove_events: vec![EventSelector::Index(0)],
+            redact_fields: vec![],
+        },
+    )
+    .expect("first redaction");
+    let second = redact_report(
+        &first,
+        &RedactionRequest {
+            remove_events: vec![EventSelector::Index(2)],
+            redact_fields: vec![FieldRedaction::parse("*:target").expect("parse")],
+        },
+    )
+    .expect("second redaction");
+
+    assert_eq!(second.events.len(), 2);
+    let result = verify_report(&second);
+    assert!(result.valid, "{:?}", result.errors);
+}
+
+#[test]
+fn report_redaction_tampered_disclosed_payload_returns_304() {
+    let report = report_with_mount_events(3);
+    let mut redacted = redact_report(
+        &report,
+        &RedactionRequest {
+            remove_events: vec![],
+            redact_fields: vec![FieldRedaction::parse("0:source").expect("parse")],
+        },
+    )
+    .expect("redact report");
+    redacted.events[0].payload["target"] = json!("/data/tampered");
+    redacted.integrity.digest = compute_integrity_digest(&redacted).expect("recompute digest");
+
+    let result = verify_report(&redacted);
+    assert!(!result.valid);
+    assert!(result.errors.iter().any(|err| err.code == SR_EVD_304));
+}
+
+#[test]
+fn report_redaction_requires_merkle_root() {
+    let mut report = report_with_mount_events(2);
+    report.integrity.merkle_root = None;
+
+    let err = redact_report(&report, &RedactionRequest::default()).expect_err("must fail");
+    assert_eq!(err.code, SR_EVD_002);
+    assert_eq!(err.path, "integrity.merkleRoot");
+}
+
+#[test]
+fn report_redaction_redacted_fields_do_not_disclose_salts_or_chain_hashes() {
+    let report = report_with_mount_events(3);
+    let seed = report
+        .integrity
+        .field_salt_seed
+        .clone()
+        .expect("full report carries the salt seed");
+    let redacted = redact_report(
+        &report,
+        &RedactionRequest {
+            remove_events: vec![],
+            redact_fields: vec![FieldRedaction::parse("1:source").expect("parse")],
+        },
+    )
+    .expect("redact report");
+
+    assert!(redacted.integrity.field_salt_seed.is_none());
+    assert!(!serde_json::to_string(&redacted)
+        .expect("serialize")
+        .contains(&seed));
+    let disclosure = &redacted.redaction.as_ref().expect("redaction").disclosures[1];
+    assert!(!disclosure.field_salts.contains_key("source"));
+    assert!(disclosure.field_salts.contains_key("target"));
+    // Without the salt, guessing the value does not reproduce the commitment.
+    let guess = json!("/var/lib/safe-run/secret-1");
+    assert_ne!(
+        disclosure.redacted_fields["source"],
+        payload_field_commitment("source", &guess, "")
+    );
+    assert!(redacted.events[1].hash_self.is_empty());
+    assert!(redacted.events[2].hash_prev.is_empty());
+    assert_eq!(redacted.events[0].hash_self, report.events[0].hash_self);
+
+    let result = verify_report(&redacted);
+    assert!(result.valid, "{:?}", result.errors);
+
+    let again = redact_report(
+        &redacted,
+        &RedactionRequest {
+            remove_events: vec![EventSelector::Index(1)],
+            redact_fields: vec![],
+        },
+    )
+    .expect("redact again");
+    let result = verify_report(&again);
+    assert!(result.valid, "{:?}", result.errors);
+}
+
+#[test]
+fn report_redaction_proofs_pin_original_position_and_leaf_count() {
+    let report = report_with_mount_events(5);
+    let redacted = redact_report(
+        &report,
+        &RedactionRequest {
+            remove_events: vec![EventSelector::Index(3)],
+            redact_fields: vec![],
+        },
+    )
+    .expect("redact report");
+
+    let mut moved = redacted.clone();
+    moved.redaction.as_mut().expect("redaction").disclosures[2].index = 3;
+    moved.integrity.digest = compute_integrity_digest(&moved).expect("recompute digest");
+    let result = verify_report(&moved);
+    assert!(result.errors.iter().any(|err| err.code == SR_EVD_304));
+
+    for leaf_count in [4, 6, 8] {
+        let mut resized = redacted.clone();
+        resized.redaction.as_mut().expect("redaction").leaf_count = leaf_count;
+        resized.integrity.digest = compute_integrity_digest(&resized).expect("recompute digest");
+        let result = verify_report(&resized);
+        assert!(
+            result.errors.iter().any(|err| err.code == SR_EVD_304),
+            "leafCount {leaf_count}"
+        );
+    }
+}
+
+#[test]
+fn report_redaction_withholds_summaries_derived_from_events() {
+    let mut report = report_with_mount_events(2);
+    report
+        .mount_audit
+        .reasons
+        .push("mount /var/lib/safe-run/secret-0 rejected".to_string());
+    report
+        .policy_summary
+        .parameters
+        .insert("dataset".to_string(), "secret-0".to_string());
+    report.integrity.digest = compute_integrity_digest(&report).expect("compute digest");
+
+    let redacted = redact_report(
+        &report,
+        &RedactionRequest {
+            remove_events: vec![EventSelector::Index(0)],
+            redact_fields: vec![],
+        },
+    )
+    .expect("redact report");
+    assert!(redacted.mount_audit.reasons.is_empty());
+    assert!(redacted.policy_summary.parameters.is_empty());
+    assert_eq!(
+        redacted.redaction.as_ref().expect("redaction").withheld,
+        vec!["mountAudit.reasons", "policySummary.parameters"]
+    );
+    assert!(!serde_json::to_string(&redacted)
+        .expect("serialize")
+        .contains("secret-0"));
+    let result = verify_report(&redacted);
+    assert!(result.valid, "{:?}", result.errors);
+}
+
+#[test]
+fn report_redaction_forged_copy_verifies_alone_but_not_against_the_original() {
+    let original = report_with_mount_events(4);
+    let request = RedactionRequest {
+        remove_events: vec![EventSelector::Index(2)],
+        redact_fields: vec![FieldRedaction::parse("0:source").expect("parse")],
+    };
+    let redacted = redact_report(&original, &request).expect("redact report");
+    let result = verify_redacted_against_original(&redacted, &original);
+    assert!(result.valid, "{:?}", result.errors);
+    assert!(result
+        .checks
+        .iter()
+        .any(|check| check.name == "original_binding" && check.ok));
+
+    // A forger redacts a report of their own and claims the original's digest.
+    let other = report_with_mount_events(4);
+    let mut forged = redact_report(&other, &request).expect("redact other report");
+    forged
+        .redaction
+        .as_mut()
+        .expect("redaction metadata")
+        .original_digest = original.integrity.digest.clone();
+    forged.integrity.digest = compute_integrity_digest(&forged).expect("compute digest");
+    assert!(verify_report(&forged).valid);
+
+    let result = verify_redacted_against_original(&forged, &original);
+    assert!(!result.valid);
+    assert!(result
+        .errors
+        .iter()
+        .any(|err| err.code == SR_EVD_304 && err.path == "redaction.originalDigest"));
+}
+
+fn report_with_mount_events(count: usize) -> RunReport {
+    let mut events = Vec::new();
+    let mut prev = GENESIS_HASH.to_string();
+    for i in 0..count {
+        let mut event = EvidenceEvent {
+            timestamp: format!("2026-02-21T10:00:0{i}Z"),
+            run_id: "sr-report-redaction-test".to_string(),
+            stage: STAGE_MOUNT.to_string(),
+            event_type: "mount.applied".to_string(),
+            payload: json!({
+                "source": format!("/var/lib/safe-run/secret-{i}"),
+                "target": format!("/data/input-{i}"),
+                "read_only": true
+            }),
+            hash_prev: prev.clone(),
+            hash_self: String::new(),
+        };
+        event.hash_self = derive_event_hash(&event);
+        prev = event.hash_self.clone();
+        events.push(event);
+    }
+
+    let mut report = build_report(
+        "sr-report-redaction-test".to_string(),
+        "2026-02-21T10:00:00Z".to_string(),
+        "2026-02-21T10:00:05Z".to_string(),
+        0,
+        ReportArtifacts {
+            kernel_hash: "sha256:1111111111111111111111111111111111111111111111111111111111111111"
+                .to_string(),
+            rootfs_hash: "sha256:2222222222222222222222222222222222222222222222222222222222222222"
+                .to_string(),
+            policy_hash: "sha256:3333333333333333333333333333333333333333333333333333333333333333"
+                .to_string(),
+            command_hash: "sha256:4444444444444444444444444444444444444444444444444444444444444444"
+                .to_string(),
+        },
+        PolicySummary {
+            network: "none".to_string(),
+            mounts: count,
+            parameters: BTreeMap::new(),
+            admission_constraints_hash: None,
+        },
+        ResourceUsage {
+            cpu: "cpuUsageUsec=0".to_string(),
+            memory: "memoryCurrentBytes=0".to_string(),
+            ..ResourceUsage::default()
+        },
+        events,
+        MountAudit::default(),
+        NetworkAudit::default(),
+        String::new(),
+    )
+    .expect("build report");
+    report.integrity.digest = compute_integrity_digest(&report).expect("compute digest");
+    report
+}
@@ -0,0 +1,148 @@
+use serde_json::json;
+use sr_common::SR_EVD_002;
+use sr_evidence::{
+    build_report, compute_integrity_digest, derive_event_hash, render_report, EvidenceEvent,
+    MountAudit, NetworkAudit, PolicySummary, RenderFormat, ReportArtifacts, ResourceUsage,
+    RunReport, EVENT_RESOURCE_SAMPLED, EVENT_RUN_PREPARED, EVENT_VM_STARTED, STAGE_LAUNCH,
+    STAGE_MONITOR, STAGE_PREPARE,
+};
+use std::collections::BTreeMap;
+
+const GENESIS_HASH: &str =
+    "sha256:0000000000000000000000000000000000000000000000000000000000000000";
+
+#[test]
+fn report_render_html_contains_sections_and_chart() {
+    let report = sample_report();
+    let html = render_report(&report, RenderFormat::Html);
+
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<h2>Verification</h2>"));
+    assert!(html.contains("Result: <strong>pass</strong>"));
+    assert!(html.contains("<h2>Mount audit</h2>"));
+    assert!(html.contains("<h2>Network audit</h2>"));
+    assert!(html.contains("<svg"));
+    assert!(html.contains("<h3>prepare</h3>"));
+    assert!(html.contains("<h3>monitor</h3>"));
+    assert!(!html.contains("<script"));
+}
+
+#[test]
+fn report_render_html_escapes_payload_content() {
+    let report = sample_report_with_payload(json!({"workdir": "<script>alert(1)</script>"}));
+    let html = render_report(&report, RenderFormat::Html);
+
+    assert!(!html.contains("<script>alert(1)</script>"));
+    assert!(html.contains("&lt;script&gt;"));
+}
+
+#[test]
+fn report_render_markdown_orders_timeline_by_stage() {
+    let report = sample_report();
+    let markdown = render_report(&report, RenderFormat::Markdown);
+
+    let prepare = markdown.find("### prepare").expect("prepare section");
+    let launch = markdown.find("### launch").expect("launch section");
+    let monitor = markdown.find("### monitor").expect("monitor section");
+    assert!(prepare < launch && launch < monitor);
+    assert!(markdown.contains("| Requested | Accepted | Rejected | Reasons |"));
+    assert!(markdown.contains("Result: **pass**"));
+    assert!(markdown.contains("Memory: `"));
+}
+
+#[test]
+fn report_render_marks_failed_verification() {
+    let mut report = sample_report();
+    report.events[0].hash_prev =
+        "sha256:aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".to_string();
+    let markdown = render_report(&report, RenderFormat::Markdown);
+
+    assert!(markdown.contains("Result: **fail**"));
+    assert!(markdown.contains("SR-EVD-303"));
+}
+
+#[test]
+fn report_render_format_parse_rejects_unknown_format() {
+    assert_eq!(
+        RenderFormat::parse("md").expect("md"),
+        RenderFormat::Markdown
+    );
+    assert_eq!(
+        RenderFormat::parse("HTML").expect("html"),
+        RenderFormat::Html
+    );
+    let err = RenderFormat::parse("pdf").expect_err("pdf is unsupported");
+    assert_eq!(err.code, SR_EVD_002);
+}
+
+fn sample_report() -> RunReport {
+    sample_report_with_payload(json!({"workdir": "/tmp/safe-run/test"}))
+}
+
+fn sample_report_with_payload(prepared_payload: serde_json::Value) -> RunReport {
+    let specs = vec![
+        (STAGE_PREPARE, EVENT_RUN_PREPARED, prepared_payload),
+        (STAGE_LAUNCH, EVENT_VM_STARTED, json!({"pid": 42})),
+        (
+            STAGE_MONITOR,
+            EVENT_RESOURCE_SAMPLED,
+            json!({"cpuUsageUsec": 100, "memoryCurrentBytes": 4096}),
+        ),
+        (
+            STAGE_MONITOR,
+            EVENT_RESOURCE_SAMPLED,
+            json!({"cpuUsageUsec": 250, "memoryCurrentBytes": 8192}),
+        ),
+    ];
+    let mut events = Vec::new();
+    let mut prev = GENESIS_HASH.to_string();
+    for (idx, (stage, event_type, payload)) in specs.into_iter().enumerate() {
+        let mut event = EvidenceEvent {
+            timestamp: format!("2026-02-21T10:00:0{idx}Z"),
+            run_id: "sr-report-render-test".to_string(),
+            stage: stage.to_string(),
+            event_type: event_type.to_string(),
+            payload,
+            hash_prev: prev.clone(),
+            hash_self: String::new(),
+        };
+        event.hash_self = derive_event_hash(&event);
+        prev = event.hash_self.clone();
+        events.push(event);
+    }
+
+    let mut report = build_report(
+        "sr-report-render-test".to_string(),
+        "2026-02-21T10:00:00Z".to_string(),
+        "2026-02-21T10:00:03Z".to_string(),
+        0,
+        ReportArtifacts {
+            kernel_hash: "sha256:1111111111111111111111111111111111111111111111111111111111111111"
+                .to_string(),
+            rootfs_hash: "sha256:2222222222222222222222222222222222222222222222222222222222222222"
+                .to_string(),
+            policy_hash: "sha256:3333333333333333333333333333333333333333333333333333333333333333"
+                .to_string(),
+            command_hash: "sha256:4444444444444444444444444444444444444444444444444444444444444444"
+                .to_string(),
+        },
+        PolicySummary {
+            network: "none".to_string(),
+            mounts: 0,
+            parameters: BTreeMap::new(),
+            admission_constraints_hash: None,
+        },
+        ResourceUsage {
+            cpu: "cpuUsageUsec=250".to_string(),
+            memory: "memoryCurrentBytes=8192".to_string(),
+            ..ResourceUsage::default()
+        },
+        events,
+        MountAudit::default(),
+        NetworkAudit::default(),
+        String::new(),
+    )
+    .expect("build report");
+    report.integrity.digest = compute_integrity_digest(&report).expect("compute digest");
+    report
+}
@@ -5,6 +5,7 @@ use sr_evidence::{
     RUN_REPORT_SCHEMA_VERSION, STAGE_PREPARE,
 };
 use serde_json::json;
+use std::collections::BTreeMap;
 
 const GENESIS_HASH: &str = "sha256:0000000000000000000000000000000000000000000000000000000000000000";
 
@@ -126,18 +127,24 @@ fn valid_report_with_events(count: usize) -> RunReport {
         policy_summary: PolicySummary {
             network: "none".to_string(),
             mounts: 0,
+            parameters: BTreeMap::new(),
+            admission_constraints_hash: None,
         },
         resource_usage: ResourceUsage {
             cpu: "cpuUsageUsec=0".to_string(),
             memory: "memoryCurrentBytes=0".to_string(),
+            ..ResourceUsage::default()
         },
         events,
         mount_audit: MountAudit::default(),
         network_audit: NetworkAudit::default(),
         archive: None,
         verification: None,
+        redaction: None,
         integrity: Integrity {
             digest: String::new(),
+            merkle_root: None,
+            field_salt_seed: None,
         },
     };
     report.integrity.digest = compute_integrity_digest(&report).expect("compute digest");
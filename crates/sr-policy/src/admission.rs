@@ -0,0 +1,358 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sr_common::{
+    ErrorItem, SR_ADM_001, SR_ADM_101, SR_ADM_102, SR_ADM_201, SR_ADM_301, SR_ADM_302, SR_ADM_401,
+};
+use std::net::Ipv4Addr;
+use std::path::{Component, Path};
+
+use crate::mount_constraints::path_components;
+use crate::runtime_constraints::{is_sha256, normalize_sha256};
+use crate::{memory_kib, NetworkMode, PolicySpec};
+
+const ADMISSION_ENV: &str = "SAFE_RUN_ADMISSION_CONSTRAINTS";
+const ADMISSION_SCHEMA_VERSION: &str = "safe-run.admission/v1";
+
+/// Organization-level caps applied on top of policy validation and the mount allowlist.
+/// Every section is optional; an absent section does not constrain the policy.
+#[derive(Debug, Clone)]
+pub struct AdmissionConstraints {
+    file: String,
+    hash: String,
+    config: AdmissionConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AdmissionConfig {
+    #[serde(rename = "schemaVersion")]
+    schema_version: String,
+    #[serde(rename = "maxMemory", default)]
+    max_memory: Option<String>,
+    /// Upper bound on `quota / period`; an unlimited (`max`) quota never fits.
+    #[serde(rename = "maxCpus", default)]
+    max_cpus: Option<f64>,
+    #[serde(rename = "allowedEgress", default)]
+    allowed_egress: Option<AllowedEgress>,
+    #[serde(rename = "allowedCommands", default)]
+    allowed_commands: Option<Vec<AllowedCommand>>,
+    /// Guest paths no mount may target, directly or beneath.
+    #[serde(rename = "forbiddenMountTargets", default)]
+    forbidden_mount_targets: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AllowedEgress {
+    /// IPv4 ranges; a rule's CIDR must fall entirely inside one of them.
+    #[serde(default)]
+    cidrs: Vec<String>,
+    /// Exact host names, or `*.example.com` for any subdomain.
+    #[serde(default)]
+    hosts: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AllowedCommand {
+    path: String,
+    /// Approved binary digest (`sha256:<hex>` or bare hex) for this path.
+    #[serde(default)]
+    sha256: Option<String>,
+}
+
+/// What admitted a policy: the constraints file, its digest, and the binary hash approved
+/// for the policy's command when the matching entry pins one.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AdmissionSummary {
+    pub file: String,
+    pub hash: String,
+    #[serde(
+        rename = "commandSha256",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub command_sha256: Option<String>,
+}
+
+impl AdmissionConstraints {
+    /// Load the file named by `SAFE_RUN_ADMISSION_CONSTRAINTS`; unset or empty means no
+    /// admission constraints.
+    pub fn from_env() -> Result<Option<Self>, ErrorItem> {
+        match std::env::var(ADMISSION_ENV) {
+            Ok(path) if !path.trim().is_empty() => {
+                Self::from_file(Path::new(path.trim())).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Error mapping: unreadable, malformed or inconsistent file -> `SR-ADM-001`.
+    pub fn from_file(path: &Path) -> Result<Self, ErrorItem> {
+        let raw = std::fs::read(path).map_err(|err| {
+            adm001(
+                "admission.file",
+                format!(
+                    "failed to read admission constraints '{}': {err}",
+                    path.display()
+                ),
+            )
+        })?;
+        let config: AdmissionConfig = serde_yaml::from_slice(&raw).map_err(|err| {
+            adm001(
+                "admission.file",
+                format!(
+                    "failed to parse admission constraints '{}': {err}",
+                    path.display()
+                ),
+            )
+        })?;
+        if config.schema_version != ADMISSION_SCHEMA_VERSION {
+            return Err(adm001(
+                "admission.schemaVersion",
+                format!("schemaVersion must be {ADMISSION_SCHEMA_VERSION}"),
+            ));
+        }
+        check_config(&config)?;
+        Ok(Self {
+            file: path.display().to_string(),
+            hash: format!("sha256:{}", hex::encode(Sha256::digest(&raw))),
+            config,
+        })
+    }
+
+    pub fn summary(&self, policy: &PolicySpec) -> AdmissionSummary {
+        AdmissionSummary {
+            file: self.file.clone(),
+            hash: self.hash.clone(),
+            command_sha256: self
+                .matching_command(&policy.runtime.command)
+                .and_then(|entry| entry.sha256.as_deref())
+                .map(normalize_sha256),
+        }
+    }
+
+    /// Check `policy` against every configured cap; errors use the `SR-ADM-*` family with the
+    /// offending policy field as `path`.
+    pub fn admit(&self, policy: &PolicySpec) -> Vec<ErrorItem> {
+        let mut errors = Vec::new();
+        let config = &self.config;
+
+        if let Some(limit) = config.max_memory.as_deref() {
+            let limit_kib = memory_kib(limit).unwrap_or_default();
+            if memory_kib(&policy.resources.memory.max).is_some_and(|kib| kib > limit_kib) {
+                errors.push(ErrorItem::new(
+                    SR_ADM_101,
+                    "resources.memory.max",
+                    format!(
+                        "memory.max {} exceeds the admission limit {limit}",
+                        policy.resources.memory.max.trim()
+                    ),
+                ));
+            }
+        }
+
+        if let Some(limit) = config.max_cpus {
+            match cpu_count(&policy.resources.cpu.max) {
+                Some(Some(cpus)) if cpus <= limit => {}
+                Some(requested) => errors.push(ErrorItem::new(
+                    SR_ADM_102,
+                    "resources.cpu.max",
+                    format!(
+                        "cpu.max requests {} CPUs, above the admission limit {limit}",
+                        requested.map_or("unlimited".to_string(), |cpus| format!("{cpus}"))
+                    ),
+                )),
+                None => {}
+            }
+        }
+
+        if let Some(allowed) = config.allowed_egress.as_ref() {
+            if policy.network.mode == NetworkMode::Allowlist {
+                for (idx, rule) in policy.network.egress.iter().enumerate() {
+                    if let Some(cidr) = rule.cidr.as_deref().map(str::trim) {
+                        if !allowed.cidrs.iter().any(|range| cidr_within(cidr, range)) {
+                            errors.push(ErrorItem::new(
+                                SR_ADM_201,
+                                format!("network.egress[{idx}].cidr"),
+                                format!("egress CIDR {cidr} is not admitted"),
+                            ));
+                        }
+                    }
+                    if let Some(host) = rule.host.as_deref().map(str::trim) {
+                        if !allowed
+                            .hosts
+                            .iter()
+                            .any(|pattern| host_matches(host, pattern))
+                        {
+                            errors.push(ErrorItem::new(
+                                SR_ADM_201,
+                                format!("network.egress[{idx}].host"),
+                                format!("egress host '{host}' is not admitted"),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        if config.allowed_commands.is_some() {
+            match self.matching_command(&policy.runtime.command) {
+                None => errors.push(ErrorItem::new(
+                    SR_ADM_301,
+                    "runtime.command",
+                    format!(
+                        "command '{}' is not in the admitted command list",
+                        policy.runtime.command
+                    ),
+                )),
+                // The approved digest is the host-side allowlist for pinned commands.
+                Some(entry) => {
+                    let approved = entry.sha256.as_deref().map(normalize_sha256);
+                    let pinned = policy
+                        .runtime
+                        .command_sha256
+                        .as_deref()
+                        .filter(|pin| is_sha256(pin))
+                        .map(normalize_sha256);
+                    if let (Some(approved), Some(pinned)) = (approved, pinned) {
+                        if approved != pinned {
+                            errors.push(ErrorItem::new(
+                                SR_ADM_302,
+                                "runtime.commandSha256",
+                                format!(
+                                    "commandSha256 {pinned} is not the approved hash for '{}'",
+                                    policy.runtime.command.trim()
+                                ),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        for (idx, mount) in policy.mounts.iter().enumerate() {
+            // Fold `..` first so `/data/out/../secrets` cannot slip past `/data/secrets`.
+            let target = path_components(Path::new(mount.target.trim()));
+            if let Some(forbidden) = config
+                .forbidden_mount_targets
+                .iter()
+                .find(|forbidden| target.starts_with(&path_components(Path::new(forbidden.trim()))))
+            {
+                errors.push(ErrorItem::new(
+                    SR_ADM_401,
+                    format!("mounts[{idx}].target"),
+                    format!(
+                        "mount target '{}' is under forbidden path '{forbidden}'",
+                        mount.target
+                    ),
+                ));
+            }
+        }
+        errors
+    }
+
+    fn matching_command(&self, command: &str) -> Option<&AllowedCommand> {
+        self.config
+            .allowed_commands
+            .as_deref()?
+            .iter()
+            .find(|entry| entry.path == command.trim())
+    }
+}
+
+fn check_config(config: &AdmissionConfig) -> Result<(), ErrorItem> {
+    if let Some(limit) = config.max_memory.as_deref() {
+        if memory_kib(limit).is_none_or(|kib| kib == 0) {
+            return Err(adm001("admission.maxMemory", "maxMemory must be like 2Gi"));
+        }
+    }
+    if config
+        .max_cpus
+        .is_some_and(|cpus| cpus.is_nan() || cpus <= 0.0)
+    {
+        return Err(adm001("admission.maxCpus", "maxCpus must be positive"));
+    }
+    if let Some(allowed) = config.allowed_egress.as_ref() {
+        if let Some(idx) = allowed
+            .cidrs
+            .iter()
+            .position(|cidr| parse_cidr(cidr).is_none())
+        {
+            return Err(adm001(
+                format!("admission.allowedEgress.cidrs[{idx}]"),
+                "allowed CIDRs must be IPv4 CIDRs like 10.0.0.0/8",
+            ));
+        }
+    }
+    for (idx, entry) in config.allowed_commands.iter().flatten().enumerate() {
+        if !entry.path.starts_with('/') {
+            return Err(adm001(
+                format!("admission.allowedCommands[{idx}].path"),
+                "allowed command paths must be absolute",
+            ));
+        }
+        if entry.sha256.as_deref().is_some_and(|hash| !is_sha256(hash)) {
+            return Err(adm001(
+                format!("admission.allowedCommands[{idx}].sha256"),
+                "sha256 must be 64 hex characters, optionally prefixed with 'sha256:'",
+            ));
+        }
+    }
+    for (idx, target) in config.forbidden_mount_targets.iter().enumerate() {
+        let path = Path::new(target.trim());
+        if !path.is_absolute() || path.components().any(|c| matches!(c, Component::ParentDir)) {
+            return Err(adm001(
+                format!("admission.forbiddenMountTargets[{idx}]"),
+                "forbidden mount targets must be absolute paths without '..'",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// CPUs requested by `<quota> <period>`: `Some(None)` for an unlimited quota, `None` when
+/// the value is malformed (validation reports that separately).
+fn cpu_count(cpu_max: &str) -> Option<Option<f64>> {
+    let (quota, period) = cpu_max.trim().split_once(char::is_whitespace)?;
+    let period = period
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|period| *period > 0.0)?;
+    if quota == "max" {
+        return Some(None);
+    }
+    Some(Some(quota.parse::<f64>().ok()? / period))
+}
+
+fn parse_cidr(cidr: &str) -> Option<(u32, u8)> {
+    let (ip, prefix) = cidr.trim().split_once('/')?;
+    let prefix = prefix.parse::<u8>().ok().filter(|prefix| *prefix <= 32)?;
+    Some((u32::from(ip.parse::<Ipv4Addr>().ok()?), prefix))
+}
+
+fn cidr_within(cidr: &str, range: &str) -> bool {
+    let (Some((addr, prefix)), Some((range_addr, range_prefix))) =
+        (parse_cidr(cidr), parse_cidr(range))
+    else {
+        return false;
+    };
+    let mask = |bits: u8| u32::MAX.checked_shl(32 - u32::from(bits)).unwrap_or(0);
+    prefix >= range_prefix && addr & mask(range_prefix) == range_addr & mask(range_prefix)
+}
+
+fn host_matches(host: &str, pattern: &str) -> bool {
+    let host = host.to_ascii_lowercase();
+    let pattern = pattern.trim().to_ascii_lowercase();
+    match pattern.strip_prefix("*.") {
+        Some(suffix) => host
+            .strip_suffix(suffix)
+            .is_some_and(|label| label.len() > 1 && label.ends_with('.')),
+        None => host == pattern,
+    }
+}
+
+fn adm001(path: impl Into<String>, message: impl Into<String>) -> ErrorItem {
+    ErrorItem::new(SR_ADM_001, path, message)
+}
@@ -0,0 +1,335 @@
+use sr_common::ErrorItem;
+use std::collections::BTreeMap;
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::Marker;
+
+/// Mount keys accepted through serde aliases, indexed under the field names used in error paths.
+pub(crate) const MOUNT_KEY_ALIASES: &[(&str, &str)] = &[
+    ("hostPath", "source"),
+    ("guestPath", "target"),
+    ("readOnly", "read_only"),
+    ("maxSize", "max_size"),
+];
+
+/// Lists `extends` merges with the base instead of replacing, so their item indexes in error
+/// paths do not match the extending file.
+const MERGED_LISTS: &[&str] = &["mounts[", "network.egress["];
+
+/// Line/column index of a policy file keyed by the field paths used in `ErrorItem.path`
+/// (`mounts[2].target`, `runtime.args[0]`). Scalars point at the value, mappings and sequences
+/// at their key. For documents using `extends` only the file's own keys are indexed; items
+/// of lists merged with the base are left out.
+#[derive(Debug, Clone)]
+pub struct PolicySourceMap {
+    file: String,
+    text: String,
+    locations: BTreeMap<String, (usize, usize)>,
+    extends: bool,
+}
+
+impl PolicySourceMap {
+    /// Read and index `path`; `None` when the file cannot be read.
+    pub fn load(path: &str) -> Option<Self> {
+        let text = std::fs::read_to_string(path).ok()?;
+        Some(Self::parse(path, text))
+    }
+
+    /// Index `text` as the contents of `file`. YAML syntax errors leave the index partial;
+    /// serde already reports those with a location.
+    pub fn parse(file: impl Into<String>, text: impl Into<String>) -> Self {
+        let text = text.into();
+        let mut indexer = SpanIndexer::default();
+        let _ = Parser::new_from_str(&text).load(&mut indexer, false);
+        let extends = indexer.locations.contains_key("extends");
+        Self {
+            file: file.into(),
+            text,
+            locations: indexer.locations,
+            extends,
+        }
+    }
+
+    /// Location of `path`, falling back to the closest indexed ancestor so fields the policy
+    /// omits point at their parent. Top-level paths that are not in the file have no location.
+    pub fn locate(&self, path: &str) -> Option<(usize, usize)> {
+        if self.extends && MERGED_LISTS.iter().any(|list| path.starts_with(list)) {
+            return None;
+        }
+        let mut current = path;
+        loop {
+            if let Some(location) = self.locations.get(current) {
+                return Some(*location);
+            }
+            let cut = current.rfind(['.', '['])?;
+            current = &current[..cut];
+        }
+    }
+
+    /// Fill in `file`/`line`/`column` for errors that do not have a location yet, at the
+    /// error's `anchor` when it has one.
+    pub fn annotate(&self, errors: &mut [ErrorItem]) {
+        for error in errors.iter_mut().filter(|error| error.line.is_none()) {
+            let path = error.anchor.as_deref().unwrap_or(&error.path);
+            if let Some((line, column)) = self.locate(path) {
+                *error = error
+                    .clone()
+                    .with_file(self.file.clone())
+                    .with_location(line, column);
+            }
+        }
+    }
+
+    fn source_line(&self, line: usize) -> Option<&str> {
+        self.text.lines().nth(line.checked_sub(1)?)
+    }
+}
+
+/// Render errors as compiler-style diagnostics, with a source excerpt when the error points
+/// into the file behind `source`.
+pub fn render_diagnostics(errors: &[ErrorItem], source: Option<&PolicySourceMap>) -> String {
+    let mut out = String::new();
+    for error in errors {
+        out.push_str(&format!("error[{}]: {}\n", error.code, error.message));
+        let location = error.line.zip(error.column);
+        let excerpt = match location.map(|(line, column)| (line as usize, column as usize)) {
+            Some((line, column)) => {
+                let file = error.file.as_deref().unwrap_or("<policy>");
+                out.push_str(&format!("  --> {file}:{line}:{column}\n"));
+                source
+                    .filter(|map| error.file.as_deref() == Some(map.file.as_str()))
+                    .and_then(|map| map.source_line(line))
+                    .map(|text| (line, column, text))
+            }
+            None => None,
+        };
+        let gutter = excerpt.map_or(3, |(line, _, _)| line.to_string().len() + 1);
+        if let Some((line, column, text)) = excerpt {
+            let pad = " ".repeat(gutter);
+            let offset = text
+                .char_indices()
+                .nth(column.saturating_sub(1))
+                .map_or(text.len(), |(idx, _)| idx);
+            let marker = " ".repeat(text[..offset].chars().count());
+            let underline = "^".repeat(token_width(&text[offset..]));
+            out.push_str(&format!(
+                "{pad}|\n{line:>width$} | {text}\n",
+                width = gutter - 1
+            ));
+            out.push_str(&format!("{pad}| {marker}{underline}\n"));
+        }
+        out.push_str(&format!("{}= path: {}\n\n", " ".repeat(gutter), error.path));
+    }
+    out
+}
+
+/// Width of the token starting at `rest`: a quoted scalar up to its closing quote, otherwise
+/// up to the next whitespace or key separator.
+fn token_width(rest: &str) -> usize {
+    let mut chars = rest.chars();
+    let width = match chars.next() {
+        Some(quote @ ('"' | '\'')) => chars
+            .position(|ch| ch == quote)
+            .map_or(rest.chars().count(), |end| end + 2),
+        Some(_) => rest
+            .chars()
+            .take_while(|ch| !ch.is_whitespace() && *ch != ':' && *ch != ',')
+            .count(),
+        None => 0,
+    };
+    width.max(1)
+}
+
+/// Locations the apiVersion migration rewrites: the `apiVersion` value, and mount keys written
+/// under their v1alpha1 alias as `(mounts[i].<alias>, field, line, column)` pointing at the key.
+/// Unlike `PolicySourceMap`, documents using `extends` are indexed too.
+pub(crate) struct VersionLocations {
+    pub(crate) api_version: Option<(usize, usize)>,
+    pub(crate) aliased_keys: Vec<(String, &'static str, usize, usize)>,
+}
+
+pub(crate) fn version_locations(text: &str) -> VersionLocations {
+    let mut indexer = SpanIndexer::default();
+    let _ = Parser::new_from_str(text).load(&mut indexer, false);
+    VersionLocations {
+        api_version: indexer.locations.get("apiVersion").copied(),
+        aliased_keys: indexer.aliased_keys,
+    }
+}
+
+/// Role of a YAML node starting at a given position, used to pick the field a parse error
+/// at that position refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NodeRole {
+    /// A mapping key; unknown-field errors point here.
+    Key,
+    /// A block mapping, at its first key; missing-field errors point here.
+    Mapping,
+    /// Any other value, including sequences.
+    Value,
+}
+
+/// Field path of the innermost node with `role` starting at the 1-based `line`/`column`,
+/// with mapping keys under their literal name. The document root maps to `None`.
+pub(crate) fn node_path_at(
+    text: &str,
+    line: usize,
+    column: usize,
+    role: NodeRole,
+) -> Option<String> {
+    let mut indexer = SpanIndexer::default();
+    let _ = Parser::new_from_str(text).load(&mut indexer, false);
+    indexer
+        .nodes
+        .get(&(line, column))?
+        .iter()
+        .rev()
+        .find(|(_, node_role)| match role {
+            // A mapping given where another value was expected.
+            NodeRole::Value => *node_role != NodeRole::Key,
+            _ => *node_role == role,
+        })
+        .map(|(path, _)| path.clone())
+        .filter(|path| !path.is_empty())
+}
+
+#[derive(Default)]
+struct SpanIndexer {
+    frames: Vec<Frame>,
+    locations: BTreeMap<String, (usize, usize)>,
+    aliased_keys: Vec<(String, &'static str, usize, usize)>,
+    /// Nodes by start position, outermost first.
+    nodes: BTreeMap<(usize, usize), Vec<(String, NodeRole)>>,
+}
+
+enum Frame {
+    Mapping {
+        path: String,
+        key: Option<(String, Marker)>,
+        keyed: bool,
+    },
+    Sequence {
+        path: String,
+        index: usize,
+    },
+}
+
+impl SpanIndexer {
+    /// Path and marker of the node that starts now, or `None` when it is a mapping key.
+    fn next_node(&mut self, mark: Marker) -> Option<(String, Marker)> {
+        match self.frames.last_mut() {
+            None => Some((String::new(), mark)),
+            Some(Frame::Mapping { path, key, .. }) => key.take().map(|(name, key_mark)| {
+                let alias = MOUNT_KEY_ALIASES
+                    .iter()
+                    .find(|(alias, _)| *alias == name)
+                    .filter(|_| is_mount_item(path));
+                let name = match alias {
+                    Some((alias, field)) => {
+                        self.aliased_keys.push((
+                            format!("{path}.{alias}"),
+                            field,
+                            key_mark.line(),
+                            key_mark.col() + 1,
+                        ));
+                        (*field).to_string()
+                    }
+                    None => name,
+                };
+                let child = if path.is_empty() {
+                    name
+                } else {
+                    format!("{path}.{name}")
+                };
+                (child, key_mark)
+            }),
+            Some(Frame::Sequence { path, index }) => {
+                let child = format!("{path}[{index}]");
+                *index += 1;
+                Some((child, mark))
+            }
+        }
+    }
+
+    fn record(&mut self, path: &str, mark: Marker) {
+        if !path.is_empty() {
+            self.locations
+                .entry(path.to_string())
+                .or_insert((mark.line(), mark.col() + 1));
+        }
+    }
+
+    fn record_node(&mut self, path: String, role: NodeRole, mark: Marker) {
+        self.nodes
+            .entry((mark.line(), mark.col() + 1))
+            .or_default()
+            .push((path, role));
+    }
+}
+
+impl MarkedEventReceiver for SpanIndexer {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        match event {
+            Event::Scalar(value, ..) => match self.next_node(mark) {
+                Some((path, _)) => {
+                    self.record(&path, mark);
+                    self.record_node(path, NodeRole::Value, mark);
+                }
+                None => {
+                    if let Some(Frame::Mapping { path, key, keyed }) = self.frames.last_mut() {
+                        let key_path = if path.is_empty() {
+                            value.clone()
+                        } else {
+                            format!("{path}.{value}")
+                        };
+                        *key = Some((value, mark));
+                        let first_key = !std::mem::replace(keyed, true);
+                        // Sequence items have no key of their own; point them at their first key.
+                        let path = path.clone();
+                        self.record(&path, mark);
+                        // Block mappings start at their first key, as serde_yaml reports them.
+                        if first_key {
+                            self.record_node(path, NodeRole::Mapping, mark);
+                        }
+                        self.record_node(key_path, NodeRole::Key, mark);
+                    }
+                }
+            },
+            Event::Alias(_) => {
+                if let Some((path, _)) = self.next_node(mark) {
+                    self.record(&path, mark);
+                    self.record_node(path, NodeRole::Value, mark);
+                }
+            }
+            Event::MappingStart(..) | Event::SequenceStart(..) => {
+                // Complex (non-scalar) keys are not addressable by error paths.
+                let (path, node_mark) = self
+                    .next_node(mark)
+                    .unwrap_or_else(|| ("?".to_string(), mark));
+                if !matches!(self.frames.last(), Some(Frame::Sequence { .. })) {
+                    self.record(&path, node_mark);
+                }
+                if matches!(event, Event::SequenceStart(..)) {
+                    self.record_node(path.clone(), NodeRole::Value, mark);
+                }
+                self.frames.push(match event {
+                    Event::MappingStart(..) => Frame::Mapping {
+                        path,
+                        key: None,
+                        keyed: false,
+                    },
+                    _ => Frame::Sequence { path, index: 0 },
+                });
+            }
+            Event::MappingEnd | Event::SequenceEnd => {
+                self.frames.pop();
+            }
+            _ => {}
+        }
+    }
+}
+
+fn is_mount_item(path: &str) -> bool {
+    path.strip_prefix("mounts[")
+        .and_then(|rest| rest.strip_suffix(']'))
+        .is_some_and(|index| index.chars().all(|ch| ch.is_ascii_digit()))
+}
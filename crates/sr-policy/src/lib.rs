@@ -1,68 +1,153 @@
 use regex::Regex;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use sr_common::{ErrorItem, SR_POL_001, SR_POL_002, SR_POL_103};
+use sr_common::{ErrorItem, SR_POL_001, SR_POL_002, SR_POL_101, SR_POL_102, SR_POL_103};
+use std::collections::BTreeMap;
+use std::path::Path;
 
+mod admission;
+mod diagnostics;
+mod lint;
 mod mount_constraints;
+mod mount_rules;
+mod mount_scan;
 mod network_constraints;
+mod parameters;
 mod path_security;
+mod policy_extends;
+mod policy_schema;
+mod policy_versions;
+mod resource_units;
+mod runtime_constraints;
+mod secret_constraints;
+pub use admission::{AdmissionConstraints, AdmissionSummary};
+use diagnostics::{node_path_at, NodeRole};
+pub use diagnostics::{render_diagnostics, PolicySourceMap};
+pub use lint::{lint_policy, LintConfig, LintFinding, LintReport, LintSeverity};
+pub use mount_constraints::EffectiveDenylist;
 use mount_constraints::MountConstraints;
+pub use mount_scan::{
+    scan_mount_source, MountScanBudget, MountScanFinding, MountScanFindingKind, MountScanReport,
+};
 use network_constraints::validate_network_constraints;
+pub use parameters::{bind_parameters, ParameterSpec, ParameterType};
+pub use path_security::MountSourceIdentity;
 use path_security::PathSecurityEngine;
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use policy_extends::resolve_extends;
+pub use policy_schema::policy_json_schema;
+use policy_versions::unsupported_api_version;
+pub use policy_versions::{
+    migrate_policy, PolicyApiVersion, PolicyMigration, POLICY_API_V1ALPHA1, POLICY_API_V1BETA1,
+};
+pub use resource_units::memory_mib;
+use resource_units::{
+    host_memory_mib, memory_kib, normalize_cpu_max, normalize_memory_max, number_or_string,
+};
+use runtime_constraints::{normalize_sha256, validate_runtime_constraints};
+use secret_constraints::validate_secret_constraints;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct PolicySpec {
     #[serde(rename = "apiVersion")]
     pub api_version: String,
     pub metadata: Metadata,
+    /// Template parameters referenced as `${name}`; removed once bound.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub parameters: BTreeMap<String, ParameterSpec>,
     pub runtime: Runtime,
     pub resources: Resources,
     pub network: Network,
     pub mounts: Vec<Mount>,
+    /// Host-side secret references; values are resolved by the runner and never serialized.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub secrets: Vec<Secret>,
     pub audit: Audit,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Metadata {
     pub name: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Runtime {
     pub command: String,
     pub args: Vec<String>,
+    /// Static guest environment; ordered so payloads and hashes are deterministic.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Host env names copied into the guest environment at run time; only exact names.
+    #[serde(
+        rename = "envPassthrough",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub env_passthrough: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<RuntimeUser>,
+    /// Expected digest of `command` inside the rootfs image, checked by the runner before
+    /// launch. Normalized to `sha256:<lowercase hex>`.
+    #[serde(
+        rename = "commandSha256",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub command_sha256: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Guest uid/gid for the command; `gid` defaults to `uid`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RuntimeUser {
+    pub uid: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gid: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Resources {
     pub cpu: Cpu,
     pub memory: Memory,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Cpu {
+    /// `<quota> <period>`, a CPU count (`1.5`) or a percent of one CPU (`50%`).
+    #[serde(deserialize_with = "number_or_string")]
     pub max: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Memory {
+    /// Size with a `Ki`/`Mi`/`Gi` (binary) or `M`/`MB`/`G`/`GB` (decimal) suffix.
     pub max: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Network {
     pub mode: NetworkMode,
     #[serde(default)]
     pub egress: Vec<NetworkEgressRule>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum NetworkMode {
     None,
     Allowlist,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct NetworkEgressRule {
     #[serde(default)]
     pub protocol: Option<String>,
@@ -74,17 +159,58 @@ pub struct NetworkEgressRule {
     pub port: Option<u32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Mount {
-    #[serde(alias = "hostPath")]
+    /// Host path for read-only bind mounts; omitted for `output`/`scratch` mounts.
+    #[serde(default, alias = "hostPath", skip_serializing_if = "String::is_empty")]
     pub source: String,
     #[serde(alias = "guestPath")]
     pub target: String,
     #[serde(default, alias = "readOnly")]
     pub read_only: bool,
+    /// Writable mount backed by a fresh per-run sparse image instead of a host path.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<MountMode>,
+    /// Image size cap for `output`/`scratch` mounts, e.g. `64Mi` or `1Gi`.
+    #[serde(default, alias = "maxSize", skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<String>,
+    /// Filled in by allowlist validation; never part of the serialized policy or its hash.
+    #[serde(skip)]
+    pub source_identity: Option<MountSourceIdentity>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// `output` images are hashed and kept as run artifacts; `scratch` images are discarded.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum MountMode {
+    Output,
+    Scratch,
+}
+
+impl MountMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MountMode::Output => "output",
+            MountMode::Scratch => "scratch",
+        }
+    }
+}
+
+/// Secret delivered to the guest under `name`, read from exactly one of a host env var or a
+/// host file.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Secret {
+    pub name: String,
+    #[serde(rename = "fromEnv", default, skip_serializing_if = "Option::is_none")]
+    pub from_env: Option<String>,
+    #[serde(rename = "fromFile", default, skip_serializing_if = "Option::is_none")]
+    pub from_file: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Audit {
     pub level: String,
 }
@@ -96,24 +222,31 @@ pub struct ValidationResult {
     pub warnings: Vec<String>,
     #[serde(rename = "normalizedPolicy")]
     pub normalized_policy: Option<PolicySpec>,
+    /// Sensitive host/guest paths in force for this validation, built-ins first.
+    #[serde(
+        rename = "effectiveDenylist",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub effective_denylist: Option<EffectiveDenylist>,
+    /// Parameter values bound during validation; empty for policies without `parameters`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub parameters: BTreeMap<String, String>,
+    /// Admission-constraints file the policy was checked against, if one is configured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admission: Option<AdmissionSummary>,
 }
 
-/// Parse policy YAML/JSON into `PolicySpec`.
+/// Parse policy YAML/JSON into `PolicySpec`. `extends` references resolve relative to the
+/// current directory.
 /// Error mapping: missing required fields -> `SR-POL-001`, invalid structure -> `SR-POL-002`.
 pub fn parse_policy(input: &str) -> Result<PolicySpec, ErrorItem> {
-    serde_yaml::from_str::<PolicySpec>(input).map_err(|e| {
-        let message = format!("failed to parse policy: {e}");
-        let code = if message.contains("missing field") {
-            SR_POL_001
-        } else {
-            SR_POL_002
-        };
-        pol_error(code, "policy", message)
-    })
+    parse_policy_from(input, None)
 }
 
-/// Load and parse policy file from disk.
-/// Error mapping: file read failures -> `SR-POL-001`, parse failures follow `parse_policy`.
+/// Load and parse policy file from disk, resolving `extends` relative to the file.
+/// Error mapping: file read failures -> `SR-POL-001`, parse failures follow `parse_policy` and
+/// carry `file` whenever they have a location.
 pub fn load_policy_from_path(path: &str) -> Result<PolicySpec, ErrorItem> {
     let text = std::fs::read_to_string(path).map_err(|e| {
         pol_error(
@@ -122,7 +255,61 @@ pub fn load_policy_from_path(path: &str) -> Result<PolicySpec, ErrorItem> {
             format!("failed to read policy file: {e}"),
         )
     })?;
-    parse_policy(&text)
+    parse_policy_from(&text, Some(Path::new(path))).map_err(|err| match err.line {
+        Some(_) => err.with_file(path),
+        None => err,
+    })
+}
+
+fn parse_policy_from(input: &str, origin: Option<&Path>) -> Result<PolicySpec, ErrorItem> {
+    let parsed = match resolve_extends(input, origin)? {
+        Some(resolved) => serde_yaml::from_value::<PolicySpec>(resolved),
+        None => serde_yaml::from_str::<PolicySpec>(input),
+    };
+    parsed.map_err(|e| parse_error(&e, input))
+}
+
+/// Map a serde_yaml error to a field-oriented `ErrorItem`. The error location is looked up
+/// in `input` with yaml-rust2 marks: unknown fields resolve to their key, missing fields to
+/// the mapping that lacks them and other errors to the offending value. The location is
+/// kept both in the message and as `line`/`column`. Documents merged through `extends`
+/// carry no location and fall back to `policy`.
+fn parse_error(err: &serde_yaml::Error, input: &str) -> ErrorItem {
+    let rendered = err.to_string();
+    let unknown_field = unknown_field_name(&rendered);
+    let missing_field = rendered.contains("missing field");
+    let role = if unknown_field.is_some() {
+        NodeRole::Key
+    } else if missing_field {
+        NodeRole::Mapping
+    } else {
+        NodeRole::Value
+    };
+    let located = err
+        .location()
+        .and_then(|location| node_path_at(input, location.line(), location.column(), role));
+    let path = match (located, unknown_field) {
+        (Some(path), _) => path,
+        (None, Some(field)) => field.to_string(),
+        (None, None) => "policy".to_string(),
+    };
+    let code = if missing_field {
+        SR_POL_001
+    } else {
+        SR_POL_002
+    };
+    let item = pol_error(code, path, format!("failed to parse policy: {rendered}"));
+    match err.location() {
+        Some(location) => item.with_location(location.line(), location.column()),
+        None => item,
+    }
+}
+
+fn unknown_field_name(message: &str) -> Option<&str> {
+    message
+        .split_once("unknown field `")
+        .and_then(|(_, rest)| rest.split_once('`'))
+        .map(|(field, _)| field)
 }
 
 /// Validate policy with default allowlist source resolution.
@@ -130,16 +317,49 @@ pub fn validate_policy(policy: PolicySpec) -> ValidationResult {
     validate_policy_with_allowlist(policy, None)
 }
 
-/// Validate policy semantics and emit normalized policy on success.
+/// Validate policy semantics and emit normalized policy on success. Admission constraints
+/// are loaded from `SAFE_RUN_ADMISSION_CONSTRAINTS` when set.
 /// Boundary: M3 allows `network.mode=allowlist`; mounts still pass allowlist + constraint checks.
-/// Error mapping: `SR-POL-001/002/101/102/103/201` with field-oriented `path`.
+/// Error mapping: `SR-POL-001/002/101/102/103/201` with field-oriented `path`; admission
+/// failures follow `validate_policy_with_admission`.
 pub fn validate_policy_with_allowlist(
+    policy: PolicySpec,
+    allowlist_path: Option<&str>,
+) -> ValidationResult {
+    match AdmissionConstraints::from_env() {
+        Ok(admission) => validate_policy_with_admission(policy, allowlist_path, admission.as_ref()),
+        Err(err) => {
+            let mut result = validate_policy_with_admission(policy, allowlist_path, None);
+            result.valid = false;
+            result.errors.push(err);
+            result.normalized_policy = None;
+            result.warnings.clear();
+            result
+        }
+    }
+}
+
+/// Validate as `validate_policy_with_allowlist` with explicit admission constraints.
+/// Error mapping: constraint file failures -> `SR-ADM-001`, memory/CPU caps ->
+/// `SR-ADM-101/102`, egress -> `SR-ADM-201`, command -> `SR-ADM-301`, mount targets ->
+/// `SR-ADM-401`.
+pub fn validate_policy_with_admission(
     mut policy: PolicySpec,
     allowlist_path: Option<&str>,
+    admission: Option<&AdmissionConstraints>,
 ) -> ValidationResult {
     let mut errors = Vec::new();
     let mut warnings = Vec::new();
 
+    // Defaults are bound here; callers with run-time overrides bind before validating.
+    let mut parameters = BTreeMap::new();
+    if !policy.parameters.is_empty() {
+        match bind_parameters(&mut policy, &BTreeMap::new()) {
+            Ok(bound) => parameters = bound,
+            Err(bind_errors) => errors.extend(bind_errors),
+        }
+    }
+
     let allowlist_engine = match PathSecurityEngine::from_sources(allowlist_path) {
         Ok(engine) => Some(engine),
         Err(err) => {
@@ -147,16 +367,30 @@ pub fn validate_policy_with_allowlist(
             None
         }
     };
-    let mount_constraints = allowlist_engine
+    let mount_constraints = allowlist_engine.as_ref().map(|engine| {
+        MountConstraints::new(
+            engine.guest_allow_prefixes().to_vec(),
+            engine.host_deny_patterns(),
+            engine.guest_deny_patterns(),
+        )
+    });
+    let effective_denylist = mount_constraints
         .as_ref()
-        .map(|engine| MountConstraints::new(engine.guest_allow_prefixes().to_vec()));
-
-    if policy.api_version != "policy.safe-run.dev/v1alpha1" {
-        errors.push(pol_error(
-            SR_POL_002,
-            "apiVersion",
-            "apiVersion must be policy.safe-run.dev/v1alpha1",
-        ));
+        .map(MountConstraints::effective_denylist);
+
+    // Every supported version is normalized to the latest; older ones still validate.
+    match PolicyApiVersion::parse(&policy.api_version) {
+        Some(version) => {
+            if !version.is_latest() {
+                warnings.push(format!(
+                    "apiVersion {} is deprecated; run `safe-run policy migrate` to move to {}",
+                    version.as_str(),
+                    PolicyApiVersion::LATEST.as_str()
+                ));
+            }
+            policy.api_version = PolicyApiVersion::LATEST.as_str().to_string();
+        }
+        None => errors.push(unsupported_api_version()),
     }
 
     if policy.metadata.name.trim().is_empty() {
@@ -175,31 +409,41 @@ pub fn validate_policy_with_allowlist(
         ));
     }
 
-    let cpu_re = Regex::new(r"^(max|[0-9]+)\s+(max|[0-9]+)$").expect("regex");
-    if !cpu_re.is_match(policy.resources.cpu.max.trim()) {
-        errors.push(pol_error(
-            SR_POL_002,
-            "resources.cpu.max",
-            "cpu.max must be '<quota> <period>'",
-        ));
+    // Resources are normalized up front so admission and later checks see canonical values.
+    match normalize_cpu_max(&policy.resources.cpu.max) {
+        Ok(canonical) => policy.resources.cpu.max = canonical,
+        Err(message) => errors.push(pol_error(SR_POL_002, "resources.cpu.max", message)),
     }
-
-    let mem_re = Regex::new(r"^[0-9]+(Ki|Mi|Gi)$").expect("regex");
-    if !mem_re.is_match(policy.resources.memory.max.trim()) {
-        errors.push(pol_error(
-            SR_POL_002,
-            "resources.memory.max",
-            "memory.max must be like 256Mi",
-        ));
+    match normalize_memory_max(&policy.resources.memory.max) {
+        Ok(canonical) => {
+            let requested = memory_mib(&canonical).unwrap_or_default();
+            if let Some(host) = host_memory_mib().filter(|host| requested > *host) {
+                errors.push(pol_error(
+                    SR_POL_002,
+                    "resources.memory.max",
+                    format!("memory.max {canonical} exceeds host memory ({host}Mi)"),
+                ));
+            }
+            policy.resources.memory.max = canonical;
+        }
+        Err(message) => errors.push(pol_error(SR_POL_002, "resources.memory.max", message)),
     }
 
+    errors.extend(validate_runtime_constraints(&policy.runtime));
+    errors.extend(validate_secret_constraints(
+        &mut policy.secrets,
+        &policy.runtime,
+        allowlist_engine.as_ref(),
+        mount_constraints.as_ref(),
+    ));
     errors.extend(validate_network_constraints(&policy.network));
 
+    let mut source_identities = vec![None; policy.mounts.len()];
     for (idx, mount) in policy.mounts.iter().enumerate() {
         let mut source_valid = true;
         let mut target_valid = true;
         let mut source_canonical = None;
-        if mount.source.trim().is_empty() {
+        if mount.mode.is_none() && mount.source.trim().is_empty() {
             errors.push(pol_error(
                 SR_POL_002,
                 mount_field_path(idx, "source"),
@@ -215,17 +459,53 @@ pub fn validate_policy_with_allowlist(
             ));
             target_valid = false;
         }
-        if !mount.read_only {
+        if !is_plain_mount_target(&mount.target) {
             errors.push(pol_error(
-                SR_POL_103,
-                mount_field_path(idx, "read_only"),
-                "mounts must be read-only in M2",
+                SR_POL_102,
+                mount_field_path(idx, "target"),
+                "mount target cannot contain whitespace, control characters, quotes or '='",
             ));
+            target_valid = false;
+        }
+        if let Some(mode) = mount.mode {
+            errors.extend(validate_writable_mount(mount, mode, idx));
+            source_valid = false;
+        } else {
+            if !mount.read_only {
+                errors.push(pol_error(
+                    SR_POL_103,
+                    mount_field_path(idx, "read_only"),
+                    "mounts must be read-only unless mode is output or scratch",
+                ));
+            }
+            if mount.max_size.is_some() {
+                errors.push(pol_error(
+                    SR_POL_002,
+                    mount_field_path(idx, "max_size"),
+                    "max_size only applies to output and scratch mounts",
+                ));
+            }
         }
         if let Some(engine) = allowlist_engine.as_ref() {
             if source_valid {
                 match engine.validate_source_path(&mount.source, idx) {
                     Ok(canonical) => {
+                        errors.extend(engine.validate_source_rules(
+                            &canonical,
+                            target_valid.then_some(mount.target.as_str()),
+                            idx,
+                        ));
+                        source_identities[idx] = engine.source_identity(&canonical);
+                        if source_identities[idx].is_none() {
+                            errors.push(pol_error(
+                                SR_POL_101,
+                                mount_field_path(idx, "source"),
+                                format!(
+                                    "mount source '{}' disappeared during validation",
+                                    canonical.display()
+                                ),
+                            ));
+                        }
                         source_canonical = Some(canonical);
                     }
                     Err(err) => {
@@ -236,7 +516,9 @@ pub fn validate_policy_with_allowlist(
         }
         if let Some(constraints) = mount_constraints.as_ref() {
             if let Some(canonical) = source_canonical.as_ref() {
-                if let Err(err) = constraints.validate_source_sensitive(canonical, idx) {
+                if let Err(err) =
+                    constraints.validate_source_sensitive(Path::new(&mount.source), canonical, idx)
+                {
                     errors.push(err);
                 }
             }
@@ -256,14 +538,36 @@ pub fn validate_policy_with_allowlist(
         ));
     }
 
+    if let Some(admission) = admission {
+        errors.extend(admission.admit(&policy));
+    }
+    let admission = admission.map(|constraints| constraints.summary(&policy));
+
     if errors.is_empty() {
         policy.runtime.args.retain(|arg| !arg.trim().is_empty());
+        // Without a pin of its own, the policy inherits the digest admission approved.
+        policy.runtime.command_sha256 = policy
+            .runtime
+            .command_sha256
+            .as_deref()
+            .map(normalize_sha256)
+            .or_else(|| {
+                admission
+                    .as_ref()
+                    .and_then(|summary| summary.command_sha256.clone())
+            });
+        for (mount, identity) in policy.mounts.iter_mut().zip(source_identities) {
+            mount.source_identity = identity;
+        }
         warnings.push("default deny policy is active".to_string());
         ValidationResult {
             valid: true,
             errors,
             warnings,
             normalized_policy: Some(policy),
+            effective_denylist,
+            parameters,
+            admission,
         }
     } else {
         ValidationResult {
@@ -271,10 +575,87 @@ pub fn validate_policy_with_allowlist(
             errors,
             warnings,
             normalized_policy: None,
+            effective_denylist,
+            parameters,
+            admission,
         }
     }
 }
 
+fn pol_error(
+    code: impl Into<String>,
+    path: impl Into<String>,
+    message: impl Into<String>,
+) -> ErrorItem {
+    ErrorItem::new(code, path, message)
+}
+
+/// Upper bound on a writable mount's `max_size` (1 TiB).
+const WRITABLE_MOUNT_MAX_MIB: u64 = 1024 * 1024;
+
+/// Writable mounts have no host source and must declare a non-zero `Mi`/`Gi` size cap.
+fn validate_writable_mount(mount: &Mount, mode: MountMode, idx: usize) -> Vec<ErrorItem> {
+    let mut errors = Vec::new();
+    if !mount.source.trim().is_empty() {
+        errors.push(pol_error(
+            SR_POL_002,
+            mount_field_path(idx, "source"),
+            format!(
+                "{} mounts are backed by a per-run image and cannot set source",
+                mode.as_str()
+            ),
+        ));
+    }
+    if mount.read_only {
+        errors.push(pol_error(
+            SR_POL_002,
+            mount_field_path(idx, "read_only"),
+            format!(
+                "{} mounts are writable; read_only must be false",
+                mode.as_str()
+            ),
+        ));
+    }
+    let size_re = Regex::new(r"^[1-9][0-9]*(Mi|Gi)$").expect("regex");
+    match mount.max_size.as_deref().map(str::trim) {
+        Some(size) if size_re.is_match(size) => {
+            if memory_mib(size).is_none_or(|mib| mib > WRITABLE_MOUNT_MAX_MIB) {
+                errors.push(pol_error(
+                    SR_POL_002,
+                    mount_field_path(idx, "max_size"),
+                    format!(
+                        "max_size {size} exceeds the limit of {}Gi",
+                        WRITABLE_MOUNT_MAX_MIB / 1024
+                    ),
+                ));
+            }
+        }
+        Some(_) => errors.push(pol_error(
+            SR_POL_002,
+            mount_field_path(idx, "max_size"),
+            "max_size must be a non-zero size like 64Mi or 1Gi",
+        )),
+        None => errors.push(pol_error(
+            SR_POL_001,
+            mount_field_path(idx, "max_size"),
+            format!("{} mounts require max_size", mode.as_str()),
+        )),
+    }
+    errors
+}
+
+/// Writable mount targets are passed to the guest on the kernel command line, where
+/// whitespace, quotes and `=` would start or reshape another argument.
+pub fn is_plain_mount_target(target: &str) -> bool {
+    !target
+        .chars()
+        .any(|ch| matches!(ch, '"' | '\'' | '=') || ch.is_whitespace() || ch.is_control())
+}
+
+fn mount_field_path(idx: usize, field: &str) -> String {
+    format!("mounts[{idx}].{field}")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -287,9 +668,15 @@ mod tests {
             metadata: Metadata {
                 name: "demo".to_string(),
             },
+            parameters: BTreeMap::new(),
             runtime: Runtime {
                 command: "/bin/echo".to_string(),
                 args: vec!["ok".to_string()],
+                env: BTreeMap::new(),
+                env_passthrough: Vec::new(),
+                cwd: None,
+                user: None,
+                command_sha256: None,
             },
             resources: Resources {
                 cpu: Cpu {
@@ -304,6 +691,7 @@ mod tests {
                 egress: vec![],
             },
             mounts: vec![],
+            secrets: Vec::new(),
             audit: Audit {
                 level: "basic".to_string(),
             },
@@ -315,15 +703,3 @@ mod tests {
         assert!(result.errors.iter().any(|err| err.path == "network.egress"));
     }
 }
-
-fn pol_error(
-    code: impl Into<String>,
-    path: impl Into<String>,
-    message: impl Into<String>,
-) -> ErrorItem {
-    ErrorItem::new(code, path, message)
-}
-
-fn mount_field_path(idx: usize, field: &str) -> String {
-    format!("mounts[{idx}].{field}")
-}
@@ -0,0 +1,291 @@
+use serde::{Deserialize, Serialize};
+use sr_common::{ErrorItem, SR_LNT_001, SR_LNT_002, SR_LNT_003, SR_LNT_004, SR_POL_002};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::path_security::{normalize_path_lexically, PathSecurityEngine};
+use crate::{bind_parameters, memory_kib, NetworkMode, PolicySpec};
+
+const LINT_CONFIG_SCHEMA_VERSION: &str = "safe-run.lint/v1";
+const DEFAULT_MEMORY_THRESHOLD: &str = "4Gi";
+const PLAIN_HTTP_PORT: u32 = 80;
+
+/// How a lint finding is reported; `off` disables the rule.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    Off,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintFinding {
+    pub severity: LintSeverity,
+    #[serde(flatten)]
+    pub item: ErrorItem,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintReport {
+    /// False when any finding has `error` severity.
+    pub passed: bool,
+    pub errors: usize,
+    pub warnings: usize,
+    pub findings: Vec<LintFinding>,
+}
+
+/// Per-rule severity overrides plus rule parameters, loaded from a
+/// `safe-run.lint/v1` YAML file. Rules not listed keep their default severity.
+#[derive(Debug, Clone)]
+pub struct LintConfig {
+    severities: BTreeMap<String, LintSeverity>,
+    memory_threshold_kib: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LintConfigFile {
+    #[serde(rename = "schemaVersion")]
+    schema_version: String,
+    #[serde(default)]
+    severity: BTreeMap<String, LintSeverity>,
+    #[serde(rename = "memoryThreshold", default)]
+    memory_threshold: Option<String>,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            severities: BTreeMap::new(),
+            memory_threshold_kib: memory_kib(DEFAULT_MEMORY_THRESHOLD).unwrap_or_default(),
+        }
+    }
+}
+
+impl LintConfig {
+    /// Error mapping: unreadable or malformed config, unknown rule codes and invalid
+    /// thresholds -> `SR-POL-002` under `lintConfig`.
+    pub fn from_file(path: &Path) -> Result<Self, ErrorItem> {
+        let raw = std::fs::read_to_string(path).map_err(|err| {
+            config_error(
+                "lintConfig.file",
+                format!("failed to read lint config '{}': {err}", path.display()),
+            )
+        })?;
+        let file: LintConfigFile = serde_yaml::from_str(&raw).map_err(|err| {
+            config_error(
+                "lintConfig.file",
+                format!("failed to parse lint config '{}': {err}", path.display()),
+            )
+        })?;
+        if file.schema_version != LINT_CONFIG_SCHEMA_VERSION {
+            return Err(config_error(
+                "lintConfig.schemaVersion",
+                format!("schemaVersion must be {LINT_CONFIG_SCHEMA_VERSION}"),
+            ));
+        }
+        if let Some(code) = file
+            .severity
+            .keys()
+            .find(|code| !RULES.iter().any(|rule| rule.code == code.as_str()))
+        {
+            return Err(config_error(
+                format!("lintConfig.severity.{code}"),
+                format!("unknown lint rule '{code}'"),
+            ));
+        }
+        let mut config = Self {
+            severities: file.severity,
+            ..Self::default()
+        };
+        if let Some(threshold) = file.memory_threshold {
+            config.memory_threshold_kib = memory_kib(&threshold).ok_or_else(|| {
+                config_error(
+                    "lintConfig.memoryThreshold",
+                    "memoryThreshold must be like 4Gi",
+                )
+            })?;
+        }
+        Ok(config)
+    }
+
+    pub fn severity(&self, code: &str) -> LintSeverity {
+        self.severities.get(code).copied().unwrap_or_else(|| {
+            RULES
+                .iter()
+                .find(|rule| rule.code == code)
+                .map_or(LintSeverity::Warning, |rule| rule.default_severity)
+        })
+    }
+}
+
+struct LintContext<'a> {
+    policy: &'a PolicySpec,
+    config: &'a LintConfig,
+    host_allow_prefixes: &'a [PathBuf],
+}
+
+type Findings = Vec<(String, String)>;
+
+struct LintRule {
+    code: &'static str,
+    default_severity: LintSeverity,
+    check: fn(&LintContext) -> Findings,
+}
+
+const RULES: &[LintRule] = &[
+    LintRule {
+        code: SR_LNT_001,
+        default_severity: LintSeverity::Error,
+        check: egress_to_any_address,
+    },
+    LintRule {
+        code: SR_LNT_002,
+        default_severity: LintSeverity::Warning,
+        check: memory_above_threshold,
+    },
+    LintRule {
+        code: SR_LNT_003,
+        default_severity: LintSeverity::Warning,
+        check: mount_of_whole_allow_prefix,
+    },
+    LintRule {
+        code: SR_LNT_004,
+        default_severity: LintSeverity::Warning,
+        check: host_rule_on_plain_http,
+    },
+];
+
+/// Run the best-practice rules over a parsed policy. Declared parameters are bound to their
+/// defaults first, so the checks see concrete mount sources and hosts. The policy is not
+/// validated; run `validate` for correctness errors.
+/// Error mapping: allowlist failures follow `validate_policy_with_allowlist`, parameter
+/// binding failures follow `bind_parameters`.
+pub fn lint_policy(
+    policy: &PolicySpec,
+    allowlist_path: Option<&str>,
+    config: &LintConfig,
+) -> Result<LintReport, Vec<ErrorItem>> {
+    let mut bound = policy.clone();
+    if !bound.parameters.is_empty() {
+        bind_parameters(&mut bound, &BTreeMap::new())?;
+    }
+    let engine = PathSecurityEngine::from_sources(allowlist_path).map_err(|err| vec![err])?;
+    let context = LintContext {
+        policy: &bound,
+        config,
+        host_allow_prefixes: engine.host_allow_prefixes(),
+    };
+
+    let mut findings = Vec::new();
+    for rule in RULES {
+        let severity = config.severity(rule.code);
+        if severity == LintSeverity::Off {
+            continue;
+        }
+        findings.extend(
+            (rule.check)(&context)
+                .into_iter()
+                .map(|(path, message)| LintFinding {
+                    severity,
+                    item: ErrorItem::new(rule.code, path, message),
+                }),
+        );
+    }
+    let errors = findings
+        .iter()
+        .filter(|finding| finding.severity == LintSeverity::Error)
+        .count();
+    Ok(LintReport {
+        passed: errors == 0,
+        errors,
+        warnings: findings.len() - errors,
+        findings,
+    })
+}
+
+fn egress_to_any_address(context: &LintContext) -> Findings {
+    if context.policy.network.mode != NetworkMode::Allowlist {
+        return Vec::new();
+    }
+    context
+        .policy
+        .network
+        .egress
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, rule)| {
+            let cidr = rule.cidr.as_deref()?.trim();
+            (cidr.split_once('/')?.1 == "0").then(|| {
+                (
+                    format!("network.egress[{idx}].cidr"),
+                    format!("egress to {cidr} allows every destination"),
+                )
+            })
+        })
+        .collect()
+}
+
+fn memory_above_threshold(context: &LintContext) -> Findings {
+    let max = context.policy.resources.memory.max.trim();
+    match memory_kib(max) {
+        Some(kib) if kib > context.config.memory_threshold_kib => vec![(
+            "resources.memory.max".to_string(),
+            format!(
+                "memory.max {max} is above the {}Mi lint threshold",
+                context.config.memory_threshold_kib / 1024
+            ),
+        )],
+        _ => Vec::new(),
+    }
+}
+
+fn mount_of_whole_allow_prefix(context: &LintContext) -> Findings {
+    context
+        .policy
+        .mounts
+        .iter()
+        .enumerate()
+        .filter(|(_, mount)| mount.mode.is_none() && !mount.source.trim().is_empty())
+        .filter_map(|(idx, mount)| {
+            let source = normalize_path_lexically(Path::new(mount.source.trim()));
+            context.host_allow_prefixes.contains(&source).then(|| {
+                (
+                    format!("mounts[{idx}].source"),
+                    format!(
+                        "mount source '{}' is a whole allowlist prefix; mount a subdirectory",
+                        source.display()
+                    ),
+                )
+            })
+        })
+        .collect()
+}
+
+fn host_rule_on_plain_http(context: &LintContext) -> Findings {
+    if context.policy.network.mode != NetworkMode::Allowlist {
+        return Vec::new();
+    }
+    context
+        .policy
+        .network
+        .egress
+        .iter()
+        .enumerate()
+        .filter(|(_, rule)| rule.host.is_some() && rule.port == Some(PLAIN_HTTP_PORT))
+        .map(|(idx, rule)| {
+            (
+                format!("network.egress[{idx}].port"),
+                format!(
+                    "egress to host '{}' uses plain HTTP port 80; prefer 443",
+                    rule.host.as_deref().unwrap_or_default()
+                ),
+            )
+        })
+        .collect()
+}
+
+fn config_error(path: impl Into<String>, message: impl Into<String>) -> ErrorItem {
+    ErrorItem::new(SR_POL_002, path, message)
+}
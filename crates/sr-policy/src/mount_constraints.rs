@@ -1,5 +1,6 @@
+use serde::{Deserialize, Serialize};
 use sr_common::{ErrorItem, SR_POL_101, SR_POL_102};
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 
 const SENSITIVE_HOST_PREFIXES: [&str; 3] = ["/proc", "/sys", "/dev"];
 const GUEST_DENY_PREFIXES: [&str; 12] = [
@@ -7,28 +8,76 @@ const GUEST_DENY_PREFIXES: [&str; 12] = [
     "/usr",
 ];
 
+/// Built-in deny prefixes plus allowlist-provided glob patterns, as reported in
+/// validation output.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EffectiveDenylist {
+    #[serde(rename = "hostPaths")]
+    pub host_paths: Vec<String>,
+    #[serde(rename = "guestPaths")]
+    pub guest_paths: Vec<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct MountConstraints {
     guest_allow_prefixes: Vec<PathBuf>,
+    host_deny_patterns: Vec<String>,
+    guest_deny_patterns: Vec<String>,
 }
 
 impl MountConstraints {
-    /// Build target-path constraint validator from guest allowlist prefixes.
-    pub fn new(guest_allow_prefixes: Vec<PathBuf>) -> Self {
+    /// Build target-path constraint validator from guest allowlist prefixes and the
+    /// allowlist's extra deny patterns, which extend the built-in denylists.
+    pub fn new(
+        guest_allow_prefixes: Vec<PathBuf>,
+        host_deny_patterns: &[String],
+        guest_deny_patterns: &[String],
+    ) -> Self {
         Self {
             guest_allow_prefixes,
+            host_deny_patterns: merge_patterns(&SENSITIVE_HOST_PREFIXES, host_deny_patterns),
+            guest_deny_patterns: merge_patterns(&GUEST_DENY_PREFIXES, guest_deny_patterns),
+        }
+    }
+
+    pub fn effective_denylist(&self) -> EffectiveDenylist {
+        EffectiveDenylist {
+            host_paths: self.host_deny_patterns.clone(),
+            guest_paths: self.guest_deny_patterns.clone(),
         }
     }
 
-    /// Reject sensitive host path sources even when allowlisted.
+    /// Reject sensitive host path sources even when allowlisted. Both the requested and the
+    /// canonical path are checked so a denied symlink path cannot be named directly, and a
+    /// source is also rejected when it is a parent of a denied path, since mounting it would
+    /// expose that path.
     /// Error mapping: `SR-POL-101` with `mounts[i].source`.
-    pub fn validate_source_sensitive(&self, canonical: &Path, idx: usize) -> Result<(), ErrorItem> {
-        if is_sensitive_host_path(canonical) {
+    pub fn validate_source_sensitive(
+        &self,
+        requested: &Path,
+        canonical: &Path,
+        idx: usize,
+    ) -> Result<(), ErrorItem> {
+        if let Some(pattern) = self.host_deny_hit(requested, canonical) {
+            return Err(policy_error(
+                SR_POL_101,
+                format!("mounts[{idx}].source"),
+                format!(
+                    "mount source '{}' is within a sensitive host path '{pattern}'",
+                    canonical.display()
+                ),
+            ));
+        }
+        if let Some(pattern) = [canonical, requested].into_iter().find_map(|path| {
+            self.host_deny_patterns
+                .iter()
+                .find(|pattern| pattern_parent_hit(path, pattern))
+        }) {
             return Err(policy_error(
                 SR_POL_101,
                 format!("mounts[{idx}].source"),
                 format!(
-                    "mount source '{}' is within a sensitive host path",
+                    "mount source '{}' contains sensitive host path '{pattern}'",
                     canonical.display()
                 ),
             ));
@@ -36,6 +85,16 @@ impl MountConstraints {
         Ok(())
     }
 
+    /// First host deny pattern covering either the requested or the canonical path.
+    pub(crate) fn host_deny_hit(&self, requested: &Path, canonical: &Path) -> Option<&str> {
+        [canonical, requested].into_iter().find_map(|path| {
+            self.host_deny_patterns
+                .iter()
+                .find(|pattern| pattern_hit(path, pattern))
+                .map(String::as_str)
+        })
+    }
+
     /// Validate mount target namespace and forbidden guest path denylist.
     /// Error mapping: `SR-POL-102` with `mounts[i].target`.
     pub fn validate_target_path(&self, target: &str, idx: usize) -> Result<(), ErrorItem> {
@@ -55,7 +114,7 @@ impl MountConstraints {
             ));
         }
 
-        if let Some(deny_prefix) = guest_deny_hit(target_path) {
+        if let Some(deny_prefix) = self.guest_deny_hit(target_path) {
             return Err(policy_error(
                 SR_POL_102,
                 format!("mounts[{idx}].target"),
@@ -68,32 +127,111 @@ impl MountConstraints {
 
         Ok(())
     }
+
+    /// The guest root itself is denied, but only as an exact target.
+    fn guest_deny_hit(&self, target: &Path) -> Option<&str> {
+        self.guest_deny_patterns
+            .iter()
+            .find(|pattern| {
+                if pattern.as_str() == "/" {
+                    target == Path::new("/")
+                } else {
+                    pattern_hit(target, pattern)
+                }
+            })
+            .map(String::as_str)
+    }
 }
 
-fn is_sensitive_host_path(path: &Path) -> bool {
-    SENSITIVE_HOST_PREFIXES
+fn merge_patterns(builtin: &[&str], extra: &[String]) -> Vec<String> {
+    let mut patterns = builtin
         .iter()
-        .any(|prefix| is_prefix_or_exact(path, Path::new(prefix)))
+        .map(|pattern| pattern.to_string())
+        .collect::<Vec<_>>();
+    for pattern in extra {
+        if !patterns.contains(pattern) {
+            patterns.push(pattern.clone());
+        }
+    }
+    patterns
 }
 
-fn guest_deny_hit(target: &Path) -> Option<&'static str> {
-    for prefix in GUEST_DENY_PREFIXES {
-        let prefix_path = Path::new(prefix);
-        if prefix == "/" {
-            if target == prefix_path {
-                return Some(prefix);
-            }
-            continue;
+/// Whether `path` or one of its ancestors matches `pattern`. Patterns are absolute and
+/// matched per component after lexical `..` folding: `*`/`?` stay within a component,
+/// `**` spans any number of them.
+pub(crate) fn pattern_hit(path: &Path, pattern: &str) -> bool {
+    let pattern_parts = pattern_components(pattern);
+    let path_parts = path_components(path);
+    let path_parts = path_parts.iter().map(String::as_str).collect::<Vec<_>>();
+    (0..=path_parts.len()).any(|len| match_components(&pattern_parts, &path_parts[..len]))
+}
+
+/// Whether `path` is a strict ancestor of paths `pattern` matches, i.e. it matches a
+/// leading run of the pattern's components with components left over. Matching stops at
+/// the first `**`: paths at or above it count, paths below it do not, as every directory
+/// could hold a `**` match.
+fn pattern_parent_hit(path: &Path, pattern: &str) -> bool {
+    let pattern_parts = pattern_components(pattern);
+    let path_parts = path_components(path);
+    let path_parts = path_parts.iter().map(String::as_str).collect::<Vec<_>>();
+    match_parent(&pattern_parts, &path_parts)
+}
+
+fn match_parent(pattern: &[&str], path: &[&str]) -> bool {
+    match (pattern.split_first(), path.split_first()) {
+        (None, _) => false,
+        (Some(_), None) => true,
+        (Some((&"**", _)), Some(_)) => false,
+        (Some((head, rest)), Some((first, tail))) => {
+            match_segment(head, first) && match_parent(rest, tail)
         }
-        if is_prefix_or_exact(target, prefix_path) {
-            return Some(prefix);
+    }
+}
+
+fn pattern_components(pattern: &str) -> Vec<&str> {
+    pattern
+        .split('/')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+}
+
+/// Normal components of `path` after lexical `..` folding.
+pub(crate) fn path_components(path: &Path) -> Vec<String> {
+    path.components().fold(Vec::new(), |mut parts, component| {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+            Component::ParentDir => {
+                parts.pop();
+            }
+            _ => {}
         }
+        parts
+    })
+}
+
+fn match_components(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| match_components(rest, &path[skip..])),
+        Some((head, rest)) => path.split_first().is_some_and(|(first, tail)| {
+            match_segment(head, first) && match_components(rest, tail)
+        }),
     }
-    None
 }
 
-fn is_prefix_or_exact(path: &Path, prefix: &Path) -> bool {
-    path == prefix || path.starts_with(prefix)
+fn match_segment(pattern: &str, segment: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let segment = segment.chars().collect::<Vec<_>>();
+    match_chars(&pattern, &segment)
+}
+
+fn match_chars(pattern: &[char], segment: &[char]) -> bool {
+    match pattern.split_first() {
+        None => segment.is_empty(),
+        Some(('*', rest)) => (0..=segment.len()).any(|skip| match_chars(rest, &segment[skip..])),
+        Some(('?', rest)) => !segment.is_empty() && match_chars(rest, &segment[1..]),
+        Some((ch, rest)) => segment.first() == Some(ch) && match_chars(rest, &segment[1..]),
+    }
 }
 
 fn policy_error(
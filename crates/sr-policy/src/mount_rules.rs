@@ -0,0 +1,240 @@
+use crate::mount_scan::{walk_source, WalkEnd, MODE_SETID};
+use sr_common::{ErrorItem, SR_POL_101, SR_POL_102};
+use std::fs;
+use std::ops::ControlFlow;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+const MODE_WORLD_WRITABLE: u32 = 0o002;
+/// Entries a content check may visit per mount source; larger sources are rejected.
+const CONTENT_SCAN_MAX_ENTRIES: u64 = 100_000;
+
+/// Per-prefix constraints from a v2 allowlist. Every entry below a mount source is checked;
+/// symlinks are not followed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostPrefixRule {
+    pub host_prefix: PathBuf,
+    /// Guest targets sources under this prefix may be mounted at; empty allows any target
+    /// that passes the global guest allowlist.
+    pub guest_targets: Vec<PathBuf>,
+    pub max_files: Option<u64>,
+    pub max_bytes: Option<u64>,
+    pub owner_uid: Option<u32>,
+    pub owner_gid: Option<u32>,
+    pub deny_world_writable: bool,
+    pub deny_setuid: bool,
+}
+
+impl HostPrefixRule {
+    /// Rule used for v1 prefixes: allowlisting only, no content checks.
+    pub fn prefix_only(host_prefix: PathBuf) -> Self {
+        Self {
+            host_prefix,
+            guest_targets: Vec::new(),
+            max_files: None,
+            max_bytes: None,
+            owner_uid: None,
+            owner_gid: None,
+            deny_world_writable: false,
+            deny_setuid: false,
+        }
+    }
+
+    fn has_content_checks(&self) -> bool {
+        self.max_files.is_some()
+            || self.max_bytes.is_some()
+            || self.owner_uid.is_some()
+            || self.owner_gid.is_some()
+            || self.deny_world_writable
+            || self.deny_setuid
+    }
+}
+
+/// Enforce a rule's guest target list.
+/// Error mapping: `SR-POL-102` with `mountAllowlist.rules[r].guestTargets`, located at
+/// `mounts[i].target`.
+pub(crate) fn check_guest_target(
+    rule: &HostPrefixRule,
+    rule_idx: usize,
+    target: &str,
+    idx: usize,
+) -> Option<ErrorItem> {
+    let target_path = Path::new(target);
+    if rule.guest_targets.is_empty()
+        || rule
+            .guest_targets
+            .iter()
+            .any(|allowed| target_path.starts_with(allowed))
+    {
+        return None;
+    }
+    Some(
+        ErrorItem::new(
+            SR_POL_102,
+            rule_field_path(rule_idx, "guestTargets"),
+            format!(
+                "mount target '{target}' is not allowed for host prefix '{}'",
+                rule.host_prefix.display()
+            ),
+        )
+        .with_anchor(format!("mounts[{idx}].target")),
+    )
+}
+
+/// Walk the canonical source and enforce count, size, ownership and mode constraints.
+/// The walk stops once a count or size limit is exceeded, so at most one error is reported
+/// per constraint, naming the first offending entry in the visited prefix.
+/// Error mapping: `SR-POL-101` with `mountAllowlist.rules[r].<field>`, located at
+/// `mounts[i].source`.
+pub(crate) fn check_source_contents(
+    rule: &HostPrefixRule,
+    rule_idx: usize,
+    canonical: &Path,
+    idx: usize,
+) -> Vec<ErrorItem> {
+    if !rule.has_content_checks() || fs::symlink_metadata(canonical).is_err() {
+        return Vec::new();
+    }
+    let source_path = format!("mounts[{idx}].source");
+    let mut scan = ContentScan::default();
+    let walked = walk_source(canonical, CONTENT_SCAN_MAX_ENTRIES, |path, metadata| {
+        Ok(scan.record(rule, path, metadata))
+    });
+    match walked {
+        Ok(WalkEnd::BudgetExhausted) => {
+            return vec![ErrorItem::new(
+                SR_POL_101,
+                source_path,
+                format!(
+                    "mount source '{}' has more than {CONTENT_SCAN_MAX_ENTRIES} entries to check",
+                    canonical.display()
+                ),
+            )];
+        }
+        Ok(_) => {}
+        Err(err) => {
+            return vec![ErrorItem::new(
+                SR_POL_101,
+                source_path,
+                format!(
+                    "failed to inspect mount source '{}': {err}",
+                    canonical.display()
+                ),
+            )];
+        }
+    }
+
+    let rule_error = |field: &str, message: String| {
+        ErrorItem::new(SR_POL_101, rule_field_path(rule_idx, field), message)
+            .with_anchor(source_path.clone())
+    };
+    let mut errors = Vec::new();
+    if let Some(limit) = rule.max_files.filter(|limit| scan.files > *limit) {
+        errors.push(rule_error(
+            "maxFiles",
+            format!(
+                "mount source '{}' has more than {limit} files",
+                canonical.display()
+            ),
+        ));
+    }
+    if let Some(limit) = rule.max_bytes.filter(|limit| scan.bytes > *limit) {
+        errors.push(rule_error(
+            "maxBytes",
+            format!(
+                "mount source '{}' holds more than {limit} bytes",
+                canonical.display()
+            ),
+        ));
+    }
+    if let Some(path) = scan.wrong_uid {
+        errors.push(rule_error(
+            "owner.uid",
+            format!(
+                "'{}' is not owned by uid {}",
+                path.display(),
+                rule.owner_uid.unwrap_or_default()
+            ),
+        ));
+    }
+    if let Some(path) = scan.wrong_gid {
+        errors.push(rule_error(
+            "owner.gid",
+            format!(
+                "'{}' is not owned by gid {}",
+                path.display(),
+                rule.owner_gid.unwrap_or_default()
+            ),
+        ));
+    }
+    if let Some(path) = scan.world_writable {
+        errors.push(rule_error(
+            "denyWorldWritable",
+            format!("'{}' is world-writable", path.display()),
+        ));
+    }
+    if let Some(path) = scan.setuid {
+        errors.push(rule_error(
+            "denySetuid",
+            format!("'{}' has the setuid or setgid bit set", path.display()),
+        ));
+    }
+    errors
+}
+
+#[derive(Debug, Default)]
+struct ContentScan {
+    files: u64,
+    bytes: u64,
+    wrong_uid: Option<PathBuf>,
+    wrong_gid: Option<PathBuf>,
+    world_writable: Option<PathBuf>,
+    setuid: Option<PathBuf>,
+}
+
+impl ContentScan {
+    /// Check one entry; breaks once the source is over its file count or size limit.
+    fn record(
+        &mut self,
+        rule: &HostPrefixRule,
+        path: &Path,
+        metadata: &fs::Metadata,
+    ) -> ControlFlow<()> {
+        let file_type = metadata.file_type();
+        if rule.owner_uid.is_some_and(|uid| metadata.uid() != uid) {
+            self.wrong_uid.get_or_insert_with(|| path.to_path_buf());
+        }
+        if rule.owner_gid.is_some_and(|gid| metadata.gid() != gid) {
+            self.wrong_gid.get_or_insert_with(|| path.to_path_buf());
+        }
+        // Symlink permission bits are always 0777 and meaningless.
+        if rule.deny_world_writable
+            && !file_type.is_symlink()
+            && metadata.mode() & MODE_WORLD_WRITABLE != 0
+        {
+            self.world_writable
+                .get_or_insert_with(|| path.to_path_buf());
+        }
+        if rule.deny_setuid && file_type.is_file() && metadata.mode() & MODE_SETID != 0 {
+            self.setuid.get_or_insert_with(|| path.to_path_buf());
+        }
+
+        if !file_type.is_dir() {
+            self.files += 1;
+            if file_type.is_file() {
+                self.bytes += metadata.len();
+            }
+        }
+        let over_files = rule.max_files.is_some_and(|limit| self.files > limit);
+        let over_bytes = rule.max_bytes.is_some_and(|limit| self.bytes > limit);
+        if over_files || over_bytes {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+}
+
+fn rule_field_path(rule_idx: usize, field: &str) -> String {
+    format!("mountAllowlist.rules[{rule_idx}].{field}")
+}
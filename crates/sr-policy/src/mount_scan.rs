@@ -0,0 +1,199 @@
+use crate::path_security::normalize_path_lexically;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::ops::ControlFlow;
+use std::os::unix::fs::{FileTypeExt, MetadataExt};
+use std::path::Path;
+
+pub(crate) const MODE_SETID: u32 = 0o6000;
+/// Findings beyond this many are counted but not listed.
+const MAX_LISTED_FINDINGS: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MountScanFindingKind {
+    /// Symlink whose target resolves outside the mount source.
+    EscapingSymlink,
+    DeviceNode,
+    /// Regular file with more than one link; the other names may be outside the source.
+    Hardlink,
+    Setuid,
+}
+
+impl MountScanFindingKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::EscapingSymlink => "escaping_symlink",
+            Self::DeviceNode => "device_node",
+            Self::Hardlink => "hardlink",
+            Self::Setuid => "setuid",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MountScanFinding {
+    pub kind: MountScanFindingKind,
+    pub path: String,
+}
+
+/// Upper bound on the number of entries a single mount scan may visit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MountScanBudget {
+    pub max_entries: u64,
+}
+
+/// Summary of one mount source walk. `budget_exhausted` means the walk stopped early and
+/// the counts only cover the visited prefix.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MountScanReport {
+    pub entries: u64,
+    pub files: u64,
+    pub directories: u64,
+    #[serde(rename = "totalBytes")]
+    pub total_bytes: u64,
+    #[serde(rename = "findingsTotal")]
+    pub findings_total: u64,
+    pub findings: Vec<MountScanFinding>,
+    #[serde(rename = "budgetExhausted")]
+    pub budget_exhausted: bool,
+}
+
+impl MountScanReport {
+    pub fn is_clean(&self) -> bool {
+        self.findings_total == 0 && !self.budget_exhausted
+    }
+
+    fn record(&mut self, kind: MountScanFindingKind, path: &Path) {
+        self.findings_total += 1;
+        if self.findings.len() < MAX_LISTED_FINDINGS {
+            self.findings.push(MountScanFinding {
+                kind,
+                path: path.to_string_lossy().to_string(),
+            });
+        }
+    }
+}
+
+/// Walk a mount source without following symlinks and report entries that could expose host
+/// state to the guest: escaping symlinks, device nodes, hardlinked files and setuid/setgid
+/// files. A missing source is an error: the source must exist by the time it is scanned.
+pub fn scan_mount_source(
+    source: &Path,
+    budget: &MountScanBudget,
+) -> std::io::Result<MountScanReport> {
+    let mut report = MountScanReport::default();
+    let end = walk_source(source, budget.max_entries, |path, metadata| {
+        record_entry(source, path, metadata, &mut report)
+    })?;
+    report.budget_exhausted = end == WalkEnd::BudgetExhausted;
+    Ok(report)
+}
+
+fn record_entry(
+    root: &Path,
+    path: &Path,
+    metadata: &fs::Metadata,
+    report: &mut MountScanReport,
+) -> std::io::Result<ControlFlow<()>> {
+    report.entries += 1;
+    let file_type = metadata.file_type();
+    if file_type.is_dir() {
+        report.directories += 1;
+        return Ok(ControlFlow::Continue(()));
+    }
+
+    report.files += 1;
+    if file_type.is_symlink() {
+        let link = fs::read_link(path)?;
+        let parent = path.parent().unwrap_or(root);
+        if !normalize_path_lexically(&parent.join(link)).starts_with(root) {
+            report.record(MountScanFindingKind::EscapingSymlink, path);
+        }
+    } else if file_type.is_block_device() || file_type.is_char_device() {
+        report.record(MountScanFindingKind::DeviceNode, path);
+    } else if file_type.is_file() {
+        report.total_bytes += metadata.len();
+        if metadata.nlink() > 1 {
+            report.record(MountScanFindingKind::Hardlink, path);
+        }
+        if metadata.mode() & MODE_SETID != 0 {
+            report.record(MountScanFindingKind::Setuid, path);
+        }
+    }
+    Ok(ControlFlow::Continue(()))
+}
+
+/// How a `walk_source` walk ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum WalkEnd {
+    Complete,
+    /// The visitor asked to stop.
+    Stopped,
+    /// `max_entries` entries were visited before the walk could finish.
+    BudgetExhausted,
+}
+
+/// Depth-first walk of `root` in path order without following symlinks. `visit` sees every
+/// entry, `root` included, and may end the walk early with `ControlFlow::Break`. At most
+/// `max_entries` entries are visited, and a directory is read no further than the remaining
+/// budget, so a huge directory cannot be listed in full first.
+pub(crate) fn walk_source<F>(root: &Path, max_entries: u64, visit: F) -> std::io::Result<WalkEnd>
+where
+    F: FnMut(&Path, &fs::Metadata) -> std::io::Result<ControlFlow<()>>,
+{
+    let mut walker = Walker {
+        max_entries,
+        visited: 0,
+        visit,
+    };
+    walker.walk(root)
+}
+
+struct Walker<F> {
+    max_entries: u64,
+    visited: u64,
+    visit: F,
+}
+
+impl<F> Walker<F>
+where
+    F: FnMut(&Path, &fs::Metadata) -> std::io::Result<ControlFlow<()>>,
+{
+    fn walk(&mut self, path: &Path) -> std::io::Result<WalkEnd> {
+        if self.visited >= self.max_entries {
+            return Ok(WalkEnd::BudgetExhausted);
+        }
+        self.visited += 1;
+
+        let metadata = fs::symlink_metadata(path)?;
+        if (self.visit)(path, &metadata)?.is_break() {
+            return Ok(WalkEnd::Stopped);
+        }
+        if !metadata.file_type().is_dir() {
+            return Ok(WalkEnd::Complete);
+        }
+        let remaining = self.max_entries - self.visited;
+        let mut entries = Vec::new();
+        let mut truncated = false;
+        for entry in fs::read_dir(path)? {
+            if entries.len() as u64 >= remaining {
+                truncated = true;
+                break;
+            }
+            entries.push(entry?.path());
+        }
+        entries.sort();
+        for entry in entries {
+            let end = self.walk(&entry)?;
+            if end != WalkEnd::Complete {
+                return Ok(end);
+            }
+        }
+        Ok(if truncated {
+            WalkEnd::BudgetExhausted
+        } else {
+            WalkEnd::Complete
+        })
+    }
+}
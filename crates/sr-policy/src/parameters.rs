@@ -0,0 +1,236 @@
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+use sr_common::{ErrorItem, SR_POL_001, SR_POL_002};
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
+
+use crate::runtime_constraints::is_env_name;
+use crate::PolicySpec;
+
+/// Declared template parameter. Values are kept as strings and checked against `type`
+/// and the optional constraints when bound.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ParameterSpec {
+    #[serde(rename = "type")]
+    pub kind: ParameterType,
+    #[serde(
+        default,
+        deserialize_with = "scalar_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub default: Option<String>,
+    /// Allowed values for `string` and `path` parameters.
+    #[serde(rename = "enum", default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed: Vec<String>,
+    /// Regex the whole value must match (`string` only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ParameterType {
+    String,
+    Int,
+    Path,
+}
+
+/// Bind declared parameters from `overrides` (falling back to defaults) and substitute
+/// `${name}` in `runtime.args`, mount sources and egress hosts. On success the declarations
+/// are removed from the policy, which is then concrete, and the bound values are returned.
+/// Error mapping: missing value -> `SR-POL-001`; undeclared override, bad value or unknown
+/// reference -> `SR-POL-002`.
+pub fn bind_parameters(
+    policy: &mut PolicySpec,
+    overrides: &BTreeMap<String, String>,
+) -> Result<BTreeMap<String, String>, Vec<ErrorItem>> {
+    let declared = std::mem::take(&mut policy.parameters);
+    let mut errors = Vec::new();
+    for name in overrides.keys() {
+        if !declared.contains_key(name) {
+            errors.push(param_error(
+                SR_POL_002,
+                name,
+                format!("parameter '{name}' is not declared"),
+            ));
+        }
+    }
+
+    let mut bindings = BTreeMap::new();
+    for (name, spec) in &declared {
+        if !is_env_name(name) {
+            errors.push(param_error(
+                SR_POL_002,
+                name,
+                "parameter names must match [A-Za-z_][A-Za-z0-9_]*",
+            ));
+            continue;
+        }
+        let Some(value) = overrides.get(name).or(spec.default.as_ref()) else {
+            errors.push(param_error(
+                SR_POL_001,
+                name,
+                format!("parameter '{name}' has no default and was not set"),
+            ));
+            continue;
+        };
+        match check_value(spec, value) {
+            Ok(()) => {
+                bindings.insert(name.clone(), value.clone());
+            }
+            Err(message) => errors.push(param_error(SR_POL_002, name, message)),
+        }
+    }
+    if !errors.is_empty() {
+        policy.parameters = declared;
+        return Err(errors);
+    }
+
+    for (idx, arg) in policy.runtime.args.iter_mut().enumerate() {
+        substitute(arg, &bindings, format!("runtime.args[{idx}]"), &mut errors);
+    }
+    for (idx, mount) in policy.mounts.iter_mut().enumerate() {
+        substitute(
+            &mut mount.source,
+            &bindings,
+            format!("mounts[{idx}].source"),
+            &mut errors,
+        );
+    }
+    for (idx, rule) in policy.network.egress.iter_mut().enumerate() {
+        if let Some(host) = rule.host.as_mut() {
+            substitute(
+                host,
+                &bindings,
+                format!("network.egress[{idx}].host"),
+                &mut errors,
+            );
+        }
+    }
+    if errors.is_empty() {
+        Ok(bindings)
+    } else {
+        policy.parameters = declared;
+        Err(errors)
+    }
+}
+
+fn check_value(spec: &ParameterSpec, value: &str) -> Result<(), String> {
+    match spec.kind {
+        ParameterType::Int => {
+            let parsed = value
+                .parse::<i64>()
+                .map_err(|_| format!("'{value}' is not an integer"))?;
+            if spec.min.is_some_and(|min| parsed < min) || spec.max.is_some_and(|max| parsed > max)
+            {
+                return Err(format!(
+                    "{parsed} is outside [{}, {}]",
+                    spec.min.map_or("-inf".to_string(), |min| min.to_string()),
+                    spec.max.map_or("inf".to_string(), |max| max.to_string())
+                ));
+            }
+        }
+        ParameterType::Path => {
+            let path = Path::new(value);
+            if !path.is_absolute()
+                || path
+                    .components()
+                    .any(|component| matches!(component, Component::ParentDir))
+            {
+                return Err(format!("'{value}' must be an absolute path without '..'"));
+            }
+        }
+        ParameterType::String => {
+            if let Some(pattern) = spec.pattern.as_deref() {
+                let re = Regex::new(&format!("^(?:{pattern})$"))
+                    .map_err(|err| format!("invalid pattern '{pattern}': {err}"))?;
+                if !re.is_match(value) {
+                    return Err(format!("'{value}' does not match pattern '{pattern}'"));
+                }
+            }
+        }
+    }
+    if !spec.allowed.is_empty() && !spec.allowed.iter().any(|allowed| allowed == value) {
+        return Err(format!(
+            "'{value}' is not one of [{}]",
+            spec.allowed.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Replace `${name}` references in place; `$${` stays as a literal `${`.
+fn substitute(
+    field: &mut String,
+    bindings: &BTreeMap<String, String>,
+    path: String,
+    errors: &mut Vec<ErrorItem>,
+) {
+    if !field.contains("${") {
+        return;
+    }
+    let mut out = String::with_capacity(field.len());
+    let mut rest = field.as_str();
+    while let Some(start) = rest.find('$') {
+        out.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        if let Some(escaped) = tail.strip_prefix("$${") {
+            out.push_str("${");
+            rest = escaped;
+        } else if let Some(reference) = tail.strip_prefix("${") {
+            let Some(end) = reference.find('}') else {
+                errors.push(ErrorItem::new(
+                    SR_POL_002,
+                    path,
+                    "unterminated '${' parameter reference",
+                ));
+                return;
+            };
+            let name = &reference[..end];
+            match bindings.get(name) {
+                Some(value) => out.push_str(value),
+                None => {
+                    errors.push(ErrorItem::new(
+                        SR_POL_002,
+                        path,
+                        format!("unknown parameter '${{{name}}}'"),
+                    ));
+                    return;
+                }
+            }
+            rest = &reference[end + 1..];
+        } else {
+            out.push('$');
+            rest = &tail[1..];
+        }
+    }
+    out.push_str(rest);
+    *field = out;
+}
+
+/// Accept YAML scalars (`8`, `true`, `text`) for string-typed values.
+fn scalar_string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Option::<serde_yaml::Value>::deserialize(deserializer)?;
+    match value {
+        None | Some(serde_yaml::Value::Null) => Ok(None),
+        Some(serde_yaml::Value::String(text)) => Ok(Some(text)),
+        Some(serde_yaml::Value::Number(number)) => Ok(Some(number.to_string())),
+        Some(serde_yaml::Value::Bool(flag)) => Ok(Some(flag.to_string())),
+        Some(_) => Err(serde::de::Error::custom(
+            "parameter default must be a scalar",
+        )),
+    }
+}
+
+fn param_error(code: &str, name: &str, message: impl Into<String>) -> ErrorItem {
+    ErrorItem::new(code, format!("parameters.{name}"), message)
+}
@@ -1,40 +1,130 @@
-use serde::Deserialize;
+use crate::mount_rules::{check_guest_target, check_source_contents, HostPrefixRule};
+use crate::runtime_constraints::is_env_name;
+use serde::{Deserialize, Serialize};
 use sr_common::{ErrorItem, SR_POL_101};
+use std::os::unix::fs::MetadataExt;
 use std::path::{Path, PathBuf};
 
 const DEFAULT_HOST_ALLOW_PREFIXES: [&str; 1] = ["/var/lib/safe-run"];
 const DEFAULT_GUEST_ALLOW_PREFIXES: [&str; 1] = ["/data"];
 const ALLOWLIST_SCHEMA_VERSION: &str = "safe-run.mount-allowlist/v1";
+const ALLOWLIST_SCHEMA_VERSION_V2: &str = "safe-run.mount-allowlist/v2";
 
 #[derive(Debug, Clone)]
 pub struct MountAllowlist {
     pub host_allow_prefixes: Vec<PathBuf>,
     #[allow(dead_code)]
     pub guest_allow_prefixes: Vec<PathBuf>,
+    /// One rule per host prefix, in file order; v1 prefixes get constraint-free rules.
+    pub rules: Vec<HostPrefixRule>,
+    /// Glob patterns appended to the built-in sensitive host and guest denylists.
+    pub host_deny_patterns: Vec<String>,
+    pub guest_deny_patterns: Vec<String>,
+    /// Host directories `secrets[].fromFile` may read from; empty denies file secrets.
+    pub secret_file_prefixes: Vec<PathBuf>,
+    /// Exact runner env names `secrets[].fromEnv` may read; empty denies env secrets.
+    pub secret_env_names: Vec<String>,
 }
 
 #[derive(Debug, Deserialize)]
-struct MountAllowlistConfig {
+struct AllowlistSchemaProbe {
     #[serde(rename = "schemaVersion")]
     schema_version: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MountAllowlistConfig {
     #[serde(rename = "hostAllowPrefixes")]
     host_allow_prefixes: Vec<String>,
     #[serde(rename = "guestAllowPrefixes")]
     guest_allow_prefixes: Vec<String>,
+    #[serde(rename = "hostDenyPatterns", default)]
+    host_deny_patterns: Vec<String>,
+    #[serde(rename = "guestDenyPatterns", default)]
+    guest_deny_patterns: Vec<String>,
+    #[serde(rename = "secretFilePrefixes", default)]
+    secret_file_prefixes: Vec<String>,
+    #[serde(rename = "secretEnvNames", default)]
+    secret_env_names: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MountAllowlistConfigV2 {
+    #[serde(rename = "guestAllowPrefixes")]
+    guest_allow_prefixes: Vec<String>,
+    rules: Vec<HostPrefixRuleConfig>,
+    #[serde(rename = "hostDenyPatterns", default)]
+    host_deny_patterns: Vec<String>,
+    #[serde(rename = "guestDenyPatterns", default)]
+    guest_deny_patterns: Vec<String>,
+    #[serde(rename = "secretFilePrefixes", default)]
+    secret_file_prefixes: Vec<String>,
+    #[serde(rename = "secretEnvNames", default)]
+    secret_env_names: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HostPrefixRuleConfig {
+    #[serde(rename = "hostPrefix")]
+    host_prefix: String,
+    #[serde(rename = "guestTargets", default)]
+    guest_targets: Vec<String>,
+    #[serde(rename = "maxFiles", default)]
+    max_files: Option<u64>,
+    #[serde(rename = "maxBytes", default)]
+    max_bytes: Option<u64>,
+    #[serde(default)]
+    owner: Option<OwnerConfig>,
+    #[serde(rename = "denyWorldWritable", default = "default_true")]
+    deny_world_writable: bool,
+    #[serde(rename = "denySetuid", default = "default_true")]
+    deny_setuid: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct OwnerConfig {
+    #[serde(default)]
+    uid: Option<u32>,
+    #[serde(default)]
+    gid: Option<u32>,
+}
+
+fn default_true() -> bool {
+    true
 }
 
 impl MountAllowlist {
     /// Built-in allowlist used when CLI/env does not provide an external file.
     pub fn default_allowlist() -> Self {
-        Self {
-            host_allow_prefixes: DEFAULT_HOST_ALLOW_PREFIXES
+        Self::from_prefixes(
+            DEFAULT_HOST_ALLOW_PREFIXES
                 .iter()
                 .map(PathBuf::from)
                 .collect(),
-            guest_allow_prefixes: DEFAULT_GUEST_ALLOW_PREFIXES
+            DEFAULT_GUEST_ALLOW_PREFIXES
                 .iter()
                 .map(PathBuf::from)
                 .collect(),
+        )
+    }
+
+    fn from_prefixes(
+        host_allow_prefixes: Vec<PathBuf>,
+        guest_allow_prefixes: Vec<PathBuf>,
+    ) -> Self {
+        let rules = host_allow_prefixes
+            .iter()
+            .cloned()
+            .map(HostPrefixRule::prefix_only)
+            .collect();
+        Self {
+            host_allow_prefixes,
+            guest_allow_prefixes,
+            rules,
+            host_deny_patterns: Vec::new(),
+            guest_deny_patterns: Vec::new(),
+            secret_file_prefixes: Vec::new(),
+            secret_env_names: Vec::new(),
         }
     }
 
@@ -47,21 +137,20 @@ impl MountAllowlist {
                 format!("failed to read allowlist file '{}': {err}", path.display()),
             )
         })?;
-        let config: MountAllowlistConfig = serde_yaml::from_str(&raw).map_err(|err| {
-            pol101(
-                "mountAllowlist.file",
-                format!("failed to parse allowlist file '{}': {err}", path.display()),
-            )
-        })?;
-        if config.schema_version != ALLOWLIST_SCHEMA_VERSION {
-            return Err(pol101(
+        let probe: AllowlistSchemaProbe = parse_allowlist_yaml(&raw, path)?;
+        match probe.schema_version.as_str() {
+            ALLOWLIST_SCHEMA_VERSION => Self::from_v1(parse_allowlist_yaml(&raw, path)?),
+            ALLOWLIST_SCHEMA_VERSION_V2 => Self::from_v2(parse_allowlist_yaml(&raw, path)?),
+            _ => Err(pol101(
                 "mountAllowlist.schemaVersion",
                 format!(
-                    "allowlist schemaVersion must be '{}'",
-                    ALLOWLIST_SCHEMA_VERSION
+                    "allowlist schemaVersion must be '{ALLOWLIST_SCHEMA_VERSION}' or '{ALLOWLIST_SCHEMA_VERSION_V2}'"
                 ),
-            ));
+            )),
         }
+    }
+
+    fn from_v1(config: MountAllowlistConfig) -> Result<Self, ErrorItem> {
         let host_allow_prefixes = parse_prefixes(
             &config.host_allow_prefixes,
             "mountAllowlist.hostAllowPrefixes",
@@ -71,12 +160,105 @@ impl MountAllowlist {
             "mountAllowlist.guestAllowPrefixes",
         )?;
         Ok(Self {
-            host_allow_prefixes,
+            host_deny_patterns: parse_deny_patterns(
+                &config.host_deny_patterns,
+                "mountAllowlist.hostDenyPatterns",
+            )?,
+            guest_deny_patterns: parse_deny_patterns(
+                &config.guest_deny_patterns,
+                "mountAllowlist.guestDenyPatterns",
+            )?,
+            secret_file_prefixes: parse_prefixes(
+                &config.secret_file_prefixes,
+                "mountAllowlist.secretFilePrefixes",
+            )?,
+            secret_env_names: parse_env_names(&config.secret_env_names)?,
+            ..Self::from_prefixes(host_allow_prefixes, guest_allow_prefixes)
+        })
+    }
+
+    fn from_v2(config: MountAllowlistConfigV2) -> Result<Self, ErrorItem> {
+        let guest_allow_prefixes = parse_prefixes(
+            &config.guest_allow_prefixes,
+            "mountAllowlist.guestAllowPrefixes",
+        )?;
+        if config.rules.is_empty() {
+            return Err(pol101(
+                "mountAllowlist.rules",
+                "allowlist v2 requires at least one rule",
+            ));
+        }
+        let mut rules = Vec::with_capacity(config.rules.len());
+        for (rule_idx, rule) in config.rules.into_iter().enumerate() {
+            let host_prefix = parse_prefixes(
+                std::slice::from_ref(&rule.host_prefix),
+                &format!("mountAllowlist.rules[{rule_idx}].hostPrefix"),
+            )?
+            .remove(0);
+            let guest_targets = parse_prefixes(
+                &rule.guest_targets,
+                &format!("mountAllowlist.rules[{rule_idx}].guestTargets"),
+            )?;
+            let owner = rule.owner.unwrap_or(OwnerConfig {
+                uid: None,
+                gid: None,
+            });
+            rules.push(HostPrefixRule {
+                host_prefix,
+                guest_targets,
+                max_files: rule.max_files,
+                max_bytes: rule.max_bytes,
+                owner_uid: owner.uid,
+                owner_gid: owner.gid,
+                deny_world_writable: rule.deny_world_writable,
+                deny_setuid: rule.deny_setuid,
+            });
+        }
+        Ok(Self {
+            host_allow_prefixes: rules.iter().map(|rule| rule.host_prefix.clone()).collect(),
             guest_allow_prefixes,
+            rules,
+            host_deny_patterns: parse_deny_patterns(
+                &config.host_deny_patterns,
+                "mountAllowlist.hostDenyPatterns",
+            )?,
+            guest_deny_patterns: parse_deny_patterns(
+                &config.guest_deny_patterns,
+                "mountAllowlist.guestDenyPatterns",
+            )?,
+            secret_file_prefixes: parse_prefixes(
+                &config.secret_file_prefixes,
+                "mountAllowlist.secretFilePrefixes",
+            )?,
+            secret_env_names: parse_env_names(&config.secret_env_names)?,
         })
     }
 }
 
+fn parse_allowlist_yaml<T: for<'de> Deserialize<'de>>(
+    raw: &str,
+    path: &Path,
+) -> Result<T, ErrorItem> {
+    serde_yaml::from_str(raw).map_err(|err| {
+        pol101(
+            "mountAllowlist.file",
+            format!("failed to parse allowlist file '{}': {err}", path.display()),
+        )
+    })
+}
+
+/// Identity of a validated mount source: the allowlist prefix it was accepted under, its
+/// path relative to that prefix, and the device/inode seen at validation time.
+/// The runner re-resolves `relative_path` beneath `allow_prefix` without following
+/// symlinks and refuses to mount if the device/inode no longer match.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MountSourceIdentity {
+    pub allow_prefix: String,
+    pub relative_path: String,
+    pub device: u64,
+    pub inode: u64,
+}
+
 pub struct PathSecurityEngine {
     allowlist: MountAllowlist,
 }
@@ -129,9 +311,119 @@ impl PathSecurityEngine {
         Ok(canonical)
     }
 
+    /// Record the identity of an existing canonical source under its most specific
+    /// allowlist prefix; `None` only if the source vanished since `validate_source_path`.
+    pub fn source_identity(&self, canonical: &Path) -> Option<MountSourceIdentity> {
+        let prefix = self
+            .allowlist
+            .host_allow_prefixes
+            .iter()
+            .filter(|prefix| canonical.starts_with(prefix))
+            .max_by_key(|prefix| prefix.components().count())?;
+        let relative = canonical.strip_prefix(prefix).ok()?;
+        let metadata = std::fs::symlink_metadata(canonical).ok()?;
+        let relative_path = if relative.as_os_str().is_empty() {
+            ".".to_string()
+        } else {
+            relative.to_string_lossy().to_string()
+        };
+        Some(MountSourceIdentity {
+            allow_prefix: prefix.to_string_lossy().to_string(),
+            relative_path,
+            device: metadata.dev(),
+            inode: metadata.ino(),
+        })
+    }
+
+    /// Enforce the most specific rule covering `canonical`: guest targets plus content
+    /// checks (file count/size, ownership, world-writable and setuid bits).
+    pub fn validate_source_rules(
+        &self,
+        canonical: &Path,
+        target: Option<&str>,
+        idx: usize,
+    ) -> Vec<ErrorItem> {
+        let Some((rule_idx, rule)) = self
+            .allowlist
+            .rules
+            .iter()
+            .enumerate()
+            .filter(|(_, rule)| canonical.starts_with(&rule.host_prefix))
+            .max_by_key(|(_, rule)| rule.host_prefix.components().count())
+        else {
+            return Vec::new();
+        };
+        let mut errors = Vec::new();
+        if let Some(err) = target.and_then(|target| check_guest_target(rule, rule_idx, target, idx))
+        {
+            errors.push(err);
+        }
+        errors.extend(check_source_contents(rule, rule_idx, canonical, idx));
+        errors
+    }
+
+    pub fn host_allow_prefixes(&self) -> &[PathBuf] {
+        &self.allowlist.host_allow_prefixes
+    }
+
     pub fn guest_allow_prefixes(&self) -> &[PathBuf] {
         &self.allowlist.guest_allow_prefixes
     }
+
+    pub fn host_deny_patterns(&self) -> &[String] {
+        &self.allowlist.host_deny_patterns
+    }
+
+    pub fn guest_deny_patterns(&self) -> &[String] {
+        &self.allowlist.guest_deny_patterns
+    }
+
+    pub fn secret_file_prefixes(&self) -> &[PathBuf] {
+        &self.allowlist.secret_file_prefixes
+    }
+
+    pub fn secret_env_names(&self) -> &[String] {
+        &self.allowlist.secret_env_names
+    }
+}
+
+/// Deny patterns are absolute globs; `*`, `?` and `**` are the only wildcards.
+fn parse_deny_patterns(patterns: &[String], path_label: &str) -> Result<Vec<String>, ErrorItem> {
+    let mut parsed = Vec::new();
+    for pattern in patterns {
+        let trimmed = pattern.trim();
+        if !trimmed.starts_with('/') {
+            return Err(pol101(
+                path_label,
+                format!("deny pattern must be an absolute path glob: '{trimmed}'"),
+            ));
+        }
+        if trimmed.contains(['[', ']', '{', '}']) {
+            return Err(pol101(
+                path_label,
+                format!("deny pattern supports only '*', '?' and '**' wildcards: '{trimmed}'"),
+            ));
+        }
+        parsed.push(trimmed.to_string());
+    }
+    Ok(parsed)
+}
+
+fn parse_env_names(names: &[String]) -> Result<Vec<String>, ErrorItem> {
+    names
+        .iter()
+        .map(|name| {
+            let trimmed = name.trim();
+            if is_env_name(trimmed) {
+                Ok(trimmed.to_string())
+            } else {
+                Err(pol101(
+                    "mountAllowlist.secretEnvNames",
+                    format!("secret env name must be an exact env name: '{trimmed}'"),
+                ))
+            }
+        })
+        .collect()
 }
 
 fn parse_prefixes(prefixes: &[String], path_label: &str) -> Result<Vec<PathBuf>, ErrorItem> {
@@ -153,17 +445,13 @@ fn parse_prefixes(prefixes: &[String], path_label: &str) -> Result<Vec<PathBuf>,
     Ok(parsed)
 }
 
+/// Sources must exist at validation time: only an existing source has an identity the runner
+/// can pin, and a path created later could be a symlink swapped in after the checks.
 pub(crate) fn canonicalize_mount_source(path: &Path) -> Result<PathBuf, std::io::Error> {
-    match std::fs::canonicalize(path) {
-        Ok(resolved) => Ok(resolved),
-        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
-            Ok(normalize_path_lexically(path))
-        }
-        Err(err) => Err(err),
-    }
+    std::fs::canonicalize(path)
 }
 
-fn normalize_path_lexically(path: &Path) -> PathBuf {
+pub(crate) fn normalize_path_lexically(path: &Path) -> PathBuf {
     let mut normalized = PathBuf::new();
     for component in path.components() {
         match component {
@@ -0,0 +1,213 @@
+use serde_yaml::{Mapping, Value};
+use sr_common::{ErrorItem, SR_POL_001, SR_POL_002};
+use std::path::{Path, PathBuf};
+
+use crate::policy_versions::convert_document;
+
+const EXTENDS_KEY: &str = "extends";
+/// Colon-separated directories searched for `extends: <name>` after the extending file's own
+/// directory.
+const POLICY_PATH_ENV: &str = "SAFE_RUN_POLICY_PATH";
+
+/// Resolve `extends` chains into a single policy document.
+///
+/// Merge rules, applied from the root base down to the extending file:
+/// - mappings merge key by key, the extending file winning on conflicts;
+/// - `mounts` merge by `target`: an entry replaces the base entry with the same target in
+///   place, new targets are appended in file order;
+/// - `network.egress` is the ordered union of base and extending rules;
+/// - every other list and scalar is replaced wholesale.
+///
+/// Returns `None` when the document does not use `extends`, so callers can keep parsing the
+/// original text and its error locations.
+/// Error mapping: unknown base -> `SR-POL-001`, malformed reference or cycle -> `SR-POL-002`.
+pub(crate) fn resolve_extends(
+    input: &str,
+    origin: Option<&Path>,
+) -> Result<Option<Value>, ErrorItem> {
+    let document = parse_document(input)?;
+    if !has_extends(&document) {
+        return Ok(None);
+    }
+    let base_dir = match origin.and_then(Path::parent) {
+        Some(dir) => dir.to_path_buf(),
+        None => PathBuf::from("."),
+    };
+    let mut chain = origin
+        .map(|path| vec![std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())])
+        .unwrap_or_default();
+    resolve_document(document, &base_dir, &mut chain).map(Some)
+}
+
+fn resolve_document(
+    mut document: Value,
+    base_dir: &Path,
+    chain: &mut Vec<PathBuf>,
+) -> Result<Value, ErrorItem> {
+    let Some(reference) = document
+        .as_mapping_mut()
+        .and_then(|mapping| mapping.remove(EXTENDS_KEY))
+    else {
+        return Ok(document);
+    };
+    let Some(reference) = reference.as_str().map(str::trim).filter(|r| !r.is_empty()) else {
+        return Err(extends_error(
+            SR_POL_002,
+            "extends must be a policy file path or name",
+        ));
+    };
+
+    let base_path = locate_base(reference, base_dir)?;
+    let canonical = std::fs::canonicalize(&base_path).unwrap_or(base_path);
+    if chain.contains(&canonical) {
+        let cycle = chain
+            .iter()
+            .chain(std::iter::once(&canonical))
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        return Err(extends_error(
+            SR_POL_002,
+            format!("policy extends cycle detected: {cycle}"),
+        ));
+    }
+
+    let text = std::fs::read_to_string(&canonical).map_err(|err| {
+        extends_error(
+            SR_POL_001,
+            format!(
+                "failed to read extended policy '{}': {err}",
+                canonical.display()
+            ),
+        )
+    })?;
+    let parent_dir = canonical
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("."));
+    chain.push(canonical);
+    let base = resolve_document(parse_document(&text)?, &parent_dir, chain)?;
+    chain.pop();
+
+    Ok(merge_values(base, document, ""))
+}
+
+/// References that look like paths resolve relative to the extending file; bare names are
+/// looked up as `<name>.yaml`/`<name>.yml` next to it, then in `SAFE_RUN_POLICY_PATH`.
+fn locate_base(reference: &str, base_dir: &Path) -> Result<PathBuf, ErrorItem> {
+    let is_path =
+        reference.contains('/') || reference.ends_with(".yaml") || reference.ends_with(".yml");
+    let candidates = if is_path {
+        vec![base_dir.join(reference)]
+    } else {
+        let mut dirs = vec![base_dir.to_path_buf()];
+        if let Ok(search_path) = std::env::var(POLICY_PATH_ENV) {
+            dirs.extend(
+                search_path
+                    .split(':')
+                    .map(str::trim)
+                    .filter(|dir| !dir.is_empty())
+                    .map(PathBuf::from),
+            );
+        }
+        dirs.iter()
+            .flat_map(|dir| {
+                [
+                    dir.join(format!("{reference}.yaml")),
+                    dir.join(format!("{reference}.yml")),
+                ]
+            })
+            .collect()
+    };
+    candidates
+        .into_iter()
+        .find(|candidate| candidate.is_file())
+        .ok_or_else(|| {
+            extends_error(
+                SR_POL_001,
+                format!("extended policy '{reference}' was not found"),
+            )
+        })
+}
+
+fn merge_values(base: Value, overlay: Value, path: &str) -> Value {
+    match (base, overlay) {
+        (Value::Mapping(base), Value::Mapping(overlay)) => {
+            Value::Mapping(merge_mappings(base, overlay, path))
+        }
+        (Value::Sequence(base), Value::Sequence(overlay)) if path == "mounts" => {
+            Value::Sequence(merge_by_target(base, overlay))
+        }
+        (Value::Sequence(base), Value::Sequence(overlay)) if path == "network.egress" => {
+            Value::Sequence(union(base, overlay))
+        }
+        (_, overlay) => overlay,
+    }
+}
+
+fn merge_mappings(mut base: Mapping, overlay: Mapping, path: &str) -> Mapping {
+    for (key, value) in overlay {
+        let child_path = match key.as_str() {
+            Some(name) if path.is_empty() => name.to_string(),
+            Some(name) => format!("{path}.{name}"),
+            None => String::new(),
+        };
+        let merged = match base.remove(&key) {
+            Some(existing) => merge_values(existing, value, &child_path),
+            None => value,
+        };
+        base.insert(key, merged);
+    }
+    base
+}
+
+fn merge_by_target(mut base: Vec<Value>, overlay: Vec<Value>) -> Vec<Value> {
+    for entry in overlay {
+        let existing = mount_target(&entry).and_then(|target| {
+            base.iter()
+                .position(|item| mount_target(item) == Some(target))
+        });
+        match existing {
+            Some(idx) => base[idx] = entry,
+            None => base.push(entry),
+        }
+    }
+    base
+}
+
+fn union(mut base: Vec<Value>, overlay: Vec<Value>) -> Vec<Value> {
+    for entry in overlay {
+        if !base.contains(&entry) {
+            base.push(entry);
+        }
+    }
+    base
+}
+
+fn mount_target(entry: &Value) -> Option<&str> {
+    entry.get("target").and_then(Value::as_str)
+}
+
+fn has_extends(document: &Value) -> bool {
+    document
+        .as_mapping()
+        .is_some_and(|mapping| mapping.contains_key(EXTENDS_KEY))
+}
+
+/// Parse one document and convert it to the latest key layout, so bases written for older
+/// `apiVersion`s merge with newer extending files.
+fn parse_document(input: &str) -> Result<Value, ErrorItem> {
+    let mut document = serde_yaml::from_str::<Value>(input).map_err(|err| {
+        ErrorItem::new(
+            SR_POL_002,
+            "policy",
+            format!("failed to parse policy: {err}"),
+        )
+    })?;
+    convert_document(&mut document)?;
+    Ok(document)
+}
+
+fn extends_error(code: &str, message: impl Into<String>) -> ErrorItem {
+    ErrorItem::new(code, EXTENDS_KEY, message)
+}
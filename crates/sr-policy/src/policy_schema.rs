@@ -0,0 +1,63 @@
+use serde_json::{json, Value};
+
+use crate::diagnostics::MOUNT_KEY_ALIASES;
+use crate::{PolicyApiVersion, PolicySpec};
+
+const SCHEMA_ID: &str = "https://safe-run.dev/schemas/policy.v1beta1.json";
+
+/// JSON Schema (draft-07) for policy documents, derived from `PolicySpec` so it tracks the
+/// strict parser: unknown keys are rejected everywhere. Parser behaviour that lives outside
+/// the struct fields is patched in on top: the `extends` reference (documents using it may
+/// omit top-level sections), the supported `apiVersion`s, v1alpha1 mount aliases, numeric
+/// `cpu.max`, and scalar parameter defaults.
+pub fn policy_json_schema() -> Value {
+    let schema = schemars::schema_for!(PolicySpec);
+    let mut value = serde_json::to_value(schema).unwrap_or_default();
+    value["$id"] = json!(SCHEMA_ID);
+    value["properties"]["apiVersion"]["enum"] = json!(PolicyApiVersion::ALL
+        .iter()
+        .map(|version| version.as_str())
+        .collect::<Vec<_>>());
+
+    value["properties"]["extends"] = json!({
+        "description": "Base policy file path or name searched in SAFE_RUN_POLICY_PATH.",
+        "type": "string"
+    });
+    if let Some(required) = value
+        .as_object_mut()
+        .and_then(|root| root.remove("required"))
+    {
+        value["if"] = json!({"not": {"required": ["extends"]}});
+        value["then"] = json!({"required": required});
+    }
+
+    let mount = &mut value["definitions"]["Mount"];
+    for (alias, field) in MOUNT_KEY_ALIASES {
+        let mut field_schema = mount["properties"][*field].clone();
+        field_schema["description"] = json!(format!(
+            "v1alpha1 spelling of `{field}`; rejected in {}.",
+            PolicyApiVersion::LATEST.as_str()
+        ));
+        mount["properties"][*alias] = field_schema;
+        let required = mount["required"].as_array_mut();
+        if let Some(required) = required.filter(|list| list.contains(&json!(field))) {
+            required.retain(|name| name != field);
+            mount["anyOf"] = json!([{"required": [field]}, {"required": [alias]}]);
+        }
+    }
+    if let Some(object) = mount.as_object_mut() {
+        if object
+            .get("required")
+            .and_then(Value::as_array)
+            .is_some_and(Vec::is_empty)
+        {
+            object.remove("required");
+        }
+    }
+
+    value["definitions"]["Cpu"]["properties"]["max"]["type"] = json!(["string", "number"]);
+    value["definitions"]["ParameterSpec"]["properties"]["default"] = json!({
+        "type": ["string", "integer", "number", "boolean", "null"]
+    });
+    value
+}
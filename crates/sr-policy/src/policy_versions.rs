@@ -0,0 +1,251 @@
+use serde::Serialize;
+use serde_yaml::Value;
+use sr_common::{ErrorItem, SR_POL_001, SR_POL_002};
+
+use crate::diagnostics::{version_locations, MOUNT_KEY_ALIASES};
+
+pub const POLICY_API_V1ALPHA1: &str = "policy.safe-run.dev/v1alpha1";
+pub const POLICY_API_V1BETA1: &str = "policy.safe-run.dev/v1beta1";
+
+const API_VERSION_KEY: &str = "apiVersion";
+
+/// Policy `apiVersion`s this build reads. Older versions are converted to `LATEST` on load;
+/// validated policies always carry `LATEST`.
+///
+/// v1beta1 changes from v1alpha1:
+/// - mount keys `hostPath`, `guestPath`, `readOnly` and `maxSize` are no longer accepted;
+///   use `source`, `target`, `read_only` and `max_size`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PolicyApiVersion {
+    V1alpha1,
+    V1beta1,
+}
+
+impl PolicyApiVersion {
+    pub const LATEST: PolicyApiVersion = PolicyApiVersion::V1beta1;
+    pub const ALL: [PolicyApiVersion; 2] = [PolicyApiVersion::V1alpha1, PolicyApiVersion::V1beta1];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|version| version.as_str() == value.trim())
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PolicyApiVersion::V1alpha1 => POLICY_API_V1ALPHA1,
+            PolicyApiVersion::V1beta1 => POLICY_API_V1BETA1,
+        }
+    }
+
+    pub fn is_latest(self) -> bool {
+        self == Self::LATEST
+    }
+}
+
+/// Outcome of `migrate_policy`. `output` is the input text with only the migrated tokens
+/// replaced, so comments and layout survive.
+#[derive(Debug, Clone, Serialize)]
+pub struct PolicyMigration {
+    /// `apiVersion` found in the file; `None` for `extends` files that inherit it.
+    pub from: Option<String>,
+    pub to: String,
+    /// One entry per rewrite, e.g. `mounts[0].hostPath -> mounts[0].source`.
+    pub changes: Vec<String>,
+    #[serde(skip)]
+    pub output: String,
+}
+
+impl PolicyMigration {
+    pub fn changed(&self) -> bool {
+        !self.changes.is_empty()
+    }
+}
+
+/// Rewrite a policy document to `PolicyApiVersion::LATEST`. Only the file itself is migrated;
+/// files it `extends` are migrated on their own.
+/// Error mapping: unreadable YAML or unsupported `apiVersion` -> `SR-POL-002`, missing
+/// `apiVersion` without `extends` -> `SR-POL-001`.
+pub fn migrate_policy(input: &str) -> Result<PolicyMigration, ErrorItem> {
+    let mut document = serde_yaml::from_str::<Value>(input).map_err(|err| {
+        ErrorItem::new(
+            SR_POL_002,
+            "policy",
+            format!("failed to parse policy: {err}"),
+        )
+    })?;
+    let from = document_api_version(&document)?;
+    if from.is_none() && document.get("extends").is_none() {
+        return Err(ErrorItem::new(
+            SR_POL_001,
+            API_VERSION_KEY,
+            "apiVersion is required",
+        ));
+    }
+
+    let locations = version_locations(input);
+    let mut edits = Vec::new();
+    let mut changes = Vec::new();
+    if let Some(version) = from.filter(|version| !version.is_latest()) {
+        let (line, column) = locations
+            .api_version
+            .ok_or_else(|| migrate_error(API_VERSION_KEY))?;
+        edits.push((
+            line,
+            column,
+            version.as_str(),
+            PolicyApiVersion::LATEST.as_str(),
+        ));
+        changes.push(format!(
+            "apiVersion: {} -> {}",
+            version.as_str(),
+            PolicyApiVersion::LATEST.as_str()
+        ));
+    }
+    for (path, field, line, column) in &locations.aliased_keys {
+        let (mount, alias) = path.rsplit_once('.').unwrap_or(("", path));
+        edits.push((*line, *column, alias, *field));
+        changes.push(format!("{path} -> {mount}.{field}"));
+    }
+
+    // Later edits first, so earlier offsets stay valid.
+    edits.sort_by_key(|edit| std::cmp::Reverse((edit.0, edit.1)));
+    let mut output = input.to_string();
+    for (line, column, old, new) in edits {
+        let offset = token_offset(&output, line, column, old)
+            .ok_or_else(|| migrate_error(&format!("{old} at line {line} column {column}")))?;
+        output.replace_range(offset..offset + old.len(), new);
+    }
+
+    // The rewrite must read back as the converted document and nothing else.
+    let mut migrated = serde_yaml::from_str::<Value>(&output)
+        .map_err(|err| migrate_error(&format!("rewritten policy: {err}")))?;
+    rename_mount_aliases(&mut document);
+    if let (Some(_), Some(mapping)) = (from, document.as_mapping_mut()) {
+        mapping.insert(
+            Value::from(API_VERSION_KEY),
+            Value::from(PolicyApiVersion::LATEST.as_str()),
+        );
+    }
+    rename_mount_aliases(&mut migrated);
+    if migrated != document {
+        return Err(migrate_error("rewritten policy"));
+    }
+
+    Ok(PolicyMigration {
+        from: from.map(|version| version.as_str().to_string()),
+        to: PolicyApiVersion::LATEST.as_str().to_string(),
+        changes,
+        output,
+    })
+}
+
+/// Convert one parsed policy document, base or extending file, to the v1beta1 key layout
+/// before it is merged or deserialized. Documents without `apiVersion` inherit it through
+/// `extends` and are read leniently.
+/// Error mapping: v1alpha1 mount keys in a v1beta1 document -> `SR-POL-002`.
+pub(crate) fn convert_document(document: &mut Value) -> Result<(), ErrorItem> {
+    if document_api_version(document).ok().flatten() == Some(PolicyApiVersion::V1beta1) {
+        if let Some((idx, alias, field)) = mount_items(document).find_map(|(idx, mount)| {
+            MOUNT_KEY_ALIASES
+                .iter()
+                .find(|(alias, _)| mount.contains_key(*alias))
+                .map(|(alias, field)| (idx, *alias, *field))
+        }) {
+            return Err(ErrorItem::new(
+                SR_POL_002,
+                format!("mounts[{idx}].{alias}"),
+                format!(
+                    "mount key '{alias}' is not accepted in {POLICY_API_V1BETA1}; use \
+                     '{field}' (safe-run policy migrate rewrites it)"
+                ),
+            ));
+        }
+    }
+    rename_mount_aliases(document);
+    Ok(())
+}
+
+/// Rename v1alpha1 mount keys to their field names. Entries spelling both are left for
+/// deserialization to report as duplicates.
+fn rename_mount_aliases(document: &mut Value) {
+    let Some(mounts) = document.get_mut("mounts").and_then(Value::as_sequence_mut) else {
+        return;
+    };
+    for mount in mounts.iter_mut().filter_map(Value::as_mapping_mut) {
+        for (alias, field) in MOUNT_KEY_ALIASES {
+            if mount.contains_key(*field) {
+                continue;
+            }
+            if let Some(value) = mount.remove(*alias) {
+                mount.insert(Value::from(*field), value);
+            }
+        }
+    }
+}
+
+fn mount_items(document: &Value) -> impl Iterator<Item = (usize, &serde_yaml::Mapping)> {
+    document
+        .get("mounts")
+        .and_then(Value::as_sequence)
+        .into_iter()
+        .flatten()
+        .enumerate()
+        .filter_map(|(idx, mount)| mount.as_mapping().map(|mount| (idx, mount)))
+}
+
+/// `None` when the document has no `apiVersion`; unsupported values are an error.
+fn document_api_version(document: &Value) -> Result<Option<PolicyApiVersion>, ErrorItem> {
+    let Some(value) = document.get(API_VERSION_KEY) else {
+        return Ok(None);
+    };
+    value
+        .as_str()
+        .and_then(PolicyApiVersion::parse)
+        .map(Some)
+        .ok_or_else(unsupported_api_version)
+}
+
+pub(crate) fn unsupported_api_version() -> ErrorItem {
+    let supported = PolicyApiVersion::ALL
+        .iter()
+        .map(|version| version.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    ErrorItem::new(
+        SR_POL_002,
+        API_VERSION_KEY,
+        format!("apiVersion must be one of {supported}"),
+    )
+}
+
+/// Byte offset of `token` at a 1-based line/column, allowing for a quote before it.
+fn token_offset(text: &str, line: usize, column: usize, token: &str) -> Option<usize> {
+    let line_start = if line <= 1 {
+        0
+    } else {
+        text.match_indices('\n').nth(line - 2)?.0 + 1
+    };
+    let line_text = &text[line_start..];
+    let start = line_start
+        + line_text
+            .char_indices()
+            .nth(column.checked_sub(1)?)
+            .map(|(idx, _)| idx)?;
+    let rest = &text[start..];
+    if rest.starts_with(token) {
+        Some(start)
+    } else if rest.starts_with(['"', '\'']) && rest[1..].starts_with(token) {
+        Some(start + 1)
+    } else {
+        None
+    }
+}
+
+fn migrate_error(what: &str) -> ErrorItem {
+    ErrorItem::new(
+        SR_POL_002,
+        "policy",
+        format!("failed to migrate policy: could not rewrite {what}"),
+    )
+}
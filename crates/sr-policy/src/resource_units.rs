@@ -0,0 +1,142 @@
+use serde::{Deserialize, Deserializer};
+
+/// cgroup v2 default period; CPU counts and percentages become a quota over it.
+const CPU_PERIOD_US: u64 = 100_000;
+/// Smallest quota the kernel accepts for `cpu.max`.
+const CPU_MIN_QUOTA_US: u64 = 1_000;
+const MIB: u128 = 1024 * 1024;
+const MEMINFO_PATH: &str = "/proc/meminfo";
+
+/// `memory.max` suffixes and their size in bytes; `M`/`MB` and `G`/`GB` are decimal.
+/// Two-letter suffixes come first so `MB` is not read as `M`.
+const MEMORY_UNITS: &[(&str, u128)] = &[
+    ("Ki", 1024),
+    ("Mi", MIB),
+    ("Gi", 1024 * MIB),
+    ("MB", 1_000_000),
+    ("GB", 1_000_000_000),
+    ("M", 1_000_000),
+    ("G", 1_000_000_000),
+];
+
+const CPU_FORMAT: &str =
+    "cpu.max must be '<quota> <period>', a CPU count like 1.5, or a percent like 50%";
+const MEMORY_FORMAT: &str = "memory.max must be a size like 256Mi, 2Gi, 512M or 1GB";
+
+/// Canonical `cpu.max`. Raw `<quota> <period>` is kept (single-spaced); a CPU count (`1.5`)
+/// or a percent of one CPU (`50%`) becomes `<quota> 100000`, and a bare `max` gets the
+/// default period.
+pub(crate) fn normalize_cpu_max(value: &str) -> Result<String, String> {
+    let value = value.trim();
+    if let Some((quota, period)) = value.split_once(char::is_whitespace) {
+        let is_part = |part: &str| {
+            part == "max" || (!part.is_empty() && part.chars().all(|ch| ch.is_ascii_digit()))
+        };
+        let period = period.trim();
+        if !is_part(quota) || !is_part(period) {
+            return Err(CPU_FORMAT.to_string());
+        }
+        return Ok(format!("{quota} {period}"));
+    }
+    if value == "max" {
+        return Ok(format!("max {CPU_PERIOD_US}"));
+    }
+    let (number, scale) = match value.strip_suffix('%') {
+        Some(percent) => (percent, CPU_PERIOD_US / 100),
+        None => (value, CPU_PERIOD_US),
+    };
+    let (whole, frac) = parse_decimal(number).ok_or_else(|| CPU_FORMAT.to_string())?;
+    let quota = scaled(whole, frac, u128::from(scale), true);
+    if quota < u128::from(CPU_MIN_QUOTA_US) {
+        return Err(format!(
+            "cpu.max {value} is below the minimum of 1% of one CPU"
+        ));
+    }
+    let quota = u64::try_from(quota).map_err(|_| format!("cpu.max {value} is too large"))?;
+    Ok(format!("{quota} {CPU_PERIOD_US}"))
+}
+
+/// Canonical `memory.max`: whole MiB, rounded down, written as `Gi` when it divides evenly.
+/// Values that round down to 0 MiB are rejected.
+pub(crate) fn normalize_memory_max(value: &str) -> Result<String, String> {
+    let mib = memory_mib(value).ok_or_else(|| MEMORY_FORMAT.to_string())?;
+    if mib == 0 {
+        return Err(format!("memory.max {} rounds down to 0 MiB", value.trim()));
+    }
+    if mib % 1024 == 0 {
+        Ok(format!("{}Gi", mib / 1024))
+    } else {
+        Ok(format!("{mib}Mi"))
+    }
+}
+
+/// Size in whole MiB (rounded down) for any accepted `memory.max` form; `None` when the
+/// value is malformed or overflows.
+pub fn memory_mib(value: &str) -> Option<u64> {
+    u64::try_from(memory_bytes(value)? / MIB).ok()
+}
+
+/// Size in KiB (rounded down) for any accepted `memory.max` form.
+pub(crate) fn memory_kib(value: &str) -> Option<u64> {
+    u64::try_from(memory_bytes(value)? / 1024).ok()
+}
+
+fn memory_bytes(value: &str) -> Option<u128> {
+    let value = value.trim();
+    let (number, unit) = MEMORY_UNITS
+        .iter()
+        .find_map(|(suffix, unit)| value.strip_suffix(suffix).map(|number| (number, *unit)))?;
+    let (whole, frac) = parse_decimal(number)?;
+    Some(scaled(whole, frac, unit, false))
+}
+
+/// Host `MemTotal` in MiB, when `/proc/meminfo` is readable.
+pub(crate) fn host_memory_mib() -> Option<u64> {
+    let meminfo = std::fs::read_to_string(MEMINFO_PATH).ok()?;
+    let kib = meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemTotal:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kib / 1024)
+}
+
+/// Accept YAML numbers (`cpu.max: 1.5`) as well as strings.
+pub(crate) fn number_or_string<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match serde_yaml::Value::deserialize(deserializer)? {
+        serde_yaml::Value::String(text) => Ok(text),
+        serde_yaml::Value::Number(number) => Ok(number.to_string()),
+        _ => Err(serde::de::Error::custom("expected a string or number")),
+    }
+}
+
+/// Split `12.375` into `(12, "375")`; digits only, at most nine fractional digits.
+fn parse_decimal(number: &str) -> Option<(u128, &str)> {
+    let (whole, frac) = number.split_once('.').unwrap_or((number, ""));
+    let digits = |part: &str| part.chars().all(|ch| ch.is_ascii_digit());
+    if whole.is_empty() || !digits(whole) || !digits(frac) || frac.len() > 9 {
+        return None;
+    }
+    if number.ends_with('.') {
+        return None;
+    }
+    Some((whole.parse().ok()?, frac))
+}
+
+/// `whole.frac * unit` in integer arithmetic, rounded to nearest or down.
+fn scaled(whole: u128, frac: &str, unit: u128, round: bool) -> u128 {
+    let denominator = 10u128.pow(frac.len() as u32);
+    let numerator = frac.parse::<u128>().unwrap_or(0) * unit;
+    let frac_part = if round {
+        (numerator + denominator / 2) / denominator
+    } else {
+        numerator / denominator
+    };
+    whole.saturating_mul(unit).saturating_add(frac_part)
+}
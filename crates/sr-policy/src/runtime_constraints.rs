@@ -0,0 +1,107 @@
+use std::path::{Component, Path};
+
+use crate::Runtime;
+use sr_common::{ErrorItem, SR_POL_002};
+
+/// Validate runtime execution context (env, passthrough names, cwd, user) and return
+/// field-oriented errors.
+pub fn validate_runtime_constraints(runtime: &Runtime) -> Vec<ErrorItem> {
+    let mut errors = Vec::new();
+    for (name, value) in &runtime.env {
+        if !is_env_name(name) {
+            errors.push(pol002(
+                format!("runtime.env.{name}"),
+                "env names must match [A-Za-z_][A-Za-z0-9_]*",
+            ));
+        }
+        if value.contains('\0') {
+            errors.push(pol002(
+                format!("runtime.env.{name}"),
+                "env values cannot contain NUL bytes",
+            ));
+        }
+    }
+
+    for (idx, name) in runtime.env_passthrough.iter().enumerate() {
+        let path = format!("runtime.envPassthrough[{idx}]");
+        if !is_env_name(name) {
+            errors.push(pol002(
+                path,
+                "passthrough entries must be exact env names; wildcards are not allowed",
+            ));
+        } else if runtime.env.contains_key(name) {
+            errors.push(pol002(
+                path,
+                format!("'{name}' is already set in runtime.env"),
+            ));
+        } else if runtime.env_passthrough[..idx].contains(name) {
+            errors.push(pol002(path, format!("'{name}' is listed more than once")));
+        }
+    }
+
+    if let Some(cwd) = runtime.cwd.as_deref() {
+        let cwd_path = Path::new(cwd);
+        if !cwd_path.is_absolute()
+            || cwd_path
+                .components()
+                .any(|component| matches!(component, Component::ParentDir))
+        {
+            errors.push(pol002(
+                "runtime.cwd",
+                "runtime.cwd must be an absolute guest path without '..'",
+            ));
+        }
+    }
+
+    if let Some(pin) = runtime.command_sha256.as_deref() {
+        if !is_sha256(pin) {
+            errors.push(pol002(
+                "runtime.commandSha256",
+                "commandSha256 must be 64 hex characters, optionally prefixed with 'sha256:'",
+            ));
+        }
+        if !runtime.command.trim().starts_with('/') {
+            errors.push(pol002(
+                "runtime.command",
+                "a pinned command must be an absolute path in the rootfs",
+            ));
+        } else if !is_plain_guest_path(runtime.command.trim()) {
+            errors.push(pol002(
+                "runtime.command",
+                "a pinned command path cannot contain quotes, backslashes, whitespace or control characters",
+            ));
+        }
+    }
+    errors
+}
+
+pub(crate) fn is_sha256(value: &str) -> bool {
+    let hex = value.trim().strip_prefix("sha256:").unwrap_or(value.trim());
+    hex.len() == 64 && hex.chars().all(|ch| ch.is_ascii_hexdigit())
+}
+
+/// `sha256:<lowercase hex>` form of a digest accepted by `is_sha256`.
+pub(crate) fn normalize_sha256(value: &str) -> String {
+    let hex = value.trim().strip_prefix("sha256:").unwrap_or(value.trim());
+    format!("sha256:{}", hex.to_ascii_lowercase())
+}
+
+/// Pinned commands are read through `debugfs -R "cat <path>"`, whose request parser splits
+/// on whitespace and interprets quotes and backslashes.
+fn is_plain_guest_path(path: &str) -> bool {
+    !path
+        .chars()
+        .any(|ch| matches!(ch, '"' | '\\') || ch.is_whitespace() || ch.is_control())
+}
+
+pub(crate) fn is_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first == '_' || first.is_ascii_alphabetic())
+        && chars.all(|ch| ch == '_' || ch.is_ascii_alphanumeric())
+}
+
+fn pol002(path: impl Into<String>, message: impl Into<String>) -> ErrorItem {
+    ErrorItem::new(SR_POL_002, path, message)
+}
@@ -0,0 +1,149 @@
+use std::path::Path;
+
+use crate::mount_constraints::MountConstraints;
+use crate::path_security::PathSecurityEngine;
+use crate::runtime_constraints::is_env_name;
+use crate::{Runtime, Secret};
+use sr_common::{ErrorItem, SR_POL_001, SR_POL_002, SR_POL_003, SR_POL_101};
+
+/// Validate `secrets` references and return field-oriented errors.
+/// A host variable used as a secret source cannot also be passed through into the guest env,
+/// because the command payload is hashed into the report.
+/// Sources are operator-controlled: `fromEnv` must be listed in the allowlist's
+/// `secretEnvNames`, and `fromFile` must be an existing regular file under
+/// `secretFilePrefixes` outside the host denylist. Accepted files are rewritten to their
+/// canonical path so the runner reads exactly what was checked. Without a loaded allowlist
+/// (its error is already reported) only the shape checks run.
+/// Error mapping: shape -> `SR-POL-001/002/003`, allowlist or denylist -> `SR-POL-101`.
+pub(crate) fn validate_secret_constraints(
+    secrets: &mut [Secret],
+    runtime: &Runtime,
+    engine: Option<&PathSecurityEngine>,
+    constraints: Option<&MountConstraints>,
+) -> Vec<ErrorItem> {
+    let mut errors = Vec::new();
+    for idx in 0..secrets.len() {
+        let (earlier, rest) = secrets.split_at_mut(idx);
+        let secret = &mut rest[0];
+        if !is_env_name(&secret.name) {
+            errors.push(ErrorItem::new(
+                SR_POL_002,
+                secret_field_path(idx, "name"),
+                "secret names must match [A-Za-z_][A-Za-z0-9_]*",
+            ));
+        } else if earlier.iter().any(|prev| prev.name == secret.name) {
+            errors.push(ErrorItem::new(
+                SR_POL_002,
+                secret_field_path(idx, "name"),
+                format!("secret '{}' is declared more than once", secret.name),
+            ));
+        }
+
+        match (secret.from_env.clone(), secret.from_file.clone()) {
+            (None, None) => errors.push(ErrorItem::new(
+                SR_POL_001,
+                format!("secrets[{idx}]"),
+                "secret requires one of fromEnv or fromFile",
+            )),
+            (Some(_), Some(_)) => errors.push(ErrorItem::new(
+                SR_POL_002,
+                format!("secrets[{idx}]"),
+                "secret cannot set both fromEnv and fromFile",
+            )),
+            (Some(var), None) => {
+                if !is_env_name(&var) {
+                    errors.push(ErrorItem::new(
+                        SR_POL_002,
+                        secret_field_path(idx, "fromEnv"),
+                        "fromEnv must be an exact env name",
+                    ));
+                } else if runtime.env_passthrough.contains(&var) {
+                    errors.push(ErrorItem::new(
+                        SR_POL_003,
+                        secret_field_path(idx, "fromEnv"),
+                        format!("'{var}' is also listed in runtime.envPassthrough"),
+                    ));
+                } else if engine.is_some_and(|engine| !engine.secret_env_names().contains(&var)) {
+                    errors.push(ErrorItem::new(
+                        SR_POL_101,
+                        secret_field_path(idx, "fromEnv"),
+                        format!("'{var}' is not listed in the allowlist secretEnvNames"),
+                    ));
+                }
+            }
+            (None, Some(file)) => {
+                if !Path::new(&file).is_absolute() {
+                    errors.push(ErrorItem::new(
+                        SR_POL_002,
+                        secret_field_path(idx, "fromFile"),
+                        "fromFile must be an absolute host path",
+                    ));
+                } else if let Some(engine) = engine {
+                    match check_secret_file(&file, engine, constraints, idx) {
+                        Ok(canonical) => secret.from_file = Some(canonical),
+                        Err(err) => errors.push(err),
+                    }
+                }
+            }
+        }
+    }
+    errors
+}
+
+fn check_secret_file(
+    file: &str,
+    engine: &PathSecurityEngine,
+    constraints: Option<&MountConstraints>,
+    idx: usize,
+) -> Result<String, ErrorItem> {
+    let path = secret_field_path(idx, "fromFile");
+    let requested = Path::new(file);
+    let canonical = std::fs::canonicalize(requested).map_err(|err| {
+        ErrorItem::new(
+            SR_POL_101,
+            &path,
+            format!("failed to resolve secret file '{file}': {err}"),
+        )
+    })?;
+    if !canonical.is_file() {
+        return Err(ErrorItem::new(
+            SR_POL_101,
+            path,
+            format!(
+                "secret file '{}' is not a regular file",
+                canonical.display()
+            ),
+        ));
+    }
+    if !engine
+        .secret_file_prefixes()
+        .iter()
+        .any(|prefix| canonical.starts_with(prefix))
+    {
+        return Err(ErrorItem::new(
+            SR_POL_101,
+            path,
+            format!(
+                "secret file '{}' is outside the allowlist secretFilePrefixes",
+                canonical.display()
+            ),
+        ));
+    }
+    if let Some(pattern) =
+        constraints.and_then(|constraints| constraints.host_deny_hit(requested, &canonical))
+    {
+        return Err(ErrorItem::new(
+            SR_POL_101,
+            path,
+            format!(
+                "secret file '{}' is within a sensitive host path '{pattern}'",
+                canonical.display()
+            ),
+        ));
+    }
+    Ok(canonical.to_string_lossy().to_string())
+}
+
+fn secret_field_path(idx: usize, field: &str) -> String {
+    format!("secrets[{idx}].{field}")
+}
@@ -0,0 +1,208 @@
+use sha2::{Digest, Sha256};
+use sr_common::{
+    SR_ADM_001, SR_ADM_101, SR_ADM_102, SR_ADM_201, SR_ADM_301, SR_ADM_302, SR_ADM_401,
+};
+mod common;
+
+use sr_policy::{
+    load_policy_from_path, validate_policy_with_admission, AdmissionConstraints, PolicySpec,
+    ValidationResult,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn repo_file(path: &str) -> String {
+    let mut p = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    p.push("../../");
+    p.push(path);
+    p.to_string_lossy().to_string()
+}
+
+fn unique_temp_dir(label: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let mut dir = std::env::temp_dir();
+    dir.push(format!(
+        "safe-run-vibe-{label}-{}-{nanos}",
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir).expect("create temp dir");
+    dir
+}
+
+fn constraints() -> AdmissionConstraints {
+    AdmissionConstraints::from_file(Path::new(&repo_file(
+        "tests/admission_constraints/constraints.yaml",
+    )))
+    .expect("load admission constraints")
+}
+
+fn validate(mut policy: PolicySpec, admission: Option<&AdmissionConstraints>) -> ValidationResult {
+    let allowlist = common::relocate_sources(&mut policy, None, "admission-sources");
+    let result = validate_policy_with_admission(policy, Some(&allowlist), admission);
+    common::remove_temp_root(&allowlist);
+    result
+}
+
+#[test]
+fn admitted_policy_records_constraints_hash_and_command_digest() {
+    let policy = load_policy_from_path(&repo_file("tests/admission_constraints/admitted.yaml"))
+        .expect("load policy");
+    let result = validate(policy, Some(&constraints()));
+
+    assert!(result.valid, "unexpected errors: {:?}", result.errors);
+    let admission = result.admission.expect("admission summary");
+    let raw = fs::read(repo_file("tests/admission_constraints/constraints.yaml"))
+        .expect("read constraints");
+    assert_eq!(
+        admission.hash,
+        format!("sha256:{}", hex::encode(Sha256::digest(&raw)))
+    );
+    assert_eq!(
+        admission.command_sha256.as_deref(),
+        Some("sha256:9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08")
+    );
+}
+
+#[test]
+fn violating_policy_reports_each_admission_code_at_its_field() {
+    let policy = load_policy_from_path(&repo_file("tests/admission_constraints/violating.yaml"))
+        .expect("load policy");
+    let result = validate(policy, Some(&constraints()));
+
+    assert!(!result.valid);
+    assert!(result.normalized_policy.is_none());
+    let found = result
+        .errors
+        .iter()
+        .map(|err| (err.code.as_str(), err.path.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        [
+            (SR_ADM_101, "resources.memory.max"),
+            (SR_ADM_102, "resources.cpu.max"),
+            (SR_ADM_201, "network.egress[0].cidr"),
+            (SR_ADM_201, "network.egress[1].host"),
+            (SR_ADM_301, "runtime.command"),
+            (SR_ADM_401, "mounts[0].target"),
+        ]
+    );
+}
+
+#[test]
+fn forbidden_mount_targets_fold_parent_components() {
+    for target in ["/data/out/../secrets", "/data/out/../secrets/../keys/tls"] {
+        let mut policy =
+            load_policy_from_path(&repo_file("tests/admission_constraints/admitted.yaml"))
+                .expect("load policy");
+        policy.mounts[0].target = target.to_string();
+        let result = validate(policy, Some(&constraints()));
+
+        assert!(!result.valid, "{target} must not be admitted");
+        assert!(
+            result
+                .errors
+                .iter()
+                .any(|err| err.code == SR_ADM_401 && err.path == "mounts[0].target"),
+            "{target}: {:?}",
+            result.errors
+        );
+    }
+}
+
+#[test]
+fn unlimited_cpu_quota_exceeds_any_cpu_cap() {
+    let mut policy = load_policy_from_path(&repo_file("tests/admission_constraints/admitted.yaml"))
+        .expect("load policy");
+    policy.resources.cpu.max = "max 100000".to_string();
+    let result = validate(policy, Some(&constraints()));
+
+    assert!(!result.valid);
+    assert!(result
+        .errors
+        .iter()
+        .any(|err| err.code == SR_ADM_102 && err.message.contains("unlimited")));
+}
+
+#[test]
+fn validation_without_constraints_has_no_admission_summary() {
+    let policy = load_policy_from_path(&repo_file("tests/admission_constraints/violating.yaml"))
+        .expect("load policy");
+    let result = validate(policy, None);
+
+    assert!(result.valid, "unexpected errors: {:?}", result.errors);
+    assert!(result.admission.is_none());
+}
+
+#[test]
+fn malformed_constraints_files_are_rejected() {
+    let dir = unique_temp_dir("admission-config");
+    let cases = [
+        ("schemaVersion: safe-run.admission/v0\n", "admission.schemaVersion"),
+        (
+            "schemaVersion: safe-run.admission/v1\nmaxMemory: lots\n",
+            "admission.maxMemory",
+        ),
+        (
+            "schemaVersion: safe-run.admission/v1\nallowedEgress:\n  cidrs: [10.0.0.0/33]\n",
+            "admission.allowedEgress.cidrs[0]",
+        ),
+        (
+            "schemaVersion: safe-run.admission/v1\nallowedCommands:\n  - path: bin/echo\n",
+            "admission.allowedCommands[0].path",
+        ),
+        (
+            "schemaVersion: safe-run.admission/v1\nallowedCommands:\n  - path: /bin/echo\n    sha256: abc\n",
+            "admission.allowedCommands[0].sha256",
+        ),
+        (
+            "schemaVersion: safe-run.admission/v1\nforbiddenMountTargets: [etc]\n",
+            "admission.forbiddenMountTargets[0]",
+        ),
+        (
+            "schemaVersion: safe-run.admission/v1\nmaxMemroy: 2Gi\n",
+            "admission.file",
+        ),
+    ];
+    for (idx, (content, path)) in cases.iter().enumerate() {
+        let file = dir.join(format!("constraints-{idx}.yaml"));
+        fs::write(&file, content).expect("write constraints");
+        let err = AdmissionConstraints::from_file(&file).expect_err("constraints must be rejected");
+        assert_eq!(err.code, SR_ADM_001);
+        assert_eq!(err.path, *path, "case {idx}");
+    }
+    let missing = AdmissionConstraints::from_file(&dir.join("missing.yaml"))
+        .expect_err("missing file must be rejected");
+    assert_eq!(missing.code, SR_ADM_001);
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn approved_command_hash_becomes_the_runtime_pin() {
+    let policy = load_policy_from_path(&repo_file("tests/admission_constraints/admitted.yaml"))
+        .expect("load policy");
+    let result = validate(policy, Some(&constraints()));
+
+    let normalized = result.normalized_policy.expect("normalized policy");
+    assert_eq!(
+        normalized.runtime.command_sha256.as_deref(),
+        Some("sha256:9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08")
+    );
+}
+
+#[test]
+fn pin_that_differs_from_the_approved_hash_is_rejected() {
+    let mut policy = load_policy_from_path(&repo_file("tests/admission_constraints/admitted.yaml"))
+        .expect("load policy");
+    policy.runtime.command_sha256 = Some(format!("sha256:{}", "0".repeat(64)));
+    let result = validate(policy, Some(&constraints()));
+
+    assert!(!result.valid);
+    assert_eq!(result.errors.len(), 1);
+    assert_eq!(result.errors[0].code, SR_ADM_302);
+    assert_eq!(result.errors[0].path, "runtime.commandSha256");
+}
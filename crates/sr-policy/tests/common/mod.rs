@@ -0,0 +1,63 @@
+#![allow(dead_code)]
+
+use sr_policy::PolicySpec;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Host prefix the fixtures are written against; it does not exist on test hosts.
+pub const FIXTURE_HOST_PREFIX: &str = "/var/lib/safe-run";
+
+const DEFAULT_ALLOWLIST: &str = "schemaVersion: safe-run.mount-allowlist/v1\nhostAllowPrefixes:\n  - /var/lib/safe-run\nguestAllowPrefixes:\n  - /data\n";
+
+pub fn repo_file(path: &str) -> String {
+    let mut p = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    p.push("../../");
+    p.push(path);
+    p.to_string_lossy().to_string()
+}
+
+pub fn unique_temp_dir(label: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let mut dir = std::env::temp_dir();
+    dir.push(format!(
+        "safe-run-vibe-{label}-{}-{nanos}",
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir).expect("create temp dir");
+    dir
+}
+
+/// Mount sources must exist at validation time. Move the policy's bind sources and the
+/// allowlist (the built-in default when `allowlist` is `None`) from `FIXTURE_HOST_PREFIX`
+/// into a temp root, create the sources there, and return the rewritten allowlist path.
+pub fn relocate_sources(policy: &mut PolicySpec, allowlist: Option<&str>, label: &str) -> String {
+    let root = unique_temp_dir(label);
+    let prefix = root.to_string_lossy().to_string();
+    for mount in policy
+        .mounts
+        .iter_mut()
+        .filter(|mount| mount.mode.is_none())
+    {
+        if let Some(rest) = mount.source.strip_prefix(FIXTURE_HOST_PREFIX) {
+            mount.source = format!("{prefix}{rest}");
+            fs::create_dir_all(&mount.source).expect("create mount source");
+        }
+    }
+    let text = match allowlist {
+        Some(path) => fs::read_to_string(path).expect("read allowlist"),
+        None => DEFAULT_ALLOWLIST.to_string(),
+    };
+    let path = root.join("allowlist.yaml");
+    fs::write(&path, text.replace(FIXTURE_HOST_PREFIX, &prefix)).expect("write allowlist");
+    path.to_string_lossy().to_string()
+}
+
+pub fn remove_temp_root(allowlist: &str) {
+    if let Some(root) = Path::new(allowlist).parent() {
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -1,3 +1,5 @@
+mod common;
+
 use sr_common::SR_POL_101;
 use sr_policy::{load_policy_from_path, validate_policy_with_allowlist};
 use std::path::PathBuf;
@@ -11,15 +13,20 @@ fn repo_file(path: &str) -> String {
 
 #[test]
 fn allowlist_policy_passes_with_valid_allowlist() {
-    let policy = load_policy_from_path(&repo_file(
+    let mut policy = load_policy_from_path(&repo_file(
         "tests/mount_allowlist/policy_allowlist_pass.yaml",
     ))
     .expect("load allowlist pass policy");
-    let allowlist_path = repo_file("tests/mount_allowlist/allowlist-valid.yaml");
+    let allowlist_path = common::relocate_sources(
+        &mut policy,
+        Some(&repo_file("tests/mount_allowlist/allowlist-valid.yaml")),
+        "allowlist-pass",
+    );
 
     let result = validate_policy_with_allowlist(policy, Some(&allowlist_path));
+    common::remove_temp_root(&allowlist_path);
 
-    assert!(result.valid);
+    assert!(result.valid, "{:?}", result.errors);
     assert!(result.errors.is_empty());
 }
 
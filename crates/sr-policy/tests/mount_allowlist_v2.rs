@@ -0,0 +1,260 @@
+mod common;
+
+use sr_common::{SR_POL_101, SR_POL_102};
+use sr_policy::{
+    load_policy_from_path, parse_policy, validate_policy_with_allowlist, PolicySourceMap,
+};
+use std::fs;
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn repo_file(path: &str) -> String {
+    let mut p = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    p.push("../../");
+    p.push(path);
+    p.to_string_lossy().to_string()
+}
+
+fn unique_temp_dir(label: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let mut dir = std::env::temp_dir();
+    dir.push(format!(
+        "safe-run-vibe-{label}-{}-{nanos}",
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir).expect("create temp dir");
+    dir
+}
+
+fn write_v2_allowlist(dir: &Path, rules: &str) -> String {
+    let path = dir.join("allowlist-v2.yaml");
+    fs::write(
+        &path,
+        format!(
+            "schemaVersion: safe-run.mount-allowlist/v2\nguestAllowPrefixes:\n  - /data\nrules:\n{rules}"
+        ),
+    )
+    .expect("write allowlist");
+    path.to_string_lossy().to_string()
+}
+
+fn policy_with_mount(source: &Path, target: &str) -> sr_policy::PolicySpec {
+    parse_policy(&policy_yaml(source, target)).expect("parse policy")
+}
+
+fn policy_yaml(source: &Path, target: &str) -> String {
+    format!(
+        r#"apiVersion: policy.safe-run.dev/v1alpha1
+metadata:
+  name: allowlist-v2
+runtime:
+  command: /bin/echo
+  args: ["ok"]
+resources:
+  cpu:
+    max: "100000 100000"
+  memory:
+    max: 256Mi
+network:
+  mode: none
+mounts:
+  - source: {}
+    target: {target}
+    read_only: true
+audit:
+  level: basic
+"#,
+        source.display()
+    )
+}
+
+fn error_paths(result: &sr_policy::ValidationResult) -> Vec<(String, String)> {
+    result
+        .errors
+        .iter()
+        .map(|err| (err.code.clone(), err.path.clone()))
+        .collect()
+}
+
+#[test]
+fn v1_and_v2_allowlist_files_both_load() {
+    for allowlist in [
+        "tests/mount_allowlist/allowlist-valid.yaml",
+        "tests/mount_allowlist_v2/allowlist-v2.yaml",
+    ] {
+        let mut policy = load_policy_from_path(&repo_file(
+            "tests/mount_allowlist/policy_allowlist_pass.yaml",
+        ))
+        .expect("load allowlist pass policy");
+        let relocated =
+            common::relocate_sources(&mut policy, Some(&repo_file(allowlist)), "allowlist-v1-v2");
+        let result = validate_policy_with_allowlist(policy, Some(&relocated));
+        common::remove_temp_root(&relocated);
+        assert!(result.valid, "{allowlist}: {:?}", result.errors);
+    }
+}
+
+#[test]
+fn v2_rule_restricts_guest_targets() {
+    let base = unique_temp_dir("allowlist-v2-targets");
+    let allowed = base.join("allowed");
+    fs::create_dir_all(&allowed).expect("create allowed dir");
+    let allowlist = write_v2_allowlist(
+        &base,
+        &format!(
+            "  - hostPrefix: {}\n    guestTargets:\n      - /data/input\n",
+            allowed.display()
+        ),
+    );
+
+    let pass = validate_policy_with_allowlist(
+        policy_with_mount(&allowed, "/data/input/set"),
+        Some(&allowlist),
+    );
+    assert!(pass.valid, "{:?}", pass.errors);
+
+    let fail = validate_policy_with_allowlist(
+        policy_with_mount(&allowed, "/data/other"),
+        Some(&allowlist),
+    );
+    assert_eq!(
+        error_paths(&fail),
+        vec![(
+            SR_POL_102.to_string(),
+            "mountAllowlist.rules[0].guestTargets".to_string()
+        )]
+    );
+    let _ = fs::remove_dir_all(&base);
+}
+
+#[test]
+fn v2_rule_enforces_file_count_and_size_limits() {
+    let base = unique_temp_dir("allowlist-v2-limits");
+    let allowed = base.join("allowed");
+    fs::create_dir_all(allowed.join("nested")).expect("create nested dir");
+    fs::write(allowed.join("a.txt"), vec![b'a'; 64]).expect("write a");
+    fs::write(allowed.join("nested/b.txt"), vec![b'b'; 64]).expect("write b");
+    let allowlist = write_v2_allowlist(
+        &base,
+        &format!(
+            "  - hostPrefix: {}\n  - hostPrefix: {}\n    maxFiles: 1\n    maxBytes: 100\n",
+            base.join("unused").display(),
+            allowed.display()
+        ),
+    );
+
+    let mut result = validate_policy_with_allowlist(
+        policy_with_mount(&allowed, "/data/input"),
+        Some(&allowlist),
+    );
+    assert_eq!(
+        error_paths(&result),
+        vec![
+            (
+                SR_POL_101.to_string(),
+                "mountAllowlist.rules[1].maxFiles".to_string()
+            ),
+            (
+                SR_POL_101.to_string(),
+                "mountAllowlist.rules[1].maxBytes".to_string()
+            ),
+        ]
+    );
+    // Rule errors name the allowlist field but point at the offending mount source.
+    PolicySourceMap::parse("policy.yaml", policy_yaml(&allowed, "/data/input"))
+        .annotate(&mut result.errors);
+    for err in &result.errors {
+        assert_eq!((err.line, err.column), (Some(15), Some(13)), "{err:?}");
+    }
+    let _ = fs::remove_dir_all(&base);
+}
+
+#[test]
+fn v2_rule_enforces_owner_and_mode_constraints() {
+    let base = unique_temp_dir("allowlist-v2-owner");
+    let allowed = base.join("allowed");
+    fs::create_dir_all(&allowed).expect("create allowed dir");
+    let shared = allowed.join("shared.txt");
+    let setuid = allowed.join("tool");
+    fs::write(&shared, "shared").expect("write shared");
+    fs::write(&setuid, "tool").expect("write tool");
+    fs::set_permissions(&shared, fs::Permissions::from_mode(0o666)).expect("chmod shared");
+    fs::set_permissions(&setuid, fs::Permissions::from_mode(0o4755)).expect("chmod tool");
+    let uid = fs::metadata(&allowed).expect("stat allowed").uid();
+    let allowlist = write_v2_allowlist(
+        &base,
+        &format!(
+            "  - hostPrefix: {}\n    owner:\n      uid: {}\n",
+            allowed.display(),
+            uid + 1
+        ),
+    );
+
+    let result = validate_policy_with_allowlist(
+        policy_with_mount(&allowed, "/data/input"),
+        Some(&allowlist),
+    );
+    assert_eq!(
+        error_paths(&result),
+        vec![
+            (
+                SR_POL_101.to_string(),
+                "mountAllowlist.rules[0].owner.uid".to_string()
+            ),
+            (
+                SR_POL_101.to_string(),
+                "mountAllowlist.rules[0].denyWorldWritable".to_string()
+            ),
+            (
+                SR_POL_101.to_string(),
+                "mountAllowlist.rules[0].denySetuid".to_string()
+            ),
+        ]
+    );
+
+    let relaxed = write_v2_allowlist(
+        &base,
+        &format!(
+            "  - hostPrefix: {}\n    owner:\n      uid: {uid}\n    denyWorldWritable: false\n    denySetuid: false\n",
+            allowed.display()
+        ),
+    );
+    let result =
+        validate_policy_with_allowlist(policy_with_mount(&allowed, "/data/input"), Some(&relaxed));
+    assert!(result.valid, "{:?}", result.errors);
+    let _ = fs::remove_dir_all(&base);
+}
+
+#[test]
+fn v2_allowlist_without_rules_or_unknown_schema_is_rejected() {
+    let base = unique_temp_dir("allowlist-v2-invalid");
+    let empty = write_v2_allowlist(&base, "  []\n");
+    let result = validate_policy_with_allowlist(
+        policy_with_mount(Path::new("/var/lib/safe-run/input"), "/data/input"),
+        Some(&empty),
+    );
+    assert!(result
+        .errors
+        .iter()
+        .any(|err| err.code == SR_POL_101 && err.path == "mountAllowlist.rules"));
+
+    let unknown = base.join("allowlist-v9.yaml");
+    fs::write(
+        &unknown,
+        "schemaVersion: safe-run.mount-allowlist/v9\nguestAllowPrefixes: []\n",
+    )
+    .expect("write allowlist");
+    let result = validate_policy_with_allowlist(
+        policy_with_mount(Path::new("/var/lib/safe-run/input"), "/data/input"),
+        Some(unknown.to_string_lossy().as_ref()),
+    );
+    assert!(result
+        .errors
+        .iter()
+        .any(|err| err.code == SR_POL_101 && err.path == "mountAllowlist.schemaVersion"));
+    let _ = fs::remove_dir_all(&base);
+}
@@ -0,0 +1,304 @@
+mod common;
+
+use sr_common::{SR_POL_101, SR_POL_102};
+use sr_policy::{load_policy_from_path, parse_policy, validate_policy_with_allowlist};
+use std::fs;
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn repo_file(path: &str) -> String {
+    let mut p = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    p.push("../../");
+    p.push(path);
+    p.to_string_lossy().to_string()
+}
+
+fn unique_temp_dir(label: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let mut dir = std::env::temp_dir();
+    dir.push(format!(
+        "safe-run-vibe-{label}-{}-{nanos}",
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir).expect("create temp dir");
+    dir
+}
+
+fn write_allowlist(dir: &Path, host_prefix: &Path, deny: &str) -> String {
+    let path = dir.join("allowlist.yaml");
+    fs::write(
+        &path,
+        format!(
+            "schemaVersion: safe-run.mount-allowlist/v1\nhostAllowPrefixes:\n  - {}\nguestAllowPrefixes:\n  - /data\n{deny}",
+            host_prefix.display()
+        ),
+    )
+    .expect("write allowlist");
+    path.to_string_lossy().to_string()
+}
+
+fn policy_with_mount(source: &Path, target: &str) -> sr_policy::PolicySpec {
+    parse_policy(&format!(
+        r#"apiVersion: policy.safe-run.dev/v1alpha1
+metadata:
+  name: mount-denylist
+runtime:
+  command: /bin/echo
+  args: ["ok"]
+resources:
+  cpu:
+    max: "100000 100000"
+  memory:
+    max: 256Mi
+network:
+  mode: none
+mounts:
+  - source: {}
+    target: {target}
+    read_only: true
+audit:
+  level: basic
+"#,
+        source.display()
+    ))
+    .expect("parse policy")
+}
+
+fn error_paths(result: &sr_policy::ValidationResult) -> Vec<(String, String)> {
+    result
+        .errors
+        .iter()
+        .map(|err| (err.code.clone(), err.path.clone()))
+        .collect()
+}
+
+#[test]
+fn validation_output_reports_builtin_and_configured_denylist() {
+    let mut policy = load_policy_from_path(&repo_file(
+        "tests/mount_allowlist/policy_allowlist_pass.yaml",
+    ))
+    .expect("load policy");
+    let allowlist = common::relocate_sources(
+        &mut policy,
+        Some(&repo_file("tests/mount_denylist/allowlist-denylist.yaml")),
+        "denylist-output",
+    );
+    let result = validate_policy_with_allowlist(policy, Some(&allowlist));
+    common::remove_temp_root(&allowlist);
+    assert!(result.valid, "{:?}", result.errors);
+
+    let denylist = result.effective_denylist.expect("effective denylist");
+    assert_eq!(
+        denylist.host_paths,
+        vec![
+            "/proc",
+            "/sys",
+            "/dev",
+            "/root",
+            "/home/*/.ssh",
+            "/var/run/docker.sock"
+        ]
+    );
+    assert_eq!(denylist.guest_paths.first().map(String::as_str), Some("/"));
+    assert_eq!(
+        denylist.guest_paths.last().map(String::as_str),
+        Some("/data/secrets*")
+    );
+
+    let json = serde_json::to_value(validate_policy_with_allowlist(
+        load_policy_from_path(&repo_file(
+            "tests/mount_allowlist/policy_allowlist_pass.yaml",
+        ))
+        .expect("load policy"),
+        Some(&repo_file("tests/mount_allowlist/allowlist-valid.yaml")),
+    ))
+    .expect("serialize result");
+    assert_eq!(
+        json["effectiveDenylist"]["hostPaths"],
+        serde_json::json!(["/proc", "/sys", "/dev"])
+    );
+}
+
+#[test]
+fn host_glob_patterns_deny_matching_sources_and_descendants() {
+    let base = unique_temp_dir("denylist-host-glob");
+    let keys = base.join("home/alice/.ssh/keys");
+    let work = base.join("home/alice/work");
+    let socket_dir = base.join("srv/nested/ipc");
+    fs::create_dir_all(&keys).expect("create keys dir");
+    fs::create_dir_all(&work).expect("create work dir");
+    fs::create_dir_all(&socket_dir).expect("create socket dir");
+    fs::write(socket_dir.join("agent.sock"), b"").expect("write socket stand-in");
+    let allowlist = write_allowlist(
+        &base,
+        &base,
+        &format!(
+            "hostDenyPatterns:\n  - {0}/home/*/.ssh\n  - {0}/**/*.sock\n",
+            base.display()
+        ),
+    );
+
+    let denied =
+        validate_policy_with_allowlist(policy_with_mount(&keys, "/data/keys"), Some(&allowlist));
+    assert_eq!(
+        error_paths(&denied),
+        vec![(SR_POL_101.to_string(), "mounts[0].source".to_string())]
+    );
+    assert!(denied.errors[0].message.contains("/home/*/.ssh"));
+
+    let socket = validate_policy_with_allowlist(
+        policy_with_mount(&socket_dir.join("agent.sock"), "/data/sock"),
+        Some(&allowlist),
+    );
+    assert_eq!(
+        error_paths(&socket),
+        vec![(SR_POL_101.to_string(), "mounts[0].source".to_string())]
+    );
+
+    let allowed =
+        validate_policy_with_allowlist(policy_with_mount(&work, "/data/work"), Some(&allowlist));
+    assert!(allowed.valid, "{:?}", allowed.errors);
+    let _ = fs::remove_dir_all(base);
+}
+
+#[test]
+fn parents_of_denied_paths_are_rejected() {
+    let base = unique_temp_dir("denylist-parent");
+    let alice = base.join("home/alice");
+    let ipc = base.join("srv/ipc");
+    fs::create_dir_all(alice.join(".ssh")).expect("create ssh dir");
+    fs::create_dir_all(&ipc).expect("create ipc dir");
+    let allowlist = write_allowlist(
+        &base,
+        Path::new("/"),
+        &format!(
+            "hostDenyPatterns:\n  - {0}/home/*/.ssh\n  - {0}/srv/**/*.sock\n",
+            base.display()
+        ),
+    );
+
+    for source in [
+        alice.clone(),
+        base.join("home"),
+        base.join("srv"),
+        base.clone(),
+    ] {
+        let result = validate_policy_with_allowlist(
+            policy_with_mount(&source, "/data/in"),
+            Some(&allowlist),
+        );
+        assert_eq!(
+            error_paths(&result),
+            vec![(SR_POL_101.to_string(), "mounts[0].source".to_string())],
+            "{}",
+            source.display()
+        );
+        assert!(result.errors[0]
+            .message
+            .contains("contains sensitive host path"));
+    }
+
+    // The built-in `/proc`, `/sys` and `/dev` make the host root a denied parent.
+    let root = validate_policy_with_allowlist(
+        policy_with_mount(Path::new("/"), "/data/in"),
+        Some(&allowlist),
+    );
+    assert_eq!(
+        error_paths(&root),
+        vec![(SR_POL_101.to_string(), "mounts[0].source".to_string())]
+    );
+
+    // Below a `**` the pattern is not treated as a descendant of the source.
+    let below =
+        validate_policy_with_allowlist(policy_with_mount(&ipc, "/data/ipc"), Some(&allowlist));
+    assert!(below.valid, "{:?}", below.errors);
+    let _ = fs::remove_dir_all(base);
+}
+
+#[test]
+fn denied_symlink_path_is_rejected_even_when_target_is_allowed() {
+    let base = unique_temp_dir("denylist-symlink");
+    let real = base.join("real");
+    let run = base.join("run");
+    fs::create_dir_all(&real).expect("create real dir");
+    fs::create_dir_all(&run).expect("create run dir");
+    fs::write(real.join("docker.sock"), b"").expect("write socket stand-in");
+    symlink(real.join("docker.sock"), run.join("docker.sock")).expect("create symlink");
+    let allowlist = write_allowlist(
+        &base,
+        &base,
+        &format!(
+            "hostDenyPatterns:\n  - {}/run/docker.sock\n",
+            base.display()
+        ),
+    );
+
+    let result = validate_policy_with_allowlist(
+        policy_with_mount(&run.join("docker.sock"), "/data/docker.sock"),
+        Some(&allowlist),
+    );
+    assert_eq!(
+        error_paths(&result),
+        vec![(SR_POL_101.to_string(), "mounts[0].source".to_string())]
+    );
+
+    let direct = validate_policy_with_allowlist(
+        policy_with_mount(&real.join("docker.sock"), "/data/docker.sock"),
+        Some(&allowlist),
+    );
+    assert!(direct.valid, "{:?}", direct.errors);
+    let _ = fs::remove_dir_all(base);
+}
+
+#[test]
+fn guest_deny_patterns_extend_forbidden_targets() {
+    let base = unique_temp_dir("denylist-guest");
+    let source = base.join("input");
+    fs::create_dir_all(&source).expect("create source dir");
+    let allowlist = write_allowlist(&base, &base, "guestDenyPatterns:\n  - /data/secrets*\n");
+
+    let denied = validate_policy_with_allowlist(
+        policy_with_mount(&source, "/data/secrets-prod/db"),
+        Some(&allowlist),
+    );
+    assert_eq!(
+        error_paths(&denied),
+        vec![(SR_POL_102.to_string(), "mounts[0].target".to_string())]
+    );
+
+    let allowed =
+        validate_policy_with_allowlist(policy_with_mount(&source, "/data/input"), Some(&allowlist));
+    assert!(allowed.valid, "{:?}", allowed.errors);
+    let _ = fs::remove_dir_all(base);
+}
+
+#[test]
+fn malformed_deny_patterns_are_rejected() {
+    let base = unique_temp_dir("denylist-malformed");
+    for (deny, path) in [
+        (
+            "hostDenyPatterns:\n  - home/*/.ssh\n",
+            "mountAllowlist.hostDenyPatterns",
+        ),
+        (
+            "guestDenyPatterns:\n  - /data/[ab]\n",
+            "mountAllowlist.guestDenyPatterns",
+        ),
+    ] {
+        let allowlist = write_allowlist(&base, &base, deny);
+        let result =
+            validate_policy_with_allowlist(policy_with_mount(&base, "/data/in"), Some(&allowlist));
+        assert!(!result.valid);
+        assert!(result.effective_denylist.is_none());
+        assert!(
+            error_paths(&result).contains(&(SR_POL_101.to_string(), path.to_string())),
+            "{:?}",
+            result.errors
+        );
+    }
+    let _ = fs::remove_dir_all(base);
+}
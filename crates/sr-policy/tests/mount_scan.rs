@@ -0,0 +1,121 @@
+use sr_policy::{scan_mount_source, MountScanBudget, MountScanFindingKind};
+use std::fs;
+use std::os::unix::fs::{symlink, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const BUDGET: MountScanBudget = MountScanBudget { max_entries: 1_000 };
+
+fn unique_temp_dir(label: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let mut dir = std::env::temp_dir();
+    dir.push(format!(
+        "safe-run-vibe-{label}-{}-{nanos}",
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir).expect("create temp dir");
+    dir
+}
+
+fn kinds_at(
+    report: &sr_policy::MountScanReport,
+    root: &Path,
+) -> Vec<(MountScanFindingKind, String)> {
+    report
+        .findings
+        .iter()
+        .map(|finding| {
+            let path = Path::new(&finding.path)
+                .strip_prefix(root)
+                .expect("finding under root")
+                .to_string_lossy()
+                .to_string();
+            (finding.kind, path)
+        })
+        .collect()
+}
+
+#[test]
+fn clean_tree_reports_counts_without_findings() {
+    let base = unique_temp_dir("mount-scan-clean");
+    let source = base.join("source");
+    fs::create_dir_all(source.join("sub")).expect("create source");
+    fs::write(source.join("a.bin"), vec![0u8; 10]).expect("write file");
+    fs::write(source.join("sub/b.bin"), vec![0u8; 5]).expect("write file");
+    symlink("../a.bin", source.join("sub/link")).expect("create internal symlink");
+
+    let report = scan_mount_source(&source, &BUDGET).expect("scan");
+    assert!(report.is_clean());
+    assert_eq!(report.entries, 5);
+    assert_eq!(report.directories, 2);
+    assert_eq!(report.files, 3);
+    assert_eq!(report.total_bytes, 15);
+
+    let missing = scan_mount_source(&base.join("missing"), &BUDGET).expect_err("scan missing");
+    assert_eq!(missing.kind(), std::io::ErrorKind::NotFound);
+    let _ = fs::remove_dir_all(base);
+}
+
+#[test]
+fn escaping_symlinks_hardlinks_and_setuid_files_are_reported() {
+    let base = unique_temp_dir("mount-scan-findings");
+    let source = base.join("source");
+    fs::create_dir_all(source.join("sub")).expect("create source");
+    fs::write(base.join("outside.txt"), b"secret").expect("write outside file");
+    symlink("/etc/passwd", source.join("abs")).expect("absolute symlink");
+    symlink("../../outside.txt", source.join("sub/rel")).expect("relative symlink");
+    fs::hard_link(base.join("outside.txt"), source.join("linked.txt")).expect("hardlink");
+    fs::write(source.join("tool"), b"#!/bin/sh\n").expect("write tool");
+    fs::set_permissions(source.join("tool"), fs::Permissions::from_mode(0o4755))
+        .expect("set setuid bit");
+
+    let report = scan_mount_source(&source, &BUDGET).expect("scan");
+    assert!(!report.is_clean());
+    assert_eq!(report.findings_total, 4);
+    assert_eq!(
+        kinds_at(&report, &source),
+        vec![
+            (MountScanFindingKind::EscapingSymlink, "abs".to_string()),
+            (MountScanFindingKind::Hardlink, "linked.txt".to_string()),
+            (MountScanFindingKind::EscapingSymlink, "sub/rel".to_string()),
+            (MountScanFindingKind::Setuid, "tool".to_string()),
+        ]
+    );
+    let _ = fs::remove_dir_all(base);
+}
+
+#[test]
+fn device_node_source_is_reported() {
+    let report = scan_mount_source(Path::new("/dev/null"), &BUDGET).expect("scan /dev/null");
+    assert_eq!(report.findings_total, 1);
+    assert_eq!(report.findings[0].kind, MountScanFindingKind::DeviceNode);
+}
+
+#[test]
+fn scan_stops_at_entry_budget() {
+    let base = unique_temp_dir("mount-scan-budget");
+    for idx in 0..10 {
+        fs::write(base.join(format!("f{idx}")), b"x").expect("write file");
+    }
+
+    let report =
+        scan_mount_source(&base, &MountScanBudget { max_entries: 4 }).expect("scan budget");
+    assert!(report.budget_exhausted);
+    assert!(!report.is_clean());
+    assert_eq!(report.entries, 4);
+    assert_eq!(report.files, 3);
+
+    let json = serde_json::to_value(&report).expect("serialize report");
+    assert_eq!(json["budgetExhausted"], true);
+    assert_eq!(json["totalBytes"], 3);
+
+    // The root alone uses up the budget; its listing is not read.
+    let report =
+        scan_mount_source(&base, &MountScanBudget { max_entries: 1 }).expect("scan budget");
+    assert!(report.budget_exhausted);
+    assert_eq!((report.entries, report.files), (1, 0));
+    let _ = fs::remove_dir_all(base);
+}
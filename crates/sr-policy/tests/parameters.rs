@@ -0,0 +1,166 @@
+use sr_common::{SR_POL_001, SR_POL_002};
+use sr_policy::{
+    bind_parameters, load_policy_from_path, validate_policy_with_allowlist, PolicySpec,
+};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn repo_file(path: &str) -> String {
+    let mut p = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    p.push("../../");
+    p.push(path);
+    p.to_string_lossy().to_string()
+}
+
+fn unique_temp_dir(label: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let mut dir = std::env::temp_dir();
+    dir.push(format!(
+        "safe-run-vibe-{label}-{}-{nanos}",
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir).expect("create temp dir");
+    dir
+}
+
+fn write_allowlist(dir: &Path) -> String {
+    let path = dir.join("allowlist.yaml");
+    fs::write(
+        &path,
+        format!(
+            "schemaVersion: safe-run.mount-allowlist/v1\nhostAllowPrefixes:\n  - {}\nguestAllowPrefixes:\n  - /data\n",
+            dir.display()
+        ),
+    )
+    .expect("write allowlist");
+    path.to_string_lossy().to_string()
+}
+
+fn templated_policy() -> PolicySpec {
+    load_policy_from_path(&repo_file("tests/parameters/templated.yaml"))
+        .expect("load templated policy")
+}
+
+fn overrides(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+fn error_paths(errors: &[sr_common::ErrorItem]) -> Vec<(&str, &str)> {
+    errors
+        .iter()
+        .map(|err| (err.code.as_str(), err.path.as_str()))
+        .collect()
+}
+
+#[test]
+fn bound_parameters_are_substituted_before_validation() {
+    let base = unique_temp_dir("parameters-bind");
+    let input = base.join("input");
+    fs::create_dir_all(&input).expect("create input dir");
+    let allowlist = write_allowlist(&base);
+
+    let mut policy = templated_policy();
+    let bindings = bind_parameters(
+        &mut policy,
+        &overrides(&[
+            ("input_dir", input.to_str().expect("utf-8 path")),
+            ("iterations", "7"),
+        ]),
+    )
+    .expect("bind parameters");
+
+    assert_eq!(
+        bindings,
+        overrides(&[
+            ("api_host", "api.example.com"),
+            ("input_dir", input.to_str().expect("utf-8 path")),
+            ("iterations", "7"),
+            ("mode", "fast"),
+        ])
+    );
+    assert!(policy.parameters.is_empty());
+    assert_eq!(
+        policy.runtime.args,
+        vec!["--mode=fast", "--iterations", "7", "--literal=${HOME}"]
+    );
+    assert_eq!(policy.mounts[0].source, input.to_string_lossy());
+    assert_eq!(
+        policy.network.egress[0].host.as_deref(),
+        Some("api.example.com")
+    );
+
+    let result = validate_policy_with_allowlist(policy, Some(&allowlist));
+    assert!(result.valid, "{:?}", result.errors);
+    let _ = fs::remove_dir_all(&base);
+}
+
+#[test]
+fn validation_binds_defaults_and_reports_missing_values() {
+    let result = validate_policy_with_allowlist(templated_policy(), None);
+    assert!(!result.valid);
+    assert_eq!(
+        error_paths(&result.errors)[0],
+        (SR_POL_001, "parameters.input_dir")
+    );
+}
+
+#[test]
+fn parameter_values_are_checked_against_type_and_constraints() {
+    let mut policy = templated_policy();
+    let errors = bind_parameters(
+        &mut policy,
+        &overrides(&[
+            ("api_host", "Not A Host"),
+            ("input_dir", "data/in"),
+            ("iterations", "50"),
+            ("mode", "slow"),
+            ("undeclared", "x"),
+        ]),
+    )
+    .expect_err("invalid values must fail");
+
+    assert_eq!(
+        error_paths(&errors),
+        vec![
+            (SR_POL_002, "parameters.undeclared"),
+            (SR_POL_002, "parameters.api_host"),
+            (SR_POL_002, "parameters.input_dir"),
+            (SR_POL_002, "parameters.iterations"),
+            (SR_POL_002, "parameters.mode"),
+        ]
+    );
+    assert_eq!(policy.parameters.len(), 4, "declarations kept on failure");
+}
+
+#[test]
+fn substituted_paths_still_go_through_path_security() {
+    let base = unique_temp_dir("parameters-path-security");
+    let allowlist = write_allowlist(&base);
+
+    let mut policy = templated_policy();
+    bind_parameters(&mut policy, &overrides(&[("input_dir", "/etc")])).expect("bind parameters");
+    let result = validate_policy_with_allowlist(policy, Some(&allowlist));
+    assert!(!result.valid);
+    assert!(result
+        .errors
+        .iter()
+        .any(|err| err.path.starts_with("mounts[0].source")));
+    let _ = fs::remove_dir_all(&base);
+}
+
+#[test]
+fn unknown_references_are_rejected_per_field() {
+    let mut policy = templated_policy();
+    policy.runtime.args.push("${missing}".to_string());
+    let errors = bind_parameters(&mut policy, &overrides(&[("input_dir", "/srv/in")]))
+        .expect_err("unknown reference must fail");
+    assert_eq!(error_paths(&errors), vec![(SR_POL_002, "runtime.args[4]")]);
+}
@@ -3,6 +3,7 @@ use sr_policy::{
     validate_policy_with_allowlist, Audit, Cpu, Memory, Metadata, Mount, Network, NetworkMode,
     PolicySpec, Resources, Runtime,
 };
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -47,9 +48,15 @@ fn base_policy_with_mount_target(source: &str, target: &str) -> PolicySpec {
         metadata: Metadata {
             name: "demo".to_string(),
         },
+        parameters: BTreeMap::new(),
         runtime: Runtime {
             command: "/bin/echo".to_string(),
             args: vec!["ok".to_string()],
+            env: BTreeMap::new(),
+            env_passthrough: Vec::new(),
+            cwd: None,
+            user: None,
+            command_sha256: None,
         },
         resources: Resources {
             cpu: Cpu {
@@ -67,7 +74,11 @@ fn base_policy_with_mount_target(source: &str, target: &str) -> PolicySpec {
             source: source.to_string(),
             target: target.to_string(),
             read_only: true,
+            source_identity: None,
+            mode: None,
+            max_size: None,
         }],
+        secrets: Vec::new(),
         audit: Audit {
             level: "basic".to_string(),
         },
@@ -88,6 +99,54 @@ fn allowlisted_path_passes() {
     assert!(result.errors.is_empty());
 }
 
+#[test]
+fn allowlisted_source_records_identity_outside_serialized_policy() {
+    use std::os::unix::fs::MetadataExt;
+
+    let base = unique_temp_dir("allowlist-identity");
+    let allowed = base.join("allowed");
+    let source = allowed.join("input");
+    fs::create_dir_all(&source).expect("create source dir");
+    let allowlist_path = write_allowlist(&base, &[allowed.as_path()], &[Path::new("/data")]);
+
+    let policy = base_policy_with_mount(source.to_string_lossy().as_ref());
+    let result =
+        validate_policy_with_allowlist(policy, Some(allowlist_path.to_string_lossy().as_ref()));
+    assert!(result.valid);
+    let normalized = result.normalized_policy.expect("normalized policy");
+    let identity = normalized.mounts[0]
+        .source_identity
+        .as_ref()
+        .expect("source identity");
+    let metadata = fs::metadata(&source).expect("stat source");
+    assert_eq!(identity.allow_prefix, allowed.to_string_lossy());
+    assert_eq!(identity.relative_path, "input");
+    assert_eq!(
+        (identity.device, identity.inode),
+        (metadata.dev(), metadata.ino())
+    );
+
+    let serialized = serde_json::to_value(&normalized).expect("serialize policy");
+    assert!(serialized["mounts"][0].get("source_identity").is_none());
+}
+
+#[test]
+fn missing_source_is_rejected() {
+    let base = unique_temp_dir("allowlist-missing");
+    let allowed = base.join("allowed");
+    fs::create_dir_all(&allowed).expect("create allowed dir");
+    let allowlist_path = write_allowlist(&base, &[allowed.as_path()], &[Path::new("/data")]);
+
+    // A source created later could be a symlink swapped in after validation.
+    let policy = base_policy_with_mount(allowed.join("later").to_string_lossy().as_ref());
+    let result =
+        validate_policy_with_allowlist(policy, Some(allowlist_path.to_string_lossy().as_ref()));
+    assert!(!result.valid);
+    assert_eq!(result.errors.len(), 1, "{:?}", result.errors);
+    assert_eq!(result.errors[0].code, SR_POL_101);
+    assert_eq!(result.errors[0].path, "mounts[0].source");
+}
+
 #[test]
 fn symlink_escape_is_rejected() {
     let base = unique_temp_dir("allowlist-symlink");
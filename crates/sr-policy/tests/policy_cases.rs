@@ -1,5 +1,9 @@
-use sr_common::{SR_POL_001, SR_POL_002, SR_POL_103, SR_POL_201};
-use sr_policy::{load_policy_from_path, validate_policy};
+mod common;
+
+use sr_common::{SR_POL_001, SR_POL_002, SR_POL_102, SR_POL_103, SR_POL_201};
+use sr_policy::{
+    load_policy_from_path, validate_policy, validate_policy_with_allowlist, MountMode,
+};
 use std::path::PathBuf;
 
 fn repo_file(path: &str) -> String {
@@ -79,14 +83,18 @@ fn missing_mounts_returns_sr_pol_001() {
 
 #[test]
 fn mount_alias_fields_are_normalized() {
-    let policy = load_policy_from_path(&repo_file("tests/policy_valid_cases/mount_alias.yaml"))
+    let mut policy = load_policy_from_path(&repo_file("tests/policy_valid_cases/mount_alias.yaml"))
         .expect("load alias policy");
-    let result = validate_policy(policy);
-    assert!(result.valid);
+    let allowlist = common::relocate_sources(&mut policy, None, "mount-alias");
+    let source = policy.mounts[0].source.clone();
+    let result = validate_policy_with_allowlist(policy, Some(&allowlist));
+    common::remove_temp_root(&allowlist);
+    assert!(result.valid, "{:?}", result.errors);
     let normalized = result.normalized_policy.expect("normalized policy");
     assert_eq!(normalized.mounts.len(), 1);
     let mount = &normalized.mounts[0];
-    assert_eq!(mount.source, "/var/lib/safe-run/input");
+    assert!(source.ends_with("/input"), "{source}");
+    assert_eq!(mount.source, source);
     assert_eq!(mount.target, "/data/input");
     assert!(mount.read_only);
 }
@@ -128,3 +136,79 @@ fn invalid_mount_read_only_false_returns_sr_pol_103() {
         .expect("expected SR-POL-103");
     assert_eq!(err.path, "mounts[0].read_only");
 }
+
+#[test]
+fn writable_mounts_with_size_cap_pass() {
+    let policy = load_policy_from_path(&repo_file("tests/policy_valid_cases/writable_mounts.yaml"))
+        .expect("load writable mounts policy");
+    let result = validate_policy(policy);
+    assert!(result.valid, "{:?}", result.errors);
+    let normalized = result.normalized_policy.expect("normalized policy");
+    assert_eq!(normalized.mounts[0].mode, Some(MountMode::Output));
+    assert_eq!(normalized.mounts[0].max_size.as_deref(), Some("64Mi"));
+    let serialized = serde_json::to_value(&normalized).expect("serialize policy");
+    assert!(serialized["mounts"][0].get("source").is_none());
+}
+
+#[test]
+fn writable_mount_without_max_size_returns_sr_pol_001() {
+    let policy = load_policy_from_path(&repo_file(
+        "tests/policy_invalid_cases/invalid_writable_mount_missing_max_size.yaml",
+    ))
+    .expect("load writable mount policy");
+    let result = validate_policy(policy);
+    assert!(!result.valid);
+    let err = result
+        .errors
+        .iter()
+        .find(|e| e.code == SR_POL_001)
+        .expect("expected SR-POL-001");
+    assert_eq!(err.path, "mounts[0].max_size");
+}
+
+#[test]
+fn writable_mount_with_source_or_zero_size_returns_sr_pol_002() {
+    let policy = load_policy_from_path(&repo_file(
+        "tests/policy_invalid_cases/invalid_writable_mount_with_source.yaml",
+    ))
+    .expect("load writable mount policy");
+    let result = validate_policy(policy);
+    assert!(!result.valid);
+    let paths = result
+        .errors
+        .iter()
+        .filter(|e| e.code == SR_POL_002)
+        .map(|e| e.path.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(paths, vec!["mounts[0].source", "mounts[0].max_size"]);
+}
+
+#[test]
+fn writable_mount_target_with_boot_arg_injection_returns_sr_pol_102() {
+    let policy = load_policy_from_path(&repo_file(
+        "tests/policy_invalid_cases/invalid_writable_mount_target_injection.yaml",
+    ))
+    .expect("load writable mount policy");
+    let result = validate_policy(policy);
+    assert!(!result.valid);
+    assert_eq!(result.errors.len(), 1, "{:?}", result.errors);
+    assert_eq!(result.errors[0].code, SR_POL_102);
+    assert_eq!(result.errors[0].path, "mounts[0].target");
+}
+
+#[test]
+fn writable_mount_over_size_limit_returns_sr_pol_002() {
+    let policy = load_policy_from_path(&repo_file(
+        "tests/policy_invalid_cases/invalid_writable_mount_oversized.yaml",
+    ))
+    .expect("load writable mount policy");
+    let result = validate_policy(policy);
+    assert!(!result.valid);
+    let paths = result
+        .errors
+        .iter()
+        .filter(|e| e.code == SR_POL_002)
+        .map(|e| e.path.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(paths, vec!["mounts[0].max_size", "mounts[1].max_size"]);
+}
@@ -0,0 +1,110 @@
+use sr_common::{ErrorItem, SR_POL_001, SR_POL_002, SR_POL_103};
+use sr_policy::{
+    load_policy_from_path, parse_policy, render_diagnostics, validate_policy, PolicySourceMap,
+};
+use std::path::PathBuf;
+
+fn repo_file(path: &str) -> String {
+    let mut p = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    p.push("../../");
+    p.push(path);
+    p.to_string_lossy().to_string()
+}
+
+fn find<'a>(errors: &'a [ErrorItem], path: &str) -> &'a ErrorItem {
+    errors
+        .iter()
+        .find(|err| err.path == path)
+        .unwrap_or_else(|| panic!("no error at {path}: {errors:?}"))
+}
+
+#[test]
+fn validation_errors_are_located_in_the_policy_file() {
+    let path = repo_file("tests/policy_diagnostics/invalid_mount.yaml");
+    let policy = load_policy_from_path(&path).expect("load policy");
+    let mut result = validate_policy(policy);
+    assert!(!result.valid);
+    let source = PolicySourceMap::load(&path).expect("source map");
+    source.annotate(&mut result.errors);
+
+    let cpu = find(&result.errors, "resources.cpu.max");
+    assert_eq!(cpu.file.as_deref(), Some(path.as_str()));
+    assert_eq!((cpu.line, cpu.column), (Some(9), Some(10)));
+    // `guestPath` is indexed under the field name used in error paths.
+    let target = find(&result.errors, "mounts[0].target");
+    assert_eq!((target.line, target.column), (Some(16), Some(16)));
+    // Omitted fields point at their mount item.
+    let read_only = find(&result.errors, "mounts[1].read_only");
+    assert_eq!(read_only.code, SR_POL_103);
+    assert_eq!((read_only.line, read_only.column), (Some(18), Some(5)));
+}
+
+#[test]
+fn parse_errors_carry_file_line_and_column() {
+    let path = repo_file("tests/policy_schema/unknown_field.yaml");
+    let err = load_policy_from_path(&path).expect_err("unknown field");
+    assert_eq!(err.code, SR_POL_002);
+    assert_eq!(err.file.as_deref(), Some(path.as_str()));
+    assert_eq!((err.line, err.column), (Some(17), Some(5)));
+}
+
+#[test]
+fn render_diagnostics_prints_source_excerpt() {
+    let source = PolicySourceMap::parse(
+        "policy.yaml",
+        "apiVersion: policy.safe-run.dev/v1alpha1\nmounts:\n  - target: data/input\n",
+    );
+    let mut errors = vec![
+        ErrorItem::new(
+            SR_POL_002,
+            "mounts[0].target",
+            "mount target must be an absolute path",
+        ),
+        ErrorItem::new(SR_POL_002, "policy", "not located"),
+    ];
+    source.annotate(&mut errors);
+    let text = render_diagnostics(&errors, Some(&source));
+
+    assert!(text.contains("error[SR-POL-002]: mount target must be an absolute path\n"));
+    assert!(text.contains("  --> policy.yaml:3:13\n"), "{text}");
+    assert!(text.contains("3 |   - target: data/input\n"), "{text}");
+    assert!(text.contains("  |             ^^^^^^^^^^\n"), "{text}");
+    assert!(text.contains("= path: mounts[0].target"));
+    assert!(errors[1].line.is_none());
+    assert!(text.contains("error[SR-POL-002]: not located\n   = path: policy\n"));
+}
+
+#[test]
+fn extending_documents_index_their_own_keys_but_not_merged_lists() {
+    let source = PolicySourceMap::parse(
+        "child.yaml",
+        "extends: base\nresources:\n  cpu:\n    max: \"2\"\nruntime:\n  args: [a, b]\nmounts:\n  - target: data/input\n",
+    );
+    assert_eq!(source.locate("resources.cpu.max"), Some((4, 10)));
+    assert_eq!(source.locate("runtime.args[1]"), Some((6, 13)));
+    // Merged mounts are renumbered against the base, so their items are not located.
+    assert_eq!(source.locate("mounts[0].target"), None);
+}
+
+#[test]
+fn parse_error_paths_come_from_yaml_marks() {
+    let err =
+        parse_policy("apiVersion: policy.safe-run.dev/v1beta1\nmetadata:\n  name: x\n  label: y\n")
+            .expect_err("unknown field");
+    assert_eq!(err.path, "metadata.label");
+    assert_eq!((err.line, err.column), (Some(4), Some(3)));
+
+    let err = parse_policy("apiVersion: policy.safe-run.dev/v1beta1\nmetadata:\n  nam: x\n")
+        .expect_err("unknown field");
+    assert_eq!(err.path, "metadata.nam");
+
+    let err = parse_policy(
+        "apiVersion: policy.safe-run.dev/v1beta1\nmetadata:\n  name: x\nruntime:\n  command: /bin/true\n",
+    )
+    .expect_err("missing field");
+    assert_eq!(
+        (err.code.as_str(), err.path.as_str()),
+        (SR_POL_001, "runtime"),
+        "{err:?}"
+    );
+}
@@ -0,0 +1,112 @@
+mod common;
+
+use sr_common::{SR_POL_001, SR_POL_002};
+use sr_policy::{load_policy_from_path, parse_policy, validate_policy_with_allowlist};
+use std::path::PathBuf;
+
+fn repo_file(path: &str) -> String {
+    let mut p = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    p.push("../../");
+    p.push(path);
+    p.to_string_lossy().to_string()
+}
+
+#[test]
+fn extends_chain_resolves_with_layered_overrides() {
+    let policy = load_policy_from_path(&repo_file("tests/policy_extends/job.yaml"))
+        .expect("load extending policy");
+
+    assert_eq!(policy.api_version, "policy.safe-run.dev/v1alpha1");
+    assert_eq!(policy.metadata.name, "nightly-job");
+    // Scalars inherit through the chain; lists other than mounts/egress are replaced.
+    assert_eq!(policy.runtime.command, "/usr/bin/python3");
+    assert_eq!(policy.runtime.args, vec!["job.py"]);
+    assert_eq!(policy.resources.cpu.max, "100000 100000");
+    assert_eq!(policy.resources.memory.max, "512Mi");
+    assert_eq!(policy.audit.level, "basic");
+
+    let mounts = policy
+        .mounts
+        .iter()
+        .map(|mount| (mount.target.as_str(), mount.source.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        mounts,
+        vec![
+            ("/data/input", "/var/lib/safe-run/input-nightly"),
+            ("/data/models", "/var/lib/safe-run/models"),
+            ("/data/cache", "/var/lib/safe-run/cache"),
+        ]
+    );
+
+    let egress_hosts = policy
+        .network
+        .egress
+        .iter()
+        .map(|rule| rule.host.as_deref().unwrap_or_default())
+        .collect::<Vec<_>>();
+    assert_eq!(egress_hosts, vec!["pypi.org", "files.pythonhosted.org"]);
+}
+
+#[test]
+fn resolved_policy_is_normalized_deterministically() {
+    let mut policy =
+        load_policy_from_path(&repo_file("tests/policy_extends/job.yaml")).expect("load policy");
+    let allowlist = common::relocate_sources(&mut policy, None, "extends-normalized");
+    let first = validate_policy_with_allowlist(policy.clone(), Some(&allowlist));
+    assert!(first.valid, "{:?}", first.errors);
+    let second = validate_policy_with_allowlist(policy, Some(&allowlist));
+    common::remove_temp_root(&allowlist);
+    let normalized = serde_json::to_value(&first.normalized_policy).expect("serialize policy");
+    assert!(normalized.get("extends").is_none());
+    assert_eq!(normalized["metadata"]["name"], "nightly-job");
+    assert_eq!(normalized["mounts"].as_array().map(Vec::len), Some(3));
+    assert_eq!(
+        normalized,
+        serde_json::to_value(&second.normalized_policy).expect("serialize policy")
+    );
+}
+
+#[test]
+fn bare_names_resolve_next_to_file_and_examples_share_base() {
+    for example in [
+        "examples/m1_network_none.yaml",
+        "examples/m1_readonly_root.yaml",
+        "examples/m1_resource_limits.yaml",
+        "examples/m2_mount_readonly.yaml",
+    ] {
+        let mut policy = load_policy_from_path(&repo_file(example)).expect(example);
+        let allowlist = common::relocate_sources(&mut policy, None, "extends-examples");
+        let result = validate_policy_with_allowlist(policy, Some(&allowlist));
+        common::remove_temp_root(&allowlist);
+        assert!(result.valid, "{example}: {:?}", result.errors);
+        let policy = result.normalized_policy.expect("normalized policy");
+        assert_eq!(policy.audit.level, "basic");
+    }
+    let limits =
+        load_policy_from_path(&repo_file("examples/m1_resource_limits.yaml")).expect("load");
+    assert_eq!(limits.resources.memory.max, "128Mi");
+}
+
+#[test]
+fn extends_cycle_is_rejected() {
+    let err = load_policy_from_path(&repo_file("tests/policy_extends/cycle_a.yaml"))
+        .expect_err("cycle must fail");
+    assert_eq!(err.code, SR_POL_002);
+    assert_eq!(err.path, "extends");
+    assert!(err.message.contains("cycle"), "{}", err.message);
+    assert!(err.message.contains("cycle_b.yaml"), "{}", err.message);
+}
+
+#[test]
+fn missing_or_malformed_extends_is_rejected() {
+    let err = load_policy_from_path(&repo_file("tests/policy_extends/missing_base.yaml"))
+        .expect_err("missing base must fail");
+    assert_eq!(err.code, SR_POL_001);
+    assert_eq!(err.path, "extends");
+
+    let err = parse_policy("extends: [a, b]\nmetadata:\n  name: x\n")
+        .expect_err("non-string extends must fail");
+    assert_eq!(err.code, SR_POL_002);
+    assert_eq!(err.path, "extends");
+}
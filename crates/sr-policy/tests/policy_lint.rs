@@ -0,0 +1,117 @@
+use sr_common::{SR_LNT_001, SR_LNT_002, SR_LNT_003, SR_LNT_004, SR_POL_002};
+use sr_policy::{lint_policy, load_policy_from_path, LintConfig, LintReport, LintSeverity};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn repo_file(path: &str) -> String {
+    let mut p = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    p.push("../../");
+    p.push(path);
+    p.to_string_lossy().to_string()
+}
+
+fn unique_temp_dir(label: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let mut dir = std::env::temp_dir();
+    dir.push(format!(
+        "safe-run-vibe-{label}-{}-{nanos}",
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir).expect("create temp dir");
+    dir
+}
+
+fn severity_of(report: &LintReport, code: &str) -> Option<LintSeverity> {
+    report
+        .findings
+        .iter()
+        .find(|finding| finding.item.code == code)
+        .map(|finding| finding.severity)
+}
+
+#[test]
+fn lint_reports_each_rule_with_default_severity() {
+    let policy =
+        load_policy_from_path(&repo_file("tests/policy_lint/risky.yaml")).expect("load policy");
+    let report = lint_policy(&policy, None, &LintConfig::default()).expect("lint policy");
+
+    assert!(!report.passed);
+    assert_eq!((report.errors, report.warnings), (1, 3));
+    assert_eq!(severity_of(&report, SR_LNT_001), Some(LintSeverity::Error));
+    assert_eq!(
+        severity_of(&report, SR_LNT_002),
+        Some(LintSeverity::Warning)
+    );
+    assert_eq!(
+        severity_of(&report, SR_LNT_003),
+        Some(LintSeverity::Warning)
+    );
+    assert_eq!(
+        severity_of(&report, SR_LNT_004),
+        Some(LintSeverity::Warning)
+    );
+    let paths = report
+        .findings
+        .iter()
+        .map(|finding| finding.item.path.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        [
+            "network.egress[0].cidr",
+            "resources.memory.max",
+            "mounts[0].source",
+            "network.egress[1].port"
+        ]
+    );
+}
+
+#[test]
+fn lint_config_overrides_severity_and_threshold() {
+    let policy =
+        load_policy_from_path(&repo_file("tests/policy_lint/risky.yaml")).expect("load policy");
+    let config = LintConfig::from_file(Path::new(&repo_file("tests/policy_lint/lint-config.yaml")))
+        .expect("load lint config");
+    let report = lint_policy(&policy, None, &config).expect("lint policy");
+
+    assert!(report.passed, "{report:?}");
+    assert_eq!(
+        severity_of(&report, SR_LNT_001),
+        Some(LintSeverity::Warning)
+    );
+    assert_eq!(severity_of(&report, SR_LNT_002), None, "16Gi threshold");
+    assert_eq!(severity_of(&report, SR_LNT_004), None, "rule is off");
+}
+
+#[test]
+fn lint_config_rejects_unknown_rules_and_schema() {
+    let dir = unique_temp_dir("lint-config");
+    let unknown_rule = dir.join("unknown-rule.yaml");
+    fs::write(
+        &unknown_rule,
+        "schemaVersion: safe-run.lint/v1\nseverity:\n  SR-LNT-999: error\n",
+    )
+    .expect("write config");
+    let err = LintConfig::from_file(&unknown_rule).expect_err("unknown rule");
+    assert_eq!(err.code, SR_POL_002);
+    assert_eq!(err.path, "lintConfig.severity.SR-LNT-999");
+
+    let bad_schema = dir.join("bad-schema.yaml");
+    fs::write(&bad_schema, "schemaVersion: safe-run.lint/v0\n").expect("write config");
+    let err = LintConfig::from_file(&bad_schema).expect_err("bad schema");
+    assert_eq!(err.path, "lintConfig.schemaVersion");
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn lint_passes_conservative_policy() {
+    let policy =
+        load_policy_from_path(&repo_file("examples/m2_mount_readonly.yaml")).expect("load policy");
+    let report = lint_policy(&policy, None, &LintConfig::default()).expect("lint policy");
+    assert!(report.passed);
+    assert!(report.findings.is_empty(), "{:?}", report.findings);
+}
@@ -0,0 +1,84 @@
+use serde_json::Value;
+use sr_common::SR_POL_002;
+use sr_policy::{load_policy_from_path, parse_policy, policy_json_schema};
+use std::path::PathBuf;
+
+fn repo_file(path: &str) -> String {
+    let mut p = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    p.push("../../");
+    p.push(path);
+    p.to_string_lossy().to_string()
+}
+
+#[test]
+fn unknown_mount_field_reports_path_and_location() {
+    let err = load_policy_from_path(&repo_file("tests/policy_schema/unknown_field.yaml"))
+        .expect_err("readonly is not a mount field");
+    assert_eq!(err.code, SR_POL_002);
+    assert_eq!(err.path, "mounts[0].readonly");
+    assert!(
+        err.message.contains("unknown field `readonly`"),
+        "{}",
+        err.message
+    );
+    assert!(err.message.contains("line 17 column 5"), "{}", err.message);
+}
+
+#[test]
+fn unknown_fields_are_rejected_at_every_level() {
+    let head = "apiVersion: policy.safe-run.dev/v1alpha1\nmetadata:\n  name: strict\nruntime:\n  command: /bin/echo\n  args: [ok]\nresources:\n  cpu:\n    max: \"100000 100000\"\n  memory:\n    max: 256Mi\nmounts: []\n";
+    let network = "network:\n  mode: none\n";
+    let audit = "audit:\n  level: basic\n";
+    parse_policy(&format!("{head}{network}{audit}")).expect("baseline parses strictly");
+
+    let cases = [
+        (format!("{head}{network}{audit}labels: {{}}\n"), "labels"),
+        (
+            format!("{head}{network}audit:\n  level: basic\n  sink: file\n"),
+            "audit.sink",
+        ),
+        (
+            format!("{head}network:\n  mode: none\n  egres: []\n{audit}"),
+            "network.egres",
+        ),
+    ];
+    for (input, path) in cases {
+        let err = parse_policy(&input).expect_err(path);
+        assert_eq!(err.code, SR_POL_002, "{path}: {}", err.message);
+        assert_eq!(err.path, path);
+    }
+}
+
+#[test]
+fn policy_schema_is_strict_and_covers_serialized_fields() {
+    let schema = policy_json_schema();
+    assert_eq!(schema["additionalProperties"], Value::Bool(false));
+    assert!(schema["properties"]["extends"].is_object());
+    for (name, definition) in schema["definitions"].as_object().expect("definitions") {
+        if definition["type"] == "object" {
+            assert_eq!(
+                definition["additionalProperties"],
+                Value::Bool(false),
+                "{name} must reject unknown keys"
+            );
+        }
+    }
+    let mount = &schema["definitions"]["Mount"]["properties"];
+    for key in [
+        "source",
+        "hostPath",
+        "target",
+        "guestPath",
+        "read_only",
+        "readOnly",
+    ] {
+        assert!(mount[key].is_object(), "mount schema misses {key}");
+    }
+
+    let policy =
+        load_policy_from_path(&repo_file("examples/m2_mount_readonly.yaml")).expect("load example");
+    let serialized = serde_json::to_value(&policy).expect("serialize policy");
+    for key in serialized.as_object().expect("policy object").keys() {
+        assert!(schema["properties"][key].is_object(), "schema misses {key}");
+    }
+}
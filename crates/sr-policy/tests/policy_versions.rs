@@ -0,0 +1,165 @@
+use sr_common::{SR_POL_001, SR_POL_002};
+use sr_policy::{
+    load_policy_from_path, migrate_policy, parse_policy, validate_policy, MountMode,
+    PolicyApiVersion, POLICY_API_V1ALPHA1, POLICY_API_V1BETA1,
+};
+use std::path::PathBuf;
+
+fn repo_file(path: &str) -> String {
+    let mut p = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    p.push("../../");
+    p.push(path);
+    p.to_string_lossy().to_string()
+}
+
+fn legacy_text() -> String {
+    std::fs::read_to_string(repo_file("tests/policy_versions/legacy.yaml")).expect("read legacy")
+}
+
+#[test]
+fn legacy_policy_validates_and_normalizes_to_latest() {
+    let mut policy = parse_policy(&legacy_text()).expect("parse v1alpha1 policy");
+    assert_eq!(policy.api_version, POLICY_API_V1ALPHA1);
+    // Mount sources are host-specific; the version handling does not depend on them.
+    policy.mounts.clear();
+
+    let result = validate_policy(policy);
+    assert!(result.valid, "{:?}", result.errors);
+    let normalized = result.normalized_policy.expect("normalized policy");
+    assert_eq!(normalized.api_version, POLICY_API_V1BETA1);
+    assert_eq!(PolicyApiVersion::LATEST.as_str(), POLICY_API_V1BETA1);
+    assert!(
+        result
+            .warnings
+            .iter()
+            .any(|warning| warning.contains("deprecated") && warning.contains("policy migrate")),
+        "{:?}",
+        result.warnings
+    );
+}
+
+#[test]
+fn latest_policy_validates_without_deprecation_warning() {
+    let migrated = migrate_policy(&legacy_text()).expect("migrate");
+    let mut policy = parse_policy(&migrated.output).expect("parse v1beta1 policy");
+    policy.mounts.clear();
+
+    let result = validate_policy(policy);
+    assert!(result.valid, "{:?}", result.errors);
+    assert_eq!(result.warnings, vec!["default deny policy is active"]);
+}
+
+#[test]
+fn unsupported_api_version_is_rejected() {
+    let mut policy = parse_policy(&legacy_text()).expect("parse");
+    policy.mounts.clear();
+    policy.api_version = "policy.safe-run.dev/v1".to_string();
+
+    let result = validate_policy(policy);
+    assert!(!result.valid);
+    assert_eq!(result.errors.len(), 1, "{:?}", result.errors);
+    assert_eq!(result.errors[0].code, SR_POL_002);
+    assert_eq!(result.errors[0].path, "apiVersion");
+    assert!(result.errors[0].message.contains(POLICY_API_V1BETA1));
+}
+
+#[test]
+fn v1alpha1_mount_aliases_convert_to_fields() {
+    let policy = parse_policy(&legacy_text()).expect("parse");
+    let models = &policy.mounts[0];
+    assert_eq!(
+        (
+            models.source.as_str(),
+            models.target.as_str(),
+            models.read_only
+        ),
+        ("/var/lib/safe-run/models", "/data/models", true)
+    );
+    let output = &policy.mounts[1];
+    assert_eq!(output.target, "/data/out");
+    assert_eq!(output.mode, Some(MountMode::Output));
+    assert_eq!(output.max_size.as_deref(), Some("64Mi"));
+}
+
+#[test]
+fn v1beta1_rejects_v1alpha1_mount_keys() {
+    let err = load_policy_from_path(&repo_file("tests/policy_versions/beta_alias.yaml"))
+        .expect_err("alias key in v1beta1");
+    assert_eq!(err.code, SR_POL_002);
+    assert_eq!(err.path, "mounts[0].guestPath");
+    assert!(err.message.contains("'target'"), "{}", err.message);
+}
+
+#[test]
+fn v1beta1_policy_extends_v1alpha1_base() {
+    let policy = load_policy_from_path(&repo_file("tests/policy_versions/job_beta.yaml"))
+        .expect("load extending policy");
+    assert_eq!(policy.api_version, POLICY_API_V1BETA1);
+    // The base's `guestPath` entries are converted before merging, so `target` matches them.
+    let mounts = policy
+        .mounts
+        .iter()
+        .map(|mount| (mount.target.as_str(), mount.source.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        mounts,
+        vec![
+            ("/data/models", "/var/lib/safe-run/models-v2"),
+            ("/data/input", "/var/lib/safe-run/input"),
+        ]
+    );
+}
+
+#[test]
+fn migrate_rewrites_only_versioned_tokens() {
+    let input = legacy_text();
+    let migration = migrate_policy(&input).expect("migrate");
+    assert_eq!(migration.from.as_deref(), Some(POLICY_API_V1ALPHA1));
+    assert_eq!(migration.to, POLICY_API_V1BETA1);
+    assert_eq!(
+        migration.changes,
+        vec![
+            format!("apiVersion: {POLICY_API_V1ALPHA1} -> {POLICY_API_V1BETA1}"),
+            "mounts[0].hostPath -> mounts[0].source".to_string(),
+            "mounts[0].guestPath -> mounts[0].target".to_string(),
+            "mounts[0].readOnly -> mounts[0].read_only".to_string(),
+            "mounts[1].guestPath -> mounts[1].target".to_string(),
+            "mounts[1].maxSize -> mounts[1].max_size".to_string(),
+        ]
+    );
+    let expected = input
+        .replace(POLICY_API_V1ALPHA1, POLICY_API_V1BETA1)
+        .replace("hostPath:", "source:")
+        .replace("guestPath:", "target:")
+        .replace("readOnly:", "read_only:")
+        .replace("\"maxSize\":", "\"max_size\":");
+    assert_eq!(migration.output, expected);
+
+    let again = migrate_policy(&migration.output).expect("migrate latest");
+    assert!(!again.changed());
+    assert_eq!(again.output, migration.output);
+}
+
+#[test]
+fn migrate_reports_unusable_documents() {
+    let err = migrate_policy("metadata:\n  name: demo\n").expect_err("no apiVersion");
+    assert_eq!(
+        (err.code.as_str(), err.path.as_str()),
+        (SR_POL_001, "apiVersion")
+    );
+
+    let err = migrate_policy("apiVersion: policy.safe-run.dev/v9\n").expect_err("unknown");
+    assert_eq!(
+        (err.code.as_str(), err.path.as_str()),
+        (SR_POL_002, "apiVersion")
+    );
+
+    // Extending files may inherit apiVersion; only their own keys are rewritten.
+    let migration = migrate_policy("extends: base\nmounts:\n  - guestPath: /data/out\n")
+        .expect("migrate extending file");
+    assert_eq!(migration.from, None);
+    assert_eq!(
+        migration.output,
+        "extends: base\nmounts:\n  - target: /data/out\n"
+    );
+}
@@ -0,0 +1,116 @@
+use sr_common::SR_POL_002;
+use sr_policy::{load_policy_from_path, memory_mib, validate_policy, PolicySpec};
+use std::path::PathBuf;
+
+fn repo_file(path: &str) -> String {
+    let mut p = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    p.push("../../");
+    p.push(path);
+    p.to_string_lossy().to_string()
+}
+
+fn friendly_policy() -> PolicySpec {
+    load_policy_from_path(&repo_file("tests/resource_units/friendly.yaml")).expect("load policy")
+}
+
+fn normalized(cpu: &str, memory: &str) -> (String, String) {
+    let mut policy = friendly_policy();
+    policy.resources.cpu.max = cpu.to_string();
+    policy.resources.memory.max = memory.to_string();
+    let result = validate_policy(policy);
+    assert!(result.valid, "{cpu} / {memory}: {:?}", result.errors);
+    let resources = result
+        .normalized_policy
+        .expect("normalized policy")
+        .resources;
+    (resources.cpu.max, resources.memory.max)
+}
+
+fn resource_error(cpu: &str, memory: &str) -> (String, String) {
+    let mut policy = friendly_policy();
+    policy.resources.cpu.max = cpu.to_string();
+    policy.resources.memory.max = memory.to_string();
+    let result = validate_policy(policy);
+    assert!(!result.valid, "{cpu} / {memory} should be rejected");
+    assert_eq!(result.errors.len(), 1, "{:?}", result.errors);
+    assert_eq!(result.errors[0].code, SR_POL_002);
+    (
+        result.errors[0].path.clone(),
+        result.errors[0].message.clone(),
+    )
+}
+
+#[test]
+fn numeric_cpu_and_decimal_memory_load_and_normalize() {
+    let result = validate_policy(friendly_policy());
+    assert!(result.valid, "{:?}", result.errors);
+    let resources = result
+        .normalized_policy
+        .expect("normalized policy")
+        .resources;
+    assert_eq!(resources.cpu.max, "150000 100000");
+    // 1G = 10^9 bytes, rounded down to whole MiB.
+    assert_eq!(resources.memory.max, "953Mi");
+}
+
+#[test]
+fn friendly_units_normalize_to_canonical_forms() {
+    let cases = [
+        (("1.5", "256Mi"), ("150000 100000", "256Mi")),
+        (("50%", "2048Mi"), ("50000 100000", "2Gi")),
+        (("2", "512MB"), ("200000 100000", "488Mi")),
+        (("12.5%", "1.5Gi"), ("12500 100000", "1536Mi")),
+        (("0.25", "2GB"), ("25000 100000", "1907Mi")),
+        (("max", "1536Ki"), ("max 100000", "1Mi")),
+    ];
+    for ((cpu, memory), (want_cpu, want_memory)) in cases {
+        assert_eq!(
+            normalized(cpu, memory),
+            (want_cpu.to_string(), want_memory.to_string())
+        );
+    }
+}
+
+#[test]
+fn raw_cgroup_values_are_kept() {
+    assert_eq!(
+        normalized("100000  100000", "256Mi"),
+        ("100000 100000".to_string(), "256Mi".to_string())
+    );
+    assert_eq!(
+        normalized("max 100000", "1Gi"),
+        ("max 100000".to_string(), "1Gi".to_string())
+    );
+}
+
+#[test]
+fn malformed_and_zero_sized_values_are_rejected() {
+    for cpu in ["1.5 cpus", "-1", "1e3", "1.", "50 %", "0.001", "0%"] {
+        let (path, _) = resource_error(cpu, "256Mi");
+        assert_eq!(path, "resources.cpu.max", "{cpu}");
+    }
+    for memory in ["256MiB", "256", "1.5.0Gi", "Mi"] {
+        let (path, _) = resource_error("1", memory);
+        assert_eq!(path, "resources.memory.max", "{memory}");
+    }
+    let (_, message) = resource_error("1", "512Ki");
+    assert!(message.contains("rounds down to 0 MiB"), "{message}");
+    let (_, message) = resource_error("0.001", "256Mi");
+    assert!(message.contains("minimum"), "{message}");
+}
+
+#[test]
+fn memory_above_host_total_is_rejected() {
+    let (path, message) = resource_error("1", "65536Gi");
+    assert_eq!(path, "resources.memory.max");
+    assert!(message.contains("exceeds host memory"), "{message}");
+}
+
+#[test]
+fn memory_mib_rounds_down_without_truncating_to_zero_silently() {
+    assert_eq!(memory_mib("1536Ki"), Some(1));
+    assert_eq!(memory_mib("512Ki"), Some(0));
+    assert_eq!(memory_mib("1G"), Some(953));
+    assert_eq!(memory_mib("2Gi"), Some(2048));
+    assert_eq!(memory_mib("lots"), None);
+}
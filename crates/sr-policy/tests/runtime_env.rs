@@ -0,0 +1,64 @@
+use sr_common::SR_POL_002;
+use sr_policy::{load_policy_from_path, validate_policy};
+use std::path::PathBuf;
+
+fn repo_file(path: &str) -> String {
+    let mut p = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    p.push("../../");
+    p.push(path);
+    p.to_string_lossy().to_string()
+}
+
+#[test]
+fn runtime_env_cwd_and_user_survive_normalization() {
+    let policy = load_policy_from_path(&repo_file("tests/runtime_env/valid.yaml"))
+        .expect("load runtime env policy");
+    let validation = validate_policy(policy);
+    assert!(validation.valid, "{:?}", validation.errors);
+
+    let runtime = validation
+        .normalized_policy
+        .expect("normalized policy")
+        .runtime;
+    assert_eq!(runtime.env.get("LANG").map(String::as_str), Some("C.UTF-8"));
+    assert_eq!(runtime.env_passthrough, vec!["SAFE_RUN_TEST_TOKEN"]);
+    assert_eq!(runtime.cwd.as_deref(), Some("/work"));
+    let user = runtime.user.expect("runtime user");
+    assert_eq!((user.uid, user.gid), (1000, Some(1000)));
+}
+
+#[test]
+fn runtime_env_violations_are_reported_per_field() {
+    let policy = load_policy_from_path(&repo_file("tests/runtime_env/invalid.yaml"))
+        .expect("load invalid runtime env policy");
+    let validation = validate_policy(policy);
+    assert!(!validation.valid);
+    assert!(validation.errors.iter().all(|err| err.code == SR_POL_002));
+
+    let paths = validation
+        .errors
+        .iter()
+        .map(|err| err.path.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        vec![
+            "runtime.env.1BAD",
+            "runtime.envPassthrough[0]",
+            "runtime.envPassthrough[1]",
+            "runtime.envPassthrough[3]",
+            "runtime.cwd",
+        ]
+    );
+}
+
+#[test]
+fn policies_without_runtime_context_serialize_unchanged() {
+    let policy = load_policy_from_path(&repo_file("tests/compile_snapshot/minimal_policy.yaml"))
+        .expect("load minimal policy");
+    let runtime = serde_json::to_value(&policy.runtime).expect("serialize runtime");
+    assert_eq!(
+        runtime,
+        serde_json::json!({"command": "/bin/echo", "args": ["hello"]})
+    );
+}
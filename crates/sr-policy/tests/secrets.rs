@@ -0,0 +1,141 @@
+mod common;
+
+use sr_common::{SR_POL_001, SR_POL_002, SR_POL_003, SR_POL_101};
+use sr_policy::{
+    load_policy_from_path, validate_policy, validate_policy_with_allowlist, PolicySpec,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use common::{repo_file, unique_temp_dir};
+
+/// Allowlist permitting the `valid.yaml` env secret and files under `<base>/secrets`, with
+/// `<base>/secrets/private` denied.
+fn secrets_allowlist(base: &Path) -> String {
+    let secrets = base.join("secrets");
+    fs::create_dir_all(secrets.join("private")).expect("create secrets dir");
+    let path = base.join("allowlist.yaml");
+    fs::write(
+        &path,
+        format!(
+            "schemaVersion: safe-run.mount-allowlist/v1\nhostAllowPrefixes:\n  - /var/lib/safe-run\nguestAllowPrefixes:\n  - /data\nhostDenyPatterns:\n  - {}/private\nsecretFilePrefixes:\n  - {}\nsecretEnvNames:\n  - SAFE_RUN_TEST_API_TOKEN\n",
+            secrets.display(),
+            secrets.display()
+        ),
+    )
+    .expect("write allowlist");
+    path.to_string_lossy().to_string()
+}
+
+fn valid_policy_with_file(file: &Path) -> PolicySpec {
+    let mut policy =
+        load_policy_from_path(&repo_file("tests/secrets/valid.yaml")).expect("load secrets policy");
+    policy.secrets[1].from_file = Some(file.to_string_lossy().to_string());
+    policy
+}
+
+fn secret_errors(policy: PolicySpec, allowlist: &str) -> Vec<(String, String)> {
+    let validation = validate_policy_with_allowlist(policy, Some(allowlist));
+    assert!(!validation.valid);
+    validation
+        .errors
+        .into_iter()
+        .map(|err| (err.code, err.path))
+        .collect()
+}
+
+#[test]
+fn secret_references_are_kept_in_normalized_policy() {
+    let base = unique_temp_dir("secrets-valid");
+    let allowlist = secrets_allowlist(&base);
+    let file = base.join("secrets/db-password");
+    fs::write(&file, "hunter2").expect("write secret file");
+    // Requested through a `..` detour; the canonical path is what gets stored.
+    let detour = base.join("secrets/private/../db-password");
+
+    let validation =
+        validate_policy_with_allowlist(valid_policy_with_file(&detour), Some(&allowlist));
+    assert!(validation.valid, "{:?}", validation.errors);
+
+    let normalized = validation.normalized_policy.expect("normalized policy");
+    let names = normalized
+        .secrets
+        .iter()
+        .map(|secret| secret.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["API_TOKEN", "DB_PASSWORD"]);
+    let canonical = fs::canonicalize(&file).expect("canonical secret file");
+    assert_eq!(
+        normalized.secrets[1].from_file.as_deref(),
+        Some(canonical.to_string_lossy().as_ref())
+    );
+    let value = serde_json::to_value(&normalized).expect("serialize policy");
+    assert_eq!(
+        value["secrets"][0],
+        serde_json::json!({"name": "API_TOKEN", "fromEnv": "SAFE_RUN_TEST_API_TOKEN"})
+    );
+    let _ = fs::remove_dir_all(&base);
+}
+
+#[test]
+fn invalid_secret_references_are_reported_per_field() {
+    let policy = load_policy_from_path(&repo_file("tests/secrets/invalid.yaml"))
+        .expect("load invalid secrets policy");
+    let validation = validate_policy(policy);
+    assert!(!validation.valid);
+
+    let errors = validation
+        .errors
+        .iter()
+        .map(|err| (err.code.as_str(), err.path.as_str()))
+        .collect::<Vec<_>>();
+    // The built-in allowlist declares no secret env names.
+    assert_eq!(
+        errors,
+        vec![
+            (SR_POL_002, "secrets[0].name"),
+            (SR_POL_101, "secrets[0].fromEnv"),
+            (SR_POL_001, "secrets[1]"),
+            (SR_POL_002, "secrets[2]"),
+            (SR_POL_002, "secrets[3].fromFile"),
+            (SR_POL_003, "secrets[4].fromEnv"),
+            (SR_POL_002, "secrets[5].name"),
+            (SR_POL_101, "secrets[5].fromEnv"),
+        ]
+    );
+}
+
+#[test]
+fn secret_sources_outside_the_operator_allowlist_are_rejected() {
+    let base = unique_temp_dir("secrets-allowlist");
+    let allowlist = secrets_allowlist(&base);
+    let denied = base.join("secrets/private/key");
+    fs::write(&denied, "k").expect("write denied file");
+    let link = base.join("secrets/shadow");
+    std::os::unix::fs::symlink("/etc/passwd", &link).expect("create symlink");
+    let expected = |field: &str| vec![(SR_POL_101.to_string(), format!("secrets[1].{field}"))];
+
+    for file in [
+        PathBuf::from("/etc/passwd"),
+        link.clone(),
+        denied.clone(),
+        base.join("secrets/missing"),
+        base.join("secrets"),
+    ] {
+        assert_eq!(
+            secret_errors(valid_policy_with_file(&file), &allowlist),
+            expected("fromFile"),
+            "{}",
+            file.display()
+        );
+    }
+
+    let mut policy = valid_policy_with_file(&denied);
+    policy.secrets.truncate(1);
+    policy.secrets[0].from_env = Some("AWS_SECRET_ACCESS_KEY".to_string());
+    assert_eq!(
+        secret_errors(policy, &allowlist),
+        vec![(SR_POL_101.to_string(), "secrets[0].fromEnv".to_string())]
+    );
+    let _ = fs::remove_dir_all(&base);
+}
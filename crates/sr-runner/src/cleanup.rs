@@ -1,14 +1,25 @@
-use crate::constants::{EVENT_RUN_CLEANED, STAGE_CLEANUP};
+use crate::constants::{
+    EVENT_MOUNT_OUTPUT_COLLECTED, EVENT_RUN_CLEANED, MOUNT_OUTPUTS_DIR, STAGE_CLEANUP,
+};
 use crate::event::write_event;
 use crate::model::{PreparedRun, RunState};
+use crate::secrets::{ensure_no_secret_leak, shred_secrets_image};
 use serde_json::json;
 use sr_common::{ErrorItem, SR_RUN_001};
+use sr_evidence::sha256_file;
+use sr_policy::MountMode;
 use std::fs;
 use std::path::Path;
 
 /// Release temporary runner resources and emit `run.cleaned`.
 /// The cleanup keeps evidence artifacts and report inputs while removing transient runtime files.
+/// The secrets drive is shredded first, and the finished event log is checked for leaked
+/// secret values before they are dropped from memory.
 pub(crate) fn cleanup_run(prepared: &mut PreparedRun) -> Result<(), ErrorItem> {
+    if let Some(plan) = prepared.secret_plan.as_ref() {
+        shred_secrets_image(prepared.workdir(), plan)?;
+    }
+    collect_mount_drives(prepared)?;
     remove_file_if_exists(
         prepared.runtime_context_path().as_path(),
         "cleanup.runtimeContext",
@@ -30,7 +41,68 @@ pub(crate) fn cleanup_run(prepared: &mut PreparedRun) -> Result<(), ErrorItem> {
             "state": state_label(prepared.state),
             "cleanupMarker": prepared.cleanup_marker_path()
         }),
-    )
+    )?;
+    if prepared.secrets.is_empty() {
+        return Ok(());
+    }
+    let leak_check = ensure_no_secret_leak(&[&prepared.event_log_path()], &prepared.secrets);
+    prepared.secrets.clear();
+    leak_check
+}
+
+/// Move `output` images into `artifacts/outputs/` and record their hash; drop `scratch` images.
+fn collect_mount_drives(prepared: &mut PreparedRun) -> Result<(), ErrorItem> {
+    let drives = prepared.mount_plan.drives.clone();
+    for drive in drives {
+        let error_path = format!("cleanup.mountImages.{}", drive.drive_id);
+        let image_path = prepared.workdir().join(&drive.image);
+        if drive.mode == MountMode::Scratch {
+            remove_file_if_exists(&image_path, &error_path)?;
+            continue;
+        }
+        let artifact = format!("{MOUNT_OUTPUTS_DIR}/{}.img", drive.drive_id);
+        let artifact_path = prepared.artifacts_dir().join(&artifact);
+        let collected = fs::create_dir_all(prepared.artifacts_dir().join(MOUNT_OUTPUTS_DIR))
+            .and_then(|_| fs::rename(&image_path, &artifact_path))
+            .and_then(|_| {
+                let size_bytes = fs::metadata(&artifact_path)?.len();
+                Ok((sha256_file(&artifact_path)?, size_bytes))
+            });
+        let (sha256, size_bytes) = collected.map_err(|err| {
+            ErrorItem::new(
+                SR_RUN_001,
+                error_path.as_str(),
+                format!(
+                    "failed to collect output image '{}': {err}",
+                    image_path.display()
+                ),
+            )
+        })?;
+        if output_event_enabled(prepared) {
+            write_event(
+                prepared,
+                STAGE_CLEANUP,
+                EVENT_MOUNT_OUTPUT_COLLECTED,
+                json!({
+                    "driveId": drive.drive_id,
+                    "target": drive.target,
+                    "artifact": artifact,
+                    "sha256": sha256,
+                    "sizeBytes": size_bytes
+                }),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn output_event_enabled(prepared: &PreparedRun) -> bool {
+    prepared.evidence_plan.enabled
+        && prepared
+            .evidence_plan
+            .events
+            .iter()
+            .any(|event| event == EVENT_MOUNT_OUTPUT_COLLECTED)
 }
 
 fn remove_file_if_exists(path: &Path, error_path: &str) -> Result<(), ErrorItem> {
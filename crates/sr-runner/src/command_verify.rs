@@ -0,0 +1,102 @@
+use crate::constants::{EVENT_COMMAND_VERIFIED, STAGE_LAUNCH};
+use crate::event::write_event;
+use crate::model::PreparedRun;
+use serde_json::json;
+use sr_common::{ErrorItem, SR_RUN_401};
+use sr_evidence::sha256_bytes;
+use std::path::Path;
+use std::process::Command;
+
+/// Reads a file out of the guest rootfs image without booting it.
+pub trait RootfsReader {
+    fn read_file(&self, image: &Path, guest_path: &str) -> Result<Vec<u8>, String>;
+}
+
+/// Reads ext4 images through `debugfs` (e2fsprogs), so no mount or root is needed.
+/// Symlinks in parent directories are followed; a symlink as the final component is not.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DebugfsRootfsReader;
+
+impl RootfsReader for DebugfsRootfsReader {
+    fn read_file(&self, image: &Path, guest_path: &str) -> Result<Vec<u8>, String> {
+        // The path is quoted into a debugfs request; refuse anything that could escape it.
+        if guest_path
+            .chars()
+            .any(|ch| matches!(ch, '"' | '\\') || ch.is_whitespace() || ch.is_control())
+        {
+            return Err(format!(
+                "guest path '{}' contains characters debugfs cannot read safely",
+                guest_path.escape_debug()
+            ));
+        }
+        let output = Command::new("debugfs")
+            .arg("-R")
+            .arg(format!("cat \"{guest_path}\""))
+            .arg(image)
+            .output()
+            .map_err(|err| format!("failed to run debugfs: {err}"))?;
+        // debugfs exits 0 on lookup failures; anything besides its version banner is an error.
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if let Some(line) = stderr
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with("debugfs "))
+        {
+            return Err(line.to_string());
+        }
+        if !output.status.success() {
+            return Err(format!("debugfs exited with {}", output.status));
+        }
+        Ok(output.stdout)
+    }
+}
+
+/// Check the pinned command binary inside the rootfs and record `command.verified`.
+/// Unpinned commands are not read.
+/// Error mapping: unreadable binary or digest mismatch -> `SR-RUN-401`.
+pub(crate) fn verify_command_binary(
+    prepared: &mut PreparedRun,
+    reader: &dyn RootfsReader,
+) -> Result<(), ErrorItem> {
+    let Some(expected) = prepared.command_sha256.clone() else {
+        return Ok(());
+    };
+    let command = prepared.command_payload.command.clone();
+    let rootfs = prepared.rootfs_path().to_path_buf();
+    let binary = reader.read_file(&rootfs, &command).map_err(|err| {
+        verify_error(format!(
+            "failed to read command '{command}' from rootfs '{}': {err}",
+            rootfs.display()
+        ))
+    })?;
+    let actual = sha256_bytes(&binary);
+    if actual != expected {
+        return Err(verify_error(format!(
+            "command '{command}' in rootfs has {actual}, expected {expected}"
+        )));
+    }
+
+    if prepared.evidence_plan.enabled
+        && prepared
+            .evidence_plan
+            .events
+            .iter()
+            .any(|event| event == EVENT_COMMAND_VERIFIED)
+    {
+        write_event(
+            prepared,
+            STAGE_LAUNCH,
+            EVENT_COMMAND_VERIFIED,
+            json!({
+                "command": command,
+                "sha256": actual,
+                "sizeBytes": binary.len()
+            }),
+        )?;
+    }
+    Ok(())
+}
+
+fn verify_error(message: String) -> ErrorItem {
+    ErrorItem::new(SR_RUN_401, "launch.command.verify", message)
+}
@@ -4,11 +4,23 @@ pub(crate) const REPORT_FILE: &str = "run_report.json";
 pub(crate) const FIRECRACKER_CONFIG_FILE: &str = "firecracker-config.json";
 pub(crate) const FIRECRACKER_API_SOCKET_FILE: &str = "firecracker.socket";
 pub(crate) const RUNTIME_CONTEXT_FILE: &str = "runtime-context.json";
+pub(crate) const COMMAND_PAYLOAD_FILE: &str = "command.json";
 pub(crate) const VM_PID_FILE: &str = "vm.pid";
+pub(crate) const VM_STDOUT_LOG_FILE: &str = "vm.stdout.log";
+pub(crate) const VM_STDERR_LOG_FILE: &str = "vm.stderr.log";
+pub(crate) const VM_LOG_MAX_BYTES: u64 = 8 * 1024 * 1024;
+pub(crate) const MOUNT_OUTPUTS_DIR: &str = "outputs";
 pub(crate) const CLEANUP_MARKER_FILE: &str = "cleanup.invoked";
 pub(crate) const CGROUP_CPU_STAT_FILE: &str = "cpu.stat";
 pub(crate) const CGROUP_MEMORY_CURRENT_FILE: &str = "memory.current";
+pub(crate) const CGROUP_MEMORY_PEAK_FILE: &str = "memory.peak";
+pub(crate) const CGROUP_MEMORY_EVENTS_FILE: &str = "memory.events";
+pub(crate) const CGROUP_IO_STAT_FILE: &str = "io.stat";
+pub(crate) const CGROUP_PIDS_CURRENT_FILE: &str = "pids.current";
 pub(crate) const DEFAULT_SAMPLE_INTERVAL_MS: u64 = 1000;
+pub(crate) const DEFAULT_MOUNT_SCAN_MAX_ENTRIES: u64 = 100_000;
+pub(crate) const SECRET_SALT_BYTES: usize = 16;
+pub(crate) const SECRET_LEAK_MIN_LEN: usize = 8;
 pub(crate) const DEFAULT_CGROUP_PATH: &str = "/sys/fs/cgroup";
 pub(crate) const GENESIS_HASH: &str =
     "sha256:0000000000000000000000000000000000000000000000000000000000000000";
@@ -23,6 +35,14 @@ pub(crate) const EVENT_RUN_PREPARED: &str = sr_evidence::EVENT_RUN_PREPARED;
 pub(crate) const EVENT_MOUNT_VALIDATED: &str = sr_evidence::EVENT_MOUNT_VALIDATED;
 pub(crate) const EVENT_MOUNT_REJECTED: &str = sr_evidence::EVENT_MOUNT_REJECTED;
 pub(crate) const EVENT_MOUNT_APPLIED: &str = sr_evidence::EVENT_MOUNT_APPLIED;
+pub(crate) const EVENT_MOUNT_OUTPUT_COLLECTED: &str = sr_evidence::EVENT_MOUNT_OUTPUT_COLLECTED;
+pub(crate) const EVENT_MOUNT_SCAN: &str = sr_evidence::EVENT_MOUNT_SCAN;
+pub(crate) const EVENT_SECRETS_INJECTED: &str = sr_evidence::EVENT_SECRETS_INJECTED;
+pub(crate) const EVENT_COMMAND_VERIFIED: &str = sr_evidence::EVENT_COMMAND_VERIFIED;
+pub(crate) const MOUNT_REJECT_SOURCE_CHANGED: &str = "mount.source_changed";
+pub(crate) const MOUNT_REJECT_SCAN_FINDINGS: &str = "mount.scan_findings";
+pub(crate) const MOUNT_REJECT_SCAN_BUDGET: &str = "mount.scan_budget_exceeded";
+pub(crate) const MOUNT_REJECT_SCAN_FAILED: &str = "mount.scan_failed";
 pub(crate) const EVENT_NETWORK_PLAN_GENERATED: &str = sr_evidence::EVENT_NETWORK_PLAN_GENERATED;
 pub(crate) const EVENT_NETWORK_RULE_APPLIED: &str = sr_evidence::EVENT_NETWORK_RULE_APPLIED;
 #[allow(dead_code)]
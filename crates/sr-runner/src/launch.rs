@@ -1,9 +1,12 @@
-use crate::constants::{ARTIFACTS_DIR, FIRECRACKER_API_SOCKET_FILE, FIRECRACKER_CONFIG_FILE};
+use crate::constants::{
+    ARTIFACTS_DIR, COMMAND_PAYLOAD_FILE, FIRECRACKER_API_SOCKET_FILE, FIRECRACKER_CONFIG_FILE,
+};
 use crate::model::{CommandSpec, LaunchPlan, RunnerRuntime};
 use sr_compiler::CompileBundle;
 use std::path::Path;
 
 /// Build jailer and Firecracker command lines based on the compile bundle.
+/// The command payload is loaded into the guest metadata service via `--metadata`.
 pub(crate) fn assemble_launch_plan(
     run_id: &str,
     workdir: &Path,
@@ -23,6 +26,11 @@ pub(crate) fn assemble_launch_plan(
             .join(FIRECRACKER_CONFIG_FILE)
             .to_string_lossy()
             .to_string(),
+        "--metadata".to_string(),
+        workdir
+            .join(COMMAND_PAYLOAD_FILE)
+            .to_string_lossy()
+            .to_string(),
     ];
 
     let mut jailer_args = vec![
@@ -1,4 +1,5 @@
 mod cleanup;
+mod command_verify;
 mod constants;
 mod event;
 mod launch;
@@ -9,10 +10,12 @@ mod network_lifecycle;
 mod prepare;
 mod rollback;
 mod runner;
+mod secrets;
 mod utils;
 
+pub use command_verify::{DebugfsRootfsReader, RootfsReader};
 pub use model::{
-    CommandSpec, LaunchPlan, MonitorResult, PreparedRun, RunArtifacts, RunState,
+    CommandPayload, CommandSpec, LaunchPlan, MonitorResult, PreparedRun, RunArtifacts, RunState,
     RunnerControlRequest, RunnerControlResponse, RunnerRuntime, RuntimeContext,
 };
 pub use network_lifecycle::{
@@ -26,15 +29,17 @@ pub use runner::Runner;
 mod tests {
     use super::*;
     use crate::constants::{
-        EVENT_COMPILE, EVENT_MOUNT_APPLIED, EVENT_MOUNT_REJECTED, EVENT_MOUNT_VALIDATED,
-        EVENT_RESOURCE_SAMPLED, EVENT_RUN_CLEANED, EVENT_RUN_FAILED, EVENT_RUN_PREPARED,
-        EVENT_VM_EXITED, EVENT_VM_STARTED, STAGE_LAUNCH, STAGE_MOUNT,
+        EVENT_COMPILE, EVENT_MOUNT_APPLIED, EVENT_MOUNT_REJECTED, EVENT_MOUNT_SCAN,
+        EVENT_MOUNT_VALIDATED, EVENT_RESOURCE_SAMPLED, EVENT_RUN_CLEANED, EVENT_RUN_FAILED,
+        EVENT_RUN_PREPARED, EVENT_VM_EXITED, EVENT_VM_STARTED, STAGE_LAUNCH, STAGE_MOUNT,
+        VM_LOG_MAX_BYTES,
     };
-    use crate::mount_executor::{MountApplier, MountExecutor, MountRollbacker};
+    use crate::mount_executor::{MountApplier, MountApplyError, MountExecutor, MountRollbacker};
     use serde_json::json;
     use sr_common::{SR_RUN_001, SR_RUN_002, SR_RUN_003, SR_RUN_101};
-    use sr_compiler::{CompileBundle, EvidencePlan, MountPlan, MountPlanEntry, Plan};
-    use sr_evidence::EvidenceEvent;
+    use sr_compiler::{CommandPlan, CompileBundle, EvidencePlan, MountPlan, MountPlanEntry, Plan};
+    use sr_evidence::{mount_audit_from_events, EvidenceEvent};
+    use std::collections::BTreeMap;
     use std::fs;
     use std::path::{Path, PathBuf};
     use std::sync::atomic::{AtomicU64, Ordering};
@@ -69,6 +74,7 @@ mod tests {
             },
             mount_plan: MountPlan {
                 enabled: true,
+                drives: Vec::new(),
                 mounts: vec![],
             },
             network_plan: None,
@@ -80,10 +86,21 @@ mod tests {
                     EVENT_MOUNT_VALIDATED.to_string(),
                     EVENT_MOUNT_REJECTED.to_string(),
                     EVENT_MOUNT_APPLIED.to_string(),
+                    EVENT_MOUNT_SCAN.to_string(),
                     EVENT_VM_STARTED.to_string(),
                     EVENT_RUN_FAILED.to_string(),
                 ],
             },
+            command_plan: CommandPlan {
+                command: "/bin/echo".to_string(),
+                args: vec!["ok".to_string()],
+                env: BTreeMap::new(),
+                env_passthrough: Vec::new(),
+                cwd: "/".to_string(),
+                user: None,
+                command_sha256: None,
+            },
+            secret_plan: None,
         }
     }
 
@@ -120,6 +137,7 @@ mod tests {
                 timeout_sec: 300,
                 sample_interval_ms: None,
                 cgroup_path: None,
+                mount_scan_max_entries: None,
             },
         }
     }
@@ -131,6 +149,7 @@ mod tests {
         let mut bundle = sample_compile_bundle();
         bundle.mount_plan = MountPlan {
             enabled: true,
+            drives: Vec::new(),
             mounts,
         };
         RunnerControlRequest {
@@ -140,6 +159,7 @@ mod tests {
                 timeout_sec: 300,
                 sample_interval_ms: None,
                 cgroup_path: None,
+                mount_scan_max_entries: None,
             },
         }
     }
@@ -151,14 +171,14 @@ mod tests {
     }
 
     impl MountApplier for RecordingApplier {
-        fn apply(&self, entry: &MountPlanEntry) -> Result<(), String> {
+        fn apply(&self, entry: &MountPlanEntry) -> Result<(), MountApplyError> {
             self.calls
                 .lock()
                 .expect("lock calls")
                 .push(entry.target.clone());
             if let Some(target) = &self.fail_on {
                 if &entry.target == target {
-                    return Err("apply failed".to_string());
+                    return Err("apply failed".to_string().into());
                 }
             }
             Ok(())
@@ -217,6 +237,7 @@ mod tests {
                 timeout_sec: 300,
                 sample_interval_ms: Some(200),
                 cgroup_path: Some("/sys/fs/cgroup/demo".to_string()),
+                mount_scan_max_entries: None,
             },
         };
 
@@ -372,14 +393,16 @@ mod tests {
         let runner = runner_with_mount_executor(executor);
         let mounts = vec![
             MountPlanEntry {
-                source: "/var/lib/safe-run/input".to_string(),
+                source: mount_source(&run_dir, "input"),
                 target: "/data/input".to_string(),
                 read_only: true,
+                source_identity: None,
             },
             MountPlanEntry {
-                source: "/var/lib/safe-run/output".to_string(),
+                source: mount_source(&run_dir, "output"),
                 target: "/data/output".to_string(),
                 read_only: true,
+                source_identity: None,
             },
         ];
         let mut prepared = runner
@@ -435,14 +458,16 @@ mod tests {
         let runner = runner_with_mount_executor(executor);
         let mounts = vec![
             MountPlanEntry {
-                source: "/var/lib/safe-run/input".to_string(),
+                source: mount_source(&run_dir, "input"),
                 target: "/data/input".to_string(),
                 read_only: true,
+                source_identity: None,
             },
             MountPlanEntry {
-                source: "/var/lib/safe-run/output".to_string(),
+                source: mount_source(&run_dir, "output"),
                 target: "/data/output".to_string(),
                 read_only: true,
+                source_identity: None,
             },
         ];
         let mut prepared = runner
@@ -463,6 +488,134 @@ mod tests {
         let _ = fs::remove_dir_all(&run_dir);
     }
 
+    /// Mount sources are scanned before attach, so they must exist.
+    fn mount_source(run_dir: &Path, name: &str) -> String {
+        let source = run_dir.join("sources").join(name);
+        fs::create_dir_all(&source).expect("create mount source");
+        source.to_string_lossy().to_string()
+    }
+
+    fn read_events(prepared: &PreparedRun) -> Vec<EvidenceEvent> {
+        std::fs::read_to_string(prepared.event_log_path())
+            .expect("read event stream")
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).expect("parse event"))
+            .collect()
+    }
+
+    fn recording_executor(calls: Arc<Mutex<Vec<String>>>) -> MountExecutor {
+        MountExecutor::new(
+            RecordingApplier {
+                calls,
+                fail_on: None,
+            },
+            RecordingRollbacker {
+                calls: Arc::new(Mutex::new(Vec::new())),
+            },
+        )
+    }
+
+    #[test]
+    fn launch_scans_mount_sources_before_apply() {
+        let run_dir = new_temp_run_dir("mount-scan-clean");
+        write_mock_vm_artifacts(&run_dir);
+        let source = run_dir.join("source");
+        fs::create_dir_all(source.join("nested")).expect("create source dir");
+        fs::write(source.join("a.txt"), b"abc").expect("write file");
+        fs::write(source.join("nested/b.txt"), b"de").expect("write file");
+        std::os::unix::fs::symlink("../a.txt", source.join("nested/link"))
+            .expect("create internal symlink");
+        let apply_calls = Arc::new(Mutex::new(Vec::new()));
+        let runner = runner_with_mount_executor(recording_executor(apply_calls.clone()));
+        let mounts = vec![MountPlanEntry {
+            source: source.to_string_lossy().to_string(),
+            target: "/data/input".to_string(),
+            read_only: true,
+            source_identity: None,
+        }];
+        let mut prepared = runner
+            .prepare(sample_request_with_mounts(&run_dir, mounts))
+            .expect("prepare should succeed");
+
+        runner.launch(&mut prepared).expect("launch should succeed");
+
+        let events = read_events(&prepared);
+        let scan_idx = events
+            .iter()
+            .position(|event| event.event_type == EVENT_MOUNT_SCAN)
+            .expect("mount.scan event");
+        let validated_idx = events
+            .iter()
+            .position(|event| event.event_type == EVENT_MOUNT_VALIDATED)
+            .expect("mount.validated event");
+        assert!(scan_idx < validated_idx);
+        let payload = &events[scan_idx].payload;
+        assert_eq!(payload["files"], 3);
+        assert_eq!(payload["directories"], 2);
+        assert_eq!(payload["totalBytes"], 5);
+        assert_eq!(payload["findingsTotal"], 0);
+        assert_eq!(apply_calls.lock().expect("lock apply calls").len(), 1);
+
+        let audit = mount_audit_from_events(&events);
+        assert_eq!(audit.scans.len(), 1);
+        assert_eq!(audit.scans[0].target, "/data/input");
+
+        let _ = fs::remove_dir_all(&run_dir);
+    }
+
+    #[test]
+    fn launch_rejects_mount_on_scan_findings_or_exhausted_budget() {
+        let run_dir = new_temp_run_dir("mount-scan-reject");
+        write_mock_vm_artifacts(&run_dir);
+        let source = run_dir.join("source");
+        fs::create_dir_all(&source).expect("create source dir");
+        fs::write(source.join("a.txt"), b"abc").expect("write file");
+        std::os::unix::fs::symlink("/etc/shadow", source.join("shadow"))
+            .expect("create escaping symlink");
+        for (name, budget, reason) in [
+            ("source", None, "mount.scan_findings"),
+            ("source", Some(2), "mount.scan_budget_exceeded"),
+            ("missing", None, "mount.scan_failed"),
+        ] {
+            let mounts = vec![MountPlanEntry {
+                source: run_dir.join(name).to_string_lossy().to_string(),
+                target: "/data/input".to_string(),
+                read_only: true,
+                source_identity: None,
+            }];
+            let apply_calls = Arc::new(Mutex::new(Vec::new()));
+            let runner = runner_with_mount_executor(recording_executor(apply_calls.clone()));
+            let workdir = run_dir.join(reason);
+            let mut request = sample_request_with_mounts(&workdir, mounts);
+            request.runtime_context.mount_scan_max_entries = budget;
+            write_mock_vm_artifacts(&workdir);
+            let mut prepared = runner.prepare(request).expect("prepare should succeed");
+
+            let err = runner.launch(&mut prepared).expect_err("launch must fail");
+            assert_eq!(err.code, SR_RUN_101);
+            assert_eq!(err.path, "mount.scan");
+            assert!(apply_calls.lock().expect("lock apply calls").is_empty());
+
+            let events = read_events(&prepared);
+            let audit = mount_audit_from_events(&events);
+            assert_eq!(audit.rejected, 1);
+            assert_eq!(audit.reasons, vec![reason.to_string()]);
+            match (name, budget) {
+                ("missing", _) => assert!(audit.scans.is_empty()),
+                (_, None) => {
+                    assert_eq!(audit.scans[0].findings[0].kind, "escaping_symlink")
+                }
+                _ => assert!(audit.scans[0].budget_exhausted),
+            }
+            assert!(events
+                .iter()
+                .any(|event| event.event_type == EVENT_RUN_FAILED));
+        }
+
+        let _ = fs::remove_dir_all(&run_dir);
+    }
+
     #[test]
     fn launch_failure_returns_run_002_and_invokes_cleanup() {
         let run_dir = new_temp_run_dir("launch-failure");
@@ -547,6 +700,56 @@ mod tests {
         let _ = fs::remove_dir_all(&run_dir);
     }
 
+    #[test]
+    fn launch_caps_vm_logs_and_drains_the_rest() {
+        let run_dir = new_temp_run_dir("vm-log-cap");
+        write_mock_vm_artifacts(&run_dir);
+        let cgroup_dir = run_dir.join("mock-cgroup");
+        set_mock_cgroup(&cgroup_dir, 100, 4096);
+        let runner = runner_for_tests();
+        let mut request = sample_request(&run_dir);
+        request.runtime_context.timeout_sec = 10;
+        request.runtime_context.sample_interval_ms = Some(20);
+        request.runtime_context.cgroup_path = Some(cgroup_dir.to_string_lossy().to_string());
+
+        let mut prepared = runner.prepare(request).expect("prepare should succeed");
+        prepared.launch_plan.jailer = CommandSpec {
+            program: "/bin/sh".to_string(),
+            args: vec![
+                "-c".to_string(),
+                format!(
+                    "head -c {} /dev/zero; echo done >&2",
+                    VM_LOG_MAX_BYTES + 4096
+                ),
+            ],
+        };
+        runner.launch(&mut prepared).expect("launch should succeed");
+        let result = runner
+            .monitor(&mut prepared)
+            .expect("monitor should succeed");
+        assert_eq!(result.exit_code, 0);
+
+        // The capture threads finish once the pipes close; give them a moment to flush.
+        let marker = format!(
+            "\n[safe-run: log truncated at {VM_LOG_MAX_BYTES} bytes, 4096 bytes dropped]\n"
+        );
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        let (stdout, stderr) = loop {
+            let stdout = fs::read(prepared.vm_stdout_log_path()).expect("read stdout log");
+            let stderr = fs::read(prepared.vm_stderr_log_path()).expect("read stderr log");
+            let flushed = stdout.ends_with(marker.as_bytes()) && stderr == b"done\n";
+            if flushed || std::time::Instant::now() > deadline {
+                break (stdout, stderr);
+            }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        };
+        assert!(stdout.ends_with(marker.as_bytes()));
+        assert_eq!(stdout.len() as u64, VM_LOG_MAX_BYTES + marker.len() as u64);
+        assert_eq!(stderr, b"done\n");
+
+        let _ = fs::remove_dir_all(&run_dir);
+    }
+
     #[test]
     fn monitor_timeout_returns_run_003_and_sets_failed_state() {
         let run_dir = new_temp_run_dir("monitor-timeout");
@@ -1,10 +1,14 @@
 use crate::constants::{
-    CLEANUP_MARKER_FILE, DEFAULT_CGROUP_PATH, DEFAULT_SAMPLE_INTERVAL_MS,
-    FIRECRACKER_API_SOCKET_FILE, FIRECRACKER_CONFIG_FILE, RUNTIME_CONTEXT_FILE, VM_PID_FILE,
+    CLEANUP_MARKER_FILE, COMMAND_PAYLOAD_FILE, DEFAULT_CGROUP_PATH, DEFAULT_MOUNT_SCAN_MAX_ENTRIES,
+    DEFAULT_SAMPLE_INTERVAL_MS, FIRECRACKER_API_SOCKET_FILE, FIRECRACKER_CONFIG_FILE,
+    RUNTIME_CONTEXT_FILE, VM_PID_FILE, VM_STDERR_LOG_FILE, VM_STDOUT_LOG_FILE,
 };
 use crate::network_lifecycle::AppliedNetwork;
+use crate::secrets::ResolvedSecrets;
 use serde::{Deserialize, Serialize};
-use sr_compiler::{CompileBundle, EvidencePlan, MountPlan, NetworkPlan};
+use sr_compiler::{CommandUser, CompileBundle, EvidencePlan, MountPlan, NetworkPlan, SecretPlan};
+use sr_policy::MountScanBudget;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
@@ -33,6 +37,13 @@ pub struct RuntimeContext {
         skip_serializing_if = "Option::is_none"
     )]
     pub cgroup_path: Option<String>,
+    /// Entry budget for each pre-attach mount source scan.
+    #[serde(
+        rename = "mountScanMaxEntries",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub mount_scan_max_entries: Option<u64>,
 }
 
 impl RuntimeContext {
@@ -43,6 +54,14 @@ impl RuntimeContext {
         Duration::from_millis(interval_ms)
     }
 
+    pub fn effective_mount_scan_budget(&self) -> MountScanBudget {
+        MountScanBudget {
+            max_entries: self
+                .mount_scan_max_entries
+                .unwrap_or(DEFAULT_MOUNT_SCAN_MAX_ENTRIES),
+        }
+    }
+
     pub fn effective_cgroup_path(&self) -> String {
         self.cgroup_path
             .clone()
@@ -104,6 +123,18 @@ impl Default for RunnerRuntime {
     }
 }
 
+/// Command handed to the guest through the Firecracker metadata service, with host
+/// passthrough variables resolved. `commandHash` in the report covers this exact payload.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CommandPayload {
+    pub command: String,
+    pub args: Vec<String>,
+    pub env: BTreeMap<String, String>,
+    pub cwd: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<CommandUser>,
+}
+
 #[derive(Debug, Clone)]
 pub struct PreparedRun {
     pub run_id: String,
@@ -116,8 +147,14 @@ pub struct PreparedRun {
     pub network_plan: Option<NetworkPlan>,
     pub applied_network: Option<AppliedNetwork>,
     pub evidence_plan: EvidencePlan,
+    pub command_payload: CommandPayload,
+    /// Digest the command binary in the rootfs must match before launch.
+    pub command_sha256: Option<String>,
+    pub secret_plan: Option<SecretPlan>,
+    pub(crate) secrets: ResolvedSecrets,
     pub(crate) workdir_path: PathBuf,
     pub(crate) artifacts_dir_path: PathBuf,
+    pub(crate) rootfs_path: PathBuf,
     pub(crate) last_event_hash: String,
 }
 
@@ -130,6 +167,11 @@ impl PreparedRun {
         &self.artifacts_dir_path
     }
 
+    /// Rootfs image the VM boots, after prepare materialized it into the workdir.
+    pub fn rootfs_path(&self) -> &Path {
+        &self.rootfs_path
+    }
+
     pub fn event_log_path(&self) -> PathBuf {
         self.artifacts_dir_path.join(&self.artifacts.log)
     }
@@ -142,6 +184,10 @@ impl PreparedRun {
         self.workdir_path.join(FIRECRACKER_CONFIG_FILE)
     }
 
+    pub fn command_payload_path(&self) -> PathBuf {
+        self.workdir_path.join(COMMAND_PAYLOAD_FILE)
+    }
+
     pub fn runtime_context_path(&self) -> PathBuf {
         self.workdir_path.join(RUNTIME_CONTEXT_FILE)
     }
@@ -150,6 +196,14 @@ impl PreparedRun {
         self.artifacts_dir_path.join(VM_PID_FILE)
     }
 
+    pub fn vm_stdout_log_path(&self) -> PathBuf {
+        self.artifacts_dir_path.join(VM_STDOUT_LOG_FILE)
+    }
+
+    pub fn vm_stderr_log_path(&self) -> PathBuf {
+        self.artifacts_dir_path.join(VM_STDERR_LOG_FILE)
+    }
+
     pub fn api_socket_path(&self) -> PathBuf {
         self.artifacts_dir_path.join(FIRECRACKER_API_SOCKET_FILE)
     }
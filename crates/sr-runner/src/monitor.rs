@@ -1,4 +1,7 @@
-use crate::constants::{CGROUP_CPU_STAT_FILE, CGROUP_MEMORY_CURRENT_FILE};
+use crate::constants::{
+    CGROUP_CPU_STAT_FILE, CGROUP_IO_STAT_FILE, CGROUP_MEMORY_CURRENT_FILE,
+    CGROUP_MEMORY_EVENTS_FILE, CGROUP_MEMORY_PEAK_FILE, CGROUP_PIDS_CURRENT_FILE,
+};
 use crate::constants::{EVENT_RESOURCE_SAMPLED, EVENT_RUN_FAILED, EVENT_VM_EXITED, STAGE_MONITOR};
 use crate::event::write_event;
 use crate::model::{MonitorResult, PreparedRun, RunState};
@@ -6,7 +9,7 @@ use nix::errno::Errno;
 use nix::sys::signal::{kill, Signal};
 use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
 use nix::unistd::Pid;
-use serde_json::json;
+use serde_json::{json, Map, Value};
 use sr_common::{ErrorItem, SR_RUN_001, SR_RUN_003};
 use std::fs;
 use std::path::Path;
@@ -17,6 +20,28 @@ use std::time::Instant;
 struct ResourceSample {
     cpu_usage_usec: u64,
     memory_current_bytes: u64,
+    /// Optional cgroup v2 counters; absent files are skipped rather than failing the run.
+    memory_peak_bytes: Option<u64>,
+    memory_events: Option<MemoryEvents>,
+    io: Option<IoTotals>,
+    pids_current: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct MemoryEvents {
+    low: u64,
+    high: u64,
+    max: u64,
+    oom: u64,
+    oom_kill: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct IoTotals {
+    read_bytes: u64,
+    write_bytes: u64,
+    read_ops: u64,
+    write_ops: u64,
 }
 
 /// Monitor a running VM process until exit or timeout.
@@ -187,9 +212,65 @@ fn read_resource_sample(prepared: &PreparedRun) -> Result<ResourceSample, ErrorI
     Ok(ResourceSample {
         cpu_usage_usec,
         memory_current_bytes,
+        memory_peak_bytes: read_optional_cgroup_file(cgroup_root, CGROUP_MEMORY_PEAK_FILE)
+            .and_then(|raw| raw.trim().parse::<u64>().ok()),
+        memory_events: read_optional_cgroup_file(cgroup_root, CGROUP_MEMORY_EVENTS_FILE)
+            .map(|raw| parse_memory_events(&raw)),
+        io: read_optional_cgroup_file(cgroup_root, CGROUP_IO_STAT_FILE)
+            .map(|raw| parse_io_stat(&raw)),
+        pids_current: read_optional_cgroup_file(cgroup_root, CGROUP_PIDS_CURRENT_FILE)
+            .and_then(|raw| raw.trim().parse::<u64>().ok()),
     })
 }
 
+fn read_optional_cgroup_file(cgroup_root: &Path, name: &str) -> Option<String> {
+    fs::read_to_string(cgroup_root.join(name)).ok()
+}
+
+/// Parse flat-keyed `memory.events`; unknown keys are ignored.
+fn parse_memory_events(raw: &str) -> MemoryEvents {
+    let mut events = MemoryEvents::default();
+    for line in raw.lines() {
+        let mut parts = line.split_whitespace();
+        let key = parts.next().unwrap_or_default();
+        let Some(value) = parts.next().and_then(|value| value.parse::<u64>().ok()) else {
+            continue;
+        };
+        match key {
+            "low" => events.low = value,
+            "high" => events.high = value,
+            "max" => events.max = value,
+            "oom" => events.oom = value,
+            "oom_kill" => events.oom_kill = value,
+            _ => {}
+        }
+    }
+    events
+}
+
+/// Sum `rbytes/wbytes/rios/wios` across all device lines of `io.stat`.
+fn parse_io_stat(raw: &str) -> IoTotals {
+    let mut totals = IoTotals::default();
+    for line in raw.lines() {
+        for field in line.split_whitespace().skip(1) {
+            let Some((key, value)) = field.split_once('=') else {
+                continue;
+            };
+            let Ok(value) = value.parse::<u64>() else {
+                continue;
+            };
+            match key {
+                "rbytes" => totals.read_bytes = totals.read_bytes.saturating_add(value),
+                "wbytes" => totals.write_bytes = totals.write_bytes.saturating_add(value),
+                "rios" => totals.read_ops = totals.read_ops.saturating_add(value),
+                "wios" => totals.write_ops = totals.write_ops.saturating_add(value),
+                _ => {}
+            }
+        }
+    }
+    totals
+}
+
 fn parse_cpu_usage_usec(raw: &str) -> Result<u64, ErrorItem> {
     for line in raw.lines() {
         let mut parts = line.split_whitespace();
@@ -230,14 +311,50 @@ fn write_resource_sample_event(
         prepared,
         STAGE_MONITOR,
         EVENT_RESOURCE_SAMPLED,
-        json!({
-            "cpuUsageUsec": sample.cpu_usage_usec,
-            "memoryCurrentBytes": sample.memory_current_bytes,
-            "cgroupPath": prepared.runtime_context.effective_cgroup_path()
-        }),
+        resource_sample_payload(&sample, &prepared.runtime_context.effective_cgroup_path()),
     )
 }
 
+fn resource_sample_payload(sample: &ResourceSample, cgroup_path: &str) -> Value {
+    let mut payload = Map::new();
+    payload.insert("cpuUsageUsec".to_string(), json!(sample.cpu_usage_usec));
+    payload.insert(
+        "memoryCurrentBytes".to_string(),
+        json!(sample.memory_current_bytes),
+    );
+    payload.insert("cgroupPath".to_string(), json!(cgroup_path));
+    if let Some(peak) = sample.memory_peak_bytes {
+        payload.insert("memoryPeakBytes".to_string(), json!(peak));
+    }
+    if let Some(events) = sample.memory_events {
+        payload.insert(
+            "memoryEvents".to_string(),
+            json!({
+                "low": events.low,
+                "high": events.high,
+                "max": events.max,
+                "oom": events.oom,
+                "oomKill": events.oom_kill
+            }),
+        );
+    }
+    if let Some(io) = sample.io {
+        payload.insert(
+            "io".to_string(),
+            json!({
+                "readBytes": io.read_bytes,
+                "writeBytes": io.write_bytes,
+                "readOps": io.read_ops,
+                "writeOps": io.write_ops
+            }),
+        );
+    }
+    if let Some(pids) = sample.pids_current {
+        payload.insert("pidsCurrent".to_string(), json!(pids));
+    }
+    Value::Object(payload)
+}
+
 fn write_vm_exited_event(
     prepared: &mut PreparedRun,
     result: &MonitorResult,
@@ -1,14 +1,36 @@
+use nix::fcntl::{openat2, OFlag, OpenHow, ResolveFlag};
 use nix::mount::{mount, umount2, MntFlags, MsFlags};
+use nix::sys::stat::fstat;
 use sr_common::{ErrorItem, SR_RUN_101};
 use sr_compiler::{MountPlan, MountPlanEntry};
+use sr_policy::MountSourceIdentity;
 use std::fs;
-use std::path::Path;
+use std::os::fd::{AsRawFd, RawFd};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
 
+use crate::constants::MOUNT_REJECT_SOURCE_CHANGED;
 use crate::rollback::rollback_mounts;
 
 /// Mount operation adapter used by `MountExecutor`.
 pub trait MountApplier {
-    fn apply(&self, entry: &MountPlanEntry) -> Result<(), String>;
+    fn apply(&self, entry: &MountPlanEntry) -> Result<(), MountApplyError>;
+}
+
+/// Apply failure; `reason` is set when the rejection has a dedicated `mount.rejected` reason.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountApplyError {
+    pub reason: Option<&'static str>,
+    pub message: String,
+}
+
+impl From<String> for MountApplyError {
+    fn from(message: String) -> Self {
+        Self {
+            reason: None,
+            message,
+        }
+    }
 }
 
 /// Rollback adapter used by `MountExecutor` when partial apply fails.
@@ -20,7 +42,11 @@ pub trait MountRollbacker {
 pub trait MountEventHooks {
     fn on_validated(&mut self, entry: &MountPlanEntry) -> Result<(), ErrorItem>;
     fn on_applied(&mut self, entry: &MountPlanEntry) -> Result<(), ErrorItem>;
-    fn on_rejected(&mut self, entry: &MountPlanEntry, message: &str) -> Result<(), ErrorItem>;
+    fn on_rejected(
+        &mut self,
+        entry: &MountPlanEntry,
+        error: &MountApplyError,
+    ) -> Result<(), ErrorItem>;
 }
 
 #[allow(dead_code)]
@@ -35,7 +61,11 @@ impl MountEventHooks for NoopMountEventHooks {
         Ok(())
     }
 
-    fn on_rejected(&mut self, _entry: &MountPlanEntry, _message: &str) -> Result<(), ErrorItem> {
+    fn on_rejected(
+        &mut self,
+        _entry: &MountPlanEntry,
+        _error: &MountApplyError,
+    ) -> Result<(), ErrorItem> {
         Ok(())
     }
 }
@@ -45,7 +75,7 @@ impl MountEventHooks for NoopMountEventHooks {
 pub struct NoopMountApplier;
 
 impl MountApplier for NoopMountApplier {
-    fn apply(&self, _entry: &MountPlanEntry) -> Result<(), String> {
+    fn apply(&self, _entry: &MountPlanEntry) -> Result<(), MountApplyError> {
         Ok(())
     }
 }
@@ -84,11 +114,11 @@ impl MountExecutor {
 
         let mut applied = Vec::with_capacity(plan.mounts.len());
         for entry in &plan.mounts {
-            if let Err(message) = self.applier.apply(entry) {
+            if let Err(err) = self.applier.apply(entry) {
                 let rollback = rollback_mounts(self.rollbacker.as_ref(), &applied);
                 let mut detail = format!(
                     "failed to apply mount {} -> {}: {}",
-                    entry.source, entry.target, message
+                    entry.source, entry.target, err.message
                 );
                 if let Err(rollback_err) = rollback {
                     detail = format!("{detail}; rollback failed: {}", rollback_err.message);
@@ -113,12 +143,12 @@ impl MountExecutor {
         let mut applied = Vec::with_capacity(plan.mounts.len());
         for entry in &plan.mounts {
             hooks.on_validated(entry)?;
-            if let Err(message) = self.applier.apply(entry) {
-                hooks.on_rejected(entry, &message)?;
+            if let Err(err) = self.applier.apply(entry) {
+                hooks.on_rejected(entry, &err)?;
                 let rollback = rollback_mounts(self.rollbacker.as_ref(), &applied);
                 let mut detail = format!(
                     "failed to apply mount {} -> {}: {}",
-                    entry.source, entry.target, message
+                    entry.source, entry.target, err.message
                 );
                 if let Err(rollback_err) = rollback {
                     detail = format!("{detail}; rollback failed: {}", rollback_err.message);
@@ -137,14 +167,24 @@ impl MountExecutor {
 pub struct SystemMountApplier;
 
 impl MountApplier for SystemMountApplier {
-    fn apply(&self, entry: &MountPlanEntry) -> Result<(), String> {
-        let source = Path::new(&entry.source);
+    /// Sources are re-resolved beneath their allowlist prefix from the identity recorded at
+    /// validation and bind-mounted through the resulting descriptor, so a path swapped after
+    /// validation is never followed. Entries without an identity are refused.
+    fn apply(&self, entry: &MountPlanEntry) -> Result<(), MountApplyError> {
+        let identity = entry.source_identity.as_ref().ok_or_else(|| {
+            format!(
+                "mount source '{}' has no validated identity; refusing to mount by path",
+                entry.source
+            )
+        })?;
+        let verified = open_verified_source(identity)?;
+        let source = verified.proc_path();
         let target = Path::new(&entry.target);
         if !target.exists() {
             fs::create_dir_all(target).map_err(|err| err.to_string())?;
         }
         mount(
-            Some(source),
+            Some(source.as_path()),
             target,
             None::<&str>,
             MsFlags::MS_BIND,
@@ -154,7 +194,7 @@ impl MountApplier for SystemMountApplier {
 
         if entry.read_only {
             mount(
-                Some(source),
+                Some(source.as_path()),
                 target,
                 None::<&str>,
                 MsFlags::MS_BIND | MsFlags::MS_REMOUNT | MsFlags::MS_RDONLY,
@@ -166,6 +206,73 @@ impl MountApplier for SystemMountApplier {
     }
 }
 
+// `O_PATH` descriptor for a re-resolved mount source, closed on drop.
+struct SourceFd(RawFd);
+
+impl SourceFd {
+    fn proc_path(&self) -> PathBuf {
+        PathBuf::from(format!("/proc/self/fd/{}", self.0))
+    }
+}
+
+impl Drop for SourceFd {
+    fn drop(&mut self) {
+        let _ = nix::unistd::close(self.0);
+    }
+}
+
+/// Re-open `relative_path` beneath `allow_prefix` refusing any symlink or escape, then
+/// require the device/inode recorded at validation time.
+/// Error mapping: resolution failure or identity mismatch -> `mount.source_changed`.
+fn open_verified_source(identity: &MountSourceIdentity) -> Result<SourceFd, MountApplyError> {
+    let prefix = fs::OpenOptions::new()
+        .read(true)
+        .custom_flags((OFlag::O_PATH | OFlag::O_DIRECTORY).bits())
+        .open(&identity.allow_prefix)
+        .map_err(|err| {
+            source_changed(format!(
+                "failed to open allowlist prefix '{}': {err}",
+                identity.allow_prefix
+            ))
+        })?;
+    let how = OpenHow::new()
+        .flags(OFlag::O_PATH | OFlag::O_CLOEXEC)
+        .resolve(ResolveFlag::RESOLVE_NO_SYMLINKS | ResolveFlag::RESOLVE_BENEATH);
+    let fd = openat2(prefix.as_raw_fd(), identity.relative_path.as_str(), how)
+        .map(SourceFd)
+        .map_err(|err| {
+            source_changed(format!(
+                "failed to re-resolve mount source '{}' beneath '{}': {err}",
+                identity.relative_path, identity.allow_prefix
+            ))
+        })?;
+    let stat = fstat(fd.0).map_err(|err| {
+        source_changed(format!(
+            "failed to stat re-resolved mount source '{}': {err}",
+            identity.relative_path
+        ))
+    })?;
+    if stat.st_dev != identity.device || stat.st_ino != identity.inode {
+        return Err(source_changed(format!(
+            "mount source '{}' beneath '{}' changed after validation: expected dev={} ino={}, found dev={} ino={}",
+            identity.relative_path,
+            identity.allow_prefix,
+            identity.device,
+            identity.inode,
+            stat.st_dev,
+            stat.st_ino
+        )));
+    }
+    Ok(fd)
+}
+
+fn source_changed(message: String) -> MountApplyError {
+    MountApplyError {
+        reason: Some(MOUNT_REJECT_SOURCE_CHANGED),
+        message,
+    }
+}
+
 #[derive(Debug, Clone, Copy, Default)]
 pub struct SystemMountRollbacker;
 
@@ -194,11 +301,11 @@ mod tests {
     }
 
     impl MountApplier for RecordingApplier {
-        fn apply(&self, entry: &MountPlanEntry) -> Result<(), String> {
+        fn apply(&self, entry: &MountPlanEntry) -> Result<(), MountApplyError> {
             self.calls.borrow_mut().push(entry.target.clone());
             if let Some(target) = &self.fail_on {
                 if &entry.target == target {
-                    return Err("apply failed".to_string());
+                    return Err("apply failed".to_string().into());
                 }
             }
             Ok(())
@@ -220,21 +327,25 @@ mod tests {
     fn sample_plan() -> MountPlan {
         MountPlan {
             enabled: true,
+            drives: Vec::new(),
             mounts: vec![
                 MountPlanEntry {
                     source: "/var/lib/safe-run/input".to_string(),
                     target: "/data/input".to_string(),
                     read_only: true,
+                    source_identity: None,
                 },
                 MountPlanEntry {
                     source: "/var/lib/safe-run/cache".to_string(),
                     target: "/data/cache".to_string(),
                     read_only: true,
+                    source_identity: None,
                 },
                 MountPlanEntry {
                     source: "/var/lib/safe-run/output".to_string(),
                     target: "/data/output".to_string(),
                     read_only: true,
+                    source_identity: None,
                 },
             ],
         }
@@ -288,4 +399,56 @@ mod tests {
         );
         assert_eq!(*rollback_calls.borrow(), vec!["/data/cache", "/data/input"]);
     }
+
+    #[test]
+    fn system_applier_refuses_sources_without_identity() {
+        let entry = sample_plan().mounts.remove(0);
+        let err = SystemMountApplier
+            .apply(&entry)
+            .expect_err("unverified source must be refused");
+        assert!(err.message.contains("no validated identity"), "{}", err.message);
+        assert!(!Path::new(&entry.target).exists());
+    }
+
+    #[test]
+    fn verified_source_rejects_symlink_swap_and_replaced_inode() {
+        use std::os::unix::fs::MetadataExt;
+
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("clock")
+            .as_nanos();
+        let prefix = std::env::temp_dir().join(format!("safe-run-vibe-mount-toctou-{nanos}"));
+        let source = prefix.join("input");
+        fs::create_dir_all(&source).expect("create source");
+        let metadata = fs::metadata(&source).expect("stat source");
+        let identity = MountSourceIdentity {
+            allow_prefix: prefix.to_string_lossy().to_string(),
+            relative_path: "input".to_string(),
+            device: metadata.dev(),
+            inode: metadata.ino(),
+        };
+        assert!(open_verified_source(&identity).is_ok());
+
+        let outside = prefix.with_extension("outside");
+        fs::create_dir_all(&outside).expect("create outside");
+        // Keep the original directory alive so its inode cannot be reused below.
+        fs::rename(&source, prefix.join("original")).expect("move source aside");
+        std::os::unix::fs::symlink(&outside, &source).expect("swap in symlink");
+        let err = open_verified_source(&identity)
+            .err()
+            .expect("symlink must be refused");
+        assert_eq!(err.reason, Some(MOUNT_REJECT_SOURCE_CHANGED));
+
+        fs::remove_file(&source).expect("remove symlink");
+        fs::create_dir_all(&source).expect("recreate source");
+        let err = open_verified_source(&identity)
+            .err()
+            .expect("inode mismatch");
+        assert_eq!(err.reason, Some(MOUNT_REJECT_SOURCE_CHANGED));
+        assert!(err.message.contains("changed after validation"));
+
+        let _ = fs::remove_dir_all(&prefix);
+        let _ = fs::remove_dir_all(&outside);
+    }
 }
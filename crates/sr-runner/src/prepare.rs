@@ -1,14 +1,21 @@
 use crate::constants::{
-    ARTIFACTS_DIR, EVENTS_FILE, EVENT_COMPILE, GENESIS_HASH, REPORT_FILE, STAGE_COMPILE,
+    ARTIFACTS_DIR, COMMAND_PAYLOAD_FILE, EVENTS_FILE, EVENT_COMPILE, EVENT_SECRETS_INJECTED,
+    GENESIS_HASH, REPORT_FILE, STAGE_COMPILE, STAGE_PREPARE,
 };
 use crate::event::write_event;
 use crate::launch::assemble_launch_plan;
 use crate::model::{
-    PreparedRun, RunArtifacts, RunState, RunnerControlRequest, RunnerRuntime, RuntimeContext,
+    CommandPayload, PreparedRun, RunArtifacts, RunState, RunnerControlRequest, RunnerRuntime,
+    RuntimeContext,
+};
+use crate::secrets::{
+    ensure_no_secret_leak, resolve_secrets, secrets_event_enabled, secrets_event_payload,
+    shred_secrets_image, write_secrets_image, ResolvedSecrets,
 };
 use crate::utils::{derive_run_id, write_json_file};
 use serde_json::json;
 use sr_common::{ErrorItem, SR_RUN_001, SR_RUN_002};
+use sr_compiler::CommandPlan;
 use std::env;
 use std::fs::{self, OpenOptions};
 use std::path::{Path, PathBuf};
@@ -24,9 +31,57 @@ pub(crate) fn prepare_run(
     let workdir_path = PathBuf::from(request.runtime_context.workdir.trim());
     let artifacts_dir_path = create_workdir(&workdir_path)?;
 
-    materialize_firecracker_artifacts(&workdir_path, &mut compile_bundle)?;
-    write_firecracker_config(&workdir_path, &compile_bundle)?;
-    write_runtime_context(&workdir_path, &request.runtime_context)?;
+    let rootfs_path = materialize_firecracker_artifacts(&workdir_path, &mut compile_bundle)?;
+    // Mount images can be large and the secrets image holds plaintext; every failure from
+    // here on removes both.
+    let secret_plan = compile_bundle.secret_plan.clone();
+    let mut mount_images = Vec::new();
+    let prepared =
+        create_mount_images(&workdir_path, &compile_bundle.mount_plan, &mut mount_images)
+            .and_then(|()| write_firecracker_config(&workdir_path, &compile_bundle))
+            .and_then(|()| match secret_plan.as_ref() {
+                Some(plan) => {
+                    let secrets = resolve_secrets(plan)?;
+                    write_secrets_image(&workdir_path, plan, &secrets)?;
+                    Ok(secrets)
+                }
+                None => Ok(ResolvedSecrets::default()),
+            })
+            .and_then(|secrets| {
+                finish_prepare(
+                    runtime,
+                    request.runtime_context,
+                    compile_bundle,
+                    workdir_path.clone(),
+                    artifacts_dir_path,
+                    rootfs_path,
+                    secrets,
+                )
+            });
+    if prepared.is_err() {
+        for image in &mount_images {
+            let _ = fs::remove_file(image);
+        }
+        if let Some(plan) = secret_plan.as_ref() {
+            let _ = shred_secrets_image(&workdir_path, plan);
+        }
+    }
+    prepared
+}
+
+/// Write the remaining run artifacts once the secrets image exists.
+fn finish_prepare(
+    runtime: &RunnerRuntime,
+    runtime_context: RuntimeContext,
+    compile_bundle: sr_compiler::CompileBundle,
+    workdir_path: PathBuf,
+    artifacts_dir_path: PathBuf,
+    rootfs_path: PathBuf,
+    secrets: ResolvedSecrets,
+) -> Result<PreparedRun, ErrorItem> {
+    let command_payload = resolve_command_payload(&compile_bundle.command_plan);
+    write_command_payload(&workdir_path, &command_payload)?;
+    write_runtime_context(&workdir_path, &runtime_context)?;
     initialize_event_stream(&artifacts_dir_path)?;
 
     let run_id = derive_run_id(&workdir_path);
@@ -35,7 +90,7 @@ pub(crate) fn prepare_run(
     let mut prepared = PreparedRun {
         run_id,
         state: RunState::Prepared,
-        runtime_context: request.runtime_context,
+        runtime_context,
         artifacts: RunArtifacts {
             log: EVENTS_FILE.to_string(),
             report: REPORT_FILE.to_string(),
@@ -46,16 +101,42 @@ pub(crate) fn prepare_run(
         network_plan: compile_bundle.network_plan.clone(),
         applied_network: None,
         evidence_plan: compile_bundle.evidence_plan.clone(),
+        command_payload,
+        command_sha256: compile_bundle.command_plan.command_sha256.clone(),
+        secret_plan: compile_bundle.secret_plan.clone(),
+        secrets,
         workdir_path,
         artifacts_dir_path,
+        rootfs_path,
         last_event_hash: GENESIS_HASH.to_string(),
     };
 
     write_compile_event_if_enabled(&mut prepared, &compile_bundle)?;
+    record_injected_secrets(&mut prepared)?;
 
     Ok(prepared)
 }
 
+/// Emit `secrets.injected` and make sure no prepared artifact carries a secret value.
+fn record_injected_secrets(prepared: &mut PreparedRun) -> Result<(), ErrorItem> {
+    let Some(plan) = prepared.secret_plan.clone() else {
+        return Ok(());
+    };
+    if prepared.evidence_plan.enabled && secrets_event_enabled(&prepared.evidence_plan.events) {
+        let payload = secrets_event_payload(&plan, &prepared.secrets)?;
+        write_event(prepared, STAGE_PREPARE, EVENT_SECRETS_INJECTED, payload)?;
+    }
+    ensure_no_secret_leak(
+        &[
+            &prepared.firecracker_config_path(),
+            &prepared.command_payload_path(),
+            &prepared.runtime_context_path(),
+            &prepared.event_log_path(),
+        ],
+        &prepared.secrets,
+    )
+}
+
 fn validate_runtime_context(runtime_context: &RuntimeContext) -> Result<(), ErrorItem> {
     if runtime_context.timeout_sec == 0 {
         return Err(ErrorItem::new(
@@ -119,6 +200,32 @@ fn write_firecracker_config(
     )
 }
 
+/// Copy only the allowlisted host variables into the guest environment; names that are
+/// unset on the host are left out rather than passed as empty strings.
+fn resolve_command_payload(plan: &CommandPlan) -> CommandPayload {
+    let mut env = plan.env.clone();
+    for name in &plan.env_passthrough {
+        if let Ok(value) = env::var(name) {
+            env.insert(name.clone(), value);
+        }
+    }
+    CommandPayload {
+        command: plan.command.clone(),
+        args: plan.args.clone(),
+        env,
+        cwd: plan.cwd.clone(),
+        user: plan.user,
+    }
+}
+
+fn write_command_payload(workdir_path: &Path, payload: &CommandPayload) -> Result<(), ErrorItem> {
+    write_json_file(
+        &workdir_path.join(COMMAND_PAYLOAD_FILE),
+        &json!(payload),
+        "prepare.commandPayload",
+    )
+}
+
 fn write_runtime_context(
     workdir_path: &Path,
     runtime_context: &RuntimeContext,
@@ -152,10 +259,12 @@ fn initialize_event_stream(artifacts_dir_path: &Path) -> Result<(), ErrorItem> {
     Ok(())
 }
 
+/// Copy kernel and rootfs into the workdir and point the config at the copies; returns the
+/// rootfs copy.
 fn materialize_firecracker_artifacts(
     workdir_path: &Path,
     compile_bundle: &mut sr_compiler::CompileBundle,
-) -> Result<(), ErrorItem> {
+) -> Result<PathBuf, ErrorItem> {
     let mut config = compile_bundle.firecracker_config.clone();
     let kernel_raw = json_string_at(
         &config,
@@ -181,6 +290,50 @@ fn materialize_firecracker_artifacts(
     )?;
 
     compile_bundle.firecracker_config = config;
+    Ok(rootfs_target)
+}
+
+/// Create a fresh sparse image per writable drive; the guest formats and mounts it.
+/// The size cap is the image length, so blocks are only allocated as the guest writes.
+/// Each image is pushed to `created` as soon as it exists, so the caller can remove them on failure.
+fn create_mount_images(
+    workdir_path: &Path,
+    mount_plan: &sr_compiler::MountPlan,
+    created: &mut Vec<PathBuf>,
+) -> Result<(), ErrorItem> {
+    for drive in &mount_plan.drives {
+        let image_path = workdir_path.join(&drive.image);
+        let size_bytes = drive.size_mib.checked_mul(1024 * 1024).ok_or_else(|| {
+            ErrorItem::new(
+                SR_RUN_001,
+                format!("prepare.mountImages.{}", drive.drive_id),
+                format!("mount image size of {} MiB overflows", drive.size_mib),
+            )
+        })?;
+        let created = image_path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| {
+                OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(&image_path)
+            })
+            .and_then(|file| {
+                created.push(image_path.clone());
+                file.set_len(size_bytes)
+            });
+        created.map_err(|err| {
+            ErrorItem::new(
+                SR_RUN_001,
+                format!("prepare.mountImages.{}", drive.drive_id),
+                format!(
+                    "failed to create mount image '{}': {err}",
+                    image_path.display()
+                ),
+            )
+        })?;
+    }
     Ok(())
 }
 
@@ -339,9 +492,8 @@ fn set_json_string(
 ) -> Result<(), ErrorItem> {
     value
         .pointer_mut(pointer)
-        .and_then(|item| {
+        .map(|item| {
             *item = serde_json::Value::String(new_value);
-            Some(())
         })
         .ok_or_else(|| {
             ErrorItem::new(
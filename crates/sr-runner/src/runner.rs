@@ -1,9 +1,12 @@
 use crate::cleanup::cleanup_run;
+use crate::command_verify::{verify_command_binary, DebugfsRootfsReader, RootfsReader};
 use crate::constants::{
-    EVENT_MOUNT_APPLIED, EVENT_MOUNT_REJECTED, EVENT_MOUNT_VALIDATED, EVENT_NETWORK_PLAN_GENERATED,
-    EVENT_NETWORK_RULE_APPLIED, EVENT_NETWORK_RULE_CLEANUP_FAILED, EVENT_NETWORK_RULE_HIT,
-    EVENT_NETWORK_RULE_RELEASED, EVENT_RUN_FAILED, EVENT_RUN_PREPARED, EVENT_VM_STARTED,
-    STAGE_CLEANUP, STAGE_LAUNCH, STAGE_MOUNT, STAGE_PREPARE,
+    EVENT_MOUNT_APPLIED, EVENT_MOUNT_REJECTED, EVENT_MOUNT_SCAN, EVENT_MOUNT_VALIDATED,
+    EVENT_NETWORK_PLAN_GENERATED, EVENT_NETWORK_RULE_APPLIED, EVENT_NETWORK_RULE_CLEANUP_FAILED,
+    EVENT_NETWORK_RULE_HIT, EVENT_NETWORK_RULE_RELEASED, EVENT_RUN_FAILED, EVENT_RUN_PREPARED,
+    EVENT_VM_STARTED, MOUNT_REJECT_SCAN_BUDGET, MOUNT_REJECT_SCAN_FAILED,
+    MOUNT_REJECT_SCAN_FINDINGS, STAGE_CLEANUP, STAGE_LAUNCH, STAGE_MOUNT, STAGE_PREPARE,
+    VM_LOG_MAX_BYTES,
 };
 use crate::event::write_event;
 use crate::model::{
@@ -12,23 +15,27 @@ use crate::model::{
 };
 use crate::monitor::monitor_run;
 use crate::mount_executor::{
-    MountEventHooks, MountExecutor, SystemMountApplier, SystemMountRollbacker,
+    MountApplyError, MountEventHooks, MountExecutor, SystemMountApplier, SystemMountRollbacker,
 };
 use crate::network_lifecycle::{NetworkLifecycle, SystemNetworkLifecycle};
 use crate::prepare::prepare_run;
 use serde_json::json;
 use sr_common::{ErrorItem, SR_RUN_001, SR_RUN_002, SR_RUN_101, SR_RUN_201, SR_RUN_202};
+use sr_policy::scan_mount_source;
 use std::env;
 use std::fs;
+use std::io::{self, Read, Write};
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Stdio};
+use std::thread;
 
 pub struct Runner {
     runtime: RunnerRuntime,
     mount_executor: MountExecutor,
     network_lifecycle: Box<dyn NetworkLifecycle>,
+    rootfs_reader: Box<dyn RootfsReader>,
 }
 
 impl Default for Runner {
@@ -44,6 +51,7 @@ impl Runner {
             runtime: RunnerRuntime::default(),
             mount_executor: MountExecutor::new(SystemMountApplier, SystemMountRollbacker),
             network_lifecycle: Box::new(SystemNetworkLifecycle::default()),
+            rootfs_reader: Box::new(DebugfsRootfsReader),
         }
     }
 
@@ -53,6 +61,7 @@ impl Runner {
             runtime,
             mount_executor: MountExecutor::new(SystemMountApplier, SystemMountRollbacker),
             network_lifecycle: Box::new(SystemNetworkLifecycle::default()),
+            rootfs_reader: Box::new(DebugfsRootfsReader),
         }
     }
 
@@ -62,6 +71,7 @@ impl Runner {
             runtime,
             mount_executor,
             network_lifecycle: Box::new(SystemNetworkLifecycle::default()),
+            rootfs_reader: Box::new(DebugfsRootfsReader),
         }
     }
 
@@ -75,6 +85,7 @@ impl Runner {
             runtime,
             mount_executor,
             network_lifecycle: Box::new(network_lifecycle),
+            rootfs_reader: Box::new(DebugfsRootfsReader),
         }
     }
 
@@ -87,6 +98,20 @@ impl Runner {
             runtime,
             mount_executor: MountExecutor::new(SystemMountApplier, SystemMountRollbacker),
             network_lifecycle: Box::new(network_lifecycle),
+            rootfs_reader: Box::new(DebugfsRootfsReader),
+        }
+    }
+
+    /// Create a runner with a custom rootfs reader for command verification (used for tests).
+    pub fn with_rootfs_reader<R: RootfsReader + 'static>(
+        runtime: RunnerRuntime,
+        rootfs_reader: R,
+    ) -> Self {
+        Self {
+            runtime,
+            mount_executor: MountExecutor::new(SystemMountApplier, SystemMountRollbacker),
+            network_lifecycle: Box::new(SystemNetworkLifecycle::default()),
+            rootfs_reader: Box::new(rootfs_reader),
         }
     }
 
@@ -123,6 +148,26 @@ impl Runner {
             return Err(err);
         }
 
+        if let Err(err) = verify_command_binary(prepared, self.rootfs_reader.as_ref()) {
+            self.run_cleanup_on_failure(
+                prepared,
+                "launch.command.verify",
+                err.code.clone(),
+                err.message.clone(),
+            );
+            return Err(err);
+        }
+
+        if let Err(err) = scan_mount_sources(prepared) {
+            self.run_cleanup_on_failure(
+                prepared,
+                "mount.scan",
+                err.code.clone(),
+                err.message.clone(),
+            );
+            return Err(err);
+        }
+
         let mount_plan = prepared.mount_plan.clone();
         let mut hooks = MountEventWriter { prepared };
         if let Err(err) = self
@@ -148,7 +193,11 @@ impl Runner {
             return Err(err);
         }
 
-        let vm_pid = match self.spawn(&prepared.launch_plan.jailer) {
+        let vm_pid = match self.spawn(
+            &prepared.launch_plan.jailer,
+            &prepared.vm_stdout_log_path(),
+            &prepared.vm_stderr_log_path(),
+        ) {
             Ok(pid) => pid,
             Err(err) => {
                 self.run_cleanup_on_failure(
@@ -283,8 +332,27 @@ impl Runner {
         Ok(())
     }
 
-    pub(crate) fn spawn(&self, command: &crate::model::CommandSpec) -> std::io::Result<u32> {
-        let child = Command::new(&command.program).args(&command.args).spawn()?;
+    /// Start the jailer with its stdout/stderr captured into the run's artifact logs.
+    /// Each log keeps at most `VM_LOG_MAX_BYTES`; the rest of the stream is drained.
+    pub(crate) fn spawn(
+        &self,
+        command: &crate::model::CommandSpec,
+        stdout_path: &Path,
+        stderr_path: &Path,
+    ) -> std::io::Result<u32> {
+        let stdout_log = fs::File::create(stdout_path)?;
+        let stderr_log = fs::File::create(stderr_path)?;
+        let mut child = Command::new(&command.program)
+            .args(&command.args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let Some(stdout) = child.stdout.take() {
+            capture_capped_log(stdout, stdout_log);
+        }
+        if let Some(stderr) = child.stderr.take() {
+            capture_capped_log(stderr, stderr_log);
+        }
         let pid = child.id();
         Ok(pid)
     }
@@ -430,6 +498,21 @@ impl Runner {
     }
 }
 
+/// Copy `source` into `log` up to `VM_LOG_MAX_BYTES`, then keep reading so the VM never
+/// blocks on a full pipe. A truncated log ends with a marker line.
+fn capture_capped_log<R: Read + Send + 'static>(mut source: R, mut log: fs::File) {
+    thread::spawn(move || {
+        let _ = io::copy(&mut (&mut source).take(VM_LOG_MAX_BYTES), &mut log);
+        let dropped = io::copy(&mut source, &mut io::sink()).unwrap_or_default();
+        if dropped > 0 {
+            let _ = writeln!(
+                log,
+                "\n[safe-run: log truncated at {VM_LOG_MAX_BYTES} bytes, {dropped} bytes dropped]"
+            );
+        }
+    });
+}
+
 fn mount_event_enabled(prepared: &PreparedRun, event_type: &str) -> bool {
     prepared.evidence_plan.enabled
         && prepared
@@ -471,6 +554,81 @@ fn write_mount_event_if_enabled(
     write_event(prepared, STAGE_MOUNT, event_type, payload)
 }
 
+/// Walk every bind mount source before attach and record a `mount.scan` event per source.
+/// Any finding, or a scan cut short by the entry budget, rejects the mount with `SR-RUN-101`.
+fn scan_mount_sources(prepared: &mut PreparedRun) -> Result<(), ErrorItem> {
+    if !prepared.mount_plan.enabled {
+        return Ok(());
+    }
+    let budget = prepared.runtime_context.effective_mount_scan_budget();
+    for entry in prepared.mount_plan.mounts.clone() {
+        let report = match scan_mount_source(Path::new(&entry.source), &budget) {
+            Ok(report) => report,
+            Err(err) => {
+                // A missing or unreadable source is rejected like a failed scan.
+                let message = format!("failed to scan mount source {}: {err}", entry.source);
+                return reject_scanned_mount(prepared, &entry, MOUNT_REJECT_SCAN_FAILED, message);
+            }
+        };
+        let mut payload = serde_json::to_value(&report).map_err(|err| {
+            ErrorItem::new(
+                SR_RUN_001,
+                "mount.scan",
+                format!("failed to encode mount scan report: {err}"),
+            )
+        })?;
+        payload["source"] = json!(entry.source.as_str());
+        payload["target"] = json!(entry.target.as_str());
+        write_mount_event_if_enabled(prepared, EVENT_MOUNT_SCAN, payload)?;
+        if report.is_clean() {
+            continue;
+        }
+
+        let (reason, message) = match report.findings.first() {
+            Some(finding) if !report.budget_exhausted => (
+                MOUNT_REJECT_SCAN_FINDINGS,
+                format!(
+                    "mount source {} failed pre-attach scan with {} finding(s), first {} at {}",
+                    entry.source,
+                    report.findings_total,
+                    finding.kind.as_str(),
+                    finding.path
+                ),
+            ),
+            _ => (
+                MOUNT_REJECT_SCAN_BUDGET,
+                format!(
+                    "mount source {} exceeds the scan budget of {} entries",
+                    entry.source, budget.max_entries
+                ),
+            ),
+        };
+        return reject_scanned_mount(prepared, &entry, reason, message);
+    }
+    Ok(())
+}
+
+fn reject_scanned_mount(
+    prepared: &mut PreparedRun,
+    entry: &sr_compiler::MountPlanEntry,
+    reason: &str,
+    message: String,
+) -> Result<(), ErrorItem> {
+    write_mount_event_if_enabled(
+        prepared,
+        EVENT_MOUNT_REJECTED,
+        json!({
+            "source": entry.source.as_str(),
+            "target": entry.target.as_str(),
+            "read_only": entry.read_only,
+            "errorCode": SR_RUN_101,
+            "message": message.as_str(),
+            "reason": reason
+        }),
+    )?;
+    Err(ErrorItem::new(SR_RUN_101, "mount.scan", message))
+}
+
 struct MountEventWriter<'a> {
     prepared: &'a mut PreparedRun,
 }
@@ -503,19 +661,19 @@ impl<'a> MountEventHooks for MountEventWriter<'a> {
     fn on_rejected(
         &mut self,
         entry: &sr_compiler::MountPlanEntry,
-        message: &str,
+        error: &MountApplyError,
     ) -> Result<(), ErrorItem> {
-        write_mount_event_if_enabled(
-            self.prepared,
-            EVENT_MOUNT_REJECTED,
-            json!({
-                "source": entry.source.as_str(),
-                "target": entry.target.as_str(),
-                "read_only": entry.read_only,
-                "errorCode": SR_RUN_101,
-                "message": message
-            }),
-        )
+        let mut payload = json!({
+            "source": entry.source.as_str(),
+            "target": entry.target.as_str(),
+            "read_only": entry.read_only,
+            "errorCode": SR_RUN_101,
+            "message": error.message.as_str()
+        });
+        if let Some(reason) = error.reason {
+            payload["reason"] = json!(reason);
+        }
+        write_mount_event_if_enabled(self.prepared, EVENT_MOUNT_REJECTED, payload)
     }
 }
 
@@ -0,0 +1,241 @@
+use crate::constants::{EVENT_SECRETS_INJECTED, SECRET_LEAK_MIN_LEN, SECRET_SALT_BYTES};
+use nix::fcntl::OFlag;
+use serde_json::{json, Value};
+use sr_common::{ErrorItem, SR_RUN_301};
+use sr_compiler::SecretPlan;
+use sr_evidence::salted_secret_hash;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+
+/// Secret values resolved on the host for one run. Kept only in memory so the runner can
+/// check its own artifacts for leaks; `Debug` never prints the values.
+#[derive(Clone, Default)]
+pub(crate) struct ResolvedSecrets {
+    entries: Vec<ResolvedSecret>,
+}
+
+#[derive(Clone)]
+struct ResolvedSecret {
+    name: String,
+    source: &'static str,
+    value: String,
+}
+
+impl fmt::Debug for ResolvedSecrets {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.entries.iter().map(|entry| &entry.name))
+            .finish()
+    }
+}
+
+impl ResolvedSecrets {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// Read every secret from its host env var or file.
+/// Error mapping: unset variable, unreadable or non-UTF-8 file -> `SR-RUN-301`.
+pub(crate) fn resolve_secrets(plan: &SecretPlan) -> Result<ResolvedSecrets, ErrorItem> {
+    let mut entries = Vec::with_capacity(plan.secrets.len());
+    for secret in &plan.secrets {
+        let error_path = format!("prepare.secrets.{}", secret.name);
+        let (source, value) = match (secret.from_env.as_deref(), secret.from_file.as_deref()) {
+            (Some(var), _) => (
+                "env",
+                std::env::var(var).map_err(|_| {
+                    secret_error(
+                        &error_path,
+                        format!("host env var '{var}' is not set or not valid UTF-8"),
+                    )
+                })?,
+            ),
+            (None, Some(file)) => (
+                "file",
+                read_secret_file(file).map_err(|err| {
+                    secret_error(
+                        &error_path,
+                        format!("failed to read secret file '{file}': {err}"),
+                    )
+                })?,
+            ),
+            (None, None) => {
+                return Err(secret_error(&error_path, "secret has no source"));
+            }
+        };
+        entries.push(ResolvedSecret {
+            name: secret.name.clone(),
+            source,
+            value,
+        });
+    }
+    Ok(ResolvedSecrets { entries })
+}
+
+/// Validation stored the canonical path, so a symlink at the final component can only be a
+/// swap made after the checks; it is refused rather than followed.
+fn read_secret_file(file: &str) -> std::io::Result<String> {
+    let mut value = String::new();
+    OpenOptions::new()
+        .read(true)
+        .custom_flags(OFlag::O_NOFOLLOW.bits())
+        .open(file)?
+        .read_to_string(&mut value)?;
+    Ok(value)
+}
+
+/// Write the secrets drive: a JSON object `{name: value}` padded with spaces to a whole
+/// number of 512-byte sectors, so the raw device still parses as JSON in the guest.
+pub(crate) fn write_secrets_image(
+    workdir: &Path,
+    plan: &SecretPlan,
+    secrets: &ResolvedSecrets,
+) -> Result<(), ErrorItem> {
+    let document = secrets
+        .entries
+        .iter()
+        .map(|entry| (entry.name.clone(), Value::String(entry.value.clone())))
+        .collect::<serde_json::Map<_, _>>();
+    let mut bytes = serde_json::to_vec(&document).unwrap_or_default();
+    bytes.resize(bytes.len().div_ceil(512).max(1) * 512, b' ');
+
+    let image_path = workdir.join(&plan.image);
+    image_path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| {
+            OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .mode(0o600)
+                .open(&image_path)
+        })
+        .and_then(|mut file| file.write_all(&bytes))
+        .map_err(|err| {
+            secret_error(
+                "prepare.secrets.image",
+                format!(
+                    "failed to write secrets image '{}': {err}",
+                    image_path.display()
+                ),
+            )
+        })
+}
+
+/// Overwrite the secrets image with zeros before unlinking it. A missing image is not an error.
+pub(crate) fn shred_secrets_image(workdir: &Path, plan: &SecretPlan) -> Result<(), ErrorItem> {
+    let image_path = workdir.join(&plan.image);
+    if !image_path.exists() {
+        return Ok(());
+    }
+    let shredded = fs::metadata(&image_path)
+        .and_then(|metadata| {
+            let mut file = OpenOptions::new().write(true).open(&image_path)?;
+            file.write_all(&vec![0u8; metadata.len() as usize])?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::remove_file(&image_path));
+    shredded.map_err(|err| {
+        secret_error(
+            "cleanup.secrets.image",
+            format!(
+                "failed to shred secrets image '{}': {err}",
+                image_path.display()
+            ),
+        )
+    })
+}
+
+/// Evidence payload for `secrets.injected`: names, sources and salted hashes only.
+pub(crate) fn secrets_event_payload(
+    plan: &SecretPlan,
+    secrets: &ResolvedSecrets,
+) -> Result<Value, ErrorItem> {
+    let salt = random_salt_hex()?;
+    let entries = secrets
+        .entries
+        .iter()
+        .map(|entry| {
+            json!({
+                "name": entry.name,
+                "source": entry.source,
+                "hash": salted_secret_hash(&salt, entry.value.as_bytes()).unwrap_or_default()
+            })
+        })
+        .collect::<Vec<_>>();
+    Ok(json!({
+        "driveId": plan.drive_id,
+        "target": plan.target,
+        "salt": salt,
+        "secrets": entries
+    }))
+}
+
+/// Fail if any secret value appears verbatim or JSON-escaped in the given files.
+/// Values shorter than `SECRET_LEAK_MIN_LEN` bytes are skipped to avoid matching hash hex.
+pub(crate) fn ensure_no_secret_leak(
+    files: &[&Path],
+    secrets: &ResolvedSecrets,
+) -> Result<(), ErrorItem> {
+    for file in files {
+        let Ok(content) = fs::read(file) else {
+            continue;
+        };
+        for entry in &secrets.entries {
+            if entry.value.len() < SECRET_LEAK_MIN_LEN {
+                continue;
+            }
+            let escaped = serde_json::to_string(&entry.value).unwrap_or_default();
+            let needles = [
+                entry.value.as_bytes(),
+                &escaped.as_bytes()[1..escaped.len() - 1],
+            ];
+            if needles.iter().any(|needle| contains(&content, needle)) {
+                return Err(secret_error(
+                    "secrets.leak",
+                    format!(
+                        "value of secret '{}' appears in '{}'",
+                        entry.name,
+                        file.display()
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn secrets_event_enabled(events: &[String]) -> bool {
+    events.iter().any(|event| event == EVENT_SECRETS_INJECTED)
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
+fn random_salt_hex() -> Result<String, ErrorItem> {
+    let mut salt = [0u8; SECRET_SALT_BYTES];
+    File::open("/dev/urandom")
+        .and_then(|mut urandom| urandom.read_exact(&mut salt))
+        .map_err(|err| {
+            secret_error(
+                "prepare.secrets.salt",
+                format!("failed to read salt: {err}"),
+            )
+        })?;
+    Ok(salt.iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+fn secret_error(path: &str, message: impl Into<String>) -> ErrorItem {
+    ErrorItem::new(SR_RUN_301, path, message)
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use common::{
+    new_temp_dir, remove_temp_dir, runner_with_mock_runtime, runtime_context,
+    write_mock_vm_artifacts,
+};
+use sr_compiler::{compile_dry_run, CompileBundle};
+use sr_evidence::{compute_artifact_hashes_from_json, ArtifactJsonInputs};
+use sr_policy::{load_policy_from_path, validate_policy, PolicySpec};
+use sr_runner::{CommandPayload, RunnerControlRequest};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const PASSTHROUGH_VAR: &str = "SAFE_RUN_TEST_TOKEN";
+
+fn repo_file(path: &str) -> String {
+    let mut p = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    p.push("../../");
+    p.push(path);
+    p.to_string_lossy().to_string()
+}
+
+fn compile_runtime_env_policy(mutate: impl FnOnce(&mut PolicySpec)) -> CompileBundle {
+    let mut policy = load_policy_from_path(&repo_file("tests/runtime_env/valid.yaml"))
+        .expect("load runtime env policy");
+    mutate(&mut policy);
+    let validation = validate_policy(policy);
+    assert!(validation.valid, "{:?}", validation.errors);
+    compile_dry_run(&validation.normalized_policy.expect("normalized policy"))
+        .expect("compile should succeed")
+}
+
+fn prepare_payload(label: &str, bundle: CompileBundle) -> (PathBuf, CommandPayload) {
+    let workdir = new_temp_dir(label);
+    write_mock_vm_artifacts(&workdir);
+    let request = RunnerControlRequest {
+        compile_bundle: bundle,
+        runtime_context: runtime_context(&workdir, None, 3, 20),
+    };
+    let prepared = runner_with_mock_runtime()
+        .prepare(request)
+        .expect("prepare should succeed");
+    let payload_path = prepared.command_payload_path();
+    assert!(prepared
+        .launch_plan
+        .firecracker
+        .args
+        .windows(2)
+        .any(|pair| pair[0] == "--metadata" && Path::new(&pair[1]) == payload_path));
+
+    let on_disk: CommandPayload =
+        serde_json::from_str(&fs::read_to_string(&payload_path).expect("read command payload"))
+            .expect("parse command payload");
+    assert_eq!(on_disk, prepared.command_payload);
+    (workdir, on_disk)
+}
+
+fn command_hash(workdir: &Path, payload: &CommandPayload) -> String {
+    let artifacts = workdir.join("artifacts");
+    compute_artifact_hashes_from_json(ArtifactJsonInputs {
+        kernel_path: &artifacts.join("vmlinux"),
+        rootfs_path: &artifacts.join("rootfs.ext4"),
+        policy_json: &serde_json::json!({}),
+        command_json: &serde_json::to_value(payload).expect("serialize payload"),
+    })
+    .expect("compute artifact hashes")
+    .command_hash
+}
+
+#[test]
+fn prepare_writes_command_payload_with_passthrough_env() {
+    std::env::set_var(PASSTHROUGH_VAR, "from-host");
+    let (workdir, payload) = prepare_payload("command-payload", compile_runtime_env_policy(|_| {}));
+
+    assert_eq!(payload.command, "/usr/bin/env");
+    assert_eq!(payload.cwd, "/work");
+    assert_eq!(payload.env.get("LANG").map(String::as_str), Some("C.UTF-8"));
+    assert_eq!(
+        payload.env.get(PASSTHROUGH_VAR).map(String::as_str),
+        Some("from-host")
+    );
+    let user = payload.user.expect("payload user");
+    assert_eq!((user.uid, user.gid), (1000, 1000));
+    assert!(
+        !payload.env.contains_key("PATH"),
+        "only allowlisted host variables are passed through"
+    );
+    remove_temp_dir(&workdir);
+}
+
+#[test]
+fn command_hash_changes_with_runtime_env() {
+    let (base_dir, base) = prepare_payload("command-hash-base", compile_runtime_env_policy(|_| {}));
+    let (changed_dir, changed) = prepare_payload(
+        "command-hash-env",
+        compile_runtime_env_policy(|policy| {
+            policy
+                .runtime
+                .env
+                .insert("APP_MODE".to_string(), "interactive".to_string());
+        }),
+    );
+
+    assert_ne!(
+        command_hash(&base_dir, &base),
+        command_hash(&changed_dir, &changed)
+    );
+    remove_temp_dir(&base_dir);
+    remove_temp_dir(&changed_dir);
+}
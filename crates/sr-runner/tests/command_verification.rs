@@ -0,0 +1,238 @@
+mod common;
+
+use common::{
+    new_temp_dir, parse_event_stream, remove_temp_dir, runtime_context, sample_policy,
+    write_mock_vm_artifacts,
+};
+use sr_common::SR_RUN_401;
+use sr_compiler::{compile_dry_run, CompileBundle};
+use sr_evidence::{sha256_bytes, EVENT_COMMAND_VERIFIED, EVENT_RUN_FAILED, EVENT_VM_STARTED};
+use sr_policy::validate_policy;
+use sr_runner::{
+    DebugfsRootfsReader, RootfsReader, RunState, Runner, RunnerControlRequest, RunnerRuntime,
+};
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+const ECHO_BINARY: &[u8] = b"\x7fELF-echo-binary";
+
+/// Serves fixed bytes for every path and records which paths were read.
+#[derive(Clone, Default)]
+struct FakeRootfsReader {
+    contents: Vec<u8>,
+    reads: Arc<Mutex<Vec<String>>>,
+}
+
+impl RootfsReader for FakeRootfsReader {
+    fn read_file(&self, _image: &Path, guest_path: &str) -> Result<Vec<u8>, String> {
+        self.reads
+            .lock()
+            .expect("lock reads")
+            .push(guest_path.to_string());
+        Ok(self.contents.clone())
+    }
+}
+
+fn compile_with_pin(pin: Option<&str>) -> CompileBundle {
+    let mut policy = sample_policy();
+    policy.runtime.command_sha256 = pin.map(str::to_string);
+    let validation = validate_policy(policy);
+    assert!(validation.valid, "{:?}", validation.errors);
+    compile_dry_run(&validation.normalized_policy.expect("normalized policy"))
+        .expect("compile should succeed")
+}
+
+fn runtime() -> RunnerRuntime {
+    RunnerRuntime {
+        jailer_bin: "/bin/true".to_string(),
+        firecracker_bin: "/bin/true".to_string(),
+    }
+}
+
+fn request(workdir: &Path, bundle: CompileBundle) -> RunnerControlRequest {
+    write_mock_vm_artifacts(workdir);
+    RunnerControlRequest {
+        compile_bundle: bundle,
+        runtime_context: runtime_context(workdir, None, 3, 20),
+    }
+}
+
+#[test]
+fn pinned_command_matching_rootfs_emits_command_verified() {
+    let workdir = new_temp_dir("command-verify-match");
+    let pin = sha256_bytes(ECHO_BINARY);
+    let reader = FakeRootfsReader {
+        contents: ECHO_BINARY.to_vec(),
+        ..FakeRootfsReader::default()
+    };
+    let runner = Runner::with_rootfs_reader(runtime(), reader.clone());
+    let bundle = compile_with_pin(Some(&pin));
+    assert!(bundle
+        .evidence_plan
+        .events
+        .iter()
+        .any(|event| event == EVENT_COMMAND_VERIFIED));
+
+    let mut prepared = runner
+        .prepare(request(&workdir, bundle))
+        .expect("prepare should succeed");
+    runner.launch(&mut prepared).expect("launch should succeed");
+    let events = parse_event_stream(&prepared.event_log_path());
+
+    assert_eq!(*reader.reads.lock().expect("lock reads"), ["/bin/echo"]);
+    let verified = events
+        .iter()
+        .position(|event| event.event_type == EVENT_COMMAND_VERIFIED)
+        .expect("command.verified event");
+    let started = events
+        .iter()
+        .position(|event| event.event_type == EVENT_VM_STARTED)
+        .expect("vm.started event");
+    assert!(verified < started);
+    assert_eq!(events[verified].payload["command"], "/bin/echo");
+    assert_eq!(events[verified].payload["sha256"], pin.as_str());
+    assert_eq!(events[verified].payload["sizeBytes"], ECHO_BINARY.len());
+    remove_temp_dir(&workdir);
+}
+
+#[test]
+fn hash_mismatch_fails_launch_with_sr_run_401() {
+    let workdir = new_temp_dir("command-verify-mismatch");
+    let reader = FakeRootfsReader {
+        contents: b"tampered".to_vec(),
+        ..FakeRootfsReader::default()
+    };
+    let runner = Runner::with_rootfs_reader(runtime(), reader);
+    let pin = sha256_bytes(ECHO_BINARY);
+    let mut prepared = runner
+        .prepare(request(&workdir, compile_with_pin(Some(&pin))))
+        .expect("prepare should succeed");
+
+    let err = runner.launch(&mut prepared).expect_err("launch must fail");
+    let events = parse_event_stream(&prepared.event_log_path());
+
+    assert_eq!(err.code, SR_RUN_401);
+    assert_eq!(err.path, "launch.command.verify");
+    assert!(err.message.contains(&pin));
+    assert_eq!(prepared.state, RunState::Failed);
+    let failed = events
+        .iter()
+        .find(|event| event.event_type == EVENT_RUN_FAILED)
+        .expect("run.failed event");
+    assert_eq!(failed.payload["reason"], "launch.command.verify");
+    assert_eq!(failed.payload["errorCode"], SR_RUN_401);
+    assert!(!events
+        .iter()
+        .any(|event| event.event_type == EVENT_COMMAND_VERIFIED
+            || event.event_type == EVENT_VM_STARTED));
+    remove_temp_dir(&workdir);
+}
+
+#[test]
+fn unpinned_command_is_not_read_from_rootfs() {
+    let workdir = new_temp_dir("command-verify-unpinned");
+    let reader = FakeRootfsReader::default();
+    let runner = Runner::with_rootfs_reader(runtime(), reader.clone());
+    let mut prepared = runner
+        .prepare(request(&workdir, compile_with_pin(None)))
+        .expect("prepare should succeed");
+
+    runner.launch(&mut prepared).expect("launch should succeed");
+    let events = parse_event_stream(&prepared.event_log_path());
+
+    assert!(reader.reads.lock().expect("lock reads").is_empty());
+    assert!(!events
+        .iter()
+        .any(|event| event.event_type == EVENT_COMMAND_VERIFIED));
+    remove_temp_dir(&workdir);
+}
+
+#[test]
+fn debugfs_reader_reads_binaries_from_ext4_image() {
+    let dir = new_temp_dir("command-verify-debugfs");
+    let tree = dir.join("tree");
+    fs::create_dir_all(tree.join("usr/bin")).expect("create rootfs tree");
+    fs::write(tree.join("usr/bin/tool"), ECHO_BINARY).expect("write binary");
+    std::os::unix::fs::symlink("usr/bin", tree.join("bin")).expect("link /bin");
+    let image = dir.join("rootfs.ext4");
+    let status = Command::new("mkfs.ext4")
+        .arg("-q")
+        .arg("-d")
+        .arg(&tree)
+        .arg(&image)
+        .arg("4M")
+        .stdout(Stdio::null())
+        .status()
+        .expect("run mkfs.ext4");
+    assert!(status.success());
+
+    let reader = DebugfsRootfsReader;
+    assert_eq!(
+        reader
+            .read_file(&image, "/usr/bin/tool")
+            .expect("read tool"),
+        ECHO_BINARY
+    );
+    assert_eq!(
+        reader
+            .read_file(&image, "/bin/tool")
+            .expect("read through /bin link"),
+        ECHO_BINARY
+    );
+    let err = reader
+        .read_file(&image, "/usr/bin/missing")
+        .expect_err("missing file must fail");
+    assert!(err.contains("not found"), "{err}");
+    for injected in [
+        "/bin/tool\" -f \"/etc/shadow",
+        "/bin/too l",
+        "/bin/tool\ncat /etc",
+    ] {
+        let err = reader
+            .read_file(&image, injected)
+            .expect_err("unquotable path must be refused");
+        assert!(err.contains("cannot read safely"), "{err}");
+    }
+    remove_temp_dir(&dir);
+}
+
+#[test]
+fn validation_normalizes_pins_and_rejects_malformed_ones() {
+    let upper = sha256_bytes(ECHO_BINARY)
+        .trim_start_matches("sha256:")
+        .to_ascii_uppercase();
+    let mut policy = sample_policy();
+    policy.runtime.command_sha256 = Some(upper);
+    let validation = validate_policy(policy);
+    assert!(validation.valid, "{:?}", validation.errors);
+    assert_eq!(
+        validation
+            .normalized_policy
+            .expect("normalized policy")
+            .runtime
+            .command_sha256,
+        Some(sha256_bytes(ECHO_BINARY))
+    );
+
+    let mut policy = sample_policy();
+    policy.runtime.command = "echo".to_string();
+    policy.runtime.command_sha256 = Some("sha256:abc".to_string());
+    let validation = validate_policy(policy);
+    let paths = validation
+        .errors
+        .iter()
+        .map(|err| err.path.as_str())
+        .collect::<Vec<_>>();
+    assert!(!validation.valid);
+    assert_eq!(paths, ["runtime.commandSha256", "runtime.command"]);
+
+    let mut policy = sample_policy();
+    policy.runtime.command = "/bin/echo\" -f \"/etc/shadow".to_string();
+    policy.runtime.command_sha256 = Some(sha256_bytes(ECHO_BINARY));
+    let validation = validate_policy(policy);
+    assert!(!validation.valid);
+    assert_eq!(validation.errors[0].path, "runtime.command");
+    assert!(validation.errors[0].message.contains("quotes"));
+}
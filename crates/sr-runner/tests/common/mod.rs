@@ -1,6 +1,5 @@
 #![allow(dead_code)]
 
-use serde_json::json;
 use sr_compiler::{compile_dry_run, CompileBundle};
 use sr_evidence::{
     build_report, compute_artifact_hashes_from_json, compute_integrity_digest, event_time_range,
@@ -12,6 +11,7 @@ use sr_policy::{
     Runtime,
 };
 use sr_runner::{CommandSpec, MonitorResult, PreparedRun, Runner, RunnerRuntime, RuntimeContext};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -83,6 +83,7 @@ pub fn runtime_context(
         timeout_sec,
         sample_interval_ms: Some(sample_interval_ms),
         cgroup_path: cgroup_path.map(|path| path.to_string_lossy().to_string()),
+        mount_scan_max_entries: None,
     }
 }
 
@@ -135,13 +136,16 @@ pub fn build_report_from_events(
         PolicySummary {
             network: network_mode.to_string(),
             mounts: policy.mounts.len(),
+            parameters: BTreeMap::new(),
+            admission_constraints_hash: None,
         },
         resource_usage,
         events.to_vec(),
         mount_audit,
         network_audit,
         String::new(),
-    );
+    )
+    .expect("build report");
     report.integrity.digest = compute_integrity_digest(&report).expect("compute report digest");
     report
 }
@@ -151,15 +155,21 @@ pub fn write_report(path: &Path, report: &RunReport) {
     fs::write(path, content).expect("write run report");
 }
 
-fn sample_policy() -> PolicySpec {
+pub fn sample_policy() -> PolicySpec {
     PolicySpec {
         api_version: "policy.safe-run.dev/v1alpha1".to_string(),
         metadata: Metadata {
             name: "integration-smoke".to_string(),
         },
+        parameters: BTreeMap::new(),
         runtime: Runtime {
             command: "/bin/echo".to_string(),
             args: vec!["hello".to_string()],
+            env: BTreeMap::new(),
+            env_passthrough: Vec::new(),
+            cwd: None,
+            user: None,
+            command_sha256: None,
         },
         resources: Resources {
             cpu: Cpu {
@@ -174,6 +184,7 @@ fn sample_policy() -> PolicySpec {
             egress: vec![],
         },
         mounts: vec![],
+        secrets: Vec::new(),
         audit: Audit {
             level: "basic".to_string(),
         },
@@ -186,10 +197,10 @@ fn report_artifacts(
     compile_bundle: &CompileBundle,
 ) -> sr_evidence::ReportArtifacts {
     let policy_json = serde_json::to_value(policy).expect("serialize policy to json");
-    let command_json = json!({
-        "command": policy.runtime.command,
-        "args": policy.runtime.args
-    });
+    let command_json: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(workdir.join("command.json")).expect("read command payload"),
+    )
+    .expect("parse command payload");
     let (kernel_path, rootfs_path) = artifact_paths(workdir, &compile_bundle.firecracker_config);
     compute_artifact_hashes_from_json(ArtifactJsonInputs {
         kernel_path: &kernel_path,
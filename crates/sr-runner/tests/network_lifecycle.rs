@@ -18,6 +18,7 @@ use sr_runner::{
     AppliedNetwork, AppliedNetworkRule, NetworkLifecycle, NetworkLifecycleError, NetworkRuleHit,
     Runner, RunnerControlRequest, RunnerRuntime,
 };
+use std::collections::BTreeMap;
 use std::sync::{Arc, Mutex};
 
 #[derive(Clone)]
@@ -484,9 +485,15 @@ fn compile_allowlist_policy_and_bundle(
         metadata: Metadata {
             name: "network-stage3".to_string(),
         },
+        parameters: BTreeMap::new(),
         runtime: Runtime {
             command: "/bin/echo".to_string(),
             args: vec!["hello".to_string()],
+            env: BTreeMap::new(),
+            env_passthrough: Vec::new(),
+            cwd: None,
+            user: None,
+            command_sha256: None,
         },
         resources: Resources {
             cpu: Cpu {
@@ -506,6 +513,7 @@ fn compile_allowlist_policy_and_bundle(
             }],
         },
         mounts: vec![],
+        secrets: Vec::new(),
         audit: Audit {
             level: "basic".to_string(),
         },
@@ -8,6 +8,7 @@ mod stage6_real_world {
         PolicySpec, Resources, Runtime,
     };
     use sr_runner::{Runner, RunnerControlRequest, RunnerRuntime, RuntimeContext};
+    use std::collections::BTreeMap;
     use std::fs;
     use std::path::Path;
     use std::process::Command;
@@ -70,6 +71,7 @@ mod stage6_real_world {
                     timeout_sec: 3,
                     sample_interval_ms: Some(20),
                     cgroup_path: Some(cgroup_dir.to_string_lossy().to_string()),
+                    mount_scan_max_entries: None,
                 },
             })
             .expect("prepare should succeed");
@@ -102,9 +104,15 @@ mod stage6_real_world {
             metadata: Metadata {
                 name: "stage6-real-world".to_string(),
             },
+            parameters: BTreeMap::new(),
             runtime: Runtime {
                 command: "/bin/echo".to_string(),
                 args: vec!["stage6".to_string()],
+                env: BTreeMap::new(),
+                env_passthrough: Vec::new(),
+                cwd: None,
+                user: None,
+                command_sha256: None,
             },
             resources: Resources {
                 cpu: Cpu {
@@ -124,6 +132,7 @@ mod stage6_real_world {
                 }],
             },
             mounts: vec![],
+            secrets: Vec::new(),
             audit: Audit {
                 level: "basic".to_string(),
             },
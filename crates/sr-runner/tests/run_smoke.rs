@@ -73,3 +73,71 @@ fn minimal_run_executes_and_writes_report() {
     assert!(report_path.exists());
     remove_temp_dir(&workdir);
 }
+
+#[test]
+fn monitor_samples_optional_cgroup_counters_into_report() {
+    let workdir = new_temp_dir("run-smoke-counters");
+    let cgroup_dir = workdir.join("mock-cgroup");
+    write_mock_vm_artifacts(&workdir);
+    write_mock_cgroup_files(&cgroup_dir, 1500, 4096);
+    std::fs::write(cgroup_dir.join("memory.peak"), "8192\n").expect("write memory.peak");
+    std::fs::write(
+        cgroup_dir.join("memory.events"),
+        "low 0\nhigh 2\nmax 1\noom 1\noom_kill 1\n",
+    )
+    .expect("write memory.events");
+    std::fs::write(
+        cgroup_dir.join("io.stat"),
+        "8:0 rbytes=100 wbytes=20 rios=3 wios=1\n8:16 rbytes=50 wbytes=5 rios=1 wios=1\n",
+    )
+    .expect("write io.stat");
+    std::fs::write(cgroup_dir.join("pids.current"), "7\n").expect("write pids.current");
+
+    let (policy, compile_bundle) = compile_bundle_from_policy();
+    let request = RunnerControlRequest {
+        compile_bundle: compile_bundle.clone(),
+        runtime_context: runtime_context(&workdir, Some(&cgroup_dir), 3, 20),
+    };
+
+    let runner = runner_with_mock_runtime();
+    let mut prepared = runner.prepare(request).expect("prepare should succeed");
+    override_launch_command(&mut prepared, "sleep 0.12");
+    runner.launch(&mut prepared).expect("launch should succeed");
+    let monitor_result = runner
+        .monitor(&mut prepared)
+        .expect("monitor should succeed");
+    runner
+        .cleanup(&mut prepared)
+        .expect("cleanup should succeed");
+
+    let events = parse_event_stream(&prepared.event_log_path());
+    let sample = events
+        .iter()
+        .find(|event| event.event_type == EVENT_RESOURCE_SAMPLED)
+        .expect("resource sample");
+    assert_eq!(sample.payload["memoryPeakBytes"], 8192);
+    assert_eq!(sample.payload["memoryEvents"]["oomKill"], 1);
+    assert_eq!(sample.payload["io"]["readBytes"], 150);
+    assert_eq!(sample.payload["pidsCurrent"], 7);
+
+    let report = build_report_from_events(
+        &workdir,
+        &prepared.run_id,
+        &monitor_result,
+        &events,
+        &policy,
+        &compile_bundle,
+    );
+    let usage = &report.resource_usage;
+    assert_eq!(usage.sample_count, monitor_result.sample_count);
+    assert_eq!(usage.cpu_total_usec, Some(1500));
+    assert_eq!(usage.memory_peak_bytes, Some(8192));
+    assert_eq!(usage.memory_events.as_ref().map(|item| item.oom), Some(1));
+    assert_eq!(usage.io.as_ref().map(|item| item.write_bytes), Some(25));
+    assert_eq!(usage.pids_peak, Some(7));
+    assert!(usage
+        .series
+        .as_ref()
+        .is_some_and(|series| !series.is_empty()));
+    remove_temp_dir(&workdir);
+}
@@ -0,0 +1,192 @@
+mod common;
+
+use common::{
+    new_temp_dir, parse_event_stream, remove_temp_dir, runner_with_mock_runtime, runtime_context,
+    write_mock_vm_artifacts,
+};
+use sr_common::SR_RUN_301;
+use sr_compiler::{compile_dry_run, CompileBundle};
+use sr_evidence::{salted_secret_hash, EVENT_SECRETS_INJECTED};
+use sr_policy::{load_policy_from_path, validate_policy_with_allowlist, PolicySpec};
+use sr_runner::RunnerControlRequest;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+const TOKEN_VAR: &str = "SAFE_RUN_TEST_API_TOKEN";
+const TOKEN_VALUE: &str = "tok-3f9a7c2e1b";
+const DB_PASSWORD: &str = "pw \"quoted\"\nsecond-line";
+
+fn repo_file(path: &str) -> String {
+    let mut p = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    p.push("../../");
+    p.push(path);
+    p.to_string_lossy().to_string()
+}
+
+fn compile_secrets_policy(workdir: &Path, mutate: impl FnOnce(&mut PolicySpec)) -> CompileBundle {
+    let secret_file = workdir.join("db-password");
+    fs::write(&secret_file, DB_PASSWORD).expect("write secret file");
+    let mut policy =
+        load_policy_from_path(&repo_file("tests/secrets/valid.yaml")).expect("load policy");
+    policy.secrets[1].from_file = Some(secret_file.to_string_lossy().to_string());
+    mutate(&mut policy);
+    // Secret sources must be declared by the operator in the allowlist.
+    let allowlist = workdir.join("allowlist.yaml");
+    fs::write(
+        &allowlist,
+        format!(
+            "schemaVersion: safe-run.mount-allowlist/v1\nhostAllowPrefixes:\n  - /var/lib/safe-run\nguestAllowPrefixes:\n  - /data\nsecretFilePrefixes:\n  - {}\nsecretEnvNames:\n  - {TOKEN_VAR}\n",
+            workdir.display()
+        ),
+    )
+    .expect("write allowlist");
+    let validation = validate_policy_with_allowlist(policy, Some(&allowlist.to_string_lossy()));
+    assert!(validation.valid, "{:?}", validation.errors);
+    compile_dry_run(&validation.normalized_policy.expect("normalized policy"))
+        .expect("compile should succeed")
+}
+
+fn request(workdir: &Path, compile_bundle: CompileBundle) -> RunnerControlRequest {
+    write_mock_vm_artifacts(workdir);
+    RunnerControlRequest {
+        compile_bundle,
+        runtime_context: runtime_context(workdir, None, 3, 20),
+    }
+}
+
+#[test]
+fn secrets_drive_is_attached_and_evidence_records_only_salted_hashes() {
+    std::env::set_var(TOKEN_VAR, TOKEN_VALUE);
+    let workdir = new_temp_dir("secrets-injection");
+    let bundle = compile_secrets_policy(&workdir, |_| {});
+
+    let drives = bundle.firecracker_config["drives"]
+        .as_array()
+        .expect("drives array");
+    assert_eq!(drives[0]["path_on_host"], "secrets/secrets.img");
+    assert_eq!(drives[0]["is_read_only"], true);
+    assert!(bundle.firecracker_config["boot-source"]["boot_args"]
+        .as_str()
+        .expect("boot args")
+        .contains("safe_run.secrets=secrets:/run/secrets"));
+
+    let runner = runner_with_mock_runtime();
+    let mut prepared = runner
+        .prepare(request(&workdir, bundle))
+        .expect("prepare should succeed");
+
+    let image_path = workdir.join("secrets/secrets.img");
+    let image = fs::read(&image_path).expect("read secrets image");
+    assert_eq!(image.len() % 512, 0);
+    assert_eq!(
+        fs::metadata(&image_path)
+            .expect("image metadata")
+            .permissions()
+            .mode()
+            & 0o777,
+        0o600
+    );
+    let delivered: serde_json::Value = serde_json::from_slice(&image).expect("image is JSON");
+    assert_eq!(delivered["API_TOKEN"], TOKEN_VALUE);
+    assert_eq!(delivered["DB_PASSWORD"], DB_PASSWORD);
+
+    let events = parse_event_stream(&prepared.event_log_path());
+    let injected = events
+        .iter()
+        .find(|event| event.event_type == EVENT_SECRETS_INJECTED)
+        .expect("secrets.injected event");
+    let salt = injected.payload["salt"].as_str().expect("salt");
+    let recorded = injected.payload["secrets"].as_array().expect("secrets");
+    assert_eq!(recorded.len(), 2);
+    assert_eq!(recorded[0]["name"], "API_TOKEN");
+    assert_eq!(recorded[0]["source"], "env");
+    assert_eq!(
+        recorded[0]["hash"].as_str(),
+        salted_secret_hash(salt, TOKEN_VALUE.as_bytes()).as_deref()
+    );
+    assert_eq!(recorded[1]["source"], "file");
+
+    runner
+        .cleanup(&mut prepared)
+        .expect("cleanup should succeed");
+    assert!(!image_path.exists(), "secrets image is shredded on cleanup");
+    for path in [
+        prepared.firecracker_config_path(),
+        prepared.command_payload_path(),
+        prepared.event_log_path(),
+    ] {
+        let content = fs::read_to_string(&path).expect("read artifact");
+        assert!(!content.contains(TOKEN_VALUE), "{}", path.display());
+        assert!(!content.contains("second-line"), "{}", path.display());
+    }
+    remove_temp_dir(&workdir);
+}
+
+#[test]
+fn secret_value_in_command_args_fails_prepare_and_removes_image() {
+    std::env::set_var(TOKEN_VAR, TOKEN_VALUE);
+    let workdir = new_temp_dir("secrets-leak");
+    let bundle = compile_secrets_policy(&workdir, |policy| {
+        policy.runtime.args.push(TOKEN_VALUE.to_string());
+    });
+
+    let err = runner_with_mock_runtime()
+        .prepare(request(&workdir, bundle))
+        .expect_err("leaked secret must fail prepare");
+    assert_eq!(err.code, SR_RUN_301);
+    assert_eq!(err.path, "secrets.leak");
+    assert!(err.message.contains("API_TOKEN"));
+    assert!(!err.message.contains(TOKEN_VALUE));
+    assert!(!workdir.join("secrets/secrets.img").exists());
+    remove_temp_dir(&workdir);
+}
+
+#[test]
+fn unreadable_secret_source_fails_prepare() {
+    std::env::set_var(TOKEN_VAR, TOKEN_VALUE);
+    let workdir = new_temp_dir("secrets-missing");
+    let bundle = compile_secrets_policy(&workdir, |_| {});
+    fs::remove_file(workdir.join("db-password")).expect("remove secret file");
+
+    let err = runner_with_mock_runtime()
+        .prepare(request(&workdir, bundle))
+        .expect_err("missing secret file must fail prepare");
+    assert_eq!(err.code, SR_RUN_301);
+    assert_eq!(err.path, "prepare.secrets.DB_PASSWORD");
+    remove_temp_dir(&workdir);
+}
+
+#[test]
+fn secret_file_swapped_for_symlink_after_validation_fails_prepare() {
+    std::env::set_var(TOKEN_VAR, TOKEN_VALUE);
+    let workdir = new_temp_dir("secrets-symlink");
+    let bundle = compile_secrets_policy(&workdir, |_| {});
+    let secret_file = workdir.join("db-password");
+    fs::remove_file(&secret_file).expect("remove secret file");
+    std::os::unix::fs::symlink("/etc/hostname", &secret_file).expect("create symlink");
+
+    let err = runner_with_mock_runtime()
+        .prepare(request(&workdir, bundle))
+        .expect_err("symlinked secret file must fail prepare");
+    assert_eq!(err.code, SR_RUN_301);
+    assert_eq!(err.path, "prepare.secrets.DB_PASSWORD");
+    assert!(!workdir.join("secrets/secrets.img").exists());
+    remove_temp_dir(&workdir);
+}
+
+#[test]
+fn prepare_failure_after_secrets_image_shreds_it() {
+    std::env::set_var(TOKEN_VAR, TOKEN_VALUE);
+    let workdir = new_temp_dir("secrets-late-failure");
+    let bundle = compile_secrets_policy(&workdir, |_| {});
+    // A directory in place of command.json makes the payload write fail after the image.
+    fs::create_dir_all(workdir.join("command.json")).expect("block command payload");
+
+    let err = runner_with_mock_runtime()
+        .prepare(request(&workdir, bundle))
+        .expect_err("payload write must fail prepare");
+    assert_eq!(err.path, "prepare.commandPayload");
+    assert!(!workdir.join("secrets/secrets.img").exists());
+    remove_temp_dir(&workdir);
+}
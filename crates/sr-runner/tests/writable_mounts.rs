@@ -0,0 +1,216 @@
+mod common;
+
+use common::{
+    build_report_from_events, new_temp_dir, override_launch_command, parse_event_stream,
+    remove_temp_dir, runner_with_mock_runtime, runtime_context, write_mock_cgroup_files,
+    write_mock_vm_artifacts,
+};
+use sr_compiler::{compile_dry_run, CompileBundle};
+use sr_evidence::{sha256_file, EVENT_MOUNT_OUTPUT_COLLECTED};
+use sr_policy::{
+    validate_policy, Audit, Cpu, Memory, Metadata, Mount, MountMode, Network, NetworkMode,
+    PolicySpec, Resources, Runtime,
+};
+use sr_runner::RunnerControlRequest;
+use std::collections::BTreeMap;
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+
+fn writable_mount(target: &str, mode: MountMode, max_size: &str) -> Mount {
+    Mount {
+        source: String::new(),
+        target: target.to_string(),
+        read_only: false,
+        source_identity: None,
+        mode: Some(mode),
+        max_size: Some(max_size.to_string()),
+    }
+}
+
+fn compile_writable_policy() -> (PolicySpec, CompileBundle) {
+    let policy = PolicySpec {
+        api_version: "policy.safe-run.dev/v1alpha1".to_string(),
+        metadata: Metadata {
+            name: "writable-mounts".to_string(),
+        },
+        parameters: BTreeMap::new(),
+        runtime: Runtime {
+            command: "/bin/echo".to_string(),
+            args: vec!["hello".to_string()],
+            env: BTreeMap::new(),
+            env_passthrough: Vec::new(),
+            cwd: None,
+            user: None,
+            command_sha256: None,
+        },
+        resources: Resources {
+            cpu: Cpu {
+                max: "100000 100000".to_string(),
+            },
+            memory: Memory {
+                max: "256Mi".to_string(),
+            },
+        },
+        network: Network {
+            mode: NetworkMode::None,
+            egress: vec![],
+        },
+        mounts: vec![
+            writable_mount("/data/out", MountMode::Output, "4Mi"),
+            writable_mount("/data/tmp", MountMode::Scratch, "2Mi"),
+        ],
+        secrets: Vec::new(),
+        audit: Audit {
+            level: "basic".to_string(),
+        },
+    };
+    let validation = validate_policy(policy);
+    assert!(validation.valid, "{:?}", validation.errors);
+    let policy = validation.normalized_policy.expect("normalized policy");
+    let bundle = compile_dry_run(&policy).expect("compile should succeed");
+    (policy, bundle)
+}
+
+#[test]
+fn compile_attaches_writable_mounts_as_drives() {
+    let (_, bundle) = compile_writable_policy();
+
+    assert!(bundle.mount_plan.mounts.is_empty());
+    assert_eq!(bundle.mount_plan.drives.len(), 2);
+    assert_eq!(bundle.mount_plan.drives[0].drive_id, "output_0");
+    assert_eq!(bundle.mount_plan.drives[0].size_mib, 4);
+    assert_eq!(bundle.mount_plan.drives[1].drive_id, "scratch_1");
+
+    let drives = bundle.firecracker_config["drives"]
+        .as_array()
+        .expect("drives array");
+    assert_eq!(drives.len(), 2);
+    assert_eq!(drives[0]["path_on_host"], "mounts/output_0.img");
+    assert_eq!(drives[0]["is_read_only"], false);
+    let boot_args = bundle.firecracker_config["boot-source"]["boot_args"]
+        .as_str()
+        .expect("boot args");
+    assert!(boot_args.contains("safe_run.drive=output_0:/data/out"));
+    assert!(boot_args.contains("safe_run.drive=scratch_1:/data/tmp"));
+    assert!(bundle
+        .evidence_plan
+        .events
+        .iter()
+        .any(|event| event == EVENT_MOUNT_OUTPUT_COLLECTED));
+}
+
+#[test]
+fn prepare_rejects_mount_image_sizes_that_overflow() {
+    let workdir = new_temp_dir("writable-mounts-overflow");
+    write_mock_vm_artifacts(&workdir);
+
+    let (_, mut compile_bundle) = compile_writable_policy();
+    compile_bundle.mount_plan.drives[0].size_mib = u64::MAX;
+    let request = RunnerControlRequest {
+        compile_bundle,
+        runtime_context: runtime_context(&workdir, None, 3, 20),
+    };
+    let err = runner_with_mock_runtime()
+        .prepare(request)
+        .expect_err("oversized image must be rejected");
+    assert_eq!(err.path, "prepare.mountImages.output_0");
+    assert!(!workdir.join("mounts/output_0.img").exists());
+
+    remove_temp_dir(&workdir);
+}
+
+#[test]
+fn failed_prepare_removes_created_mount_images() {
+    let workdir = new_temp_dir("writable-mounts-failed-prepare");
+    write_mock_vm_artifacts(&workdir);
+    // Writing the Firecracker config runs after the images exist; a directory in its place
+    // makes it fail.
+    fs::create_dir_all(workdir.join("firecracker-config.json")).expect("block config path");
+
+    let (_, compile_bundle) = compile_writable_policy();
+    let request = RunnerControlRequest {
+        compile_bundle,
+        runtime_context: runtime_context(&workdir, None, 3, 20),
+    };
+    let err = runner_with_mock_runtime()
+        .prepare(request)
+        .expect_err("prepare must fail");
+    assert_eq!(err.path, "prepare.firecrackerConfig");
+    assert!(!workdir.join("mounts/output_0.img").exists());
+    assert!(!workdir.join("mounts/scratch_1.img").exists());
+
+    remove_temp_dir(&workdir);
+}
+
+#[test]
+fn output_image_is_collected_and_scratch_image_discarded() {
+    let workdir = new_temp_dir("writable-mounts");
+    let cgroup_dir = workdir.join("mock-cgroup");
+    write_mock_vm_artifacts(&workdir);
+    write_mock_cgroup_files(&cgroup_dir, 1000, 2048);
+
+    let (policy, compile_bundle) = compile_writable_policy();
+    let request = RunnerControlRequest {
+        compile_bundle: compile_bundle.clone(),
+        runtime_context: runtime_context(&workdir, Some(&cgroup_dir), 3, 20),
+    };
+    let runner = runner_with_mock_runtime();
+    let mut prepared = runner.prepare(request).expect("prepare should succeed");
+
+    let output_image = workdir.join("mounts/output_0.img");
+    let scratch_image = workdir.join("mounts/scratch_1.img");
+    let metadata = fs::metadata(&output_image).expect("output image exists");
+    assert_eq!(metadata.len(), 4 * 1024 * 1024);
+    assert!(metadata.blocks() * 512 < metadata.len(), "image is sparse");
+    assert_eq!(
+        fs::metadata(&scratch_image).expect("scratch image").len(),
+        2 * 1024 * 1024
+    );
+
+    // Stand-in for the guest writing into its output drive.
+    override_launch_command(
+        &mut prepared,
+        &format!(
+            "printf result | dd of={} conv=notrunc status=none",
+            output_image.display()
+        ),
+    );
+    runner.launch(&mut prepared).expect("launch should succeed");
+    let monitor_result = runner
+        .monitor(&mut prepared)
+        .expect("monitor should succeed");
+    runner
+        .cleanup(&mut prepared)
+        .expect("cleanup should succeed");
+
+    let collected = prepared.artifacts_dir().join("outputs/output_0.img");
+    assert!(!output_image.exists());
+    assert!(!scratch_image.exists());
+    assert!(!prepared
+        .artifacts_dir()
+        .join("outputs/scratch_1.img")
+        .exists());
+    let content = fs::read(&collected).expect("read collected output");
+    assert!(content.starts_with(b"result"));
+
+    let events = parse_event_stream(&prepared.event_log_path());
+    let report = build_report_from_events(
+        &workdir,
+        &prepared.run_id,
+        &monitor_result,
+        &events,
+        &policy,
+        &compile_bundle,
+    );
+    let outputs = &report.mount_audit.outputs;
+    assert_eq!(outputs.len(), 1);
+    assert_eq!(outputs[0].drive_id, "output_0");
+    assert_eq!(outputs[0].target, "/data/out");
+    assert_eq!(outputs[0].artifact, "outputs/output_0.img");
+    assert_eq!(outputs[0].size_bytes, 4 * 1024 * 1024);
+    assert_eq!(
+        outputs[0].sha256,
+        sha256_file(&collected).expect("hash output")
+    );
+    remove_temp_dir(&workdir);
+}